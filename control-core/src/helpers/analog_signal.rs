@@ -0,0 +1,329 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use anyhow::Error;
+use ethercat_hal::io::analog_input::{AnalogInput, physical::AnalogInputValue};
+use serde::{Deserialize, Serialize};
+use units::electric_current::milliampere;
+use units::electric_potential::volt;
+
+use super::interpolation::{normalize, scale};
+
+/// Standard signal of an analog sensor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnalogSensorSignal {
+    Current4To20mA,
+    Current0To20mA,
+    Voltage0To10V,
+}
+
+impl AnalogSensorSignal {
+    /// Position of the value in the signal range from 0 to 1, clamped
+    pub fn normalize(&self, value: &AnalogInputValue) -> Result<f64, Error> {
+        match (self, value) {
+            (Self::Current4To20mA, AnalogInputValue::Current(current)) => {
+                Ok(normalize(current.get::<milliampere>(), 4.0, 20.0))
+            }
+            (Self::Current0To20mA, AnalogInputValue::Current(current)) => {
+                Ok(normalize(current.get::<milliampere>(), 0.0, 20.0))
+            }
+            (Self::Voltage0To10V, AnalogInputValue::Potential(potential)) => {
+                Ok(normalize(potential.get::<volt>(), 0.0, 10.0))
+            }
+            _ => Err(anyhow::anyhow!(
+                "[{}::AnalogSensorSignal::normalize] {:?} sensor can't read {:?}",
+                module_path!(),
+                self,
+                value
+            )),
+        }
+    }
+}
+
+/// Maps the sensor signal linearly to the measuring range of the sensor
+///
+/// `min` and `max` are in the engineering unit the caller wraps the result in, e.g. bar for a
+/// pressure sensor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SensorScaling {
+    pub signal: AnalogSensorSignal,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl SensorScaling {
+    pub const fn new(signal: AnalogSensorSignal, min: f64, max: f64) -> Self {
+        Self { signal, min, max }
+    }
+
+    pub fn scale(&self, value: &AnalogInputValue) -> Result<f64, Error> {
+        Ok(scale(self.signal.normalize(value)?, self.min, self.max))
+    }
+}
+
+/// Value shown by the sensor and the true value at the same moment
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CalibrationPoint {
+    /// Scaled value before calibration
+    pub measured: f64,
+    /// Value of the reference
+    pub actual: f64,
+}
+
+/// Offset and gain correction of a scaled sensor value, `actual = measured * gain + offset`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TwoPointCalibration {
+    pub gain: f64,
+    pub offset: f64,
+}
+
+impl Default for TwoPointCalibration {
+    fn default() -> Self {
+        Self {
+            gain: 1.0,
+            offset: 0.0,
+        }
+    }
+}
+
+impl TwoPointCalibration {
+    /// Calibration through two reference points, which need different measured values
+    pub fn from_points(a: CalibrationPoint, b: CalibrationPoint) -> Result<Self, Error> {
+        let measured_span = b.measured - a.measured;
+        if measured_span.abs() < f64::EPSILON || !measured_span.is_finite() {
+            return Err(anyhow::anyhow!(
+                "[{}::TwoPointCalibration::from_points] Calibration points need different measured values",
+                module_path!()
+            ));
+        }
+        let gain = (b.actual - a.actual) / measured_span;
+        Ok(Self {
+            gain,
+            offset: a.measured.mul_add(-gain, a.actual),
+        })
+    }
+
+    pub const fn apply(&self, measured: f64) -> f64 {
+        measured.mul_add(self.gain, self.offset)
+    }
+}
+
+/// Filter applied after scaling and calibration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnalogFilter {
+    None,
+    /// Mean of the last `window` samples
+    MovingAverage {
+        window: usize,
+    },
+    /// Median of the last `window` samples, removes single spikes
+    Median {
+        window: usize,
+    },
+    /// First order low-pass with the time constant, independent of the cycle time
+    LowPass {
+        time_constant: Duration,
+    },
+}
+
+/// [`AnalogFilter`] with its sample history
+#[derive(Debug, Clone)]
+pub struct AnalogSignalFilter {
+    filter: AnalogFilter,
+    samples: VecDeque<f64>,
+    last: Option<(Instant, f64)>,
+}
+
+impl AnalogSignalFilter {
+    pub const fn new(filter: AnalogFilter) -> Self {
+        Self {
+            filter,
+            samples: VecDeque::new(),
+            last: None,
+        }
+    }
+
+    /// Feed one sample and get the filtered value
+    pub fn update(&mut self, now: Instant, value: f64) -> f64 {
+        let filtered = match self.filter {
+            AnalogFilter::None => value,
+            AnalogFilter::MovingAverage { window } => {
+                self.push_sample(window, value);
+                self.samples.iter().sum::<f64>() / self.samples.len() as f64
+            }
+            AnalogFilter::Median { window } => {
+                self.push_sample(window, value);
+                let mut sorted: Vec<f64> = self.samples.iter().copied().collect();
+                sorted.sort_by(f64::total_cmp);
+                let len = sorted.len();
+                (sorted[(len - 1) / 2] + sorted[len / 2]) / 2.0
+            }
+            AnalogFilter::LowPass { time_constant } => match self.last {
+                None => value,
+                Some((last_time, last_value)) => {
+                    let dt = now.duration_since(last_time).as_secs_f64();
+                    let alpha = dt / (time_constant.as_secs_f64() + dt);
+                    if alpha.is_finite() {
+                        (value - last_value).mul_add(alpha, last_value)
+                    } else {
+                        value
+                    }
+                }
+            },
+        };
+        self.last = Some((now, filtered));
+        filtered
+    }
+
+    /// Forget the history, the next sample passes unfiltered
+    pub fn reset(&mut self) {
+        self.samples.clear();
+        self.last = None;
+    }
+
+    fn push_sample(&mut self, window: usize, value: f64) {
+        self.samples.push_back(value);
+        while self.samples.len() > window.max(1) {
+            self.samples.pop_front();
+        }
+    }
+}
+
+/// [`AnalogInput`] with sensor scaling, two-point calibration and filtering
+///
+/// [`AnalogSignalChain::update`] has to be called once per `act` cycle, the getters report the
+/// values of the last update.
+#[derive(Debug)]
+pub struct AnalogSignalChain {
+    input: AnalogInput,
+    scaling: SensorScaling,
+    calibration: TwoPointCalibration,
+    filter: AnalogSignalFilter,
+    uncalibrated_value: Option<f64>,
+    value: Option<f64>,
+}
+
+impl AnalogSignalChain {
+    pub fn new(input: AnalogInput, scaling: SensorScaling, filter: AnalogFilter) -> Self {
+        Self {
+            input,
+            scaling,
+            calibration: TwoPointCalibration::default(),
+            filter: AnalogSignalFilter::new(filter),
+            uncalibrated_value: None,
+            value: None,
+        }
+    }
+
+    /// Read the input and update the filtered value, call once per `act` cycle
+    pub fn update(&mut self, now: Instant) -> Result<f64, Error> {
+        let uncalibrated_value = self.scaling.scale(&self.input.get_physical())?;
+        let value = self
+            .filter
+            .update(now, self.calibration.apply(uncalibrated_value));
+        self.uncalibrated_value = Some(uncalibrated_value);
+        self.value = Some(value);
+        Ok(value)
+    }
+
+    /// Scaled, calibrated and filtered value, `None` before the first successful update
+    pub const fn value(&self) -> Option<f64> {
+        self.value
+    }
+
+    /// Scaled value before calibration and filtering, the `measured` of a [`CalibrationPoint`]
+    pub const fn uncalibrated_value(&self) -> Option<f64> {
+        self.uncalibrated_value
+    }
+
+    pub const fn calibration(&self) -> TwoPointCalibration {
+        self.calibration
+    }
+
+    /// Also resets the filter, so old and new calibration aren't mixed
+    pub fn set_calibration(&mut self, calibration: TwoPointCalibration) {
+        self.calibration = calibration;
+        self.filter.reset();
+    }
+
+    pub fn get_wiring_error(&self) -> bool {
+        self.input.get_wiring_error()
+    }
+
+    pub fn get_physical(&self) -> AnalogInputValue {
+        self.input.get_physical()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use units::f64::{ElectricCurrent, ElectricPotential};
+
+    #[test]
+    fn test_scaling_and_calibration() {
+        let scaling = SensorScaling::new(AnalogSensorSignal::Current4To20mA, 0.0, 350.0);
+        let current = |ma| AnalogInputValue::Current(ElectricCurrent::new::<milliampere>(ma));
+
+        assert_relative_eq!(scaling.scale(&current(4.0)).unwrap(), 0.0);
+        assert_relative_eq!(scaling.scale(&current(12.0)).unwrap(), 175.0);
+        // clamped below live zero
+        assert_relative_eq!(scaling.scale(&current(2.0)).unwrap(), 0.0);
+        assert!(
+            scaling
+                .scale(&AnalogInputValue::Potential(
+                    ElectricPotential::new::<volt>(5.0)
+                ))
+                .is_err()
+        );
+
+        let calibration = TwoPointCalibration::from_points(
+            CalibrationPoint {
+                measured: 2.0,
+                actual: 0.0,
+            },
+            CalibrationPoint {
+                measured: 202.0,
+                actual: 210.0,
+            },
+        )
+        .unwrap();
+        assert_relative_eq!(calibration.gain, 1.05);
+        assert_relative_eq!(calibration.apply(2.0), 0.0);
+        assert_relative_eq!(calibration.apply(102.0), 105.0);
+
+        let point = CalibrationPoint {
+            measured: 1.0,
+            actual: 2.0,
+        };
+        assert!(TwoPointCalibration::from_points(point, point).is_err());
+    }
+
+    #[test]
+    fn test_filters() {
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        let mut average = AnalogSignalFilter::new(AnalogFilter::MovingAverage { window: 3 });
+        let averaged: Vec<f64> = [3.0, 6.0, 9.0, 12.0]
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| average.update(at(i as u64), value))
+            .collect();
+        assert_eq!(averaged, vec![3.0, 4.5, 6.0, 9.0]);
+
+        let mut median = AnalogSignalFilter::new(AnalogFilter::Median { window: 3 });
+        median.update(at(0), 1.0);
+        median.update(at(1), 100.0);
+        assert_relative_eq!(median.update(at(2), 2.0), 2.0);
+
+        let mut low_pass = AnalogSignalFilter::new(AnalogFilter::LowPass {
+            time_constant: Duration::from_millis(100),
+        });
+        assert_relative_eq!(low_pass.update(at(0), 0.0), 0.0);
+        assert_relative_eq!(low_pass.update(at(100), 10.0), 5.0);
+        low_pass.reset();
+        assert_relative_eq!(low_pass.update(at(200), 10.0), 10.0);
+    }
+}
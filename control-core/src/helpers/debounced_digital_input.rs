@@ -0,0 +1,252 @@
+use std::time::{Duration, Instant};
+
+use ethercat_hal::io::digital_input::DigitalInput;
+
+/// Change of the debounced level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigitalInputEdge {
+    Rising,
+    Falling,
+}
+
+/// Debounce, edge, pulse and long-press logic of [`DebouncedDigitalInput`]
+///
+/// The first sample is taken as the initial level without an edge, so an input that is already
+/// high at startup doesn't count as a pulse.
+#[derive(Debug, Clone)]
+pub struct DigitalInputDebouncer {
+    /// How long the raw level has to be stable before it is accepted
+    debounce: Duration,
+    /// How long the input has to be high to count as a long press, `None` disables it
+    long_press: Option<Duration>,
+
+    value: Option<bool>,
+    /// Since when the raw level differs from the debounced level
+    changing_since: Option<Instant>,
+    /// Since when the raw level is high, only set while the debounced level is high
+    high_since: Option<Instant>,
+    edge: Option<DigitalInputEdge>,
+    long_press_active: bool,
+    long_press_started: bool,
+    pulse_count: u64,
+}
+
+impl DigitalInputDebouncer {
+    pub const fn new(debounce: Duration, long_press: Option<Duration>) -> Self {
+        Self {
+            debounce,
+            long_press,
+            value: None,
+            changing_since: None,
+            high_since: None,
+            edge: None,
+            long_press_active: false,
+            long_press_started: false,
+            pulse_count: 0,
+        }
+    }
+
+    /// Feed the raw level, call once per cycle
+    pub fn update(&mut self, now: Instant, raw: bool) -> Option<DigitalInputEdge> {
+        self.edge = None;
+        self.long_press_started = false;
+
+        match self.value {
+            None => {
+                self.value = Some(raw);
+                self.high_since = raw.then_some(now);
+            }
+            Some(value) if value == raw => self.changing_since = None,
+            Some(_) => {
+                let since = *self.changing_since.get_or_insert(now);
+                if now.duration_since(since) >= self.debounce {
+                    self.value = Some(raw);
+                    self.changing_since = None;
+                    if raw {
+                        self.edge = Some(DigitalInputEdge::Rising);
+                        self.high_since = Some(since);
+                        self.pulse_count += 1;
+                    } else {
+                        self.edge = Some(DigitalInputEdge::Falling);
+                        self.high_since = None;
+                        self.long_press_active = false;
+                    }
+                }
+            }
+        }
+
+        if let (Some(long_press), Some(high_since)) = (self.long_press, self.high_since) {
+            if !self.long_press_active && now.duration_since(high_since) >= long_press {
+                self.long_press_active = true;
+                self.long_press_started = true;
+            }
+        }
+
+        self.edge
+    }
+
+    /// Debounced level, `false` before the first update
+    pub fn value(&self) -> bool {
+        self.value.unwrap_or(false)
+    }
+
+    /// The last update had a rising edge
+    pub fn rising_edge(&self) -> bool {
+        self.edge == Some(DigitalInputEdge::Rising)
+    }
+
+    /// The last update had a falling edge
+    pub fn falling_edge(&self) -> bool {
+        self.edge == Some(DigitalInputEdge::Falling)
+    }
+
+    /// The input is held longer than the long-press time
+    pub const fn long_press_active(&self) -> bool {
+        self.long_press_active
+    }
+
+    /// The last update detected a long press, once per press
+    pub const fn long_press_started(&self) -> bool {
+        self.long_press_started
+    }
+
+    /// Number of rising edges since creation or the last reset
+    pub const fn pulse_count(&self) -> u64 {
+        self.pulse_count
+    }
+
+    pub const fn reset_pulse_count(&mut self) {
+        self.pulse_count = 0;
+    }
+}
+
+/// [`DigitalInput`] with debounce, edge events, pulse counting and long-press detection
+///
+/// [`DebouncedDigitalInput::update`] has to be called once per `act` cycle, the other methods
+/// report the state of the last update.
+#[derive(Debug)]
+pub struct DebouncedDigitalInput {
+    input: DigitalInput,
+    debouncer: DigitalInputDebouncer,
+}
+
+impl DebouncedDigitalInput {
+    pub const fn new(
+        input: DigitalInput,
+        debounce: Duration,
+        long_press: Option<Duration>,
+    ) -> Self {
+        Self {
+            input,
+            debouncer: DigitalInputDebouncer::new(debounce, long_press),
+        }
+    }
+
+    /// Read the input and update the debounced state, call once per `act` cycle
+    pub fn update(&mut self, now: Instant) -> Result<Option<DigitalInputEdge>, anyhow::Error> {
+        let raw = self.input.get_value()?;
+        Ok(self.debouncer.update(now, raw))
+    }
+
+    pub fn value(&self) -> bool {
+        self.debouncer.value()
+    }
+
+    pub fn rising_edge(&self) -> bool {
+        self.debouncer.rising_edge()
+    }
+
+    pub fn falling_edge(&self) -> bool {
+        self.debouncer.falling_edge()
+    }
+
+    pub const fn long_press_active(&self) -> bool {
+        self.debouncer.long_press_active()
+    }
+
+    pub const fn long_press_started(&self) -> bool {
+        self.debouncer.long_press_started()
+    }
+
+    pub const fn pulse_count(&self) -> u64 {
+        self.debouncer.pulse_count()
+    }
+
+    pub const fn reset_pulse_count(&mut self) {
+        self.debouncer.reset_pulse_count();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debounce_and_pulse_count() {
+        let mut debouncer = DigitalInputDebouncer::new(Duration::from_millis(10), None);
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        // initial high level is no edge
+        assert_eq!(debouncer.update(at(0), true), None);
+        assert!(debouncer.value());
+        assert_eq!(debouncer.pulse_count(), 0);
+
+        // short glitch is filtered
+        assert_eq!(debouncer.update(at(5), false), None);
+        assert_eq!(debouncer.update(at(8), true), None);
+        assert!(debouncer.value());
+
+        // stable low level is accepted after the debounce time
+        assert_eq!(debouncer.update(at(20), false), None);
+        assert_eq!(
+            debouncer.update(at(30), false),
+            Some(DigitalInputEdge::Falling)
+        );
+        assert!(debouncer.falling_edge());
+        assert_eq!(debouncer.update(at(31), false), None);
+        assert!(!debouncer.falling_edge());
+
+        // two pulses
+        for offset in [100, 200] {
+            debouncer.update(at(offset), true);
+            assert!(debouncer.update(at(offset + 10), true).is_some());
+            debouncer.update(at(offset + 50), false);
+            assert!(debouncer.update(at(offset + 60), false).is_some());
+        }
+        assert_eq!(debouncer.pulse_count(), 2);
+        debouncer.reset_pulse_count();
+        assert_eq!(debouncer.pulse_count(), 0);
+    }
+
+    #[test]
+    fn test_long_press() {
+        let mut debouncer = DigitalInputDebouncer::new(
+            Duration::from_millis(10),
+            Some(Duration::from_millis(1000)),
+        );
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        debouncer.update(at(0), false);
+        debouncer.update(at(100), true);
+        assert!(debouncer.update(at(110), true).is_some());
+
+        // measured from the start of the press, not from the debounced edge
+        debouncer.update(at(1050), true);
+        assert!(!debouncer.long_press_active());
+        debouncer.update(at(1100), true);
+        assert!(debouncer.long_press_active());
+        assert!(debouncer.long_press_started());
+
+        // reported once
+        debouncer.update(at(1200), true);
+        assert!(debouncer.long_press_active());
+        assert!(!debouncer.long_press_started());
+
+        // released
+        debouncer.update(at(1300), false);
+        debouncer.update(at(1310), false);
+        assert!(!debouncer.long_press_active());
+    }
+}
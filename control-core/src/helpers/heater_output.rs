@@ -0,0 +1,121 @@
+use std::time::{Duration, Instant};
+
+use ethercat_hal::io::{digital_output::DigitalOutput, pwm_output::PwmOutput};
+
+/// Time proportioning of a duty cycle onto an on/off output
+///
+/// Each period starts with the output on for `period * duty_cycle`, the duty cycle is only taken
+/// at the point in time the output is evaluated, so it can change within a period.
+#[derive(Debug, Clone)]
+pub struct SoftwarePwm {
+    period: Duration,
+    window_start: Instant,
+}
+
+impl SoftwarePwm {
+    pub fn new(period: Duration) -> Self {
+        Self {
+            period,
+            window_start: Instant::now(),
+        }
+    }
+
+    /// Whether the output is on at `now`
+    pub fn update(&mut self, now: Instant, duty_cycle: f64) -> bool {
+        let mut elapsed = now.duration_since(self.window_start);
+
+        // Restart window if needed
+        if elapsed >= self.period {
+            self.window_start = now;
+            elapsed = Duration::ZERO;
+        }
+
+        elapsed < self.period.mul_f64(duty_cycle.clamp(0.0, 1.0))
+    }
+
+    pub const fn period(&self) -> Duration {
+        self.period
+    }
+}
+
+/// Output of a heater driven with a duty cycle
+///
+/// Either a relay or SSR on a [`DigitalOutput`] switched by [`SoftwarePwm`] in the `act` loop, or
+/// a [`PwmOutput`] of a PWM terminal like the EL2502 generating the pulses itself.
+#[derive(Debug)]
+pub enum HeaterOutput {
+    SoftwarePwm {
+        output: DigitalOutput,
+        pwm: SoftwarePwm,
+    },
+    HardwarePwm(PwmOutput),
+}
+
+impl HeaterOutput {
+    pub fn software(output: DigitalOutput, period: Duration) -> Self {
+        Self::SoftwarePwm {
+            output,
+            pwm: SoftwarePwm::new(period),
+        }
+    }
+
+    pub fn hardware(output: PwmOutput, period: Duration) -> Self {
+        output.set_period(period);
+        output.set_duty_cycle(0.0);
+        Self::HardwarePwm(output)
+    }
+
+    /// Set the duty cycle from 0 to 1, call once per `act` cycle
+    ///
+    /// Returns whether the heater is on, for hardware PWM whether the duty cycle is above zero.
+    pub fn set_duty_cycle(&mut self, now: Instant, duty_cycle: f64) -> bool {
+        match self {
+            Self::SoftwarePwm { output, pwm } => {
+                let on = pwm.update(now, duty_cycle);
+                output.set(on);
+                on
+            }
+            Self::HardwarePwm(output) => {
+                output.set_duty_cycle(duty_cycle);
+                duty_cycle > 0.0
+            }
+        }
+    }
+
+    /// Heater fully on
+    pub fn on(&mut self) {
+        match self {
+            Self::SoftwarePwm { output, .. } => output.set(true),
+            Self::HardwarePwm(output) => output.set_duty_cycle(1.0),
+        }
+    }
+
+    pub fn off(&mut self) {
+        match self {
+            Self::SoftwarePwm { output, .. } => output.set(false),
+            Self::HardwarePwm(output) => output.set_duty_cycle(0.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_software_pwm() {
+        let mut pwm = SoftwarePwm::new(Duration::from_millis(100));
+        let start = pwm.window_start;
+        let at = |ms| start + Duration::from_millis(ms);
+
+        assert!(pwm.update(at(0), 0.3));
+        assert!(pwm.update(at(29), 0.3));
+        assert!(!pwm.update(at(30), 0.3));
+        assert!(!pwm.update(at(99), 0.3));
+
+        // next window starts on
+        assert!(pwm.update(at(100), 0.3));
+        assert!(!pwm.update(at(110), 0.0));
+        assert!(pwm.update(at(199), 1.0));
+    }
+}
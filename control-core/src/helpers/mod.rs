@@ -1,6 +1,10 @@
+pub mod analog_signal;
 pub mod compare_lists;
+pub mod debounced_digital_input;
 pub mod hasher_serializer;
 pub mod hashing;
+pub mod heater_output;
 pub mod interpolation;
 pub mod moving_time_window;
 pub mod retry;
+pub mod stall_detector;
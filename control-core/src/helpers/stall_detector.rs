@@ -0,0 +1,178 @@
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// Why a stepper was detected as stalled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum StallReason {
+    /// The load angle stayed above the limit while moving
+    LoadAngle,
+    /// The measured position fell behind the commanded position
+    FollowingError,
+}
+
+/// Detects a stalled stepper from its load angle and its following error
+///
+/// The commanded speed is integrated to an expected position and compared against the measured
+/// position. Both conditions have to last for `debounce` before a stall is reported. Once a stall
+/// is detected it is latched until [`StallDetector::reset`].
+#[derive(Debug, Clone)]
+pub struct StallDetector {
+    /// Raw load angle above which the motor counts as overloaded, `None` disables the check
+    load_angle_limit: Option<u16>,
+    /// Maximum difference between expected and measured position in steps, `None` disables the check
+    following_error_limit: Option<f64>,
+    /// How long a condition has to last before it counts as a stall
+    debounce: Duration,
+
+    expected_position: Option<f64>,
+    last_update: Option<Instant>,
+    condition_since: Option<Instant>,
+    stalled: Option<StallReason>,
+}
+
+impl StallDetector {
+    pub const fn new(
+        load_angle_limit: Option<u16>,
+        following_error_limit: Option<f64>,
+        debounce: Duration,
+    ) -> Self {
+        Self {
+            load_angle_limit,
+            following_error_limit,
+            debounce,
+            expected_position: None,
+            last_update: None,
+            condition_since: None,
+            stalled: None,
+        }
+    }
+
+    /// The latched stall, if any
+    pub const fn stalled(&self) -> Option<StallReason> {
+        self.stalled
+    }
+
+    /// Clear a latched stall and start tracking the position again
+    pub const fn reset(&mut self) {
+        self.expected_position = None;
+        self.last_update = None;
+        self.condition_since = None;
+        self.stalled = None;
+    }
+
+    /// Update with the commanded speed and the measured position, both in steps
+    ///
+    /// `load_angle` is `None` if the terminal doesn't provide it.
+    pub fn update(
+        &mut self,
+        now: Instant,
+        steps_per_second: f64,
+        position: f64,
+        load_angle: Option<u16>,
+    ) -> Option<StallReason> {
+        if self.stalled.is_some() {
+            return self.stalled;
+        }
+
+        let dt = self.last_update.map_or(0.0, |last_update| {
+            now.duration_since(last_update).as_secs_f64()
+        });
+        self.last_update = Some(now);
+
+        // integrate the commanded position, follow the measured position while standing still
+        let expected_position = match self.expected_position {
+            Some(expected_position) if steps_per_second != 0.0 => {
+                steps_per_second.mul_add(dt, expected_position)
+            }
+            _ => position,
+        };
+        self.expected_position = Some(expected_position);
+
+        let following_error = self
+            .following_error_limit
+            .is_some_and(|limit| (position - expected_position).abs() > limit);
+        let overloaded = steps_per_second != 0.0
+            && matches!(
+                (self.load_angle_limit, load_angle),
+                (Some(limit), Some(load_angle)) if load_angle > limit
+            );
+
+        let condition = if following_error {
+            Some(StallReason::FollowingError)
+        } else if overloaded {
+            Some(StallReason::LoadAngle)
+        } else {
+            None
+        };
+
+        match condition {
+            Some(reason) => {
+                let since = *self.condition_since.get_or_insert(now);
+                if now.duration_since(since) >= self.debounce {
+                    self.stalled = Some(reason);
+                }
+            }
+            None => self.condition_since = None,
+        }
+
+        self.stalled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_angle_is_debounced() {
+        let mut detector = StallDetector::new(Some(500), None, Duration::from_millis(100));
+        let start = Instant::now();
+
+        // short peaks are ignored
+        assert_eq!(detector.update(start, 100.0, 0.0, Some(800)), None);
+        let t = start + Duration::from_millis(50);
+        assert_eq!(detector.update(t, 100.0, 5.0, Some(200)), None);
+
+        // standing still under load is fine
+        let t = start + Duration::from_millis(300);
+        assert_eq!(detector.update(t, 0.0, 5.0, Some(800)), None);
+
+        let t = start + Duration::from_millis(400);
+        assert_eq!(detector.update(t, 100.0, 5.0, Some(800)), None);
+        let t = start + Duration::from_millis(500);
+        assert_eq!(
+            detector.update(t, 100.0, 15.0, Some(800)),
+            Some(StallReason::LoadAngle)
+        );
+
+        // latched until reset
+        let t = start + Duration::from_millis(600);
+        assert_eq!(
+            detector.update(t, 0.0, 15.0, Some(0)),
+            Some(StallReason::LoadAngle)
+        );
+        detector.reset();
+        assert_eq!(detector.update(t, 0.0, 15.0, Some(0)), None);
+    }
+
+    #[test]
+    fn test_following_error() {
+        let mut detector = StallDetector::new(None, Some(50.0), Duration::ZERO);
+        let start = Instant::now();
+
+        // following the commanded speed of 100 steps/s
+        for i in 0..10 {
+            let t = start + Duration::from_millis(100 * i);
+            let position = 10.0 * i as f64;
+            assert_eq!(detector.update(t, 100.0, position, None), None);
+        }
+
+        // the motor stops turning
+        let mut stalled = None;
+        for i in 10..20 {
+            let t = start + Duration::from_millis(100 * i);
+            stalled = detector.update(t, 100.0, 90.0, None);
+        }
+        assert_eq!(stalled, Some(StallReason::FollowingError));
+    }
+}
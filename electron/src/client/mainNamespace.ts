@@ -31,6 +31,27 @@ export const ethercatDevicesEventDataSchema = rustEnumSchema({
         device_identification: deviceIdentification,
       }),
     ),
+    pdo_length_mismatches: z
+      .array(
+        z.object({
+          subdevice_index: z.number().int(),
+          configured_address: z.number().int(),
+          name: z.string(),
+          expected_input_bits: z.number().int(),
+          actual_input_bits: z.number().int(),
+          expected_output_bits: z.number().int(),
+          actual_output_bits: z.number().int(),
+        }),
+      )
+      .default([]),
+    identification_conflicts: z
+      .array(
+        z.object({
+          subdevice_index: z.number().int().nullable(),
+          message: z.string(),
+        }),
+      )
+      .default([]),
   }),
   Error: z.string(),
 });
@@ -46,11 +67,35 @@ export const ethercatDevicesEventSchema = eventSchema(
 export type EthercatDevicesEvent = z.infer<typeof ethercatDevicesEventSchema>;
 
 // Create a new schema for MachinesEvent
+export const hardwareCheckStatusSchema = z.discriminatedUnion("type", [
+  z.object({ type: z.literal("Ok") }),
+  z.object({ type: z.literal("Missing") }),
+  z.object({
+    type: z.literal("WrongIdentity"),
+    identity: z.tuple([z.number().int(), z.number().int(), z.number().int()]),
+  }),
+  z.object({ type: z.literal("Duplicate") }),
+  z.object({ type: z.literal("Unexpected") }),
+]);
+
+export type HardwareCheckStatus = z.infer<typeof hardwareCheckStatusSchema>;
+
+export const hardwareCheckItemSchema = z.object({
+  role: z.number().int(),
+  name: z.string(),
+  optional: z.boolean(),
+  subdevice_index: z.number().int().nullable(),
+  status: hardwareCheckStatusSchema,
+});
+
+export type HardwareCheckItem = z.infer<typeof hardwareCheckItemSchema>;
+
 export const machinesEventDataSchema = z.object({
   machines: z.array(
     z.object({
       machine_identification_unique: machineIdentificationUnique,
       error: z.string().nullable(),
+      hardware_checklist: z.array(hardwareCheckItemSchema).default([]),
     }),
   ),
 });
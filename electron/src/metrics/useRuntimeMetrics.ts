@@ -17,6 +17,14 @@ export type RuntimeMetricsSample = {
   jitter_max_ns: number;
   rx_rate_bytes_per_sec: number;
   tx_rate_bytes_per_sec: number;
+  nic_rx_errors: number | null;
+  nic_tx_errors: number | null;
+  nic_rx_dropped: number | null;
+  nic_tx_dropped: number | null;
+  nic_carrier_changes: number | null;
+  nic_link_up: boolean | null;
+  nic_speed_mbps: number | null;
+  nic_duplex: "full" | "half" | null;
   rt_loop_cpu_time_seconds?: number | null;
   rt_nr_switches: number | null;
   rt_nr_voluntary_switches: number | null;
@@ -14,7 +14,55 @@ import {
   getMachineProperties,
 } from "@/machines/properties";
 import { IconText } from "@/components/IconText";
-import { MachinesEventData, useMainNamespace } from "@/client/mainNamespace";
+import {
+  HardwareCheckItem,
+  MachinesEventData,
+  useMainNamespace,
+} from "@/client/mainNamespace";
+
+function hardwareCheckLabel(item: HardwareCheckItem): string {
+  const subdevice =
+    item.subdevice_index !== null ? ` (subdevice ${item.subdevice_index})` : "";
+  const prefix = `${item.role}: ${item.name}${subdevice}`;
+  switch (item.status.type) {
+    case "Ok":
+      return prefix;
+    case "Missing":
+      return item.optional
+        ? `${prefix} not present (optional)`
+        : `${prefix} missing`;
+    case "WrongIdentity":
+      return `${prefix} wrong terminal 0x${item.status.identity[1].toString(16)}`;
+    case "Duplicate":
+      return `${prefix} assigned more than once`;
+    case "Unexpected":
+      return `${prefix} unknown role`;
+  }
+}
+
+function HardwareChecklist({ items }: { items: HardwareCheckItem[] }) {
+  if (items.length === 0) {
+    return "—";
+  }
+  return (
+    <div className="flex flex-col gap-1">
+      {items.map((item, index) => {
+        const ok =
+          item.status.type === "Ok" ||
+          (item.status.type === "Missing" && item.optional);
+        return (
+          <IconText
+            key={index}
+            icon={ok ? "lu:Check" : "lu:TriangleAlert"}
+            variant={ok ? "success" : "error"}
+          >
+            {hardwareCheckLabel(item)}
+          </IconText>
+        );
+      })}
+    </div>
+  );
+}
 
 export const columns: ColumnDef<
   NonNullable<MachinesEventData>["machines"][number]
@@ -57,6 +105,13 @@ export const columns: ColumnDef<
       return <Value value={serial} />;
     },
   },
+  {
+    accessorKey: "hardware_checklist",
+    header: "Hardware",
+    cell: (row) => (
+      <HardwareChecklist items={row.row.original.hardware_checklist} />
+    ),
+  },
   {
     accessorKey: "error",
     header: "Error",
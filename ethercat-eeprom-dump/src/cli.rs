@@ -1,24 +1,26 @@
 use clap::{Command, arg};
 
+fn subdevice_arg(help: &'static str) -> clap::Arg {
+    arg!(<SUBDEVICE> "Subdevice index")
+        .value_parser(clap::value_parser!(usize))
+        .help(help)
+}
+
 pub fn cli() -> Command {
     Command::new("ethercat-eeprom-dump")
         .subcommand_required(true)
         // add arg for interface
         .arg(
             arg!(--interface <INTERFACE> "EtherCAT interface to use")
-                .required(true)
+                .required(false)
                 .short('i')
-                .help("EtherCAT interface to use"),
+                .help("EtherCAT interface to use, required for commands that access the bus"),
         )
         .subcommand(Command::new("ls").about("List all EtherCAT devices"))
         .subcommand(
             Command::new("dump")
                 .about("Read the EEPROM of a device")
-                .arg(
-                    arg!(<SUBDEVICE> "Subdevice index to read from")
-                        .value_parser(clap::value_parser!(usize))
-                        .help("Subdevice index to read from"),
-                )
+                .arg(subdevice_arg("Subdevice index to read from"))
                 .arg(
                     arg!(--file <FILE> "File to save the EEPROM to")
                         .required(false)
@@ -29,11 +31,7 @@ pub fn cli() -> Command {
         .subcommand(
             Command::new("restore")
                 .about("Write the EEPROM of a device")
-                .arg(
-                    arg!(<SUBDEVICE> "Subdevice index to write to")
-                        .value_parser(clap::value_parser!(usize))
-                        .help("Subdevice index to write to"),
-                )
+                .arg(subdevice_arg("Subdevice index to write to"))
                 .arg(
                     arg!(--file <FILE> "File to read the EEPROM from")
                         .required(true)
@@ -50,4 +48,102 @@ pub fn cli() -> Command {
                     .help("File to parse"),
             ),
         )
+        .subcommand(
+            Command::new("diff")
+                .about("Compare a dumped EEPROM file with another file or a live device")
+                .arg(
+                    arg!(<FILE> "Dumped EEPROM file")
+                        .value_parser(clap::value_parser!(String))
+                        .help("Dumped EEPROM file"),
+                )
+                .arg(
+                    arg!([OTHER] "Second dumped EEPROM file")
+                        .value_parser(clap::value_parser!(String))
+                        .help("Second dumped EEPROM file"),
+                )
+                .arg(
+                    arg!(--subdevice <SUBDEVICE> "Subdevice index to compare with")
+                        .required(false)
+                        .short('s')
+                        .value_parser(clap::value_parser!(usize))
+                        .conflicts_with("OTHER")
+                        .help("Subdevice index to compare with instead of a second file"),
+                ),
+        )
+        .subcommand(
+            Command::new("verify")
+                .about("Check the SII checksum of a dumped EEPROM file or a live device")
+                .arg(
+                    arg!(--file <FILE> "File to check")
+                        .required(false)
+                        .short('f')
+                        .value_parser(clap::value_parser!(String))
+                        .help("File to check"),
+                )
+                .arg(
+                    arg!(--subdevice <SUBDEVICE> "Subdevice index to check")
+                        .required(false)
+                        .short('s')
+                        .value_parser(clap::value_parser!(usize))
+                        .help("Subdevice index to check"),
+                )
+                .group(
+                    clap::ArgGroup::new("source")
+                        .args(["file", "subdevice"])
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            Command::new("esi-codegen")
+                .about("Generate identity constants and PDO structs for a device from an ESI file")
+                .arg(
+                    arg!(<FILE> "ESI XML file")
+                        .value_parser(clap::value_parser!(String))
+                        .help("ESI XML file"),
+                )
+                .arg(
+                    arg!([TYPE] "Device type like EL3001")
+                        .value_parser(clap::value_parser!(String))
+                        .help("Device type like EL3001, lists the devices in the file if omitted"),
+                )
+                .arg(
+                    arg!(--revision <REVISION> "Revision to take the PDOs from")
+                        .required(false)
+                        .short('r')
+                        .value_parser(clap::value_parser!(String))
+                        .help("Revision to take the PDOs from like #x00150000, defaults to the newest"),
+                )
+                .arg(
+                    arg!(--out <OUT> "File to write the module to")
+                        .required(false)
+                        .short('o')
+                        .value_parser(clap::value_parser!(String))
+                        .help("File to write the module to, prints it if omitted"),
+                ),
+        )
+        .subcommand(
+            Command::new("identify")
+                .about("Read or write the machine device identification of a device")
+                .arg(subdevice_arg("Subdevice index to identify"))
+                .arg(
+                    arg!(--vendor <VENDOR> "Vendor to write")
+                        .required(false)
+                        .value_parser(clap::value_parser!(u16)),
+                )
+                .arg(
+                    arg!(--machine <MACHINE> "Machine to write")
+                        .required(false)
+                        .value_parser(clap::value_parser!(u16)),
+                )
+                .arg(
+                    arg!(--serial <SERIAL> "Serial to write")
+                        .required(false)
+                        .value_parser(clap::value_parser!(u16)),
+                )
+                .arg(
+                    arg!(--role <ROLE> "Role to write")
+                        .required(false)
+                        .value_parser(clap::value_parser!(u16)),
+                ),
+        )
 }
@@ -0,0 +1,31 @@
+use crate::print::print_markdown_table;
+use crate::sii::diff_words;
+
+/// Prints all words that differ between two EEPROM images
+pub fn print_diff(left_name: &str, left: &[u8], right_name: &str, right: &[u8]) {
+    let diffs = diff_words(left, right);
+    if diffs.is_empty() {
+        println!("{} and {} are identical", left_name, right_name);
+        return;
+    }
+
+    let format_word = |word: Option<u16>| match word {
+        Some(word) => format!("0x{:04x}", word),
+        None => "-".to_string(),
+    };
+
+    let mut table = vec![vec![
+        "Word".to_string(),
+        left_name.to_string(),
+        right_name.to_string(),
+    ]];
+    for diff in diffs.iter() {
+        table.push(vec![
+            format!("0x{:04x}", diff.word),
+            format_word(diff.left),
+            format_word(diff.right),
+        ]);
+    }
+    print_markdown_table(&table, true);
+    println!("{} words differ", diffs.len());
+}
@@ -3,7 +3,21 @@ use std::{fs::File, io::Write};
 use ethercat_hal::helpers::ethercrab_types::EthercrabSubDeviceGroupPreoperational;
 use ethercrab::MainDevice;
 
-use crate::{MAX_SUBDEVICES, PDI_LEN, print::print_buffer};
+use crate::{MAX_SUBDEVICES, PDI_LEN, print::print_buffer, sii::EEPROM_IMAGE_SIZE};
+
+/// Reads the EEPROM image of a subdevice
+pub async fn read_live_image(
+    group: &EthercrabSubDeviceGroupPreoperational<MAX_SUBDEVICES, PDI_LEN>,
+    maindevice: &MainDevice<'_>,
+    subdevice_index: usize,
+) -> Result<Vec<u8>, anyhow::Error> {
+    let subdevice = group.subdevice(maindevice, subdevice_index)?;
+    let mut buffer = vec![0u8; EEPROM_IMAGE_SIZE];
+    subdevice
+        .eeprom_read_raw(maindevice, 0, &mut buffer)
+        .await?;
+    Ok(buffer)
+}
 
 pub async fn dump_eeprom(
     group: &EthercrabSubDeviceGroupPreoperational<MAX_SUBDEVICES, PDI_LEN>,
@@ -17,10 +31,7 @@ pub async fn dump_eeprom(
 
     println!("Reading {} bytes from EEPROM", size);
 
-    let mut buffer: [u8; 2048] = [0; 2048];
-    subdevice
-        .eeprom_read_raw(maindevice, 0, &mut buffer)
-        .await?;
+    let buffer = read_live_image(group, maindevice, subdevice_index).await?;
 
     // if file is Some write to file
     if let Some(file) = file {
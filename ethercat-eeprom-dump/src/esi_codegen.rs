@@ -0,0 +1,47 @@
+use ethercat_hal::esi::{
+    codegen::{generate_device_module, list_devices},
+    parse_esi_number, read_esi,
+};
+
+/// Generates a device module from an ESI file, or lists the devices in it if no type is given
+pub fn esi_codegen(
+    file: &str,
+    type_name: Option<&str>,
+    revision: Option<&str>,
+    out: Option<&str>,
+) -> Result<(), anyhow::Error> {
+    let esi = read_esi(file)?;
+
+    let Some(type_name) = type_name else {
+        for line in list_devices(&esi) {
+            println!("{}", line);
+        }
+        return Ok(());
+    };
+
+    let revision = revision
+        .map(|revision| {
+            parse_esi_number(revision).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "[{}::esi_codegen] Invalid revision {}",
+                    module_path!(),
+                    revision
+                )
+            })
+        })
+        .transpose()?;
+    let source = std::path::Path::new(file)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| file.to_string());
+    let module = generate_device_module(&esi, type_name, revision, &source)?;
+
+    match out {
+        Some(out) => {
+            std::fs::write(out, module)?;
+            println!("Wrote {} to {}", type_name, out);
+        }
+        None => print!("{}", module),
+    }
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use ethercat_hal::helpers::ethercrab_types::EthercrabSubDeviceGroupPreoperational;
+use ethercrab::MainDevice;
+use machines::machine_identification::{
+    DeviceMachineIdentification, get_identification_addresses, machine_device_identification,
+};
+
+use crate::{MAX_SUBDEVICES, PDI_LEN};
+
+/// Fields to change, `None` keeps the value currently stored in the EEPROM
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IdentificationUpdate {
+    pub vendor: Option<u16>,
+    pub machine: Option<u16>,
+    pub serial: Option<u16>,
+    pub role: Option<u16>,
+}
+
+impl IdentificationUpdate {
+    pub const fn is_empty(&self) -> bool {
+        self.vendor.is_none()
+            && self.machine.is_none()
+            && self.serial.is_none()
+            && self.role.is_none()
+    }
+
+    pub fn apply(&self, identification: &mut DeviceMachineIdentification) {
+        let unique = &mut identification.machine_identification_unique;
+        if let Some(vendor) = self.vendor {
+            unique.machine_identification.vendor = vendor;
+        }
+        if let Some(machine) = self.machine {
+            unique.machine_identification.machine = machine;
+        }
+        if let Some(serial) = self.serial {
+            unique.serial = serial;
+        }
+        if let Some(role) = self.role {
+            identification.role = role;
+        }
+    }
+}
+
+pub fn format_identification(identification: &DeviceMachineIdentification) -> String {
+    format!(
+        "vendor 0x{:04x} machine 0x{:04x} serial {} role {}",
+        identification
+            .machine_identification_unique
+            .machine_identification
+            .vendor,
+        identification
+            .machine_identification_unique
+            .machine_identification
+            .machine,
+        identification.machine_identification_unique.serial,
+        identification.role
+    )
+}
+
+/// Prints the identification of a subdevice and optionally changes it
+///
+/// Writes use the same addresses and encoding as the server, the result is verified by reading it back.
+pub async fn identify(
+    group: &EthercrabSubDeviceGroupPreoperational<MAX_SUBDEVICES, PDI_LEN>,
+    maindevice: &MainDevice<'_>,
+    subdevice_index: usize,
+    update: IdentificationUpdate,
+) -> Result<(), anyhow::Error> {
+    let subdevice = group.subdevice(maindevice, subdevice_index)?;
+    let addresses = get_identification_addresses(&subdevice.identity(), subdevice.name())?;
+
+    let mut identification = machine_device_identification(&subdevice, maindevice).await?;
+    println!(
+        "{} (subdevice {}): {}",
+        subdevice.name(),
+        subdevice_index,
+        format_identification(&identification)
+    );
+
+    if update.is_empty() {
+        return Ok(());
+    }
+
+    update.apply(&mut identification);
+    for (word, value) in addresses.encode(&identification) {
+        subdevice
+            .eeprom_write_dangerously(maindevice, word, value)
+            .await?;
+    }
+
+    let written = machine_device_identification(&subdevice, maindevice).await?;
+    if written != identification {
+        Err(anyhow::anyhow!(
+            "[{}::identify] Read back {} after writing {}",
+            module_path!(),
+            format_identification(&written),
+            format_identification(&identification)
+        ))?;
+    }
+
+    println!("Wrote {}", format_identification(&written));
+    Ok(())
+}
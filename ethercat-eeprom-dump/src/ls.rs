@@ -1,12 +1,12 @@
 use anyhow::anyhow;
-use control_core::ethercat::eeprom_identification::{
-    get_identification_addresses, machine_device_identification,
-};
 use ethercat_hal::{
     devices::device_from_subdevice_identity,
     helpers::ethercrab_types::EthercrabSubDeviceGroupPreoperational,
 };
 use ethercrab::MainDevice;
+use machines::machine_identification::{
+    get_identification_addresses, machine_device_identification,
+};
 use smol;
 
 use crate::{MAX_SUBDEVICES, PDI_LEN, print::print_markdown_table};
@@ -1,10 +1,15 @@
 use std::sync::Arc;
 
-use dump::dump_eeprom;
+use clap::ArgMatches;
+use diff::print_diff;
+use dump::{dump_eeprom, read_live_image};
+use esi_codegen::esi_codegen;
 use ethercrab::{MainDevice, MainDeviceConfig, PduStorage, std::ethercat_now};
-use read::read_eeprom;
-use restore::restore_eeoprom;
+use identify::{IdentificationUpdate, identify};
+use read::{read_eeprom, read_image};
+use restore::restore_eeprom;
 use smol;
+use verify::verify_image;
 
 /// Maximum number of SubDevices that can be stored. This must be a power of 2 greater than 1.
 const MAX_SUBDEVICES: usize = 16;
@@ -18,17 +23,76 @@ const PDI_LEN: usize = 1024;
 static PDU_STORAGE: PduStorage<MAX_FRAMES, MAX_PDU_DATA> = PduStorage::new();
 
 pub mod cli;
+pub mod diff;
 pub mod dump;
+pub mod esi_codegen;
+pub mod identify;
 pub mod ls;
 pub mod print;
 pub mod read;
 pub mod restore;
+pub mod sii;
+pub mod verify;
+
+/// Commands that only work on files and don't need the EtherCAT bus
+fn run_offline(subcommand: (&str, &ArgMatches)) -> Option<Result<(), anyhow::Error>> {
+    match subcommand {
+        ("read", sub_matches) => {
+            let file = sub_matches
+                .get_one::<String>("file")
+                .expect("file is required");
+            Some(smol::block_on(read_eeprom(file)))
+        }
+        ("verify", sub_matches) => {
+            let file = sub_matches.get_one::<String>("file")?;
+            Some(read_image(file).and_then(|image| verify_image(file, &image)))
+        }
+        ("diff", sub_matches) => {
+            let file = sub_matches
+                .get_one::<String>("FILE")
+                .expect("file is required");
+            let other = sub_matches.get_one::<String>("OTHER")?;
+            Some(read_image(file).and_then(|left| {
+                let right = read_image(other)?;
+                print_diff(file, &left, other, &right);
+                Ok(())
+            }))
+        }
+        ("esi-codegen", sub_matches) => {
+            let file = sub_matches
+                .get_one::<String>("FILE")
+                .expect("file is required");
+            Some(esi_codegen(
+                file,
+                sub_matches.get_one::<String>("TYPE").map(String::as_str),
+                sub_matches
+                    .get_one::<String>("revision")
+                    .map(String::as_str),
+                sub_matches.get_one::<String>("out").map(String::as_str),
+            ))
+        }
+        _ => None,
+    }
+}
 
 #[tokio::main]
 async fn main() {
     // cli parsing
     let matches = cli::cli().get_matches();
-    let interface = matches.get_one::<String>("interface").unwrap();
+    let subcommand = matches.subcommand().expect("subcommand is required");
+
+    if let Some(result) = run_offline(subcommand) {
+        if let Err(e) = result {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let Some(interface) = matches.get_one::<String>("interface") else {
+        eprintln!("--interface is required for {}", subcommand.0);
+        std::process::exit(1);
+    };
 
     // Setup PDU
     let (tx, rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
@@ -50,40 +114,70 @@ async fn main() {
         .await
         .unwrap_or_else(|_| panic!("{}", "Failed to initalize group".to_string()));
 
-    match matches.subcommand() {
-        Some(("ls", _)) => ls::ls(group, &maindevice),
-        Some(("dump", sub_matches)) => {
+    let result = match subcommand {
+        ("ls", _) => {
+            ls::ls(group, &maindevice);
+            Ok(())
+        }
+        ("dump", sub_matches) => {
             let subdevice_index = sub_matches
                 .get_one::<usize>("SUBDEVICE")
                 .expect("subdevice index is required");
             let file = sub_matches.get_one::<String>("file");
-            let result = smol::block_on(dump_eeprom(&group, &maindevice, *subdevice_index, file));
-            if let Err(e) = result {
-                eprintln!("Error reading EEPROM: {}", e);
-            }
+            smol::block_on(dump_eeprom(&group, &maindevice, *subdevice_index, file))
         }
-        Some(("restore", sub_matches)) => {
+        ("restore", sub_matches) => {
             let subdevice_index = sub_matches
                 .get_one::<usize>("SUBDEVICE")
                 .expect("subdevice index is required");
             let file = sub_matches
                 .get_one::<String>("file")
                 .expect("file is required");
-            let result =
-                smol::block_on(restore_eeoprom(&group, &maindevice, *subdevice_index, file));
-            if let Err(e) = result {
-                eprintln!("Error writing EEPROM: {}", e);
-            }
+            smol::block_on(restore_eeprom(&group, &maindevice, *subdevice_index, file))
+        }
+        ("verify", sub_matches) => {
+            let subdevice_index = sub_matches
+                .get_one::<usize>("subdevice")
+                .expect("file or subdevice is required");
+            smol::block_on(read_live_image(&group, &maindevice, *subdevice_index))
+                .and_then(|image| verify_image(&format!("subdevice {}", subdevice_index), &image))
         }
-        Some(("read", sub_matches)) => {
+        ("diff", sub_matches) => {
             let file = sub_matches
-                .get_one::<String>("file")
+                .get_one::<String>("FILE")
                 .expect("file is required");
-            let result = smol::block_on(read_eeprom(file));
-            if let Err(e) = result {
-                eprintln!("Error parsing EEPROM: {}", e);
+            match sub_matches.get_one::<usize>("subdevice") {
+                Some(subdevice_index) => read_image(file).and_then(|left| {
+                    let right =
+                        smol::block_on(read_live_image(&group, &maindevice, *subdevice_index))?;
+                    print_diff(
+                        file,
+                        &left,
+                        &format!("subdevice {}", subdevice_index),
+                        &right,
+                    );
+                    Ok(())
+                }),
+                None => Err(anyhow::anyhow!("diff needs a second file or --subdevice")),
             }
         }
-        _ => {}
+        ("identify", sub_matches) => {
+            let subdevice_index = sub_matches
+                .get_one::<usize>("SUBDEVICE")
+                .expect("subdevice index is required");
+            let update = IdentificationUpdate {
+                vendor: sub_matches.get_one::<u16>("vendor").copied(),
+                machine: sub_matches.get_one::<u16>("machine").copied(),
+                serial: sub_matches.get_one::<u16>("serial").copied(),
+                role: sub_matches.get_one::<u16>("role").copied(),
+            };
+            smol::block_on(identify(&group, &maindevice, *subdevice_index, update))
+        }
+        _ => Ok(()),
     };
+
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
 }
@@ -212,15 +212,17 @@ fn extract_string_sections(bytes: &[u8]) -> Vec<String> {
 impl std::fmt::Display for EepromData {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         // Extract device name and info from strings if available
-        let device_name = if let Some(repo) = &self.extended_information.string_repository {
-            repo.strings
-                .iter()
-                .find(|s| s.contains("EL") && s.chars().any(|c| c.is_ascii_digit()))
-                .cloned()
-                .unwrap_or_else(|| "Unknown Device".to_string())
-        } else {
-            "Unknown Device".to_string()
-        };
+        let device_name = self
+            .extended_information
+            .string_repository
+            .as_ref()
+            .and_then(|repo| {
+                repo.strings
+                    .iter()
+                    .find(|s| s.contains("EL") && s.chars().any(|c| c.is_ascii_digit()))
+                    .cloned()
+            })
+            .unwrap_or_else(|| "Unknown Device".to_string());
 
         // Format the header with device name
         writeln!(
@@ -771,12 +773,26 @@ impl From<&[u8]> for EepromData {
     }
 }
 
+/// Reads an EEPROM image from disk
+pub fn read_image(file: &str) -> Result<Vec<u8>, anyhow::Error> {
+    let mut byte_buffer = Vec::new();
+    File::open(file)
+        .and_then(|mut file| file.read_to_end(&mut byte_buffer))
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "[{}::read_image] Failed to read {}: {}",
+                module_path!(),
+                file,
+                e
+            )
+        })?;
+    Ok(byte_buffer)
+}
+
 /// Helper function for parsing EEPROM data from a file
-pub async fn read_eeprom(file: &str) -> Result<(), Box<dyn std::error::Error>> {
+pub async fn read_eeprom(file: &str) -> Result<(), anyhow::Error> {
     // Read the file
-    let mut file = File::open(file)?;
-    let mut byte_buffer = Vec::new();
-    file.read_to_end(&mut byte_buffer)?;
+    let byte_buffer = read_image(file)?;
 
     // Parse the EEPROM data directly from bytes
     let eeprom_data = EepromData::from(byte_buffer.as_slice());
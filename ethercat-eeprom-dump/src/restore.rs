@@ -1,34 +1,63 @@
-use std::{fs::File, io::Read};
-
 use ethercat_hal::helpers::ethercrab_types::EthercrabSubDeviceGroupPreoperational;
 use ethercrab::MainDevice;
 
-use crate::{MAX_SUBDEVICES, PDI_LEN};
+use crate::{
+    MAX_SUBDEVICES, PDI_LEN,
+    dump::read_live_image,
+    read::read_image,
+    sii::{SiiChecksum, diff_words},
+};
 
-pub async fn restore_eeoprom(
+/// Writes a dumped EEPROM image back to a subdevice
+///
+/// Only words that differ are written, the result is verified by reading the EEPROM again.
+pub async fn restore_eeprom(
     group: &EthercrabSubDeviceGroupPreoperational<MAX_SUBDEVICES, PDI_LEN>,
     maindevice: &MainDevice<'_>,
     subdevice_index: usize,
-    file: &String,
+    file: &str,
 ) -> Result<(), anyhow::Error> {
-    println!("Uploading EEPROM to subdevice {}...", subdevice_index);
-    let subdevice = group.subdevice(maindevice, subdevice_index)?;
-    let size = subdevice.eeprom_size(maindevice).await?;
+    let image = read_image(file)?;
+
+    match SiiChecksum::from_image(&image) {
+        Some(checksum) if checksum.is_valid() => (),
+        _ => Err(anyhow::anyhow!(
+            "[{}::restore_eeprom] {} has an invalid SII checksum, refusing to write it",
+            module_path!(),
+            file
+        ))?,
+    }
 
-    println!("Uploading {} bytes to EEPROM", size);
+    let subdevice = group.subdevice(maindevice, subdevice_index)?;
+    let current = read_live_image(group, maindevice, subdevice_index).await?;
+    let diffs = diff_words(&current, &image);
+    println!(
+        "Writing {} changed words to subdevice {}...",
+        diffs.len(),
+        subdevice_index
+    );
 
-    let mut buffer: [u8; 2048] = [0; 2048];
-    let mut file = File::open(file)?;
-    Read::read_exact(&mut file, &mut buffer)?;
+    for diff in diffs.iter() {
+        if let Some(value) = diff.right {
+            subdevice
+                .eeprom_write_dangerously(maindevice, diff.word, value)
+                .await?;
+        }
+    }
 
-    // write the buffer
-    for word in 0..(size / 2) {
-        let byte = word * 2;
-        let data = u16::from_le_bytes([buffer[byte], buffer[byte + 1]]);
-        subdevice
-            .eeprom_write_dangerously(maindevice, word as u16, data)
-            .await?;
+    let written = read_live_image(group, maindevice, subdevice_index).await?;
+    let remaining = diff_words(&written, &image)
+        .into_iter()
+        .filter(|diff| diff.left.is_some() && diff.right.is_some())
+        .count();
+    if remaining > 0 {
+        Err(anyhow::anyhow!(
+            "[{}::restore_eeprom] {} words differ after writing",
+            module_path!(),
+            remaining
+        ))?;
     }
 
+    println!("Restored EEPROM of subdevice {}", subdevice_index);
     Ok(())
 }
@@ -0,0 +1,134 @@
+use ethercrab::SubDeviceIdentity;
+
+/// Size of the EEPROM image we dump and restore
+pub const EEPROM_IMAGE_SIZE: usize = 2048;
+
+/// Byte offset of the SII checksum (low byte of word 0x0007)
+const CHECKSUM_OFFSET: usize = 14;
+
+/// CRC-8 over the first 7 SII words as defined in ETG.1000.6
+///
+/// Polynomial x^8 + x^2 + x + 1 (0x07), initial value 0xFF.
+pub fn sii_checksum(image: &[u8]) -> Option<u8> {
+    let data = image.get(..CHECKSUM_OFFSET)?;
+    let mut crc: u8 = 0xff;
+    for byte in data {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = match crc & 0x80 {
+                0 => crc << 1,
+                _ => (crc << 1) ^ 0x07,
+            };
+        }
+    }
+    Some(crc)
+}
+
+/// Result of checking the SII checksum
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SiiChecksum {
+    pub stored: u8,
+    pub calculated: u8,
+}
+
+impl SiiChecksum {
+    pub fn from_image(image: &[u8]) -> Option<Self> {
+        Some(Self {
+            stored: *image.get(CHECKSUM_OFFSET)?,
+            calculated: sii_checksum(image)?,
+        })
+    }
+
+    pub const fn is_valid(&self) -> bool {
+        self.stored == self.calculated
+    }
+}
+
+fn read_u32(image: &[u8], offset: usize) -> Option<u32> {
+    let bytes = image.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Reads vendor, product, revision and serial from SII words 0x0008 to 0x000F
+pub fn sii_identity(image: &[u8]) -> Option<SubDeviceIdentity> {
+    Some(SubDeviceIdentity {
+        vendor_id: read_u32(image, 0x10)?,
+        product_id: read_u32(image, 0x14)?,
+        revision: read_u32(image, 0x18)?,
+        serial: read_u32(image, 0x1c)?,
+    })
+}
+
+/// A word that differs between two EEPROM images
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WordDiff {
+    pub word: u16,
+    pub left: Option<u16>,
+    pub right: Option<u16>,
+}
+
+fn word_at(image: &[u8], word: usize) -> Option<u16> {
+    let bytes = image.get(word * 2..word * 2 + 2)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+/// Compares two EEPROM images word by word
+///
+/// Words only present in one of the images are reported with `None` on the other side.
+pub fn diff_words(left: &[u8], right: &[u8]) -> Vec<WordDiff> {
+    let words = left.len().max(right.len()).div_ceil(2);
+    (0..words)
+        .map(|word| WordDiff {
+            word: word as u16,
+            left: word_at(left, word),
+            right: word_at(right, word),
+        })
+        .filter(|diff| diff.left != diff.right)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EK1100: &[u8] = include_bytes!("../dumps/EK1100.bin");
+    const EL7031: &[u8] = include_bytes!("../dumps/EL7031-0000.bin");
+
+    #[test]
+    fn test_checksum_of_dumps() {
+        assert!(SiiChecksum::from_image(EK1100).unwrap().is_valid());
+        assert!(SiiChecksum::from_image(EL7031).unwrap().is_valid());
+    }
+
+    #[test]
+    fn test_checksum_detects_corruption() {
+        let mut image = EK1100.to_vec();
+        image[3] ^= 0x01;
+        assert!(!SiiChecksum::from_image(&image).unwrap().is_valid());
+        assert_eq!(sii_checksum(&image[..10]), None);
+    }
+
+    #[test]
+    fn test_sii_identity() {
+        let identity = sii_identity(EK1100).unwrap();
+        assert_eq!(identity.vendor_id, 0x2);
+        assert_eq!(identity.product_id, 0x044c2c52);
+    }
+
+    #[test]
+    fn test_diff_words() {
+        let mut image = EK1100.to_vec();
+        image[0x50] = 0x01;
+        let diffs = diff_words(EK1100, &image);
+        assert_eq!(
+            diffs,
+            vec![WordDiff {
+                word: 0x28,
+                left: Some(0x0000),
+                right: Some(0x0001),
+            }]
+        );
+        assert!(diff_words(EK1100, EK1100).is_empty());
+        assert_eq!(diff_words(&[1, 0], &[1, 0, 2, 0]).len(), 1);
+    }
+}
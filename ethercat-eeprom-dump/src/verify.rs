@@ -0,0 +1,31 @@
+use crate::sii::{SiiChecksum, sii_identity};
+
+/// Checks the SII checksum of an EEPROM image and prints the result
+///
+/// Returns an error if the checksum is invalid so the exit code can be used in scripts.
+pub fn verify_image(name: &str, image: &[u8]) -> Result<(), anyhow::Error> {
+    let checksum = SiiChecksum::from_image(image).ok_or(anyhow::anyhow!(
+        "[{}::verify_image] {} is too short for an SII header",
+        module_path!(),
+        name
+    ))?;
+
+    if !checksum.is_valid() {
+        Err(anyhow::anyhow!(
+            "[{}::verify_image] {} has an invalid SII checksum: stored 0x{:02x}, calculated 0x{:02x}",
+            module_path!(),
+            name,
+            checksum.stored,
+            checksum.calculated
+        ))?;
+    }
+
+    if let Some(identity) = sii_identity(image) {
+        println!(
+            "{}: vendor 0x{:08x} product 0x{:08x} revision 0x{:08x}",
+            name, identity.vendor_id, identity.product_id, identity.revision
+        );
+    }
+    println!("{}: SII checksum 0x{:02x} is valid", name, checksum.stored);
+    Ok(())
+}
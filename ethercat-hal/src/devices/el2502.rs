@@ -0,0 +1,193 @@
+use std::time::Duration;
+
+use super::{EthercatDeviceProcessing, NewEthercatDevice, SubDeviceIdentityTuple};
+use crate::{
+    coe::{ConfigurableDevice, Configuration},
+    helpers::ethercrab_types::EthercrabSubDevicePreoperational,
+    io::pwm_output::PwmOutputDevice,
+    pdo::{PredefinedPdoAssignment, RxPdo, TxPdo, el2502::PwmOutputWithPeriod},
+};
+use ethercat_hal_derive::{EthercatDevice, RxPdo, TxPdo};
+
+/// Shortest period the EL2502 generates, 20 kHz
+pub const EL2502_MIN_PERIOD: Duration = Duration::from_micros(50);
+/// Longest period the EL2502 generates, 1 Hz
+pub const EL2502_MAX_PERIOD: Duration = Duration::from_secs(1);
+
+/// Raw value of 100% duty cycle
+const EL2502_FULL_DUTY_CYCLE: u16 = 0x7FFF;
+
+/// EL2502 2-channel PWM output device
+///
+/// 24 V DC, 0.5 A, duty cycle and period are set in the process data
+#[derive(EthercatDevice)]
+pub struct EL2502 {
+    pub configuration: EL2502Configuration,
+    pub rxpdo: EL2502RxPdo,
+    pub txpdo: EL2502TxPdo,
+    is_used: bool,
+}
+
+impl EthercatDeviceProcessing for EL2502 {}
+
+impl std::fmt::Debug for EL2502 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EL2502")
+    }
+}
+
+impl NewEthercatDevice for EL2502 {
+    fn new() -> Self {
+        let configuration = EL2502Configuration::default();
+        Self {
+            rxpdo: configuration.pdo_assignment.rxpdo_assignment(),
+            txpdo: configuration.pdo_assignment.txpdo_assignment(),
+            configuration,
+            is_used: false,
+        }
+    }
+}
+
+impl EL2502 {
+    const fn channel(&self, port: EL2502Port) -> &PwmOutputWithPeriod {
+        let expect_text = "All channels should be Some(_)";
+        match port {
+            EL2502Port::PWM1 => self.rxpdo.channel1.as_ref().expect(expect_text),
+            EL2502Port::PWM2 => self.rxpdo.channel2.as_ref().expect(expect_text),
+        }
+    }
+
+    const fn channel_mut(&mut self, port: EL2502Port) -> &mut PwmOutputWithPeriod {
+        let expect_text = "All channels should be Some(_)";
+        match port {
+            EL2502Port::PWM1 => self.rxpdo.channel1.as_mut().expect(expect_text),
+            EL2502Port::PWM2 => self.rxpdo.channel2.as_mut().expect(expect_text),
+        }
+    }
+}
+
+impl PwmOutputDevice<EL2502Port> for EL2502 {
+    fn set_duty_cycle(&mut self, port: EL2502Port, duty_cycle: f64) {
+        self.channel_mut(port).value =
+            (duty_cycle.clamp(0.0, 1.0) * EL2502_FULL_DUTY_CYCLE as f64).round() as u16;
+    }
+
+    fn get_duty_cycle(&self, port: EL2502Port) -> f64 {
+        self.channel(port).value as f64 / EL2502_FULL_DUTY_CYCLE as f64
+    }
+
+    fn set_period(&mut self, port: EL2502Port, period: Duration) {
+        let period = period.clamp(EL2502_MIN_PERIOD, EL2502_MAX_PERIOD);
+        self.channel_mut(port).period = period.as_micros() as u32;
+    }
+
+    fn get_period(&self, port: EL2502Port) -> Duration {
+        Duration::from_micros(self.channel(port).period as u64)
+    }
+}
+
+impl ConfigurableDevice<EL2502Configuration> for EL2502 {
+    async fn write_config<'maindevice>(
+        &mut self,
+        device: &EthercrabSubDevicePreoperational<'maindevice>,
+        config: &EL2502Configuration,
+    ) -> Result<(), anyhow::Error> {
+        config.write_config(device).await?;
+        self.configuration = config.clone();
+        self.rxpdo = config.pdo_assignment.rxpdo_assignment();
+        self.txpdo = config.pdo_assignment.txpdo_assignment();
+        Ok(())
+    }
+
+    fn get_config(&self) -> EL2502Configuration {
+        self.configuration.clone()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum EL2502Port {
+    PWM1,
+    PWM2,
+}
+
+#[derive(Debug, Clone, RxPdo)]
+pub struct EL2502RxPdo {
+    #[pdo_object_index(0x1602)]
+    pub channel1: Option<PwmOutputWithPeriod>,
+    #[pdo_object_index(0x1603)]
+    pub channel2: Option<PwmOutputWithPeriod>,
+}
+
+#[derive(Debug, Clone, TxPdo)]
+pub struct EL2502TxPdo {}
+
+#[derive(Debug, Clone, Default)]
+pub struct EL2502Configuration {
+    pub pdo_assignment: EL2502PredefinedPdoAssignment,
+}
+
+impl Configuration for EL2502Configuration {
+    async fn write_config<'a>(
+        &self,
+        device: &EthercrabSubDevicePreoperational<'a>,
+    ) -> Result<(), anyhow::Error> {
+        self.pdo_assignment
+            .txpdo_assignment()
+            .write_config(device)
+            .await?;
+        self.pdo_assignment
+            .rxpdo_assignment()
+            .write_config(device)
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub enum EL2502PredefinedPdoAssignment {
+    /// Duty cycle and period per channel
+    #[default]
+    WithPeriod,
+}
+
+impl PredefinedPdoAssignment<EL2502TxPdo, EL2502RxPdo> for EL2502PredefinedPdoAssignment {
+    fn txpdo_assignment(&self) -> EL2502TxPdo {
+        EL2502TxPdo {}
+    }
+
+    fn rxpdo_assignment(&self) -> EL2502RxPdo {
+        match self {
+            Self::WithPeriod => EL2502RxPdo {
+                channel1: Some(PwmOutputWithPeriod::default()),
+                channel2: Some(PwmOutputWithPeriod::default()),
+            },
+        }
+    }
+}
+
+pub const EL2502_VENDOR_ID: u32 = 0x2;
+pub const EL2502_PRODUCT_ID: u32 = 0x09c63052;
+pub const EL2502_REVISION_A: u32 = 0x00100000;
+pub const EL2502_IDENTITY_A: SubDeviceIdentityTuple =
+    (EL2502_VENDOR_ID, EL2502_PRODUCT_ID, EL2502_REVISION_A);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duty_cycle_and_period() {
+        let mut el2502 = EL2502::new();
+
+        el2502.set_duty_cycle(EL2502Port::PWM1, 0.5);
+        el2502.set_duty_cycle(EL2502Port::PWM2, 2.0);
+        assert_eq!(el2502.rxpdo.channel1.as_ref().unwrap().value, 0x4000);
+        assert!((el2502.get_duty_cycle(EL2502Port::PWM1) - 0.5).abs() < 1e-4);
+        assert_eq!(el2502.get_duty_cycle(EL2502Port::PWM2), 1.0);
+
+        el2502.set_period(EL2502Port::PWM1, Duration::from_millis(500));
+        el2502.set_period(EL2502Port::PWM2, Duration::from_secs(10));
+        assert_eq!(el2502.rxpdo.channel1.as_ref().unwrap().period, 500_000);
+        assert_eq!(el2502.get_period(EL2502Port::PWM2), EL2502_MAX_PERIOD);
+    }
+}
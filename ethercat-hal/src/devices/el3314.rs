@@ -0,0 +1,165 @@
+use super::{EthercatDeviceProcessing, NewEthercatDevice, SubDeviceIdentityTuple};
+use crate::{
+    coe::{ConfigurableDevice, Configuration},
+    helpers::ethercrab_types::EthercrabSubDevicePreoperational,
+    io::temperature_input::{TemperatureInputDevice, TemperatureInputInput},
+    pdo::{PredefinedPdoAssignment, TxPdo, el32xx::TcInput},
+    shared_config::el33xx::EL33XXChannelConfiguration,
+};
+use ethercat_hal_derive::{EthercatDevice, RxPdo, TxPdo};
+
+/// EL3314 4-channel thermocouple input device
+///
+/// Type K, J, L, E, T, N, U, B, R, S, C with cold junction compensation
+#[derive(EthercatDevice)]
+pub struct EL3314 {
+    pub txpdo: EL3314TxPdo,
+    pub configuration: EL3314Configuration,
+    is_used: bool,
+}
+
+impl EthercatDeviceProcessing for EL3314 {}
+
+impl std::fmt::Debug for EL3314 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EL3314")
+    }
+}
+
+impl NewEthercatDevice for EL3314 {
+    fn new() -> Self {
+        let configuration = EL3314Configuration::default();
+        Self {
+            txpdo: configuration.pdo_assignment.txpdo_assignment(),
+            configuration,
+            is_used: false,
+        }
+    }
+}
+
+impl TemperatureInputDevice<EL3314Port> for EL3314 {
+    fn get_input(&self, port: EL3314Port) -> TemperatureInputInput {
+        let expect_text = "All channels should be Some(_)";
+        let channel = match port {
+            EL3314Port::T1 => self.txpdo.channel1.as_ref().expect(expect_text),
+            EL3314Port::T2 => self.txpdo.channel2.as_ref().expect(expect_text),
+            EL3314Port::T3 => self.txpdo.channel3.as_ref().expect(expect_text),
+            EL3314Port::T4 => self.txpdo.channel4.as_ref().expect(expect_text),
+        };
+        TemperatureInputInput {
+            temperature: channel.temperature,
+            undervoltage: channel.undervoltage,
+            overvoltage: channel.overvoltage,
+            limit1: channel.limit1,
+            limit2: channel.limit2,
+            error: channel.error,
+            txpdo_state: channel.txpdo_state,
+            txpdo_toggle: channel.txpdo_toggle,
+        }
+    }
+}
+
+impl ConfigurableDevice<EL3314Configuration> for EL3314 {
+    async fn write_config<'maindevice>(
+        &mut self,
+        device: &EthercrabSubDevicePreoperational<'maindevice>,
+        config: &EL3314Configuration,
+    ) -> Result<(), anyhow::Error> {
+        config.write_config(device).await?;
+        self.configuration = config.clone();
+        self.txpdo = config.pdo_assignment.txpdo_assignment();
+        Ok(())
+    }
+
+    fn get_config(&self) -> EL3314Configuration {
+        self.configuration.clone()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum EL3314Port {
+    T1,
+    T2,
+    T3,
+    T4,
+}
+
+#[derive(Debug, Clone, TxPdo)]
+pub struct EL3314TxPdo {
+    #[pdo_object_index(0x1A00)]
+    pub channel1: Option<TcInput>,
+    #[pdo_object_index(0x1A01)]
+    pub channel2: Option<TcInput>,
+    #[pdo_object_index(0x1A02)]
+    pub channel3: Option<TcInput>,
+    #[pdo_object_index(0x1A03)]
+    pub channel4: Option<TcInput>,
+}
+
+#[derive(Debug, Clone, RxPdo)]
+pub struct EL3314RxPdo {}
+
+#[derive(Debug, Clone, Default)]
+pub struct EL3314Configuration {
+    pub pdo_assignment: EL3314PredefinedPdoAssignment,
+    // TC1+ and TC1-
+    pub channel1: EL33XXChannelConfiguration,
+    // TC2+ and TC2-
+    pub channel2: EL33XXChannelConfiguration,
+    // TC3+ and TC3-
+    pub channel3: EL33XXChannelConfiguration,
+    // TC4+ and TC4-
+    pub channel4: EL33XXChannelConfiguration,
+}
+
+impl Configuration for EL3314Configuration {
+    async fn write_config<'a>(
+        &self,
+        device: &EthercrabSubDevicePreoperational<'a>,
+    ) -> Result<(), anyhow::Error> {
+        self.channel1.write_channel_config(device, 0x8000).await?;
+        self.channel2.write_channel_config(device, 0x8010).await?;
+        self.channel3.write_channel_config(device, 0x8020).await?;
+        self.channel4.write_channel_config(device, 0x8030).await?;
+        self.pdo_assignment
+            .txpdo_assignment()
+            .write_config(device)
+            .await?;
+        self.pdo_assignment
+            .rxpdo_assignment()
+            .write_config(device)
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub enum EL3314PredefinedPdoAssignment {
+    #[default]
+    Standard,
+}
+
+impl PredefinedPdoAssignment<EL3314TxPdo, EL3314RxPdo> for EL3314PredefinedPdoAssignment {
+    fn txpdo_assignment(&self) -> EL3314TxPdo {
+        match self {
+            Self::Standard => EL3314TxPdo {
+                channel1: Some(TcInput::default()),
+                channel2: Some(TcInput::default()),
+                channel3: Some(TcInput::default()),
+                channel4: Some(TcInput::default()),
+            },
+        }
+    }
+
+    fn rxpdo_assignment(&self) -> EL3314RxPdo {
+        match self {
+            Self::Standard => EL3314RxPdo {},
+        }
+    }
+}
+
+pub const EL3314_VENDOR_ID: u32 = 0x2;
+pub const EL3314_PRODUCT_ID: u32 = 0x0cf23052;
+pub const EL3314_REVISION_A: u32 = 0x00140000;
+pub const EL3314_IDENTITY_A: SubDeviceIdentityTuple =
+    (EL3314_VENDOR_ID, EL3314_PRODUCT_ID, EL3314_REVISION_A);
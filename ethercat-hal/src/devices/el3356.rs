@@ -0,0 +1,262 @@
+use super::{EthercatDeviceProcessing, NewEthercatDevice, SubDeviceIdentityTuple};
+use crate::{
+    coe::{ConfigurableDevice, Configuration},
+    helpers::ethercrab_types::EthercrabSubDevicePreoperational,
+    io::load_cell::{LoadCellDevice, LoadCellInput},
+    pdo::{
+        PredefinedPdoAssignment, RxPdo, TxPdo,
+        el3356::{RmbControl, RmbStatus, RmbValue},
+    },
+};
+use anyhow::anyhow;
+use ethercat_hal_derive::{EthercatDevice, RxPdo, TxPdo};
+use units::{f64::Mass, mass::kilogram};
+
+/// Cycles the tare bit is held so the terminal sees the rising edge
+const TARE_PULSE_CYCLES: u8 = 10;
+
+/// EL3356 1-channel load cell (strain gauge full bridge) input
+#[derive(EthercatDevice)]
+pub struct EL3356 {
+    pub txpdo: EL3356TxPdo,
+    pub rxpdo: EL3356RxPdo,
+    pub configuration: EL3356Configuration,
+    /// Remaining cycles of a requested tare
+    tare_cycles: u8,
+    /// Calibration gain of [`crate::io::load_cell::LoadCell`]
+    gain: f64,
+    is_used: bool,
+}
+
+impl EthercatDeviceProcessing for EL3356 {
+    fn output_pre_process(&mut self) -> Result<(), anyhow::Error> {
+        let rmb_control = match &mut self.rxpdo.rmb_control {
+            Some(value) => value,
+            None => return Err(anyhow!("rmb_control is None")),
+        };
+        rmb_control.tare = self.tare_cycles > 0;
+        self.tare_cycles = self.tare_cycles.saturating_sub(1);
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for EL3356 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EL3356")
+    }
+}
+
+impl NewEthercatDevice for EL3356 {
+    fn new() -> Self {
+        let configuration = EL3356Configuration::default();
+        Self {
+            txpdo: configuration.pdo_assignment.txpdo_assignment(),
+            rxpdo: configuration.pdo_assignment.rxpdo_assignment(),
+            configuration,
+            tare_cycles: 0,
+            gain: 1.0,
+            is_used: false,
+        }
+    }
+}
+
+impl LoadCellDevice<EL3356Port> for EL3356 {
+    fn get_input(&self, port: EL3356Port) -> Result<LoadCellInput, anyhow::Error> {
+        match port {
+            EL3356Port::LC1 => {
+                let rmb_status = match &self.txpdo.rmb_status {
+                    Some(value) => value,
+                    None => return Err(anyhow!("rmb_status is None")),
+                };
+                let rmb_value = match &self.txpdo.rmb_value {
+                    Some(value) => value,
+                    None => return Err(anyhow!("rmb_value is None")),
+                };
+                let mass = f64::from(rmb_value.value) / f64::from(self.configuration.scale_factor);
+                Ok(LoadCellInput {
+                    mass: Mass::new::<kilogram>(mass),
+                    steady_state: rmb_status.steady_state,
+                    underrange: rmb_status.underrange,
+                    overrange: rmb_status.overrange,
+                    error: rmb_status.error || rmb_status.data_invalid,
+                })
+            }
+        }
+    }
+
+    fn tare(&mut self, port: EL3356Port) -> Result<(), anyhow::Error> {
+        match port {
+            EL3356Port::LC1 => {
+                self.tare_cycles = TARE_PULSE_CYCLES;
+                Ok(())
+            }
+        }
+    }
+
+    fn get_gain(&self, port: EL3356Port) -> f64 {
+        match port {
+            EL3356Port::LC1 => self.gain,
+        }
+    }
+
+    fn set_gain(&mut self, port: EL3356Port, gain: f64) {
+        match port {
+            EL3356Port::LC1 => self.gain = gain,
+        }
+    }
+}
+
+impl ConfigurableDevice<EL3356Configuration> for EL3356 {
+    async fn write_config<'maindevice>(
+        &mut self,
+        device: &EthercrabSubDevicePreoperational<'maindevice>,
+        config: &EL3356Configuration,
+    ) -> Result<(), anyhow::Error> {
+        config.write_config(device).await?;
+        self.configuration = config.clone();
+        self.txpdo = config.pdo_assignment.txpdo_assignment();
+        self.rxpdo = config.pdo_assignment.rxpdo_assignment();
+        Ok(())
+    }
+
+    fn get_config(&self) -> EL3356Configuration {
+        self.configuration.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum EL3356Port {
+    LC1,
+}
+
+#[derive(Debug, Clone, TxPdo)]
+pub struct EL3356TxPdo {
+    #[pdo_object_index(0x1A00)]
+    pub rmb_status: Option<RmbStatus>,
+    #[pdo_object_index(0x1A01)]
+    pub rmb_value: Option<RmbValue>,
+}
+
+#[derive(Debug, Clone, RxPdo)]
+pub struct EL3356RxPdo {
+    #[pdo_object_index(0x1600)]
+    pub rmb_control: Option<RmbControl>,
+}
+
+/// Filter of the measured value (0x8000:11)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EL3356Filter {
+    /// Suppresses 50 Hz mains hum
+    #[default]
+    Fir50Hz,
+    /// Suppresses 60 Hz mains hum
+    Fir60Hz,
+    /// IIR low-pass, higher levels filter more and react slower, 1 to 8
+    Iir(u8),
+    /// IIR that filters less while the load changes
+    DynamicIir,
+}
+
+impl EL3356Filter {
+    pub fn value(&self) -> u16 {
+        match self {
+            Self::Fir50Hz => 0,
+            Self::Fir60Hz => 1,
+            Self::Iir(level) => 1 + u16::from((*level).clamp(1, 8)),
+            Self::DynamicIir => 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EL3356Configuration {
+    pub pdo_assignment: EL3356PredefinedPdoAssignment,
+
+    /// # 0x8000:23
+    /// Output of the load cell at nominal load in mV/V, from the data sheet
+    pub nominal_characteristic: f32,
+
+    /// # 0x8000:24
+    /// Nominal load of the load cell in kg
+    pub nominal_load: f32,
+
+    /// # 0x8000:25
+    /// Output of the load cell without load in mV/V
+    pub zero_balance: f32,
+
+    /// # 0x8000:27
+    /// The value is multiplied with it, 1000 gives a resolution of 1 g
+    pub scale_factor: f32,
+
+    /// # 0x8000:11
+    pub filter: EL3356Filter,
+}
+
+impl Default for EL3356Configuration {
+    fn default() -> Self {
+        Self {
+            pdo_assignment: EL3356PredefinedPdoAssignment::default(),
+            nominal_characteristic: 2.0,
+            nominal_load: 5.0,
+            zero_balance: 0.0,
+            scale_factor: 1000.0,
+            filter: EL3356Filter::default(),
+        }
+    }
+}
+
+impl Configuration for EL3356Configuration {
+    async fn write_config<'a>(
+        &self,
+        device: &EthercrabSubDevicePreoperational<'a>,
+    ) -> Result<(), anyhow::Error> {
+        device.sdo_write(0x8000, 0x01, true).await?;
+        device.sdo_write(0x8000, 0x11, self.filter.value()).await?;
+        device
+            .sdo_write(0x8000, 0x23, self.nominal_characteristic)
+            .await?;
+        device.sdo_write(0x8000, 0x24, self.nominal_load).await?;
+        device.sdo_write(0x8000, 0x25, self.zero_balance).await?;
+        device.sdo_write(0x8000, 0x27, self.scale_factor).await?;
+        self.pdo_assignment
+            .txpdo_assignment()
+            .write_config(device)
+            .await?;
+        self.pdo_assignment
+            .rxpdo_assignment()
+            .write_config(device)
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub enum EL3356PredefinedPdoAssignment {
+    #[default]
+    Standard,
+}
+
+impl PredefinedPdoAssignment<EL3356TxPdo, EL3356RxPdo> for EL3356PredefinedPdoAssignment {
+    fn txpdo_assignment(&self) -> EL3356TxPdo {
+        match self {
+            Self::Standard => EL3356TxPdo {
+                rmb_status: Some(RmbStatus::default()),
+                rmb_value: Some(RmbValue::default()),
+            },
+        }
+    }
+
+    fn rxpdo_assignment(&self) -> EL3356RxPdo {
+        match self {
+            Self::Standard => EL3356RxPdo {
+                rmb_control: Some(RmbControl::default()),
+            },
+        }
+    }
+}
+
+pub const EL3356_VENDOR_ID: u32 = 0x2;
+pub const EL3356_PRODUCT_ID: u32 = 0x0d1c3052;
+pub const EL3356_REVISION_A: u32 = 0x00120000;
+pub const EL3356_IDENTITY_A: SubDeviceIdentityTuple =
+    (EL3356_VENDOR_ID, EL3356_PRODUCT_ID, EL3356_REVISION_A);
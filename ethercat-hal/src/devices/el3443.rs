@@ -0,0 +1,171 @@
+use super::{EthercatDeviceProcessing, NewEthercatDevice, SubDeviceIdentityTuple};
+use crate::{
+    coe::{ConfigurableDevice, Configuration},
+    helpers::ethercrab_types::EthercrabSubDevicePreoperational,
+    io::power_measurement::{PowerMeasurementDevice, PowerMeasurementInput},
+    pdo::{
+        PredefinedPdoAssignment, TxPdo,
+        el3443::{PmxPhaseBasic, PmxPhaseEnergy, PmxStatus},
+    },
+};
+use anyhow::anyhow;
+use ethercat_hal_derive::{EthercatDevice, RxPdo, TxPdo};
+
+/// EL3443 3-phase power measurement terminal
+#[derive(EthercatDevice)]
+pub struct EL3443 {
+    pub txpdo: EL3443TxPdo,
+    pub configuration: EL3443Configuration,
+    is_used: bool,
+}
+
+impl EthercatDeviceProcessing for EL3443 {}
+
+impl std::fmt::Debug for EL3443 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EL3443")
+    }
+}
+
+impl NewEthercatDevice for EL3443 {
+    fn new() -> Self {
+        let configuration = EL3443Configuration::default();
+        Self {
+            txpdo: configuration.pdo_assignment.txpdo_assignment(),
+            configuration,
+            is_used: false,
+        }
+    }
+}
+
+impl PowerMeasurementDevice<EL3443Port> for EL3443 {
+    fn get_input(&self, port: EL3443Port) -> Result<PowerMeasurementInput, anyhow::Error> {
+        let pmx_status = match &self.txpdo.pmx_status {
+            Some(value) => value,
+            None => return Err(anyhow!("pmx_status is None")),
+        };
+        let (basic, energy) = match port {
+            EL3443Port::L1 => (&self.txpdo.pmx_l1_basic, &self.txpdo.pmx_l1_energy),
+            EL3443Port::L2 => (&self.txpdo.pmx_l2_basic, &self.txpdo.pmx_l2_energy),
+            EL3443Port::L3 => (&self.txpdo.pmx_l3_basic, &self.txpdo.pmx_l3_energy),
+        };
+        let basic = match basic {
+            Some(value) => value,
+            None => return Err(anyhow!("pmx_basic of {:?} is None", port)),
+        };
+        let energy = match energy {
+            Some(value) => value,
+            None => return Err(anyhow!("pmx_energy of {:?} is None", port)),
+        };
+        Ok(PowerMeasurementInput {
+            voltage: f64::from(basic.voltage),
+            current: f64::from(basic.current),
+            active_power: f64::from(basic.active_power),
+            // mWh to Wh
+            active_energy: energy.active_energy as f64 / 1000.0,
+            error: pmx_status.error,
+        })
+    }
+}
+
+impl ConfigurableDevice<EL3443Configuration> for EL3443 {
+    async fn write_config<'maindevice>(
+        &mut self,
+        device: &EthercrabSubDevicePreoperational<'maindevice>,
+        config: &EL3443Configuration,
+    ) -> Result<(), anyhow::Error> {
+        config.write_config(device).await?;
+        self.configuration = config.clone();
+        self.txpdo = config.pdo_assignment.txpdo_assignment();
+        Ok(())
+    }
+
+    fn get_config(&self) -> EL3443Configuration {
+        self.configuration.clone()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum EL3443Port {
+    L1,
+    L2,
+    L3,
+}
+
+#[derive(Debug, Clone, TxPdo)]
+pub struct EL3443TxPdo {
+    #[pdo_object_index(0x1A00)]
+    pub pmx_status: Option<PmxStatus>,
+    #[pdo_object_index(0x1A01)]
+    pub pmx_l1_basic: Option<PmxPhaseBasic>,
+    #[pdo_object_index(0x1A02)]
+    pub pmx_l2_basic: Option<PmxPhaseBasic>,
+    #[pdo_object_index(0x1A03)]
+    pub pmx_l3_basic: Option<PmxPhaseBasic>,
+    #[pdo_object_index(0x1A04)]
+    pub pmx_l1_energy: Option<PmxPhaseEnergy>,
+    #[pdo_object_index(0x1A05)]
+    pub pmx_l2_energy: Option<PmxPhaseEnergy>,
+    #[pdo_object_index(0x1A06)]
+    pub pmx_l3_energy: Option<PmxPhaseEnergy>,
+}
+
+#[derive(Debug, Clone, RxPdo)]
+pub struct EL3443RxPdo {}
+
+#[derive(Debug, Clone, Default)]
+pub struct EL3443Configuration {
+    pub pdo_assignment: EL3443PredefinedPdoAssignment,
+}
+
+impl Configuration for EL3443Configuration {
+    async fn write_config<'a>(
+        &self,
+        device: &EthercrabSubDevicePreoperational<'a>,
+    ) -> Result<(), anyhow::Error> {
+        self.pdo_assignment
+            .txpdo_assignment()
+            .write_config(device)
+            .await?;
+        self.pdo_assignment
+            .rxpdo_assignment()
+            .write_config(device)
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub enum EL3443PredefinedPdoAssignment {
+    /// Status, voltage, current, active power and energy of all phases
+    #[default]
+    BasicWithEnergy,
+}
+
+impl PredefinedPdoAssignment<EL3443TxPdo, EL3443RxPdo> for EL3443PredefinedPdoAssignment {
+    fn txpdo_assignment(&self) -> EL3443TxPdo {
+        match self {
+            Self::BasicWithEnergy => EL3443TxPdo {
+                pmx_status: Some(PmxStatus::default()),
+                pmx_l1_basic: Some(PmxPhaseBasic::default()),
+                pmx_l2_basic: Some(PmxPhaseBasic::default()),
+                pmx_l3_basic: Some(PmxPhaseBasic::default()),
+                pmx_l1_energy: Some(PmxPhaseEnergy::default()),
+                pmx_l2_energy: Some(PmxPhaseEnergy::default()),
+                pmx_l3_energy: Some(PmxPhaseEnergy::default()),
+            },
+        }
+    }
+
+    fn rxpdo_assignment(&self) -> EL3443RxPdo {
+        match self {
+            Self::BasicWithEnergy => EL3443RxPdo {},
+        }
+    }
+}
+
+pub const EL3443_VENDOR_ID: u32 = 0x2;
+pub const EL3443_PRODUCT_ID: u32 = 0x0d733052;
+pub const EL3443_REVISION_A: u32 = 0x00130000;
+pub const EL3443_IDENTITY_A: SubDeviceIdentityTuple =
+    (EL3443_VENDOR_ID, EL3443_PRODUCT_ID, EL3443_REVISION_A);
@@ -198,7 +198,7 @@ pub struct EL6021Configuration {
     pub pdo_assignment: EL6021PdoPreset,
 }
 
-const fn convert_serial_encoding(encoding: SerialEncoding) -> u8 {
+pub(crate) const fn convert_serial_encoding(encoding: SerialEncoding) -> u8 {
     match encoding {
         SerialEncoding::Coding7E1 => 1,
         SerialEncoding::Coding7O1 => 2,
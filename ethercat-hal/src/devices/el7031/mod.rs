@@ -7,15 +7,24 @@ use ethercat_hal_derive::EthercatDevice;
 use pdo::{EL7031RxPdo, EL7031TxPdo};
 
 use crate::{
-    helpers::counter_wrapper_u16_i128::CounterWrapperU16U128,
+    helpers::{
+        counter_wrapper_u16_i128::CounterWrapperU16U128,
+        el70x1_position_interface::EL70x1PositionInterface,
+    },
     io::{
         digital_input::{DigitalInputDevice, DigitalInputInput},
+        stepper_position_el70x1::{
+            StepperPositionEL70x1Device, StepperPositionEL70x1Input, StepperPositionEL70x1Output,
+        },
         stepper_velocity_el70x1::{
-            StepperVelocityEL70x1Device, StepperVelocityEL70x1Input, StepperVelocityEL70x1Output,
+            EL70x1InfoDataValue, StepperVelocityEL70x1Device, StepperVelocityEL70x1Input,
+            StepperVelocityEL70x1Output,
         },
     },
     pdo::{PredefinedPdoAssignment, RxPdo, TxPdo},
-    shared_config::el70x1::EL70x1OperationMode,
+    shared_config::el70x1::{
+        EL70x1MotorConfigurableDevice, EL70x1MotorConfiguration, EL70x1OperationMode,
+    },
 };
 
 use super::{EthercatDeviceProcessing, NewEthercatDevice, SubDeviceIdentityTuple};
@@ -27,36 +36,25 @@ pub struct EL7031 {
     is_used: bool,
     pub configuration: EL7031Configuration,
     pub counter_wrapper: CounterWrapperU16U128,
+    pub position_interface: EL70x1PositionInterface,
 }
 
 impl EthercatDeviceProcessing for EL7031 {
     fn input_post_process(&mut self) -> Result<(), anyhow::Error> {
-        let enc_status_compact = match &self.txpdo.enc_status_compact {
-            Some(value) => value,
-            None => return Err(anyhow!("enc_status_compact is None")),
-        };
-
-        // update the counter wrapper
-        self.counter_wrapper.update(
-            enc_status_compact.counter_value,
-            enc_status_compact.counter_underflow,
-            enc_status_compact.counter_overflow,
-        );
+        // the compact encoder status is only mapped in the velocity pdo assignments
+        if let Some(enc_status_compact) = &self.txpdo.enc_status_compact {
+            // update the counter wrapper
+            self.counter_wrapper.update(
+                enc_status_compact.counter_value,
+                enc_status_compact.counter_underflow,
+                enc_status_compact.counter_overflow,
+            );
+        }
 
         Ok(())
     }
 
     fn output_pre_process(&mut self) -> Result<(), anyhow::Error> {
-        let enc_status_compact = match &self.txpdo.enc_status_compact {
-            Some(value) => value,
-            None => return Err(anyhow!("enc_status_compact is None")),
-        };
-
-        let enc_control_compact = match &mut self.rxpdo.enc_control_compact {
-            Some(value) => value,
-            None => return Err(anyhow!("enc_control_compact is None")),
-        };
-
         let stm_status = match &self.txpdo.stm_status {
             Some(value) => value,
             None => return Err(anyhow!("stm_status is None")),
@@ -72,6 +70,25 @@ impl EthercatDeviceProcessing for EL7031 {
             stm_control.reset = true;
         }
 
+        // positioning interface
+        if let (Some(pos_control), Some(enc_control)) =
+            (&mut self.rxpdo.pos_control, &mut self.rxpdo.enc_control)
+        {
+            self.position_interface
+                .output_pre_process(pos_control, enc_control);
+            return Ok(());
+        }
+
+        let enc_status_compact = match &self.txpdo.enc_status_compact {
+            Some(value) => value,
+            None => return Err(anyhow!("enc_status_compact is None")),
+        };
+
+        let enc_control_compact = match &mut self.rxpdo.enc_control_compact {
+            Some(value) => value,
+            None => return Err(anyhow!("enc_control_compact is None")),
+        };
+
         // clear counter overflow/underflow flags by setting the counter to the current value
         if enc_status_compact.counter_overflow || enc_status_compact.counter_underflow {
             enc_control_compact.set_counter = true;
@@ -103,6 +120,7 @@ impl NewEthercatDevice for EL7031 {
             is_used: false,
             configuration,
             counter_wrapper: CounterWrapperU16U128::new(),
+            position_interface: EL70x1PositionInterface::new(),
         }
     }
 }
@@ -170,6 +188,12 @@ impl StepperVelocityEL70x1Device<EL7031StepperPort> for EL7031 {
                     None => return Err(anyhow!("stm_status is None")),
                 };
 
+                let (info_data_1, info_data_2) = EL70x1InfoDataValue::from_pdo(
+                    self.txpdo.stm_synchron_info_data.as_ref(),
+                    self.configuration.stm_features.select_info_data_1,
+                    self.configuration.stm_features.select_info_data_2,
+                );
+
                 Ok(StepperVelocityEL70x1Input {
                     counter_value: self.counter_wrapper.current(),
                     ready_to_enable: stm_status.ready_to_enable,
@@ -179,6 +203,8 @@ impl StepperVelocityEL70x1Device<EL7031StepperPort> for EL7031 {
                     moving_positive: stm_status.moving_positive,
                     moving_negative: stm_status.moving_negative,
                     torque_reduced: stm_status.torque_reduced,
+                    info_data_1,
+                    info_data_2,
                 })
             }
         }
@@ -227,6 +253,132 @@ impl StepperVelocityEL70x1Device<EL7031StepperPort> for EL7031 {
     }
 }
 
+impl StepperPositionEL70x1Device<EL7031StepperPort> for EL7031 {
+    fn set_output(
+        &mut self,
+        port: EL7031StepperPort,
+        value: StepperPositionEL70x1Output,
+    ) -> Result<(), anyhow::Error> {
+        self.check_position_controller()?;
+
+        match port {
+            EL7031StepperPort::STM1 => {
+                let stm_control = match &mut self.rxpdo.stm_control {
+                    Some(value) => value,
+                    None => return Err(anyhow!("stm_control is None")),
+                };
+
+                let pos_control = match &mut self.rxpdo.pos_control {
+                    Some(value) => value,
+                    None => return Err(anyhow!("pos_control is None")),
+                };
+
+                self.position_interface
+                    .set_output(stm_control, pos_control, &value);
+                Ok(())
+            }
+        }
+    }
+
+    fn get_input(
+        &self,
+        port: EL7031StepperPort,
+    ) -> Result<StepperPositionEL70x1Input, anyhow::Error> {
+        self.check_position_controller()?;
+
+        match port {
+            EL7031StepperPort::STM1 => {
+                let stm_status = match &self.txpdo.stm_status {
+                    Some(value) => value,
+                    None => return Err(anyhow!("stm_status is None")),
+                };
+
+                let pos_status = match &self.txpdo.pos_status {
+                    Some(value) => value,
+                    None => return Err(anyhow!("pos_status is None")),
+                };
+
+                let enc_status = match &self.txpdo.enc_status {
+                    Some(value) => value,
+                    None => return Err(anyhow!("enc_status is None")),
+                };
+
+                Ok(EL70x1PositionInterface::get_input(
+                    stm_status, pos_status, enc_status,
+                ))
+            }
+        }
+    }
+
+    fn get_output(
+        &self,
+        port: EL7031StepperPort,
+    ) -> Result<StepperPositionEL70x1Output, anyhow::Error> {
+        self.check_position_controller()?;
+
+        match port {
+            EL7031StepperPort::STM1 => {
+                let stm_control = match &self.rxpdo.stm_control {
+                    Some(value) => value,
+                    None => return Err(anyhow!("stm_control is None")),
+                };
+
+                let pos_control = match &self.rxpdo.pos_control {
+                    Some(value) => value,
+                    None => return Err(anyhow!("pos_control is None")),
+                };
+
+                Ok(self.position_interface.get_output(stm_control, pos_control))
+            }
+        }
+    }
+
+    fn get_speed_range(
+        &self,
+        _port: EL7031StepperPort,
+    ) -> crate::shared_config::el70x1::EL70x1SpeedRange {
+        self.configuration.stm_features.speed_range
+    }
+}
+
+impl EL7031 {
+    fn check_position_controller(&self) -> Result<(), anyhow::Error> {
+        if self.configuration.stm_features.operation_mode != EL70x1OperationMode::PositionController
+        {
+            return Err(anyhow!(
+                "Operation mode is not position controller, but {:?}",
+                self.configuration.stm_features.operation_mode
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl EL70x1MotorConfigurableDevice for EL7031 {
+    fn get_motor_configuration(&self) -> EL70x1MotorConfiguration {
+        EL70x1MotorConfiguration {
+            encoder: self.configuration.encoder.clone(),
+            stm_motor: self.configuration.stm_motor.clone(),
+            stm_controller_1: self.configuration.stm_controller_1.clone(),
+            stm_controller_2: self.configuration.stm_controller_2.clone(),
+        }
+    }
+
+    fn set_motor_configuration(&mut self, configuration: EL70x1MotorConfiguration) {
+        self.configuration.encoder = configuration.encoder;
+        self.configuration.stm_motor = configuration.stm_motor;
+        self.configuration.stm_controller_1 = configuration.stm_controller_1;
+        self.configuration.stm_controller_2 = configuration.stm_controller_2;
+    }
+
+    fn is_motor_enabled(&self) -> bool {
+        self.rxpdo
+            .stm_control
+            .as_ref()
+            .is_some_and(|stm_control| stm_control.enable)
+    }
+}
+
 impl DigitalInputDevice<EL7031DigitalInputPort> for EL7031 {
     fn get_input(&self, port: EL7031DigitalInputPort) -> Result<DigitalInputInput, anyhow::Error> {
         let error1 = anyhow::anyhow!(
@@ -15,11 +15,14 @@ use crate::{
         analog_input::{AnalogInputDevice, AnalogInputInput, physical::AnalogInputRange},
         digital_input::{DigitalInputDevice, DigitalInputInput},
         stepper_velocity_el70x1::{
-            StepperVelocityEL70x1Device, StepperVelocityEL70x1Input, StepperVelocityEL70x1Output,
+            EL70x1InfoDataValue, StepperVelocityEL70x1Device, StepperVelocityEL70x1Input,
+            StepperVelocityEL70x1Output,
         },
     },
     pdo::{PredefinedPdoAssignment, RxPdo, TxPdo},
-    shared_config::el70x1::EL70x1OperationMode,
+    shared_config::el70x1::{
+        EL70x1MotorConfigurableDevice, EL70x1MotorConfiguration, EL70x1OperationMode,
+    },
 };
 
 use super::{EthercatDeviceProcessing, NewEthercatDevice, SubDeviceIdentityTuple};
@@ -187,6 +190,12 @@ impl StepperVelocityEL70x1Device<EL7031_0030StepperPort> for EL7031_0030 {
                     }
                 };
 
+                let (info_data_1, info_data_2) = EL70x1InfoDataValue::from_pdo(
+                    self.txpdo.stm_synchron_info_data.as_ref(),
+                    self.configuration.stm_features.select_info_data_1,
+                    self.configuration.stm_features.select_info_data_2,
+                );
+
                 Ok(StepperVelocityEL70x1Input {
                     counter_value: self.counter_wrapper.current(),
                     ready_to_enable: stm_status.ready_to_enable,
@@ -196,6 +205,8 @@ impl StepperVelocityEL70x1Device<EL7031_0030StepperPort> for EL7031_0030 {
                     moving_positive: stm_status.moving_positive,
                     moving_negative: stm_status.moving_negative,
                     torque_reduced: stm_status.torque_reduced,
+                    info_data_1,
+                    info_data_2,
                 })
             }
         }
@@ -255,6 +266,31 @@ impl StepperVelocityEL70x1Device<EL7031_0030StepperPort> for EL7031_0030 {
     }
 }
 
+impl EL70x1MotorConfigurableDevice for EL7031_0030 {
+    fn get_motor_configuration(&self) -> EL70x1MotorConfiguration {
+        EL70x1MotorConfiguration {
+            encoder: self.configuration.encoder.clone(),
+            stm_motor: self.configuration.stm_motor.clone(),
+            stm_controller_1: self.configuration.stm_controller_1.clone(),
+            stm_controller_2: self.configuration.stm_controller_2.clone(),
+        }
+    }
+
+    fn set_motor_configuration(&mut self, configuration: EL70x1MotorConfiguration) {
+        self.configuration.encoder = configuration.encoder;
+        self.configuration.stm_motor = configuration.stm_motor;
+        self.configuration.stm_controller_1 = configuration.stm_controller_1;
+        self.configuration.stm_controller_2 = configuration.stm_controller_2;
+    }
+
+    fn is_motor_enabled(&self) -> bool {
+        self.rxpdo
+            .stm_control
+            .as_ref()
+            .is_some_and(|stm_control| stm_control.enable)
+    }
+}
+
 impl DigitalInputDevice<EL7031_0030DigitalInputPort> for EL7031_0030 {
     fn get_input(
         &self,
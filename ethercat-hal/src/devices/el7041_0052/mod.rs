@@ -3,15 +3,24 @@ use ethercat_hal_derive::EthercatDevice;
 
 use super::{EthercatDeviceProcessing, NewEthercatDevice, SubDeviceIdentityTuple};
 use crate::{
-    helpers::counter_wrapper_u16_i128::CounterWrapperU16U128,
+    helpers::{
+        counter_wrapper_u16_i128::CounterWrapperU16U128,
+        el70x1_position_interface::EL70x1PositionInterface,
+    },
     io::{
         digital_input::{DigitalInputDevice, DigitalInputInput},
+        stepper_position_el70x1::{
+            StepperPositionEL70x1Device, StepperPositionEL70x1Input, StepperPositionEL70x1Output,
+        },
         stepper_velocity_el70x1::{
-            StepperVelocityEL70x1Device, StepperVelocityEL70x1Input, StepperVelocityEL70x1Output,
+            EL70x1InfoDataValue, StepperVelocityEL70x1Device, StepperVelocityEL70x1Input,
+            StepperVelocityEL70x1Output,
         },
     },
     pdo::{PredefinedPdoAssignment, RxPdo, TxPdo},
-    shared_config::el70x1::EL70x1OperationMode,
+    shared_config::el70x1::{
+        EL70x1MotorConfigurableDevice, EL70x1MotorConfiguration, EL70x1OperationMode,
+    },
 };
 use anyhow::anyhow;
 
@@ -27,6 +36,7 @@ pub struct EL7041_0052 {
 
     // encoder wrapping
     pub counter_wrapper: CounterWrapperU16U128,
+    pub position_interface: EL70x1PositionInterface,
 }
 
 impl NewEthercatDevice for EL7041_0052 {
@@ -38,38 +48,27 @@ impl NewEthercatDevice for EL7041_0052 {
             is_used: false,
             configuration,
             counter_wrapper: CounterWrapperU16U128::new(),
+            position_interface: EL70x1PositionInterface::new(),
         }
     }
 }
 
 impl EthercatDeviceProcessing for EL7041_0052 {
     fn input_post_process(&mut self) -> Result<(), anyhow::Error> {
-        let enc_status_compact = match &self.txpdo.enc_status_compact {
-            Some(value) => value,
-            None => return Err(anyhow!("enc_status_compact is None")),
-        };
-
-        // update the counter wrapper
-        self.counter_wrapper.update(
-            enc_status_compact.counter_value,
-            enc_status_compact.counter_underflow,
-            enc_status_compact.counter_overflow,
-        );
+        // the compact encoder status is only mapped in the velocity pdo assignments
+        if let Some(enc_status_compact) = &self.txpdo.enc_status_compact {
+            // update the counter wrapper
+            self.counter_wrapper.update(
+                enc_status_compact.counter_value,
+                enc_status_compact.counter_underflow,
+                enc_status_compact.counter_overflow,
+            );
+        }
 
         Ok(())
     }
 
     fn output_pre_process(&mut self) -> Result<(), anyhow::Error> {
-        let enc_status_compact = match &self.txpdo.enc_status_compact {
-            Some(value) => value,
-            None => return Err(anyhow!("enc_status_compact is None")),
-        };
-
-        let enc_control_compact = match &mut self.rxpdo.enc_control_compact {
-            Some(value) => value,
-            None => return Err(anyhow!("enc_control_compact is None")),
-        };
-
         let stm_status = match &self.txpdo.stm_status {
             Some(value) => value,
             None => return Err(anyhow!("stm_status is None")),
@@ -85,6 +84,25 @@ impl EthercatDeviceProcessing for EL7041_0052 {
             stm_control.reset = true;
         }
 
+        // positioning interface
+        if let (Some(pos_control), Some(enc_control)) =
+            (&mut self.rxpdo.pos_control, &mut self.rxpdo.enc_control)
+        {
+            self.position_interface
+                .output_pre_process(pos_control, enc_control);
+            return Ok(());
+        }
+
+        let enc_status_compact = match &self.txpdo.enc_status_compact {
+            Some(value) => value,
+            None => return Err(anyhow!("enc_status_compact is None")),
+        };
+
+        let enc_control_compact = match &mut self.rxpdo.enc_control_compact {
+            Some(value) => value,
+            None => return Err(anyhow!("enc_control_compact is None")),
+        };
+
         // clear counter overflow/underflow flags by setting the counter to the current value
         if enc_status_compact.counter_overflow || enc_status_compact.counter_underflow {
             enc_control_compact.set_counter = true;
@@ -174,6 +192,12 @@ impl StepperVelocityEL70x1Device<EL7041_0052Port> for EL7041_0052 {
                     None => return Err(anyhow!("stm_status is None")),
                 };
 
+                let (info_data_1, info_data_2) = EL70x1InfoDataValue::from_pdo(
+                    self.txpdo.stm_synchron_info_data.as_ref(),
+                    self.configuration.stm_features.select_info_data_1,
+                    self.configuration.stm_features.select_info_data_2,
+                );
+
                 Ok(StepperVelocityEL70x1Input {
                     // Use the counter wrapper to get the current counter value
                     counter_value: self.counter_wrapper.current(),
@@ -184,6 +208,8 @@ impl StepperVelocityEL70x1Device<EL7041_0052Port> for EL7041_0052 {
                     moving_positive: stm_status.moving_positive,
                     moving_negative: stm_status.moving_negative,
                     torque_reduced: stm_status.torque_reduced,
+                    info_data_1,
+                    info_data_2,
                 })
             }
             _ => Err(anyhow!(
@@ -240,6 +266,144 @@ impl StepperVelocityEL70x1Device<EL7041_0052Port> for EL7041_0052 {
     }
 }
 
+impl StepperPositionEL70x1Device<EL7041_0052Port> for EL7041_0052 {
+    fn set_output(
+        &mut self,
+        port: EL7041_0052Port,
+        value: StepperPositionEL70x1Output,
+    ) -> Result<(), anyhow::Error> {
+        self.check_position_controller()?;
+
+        match port {
+            EL7041_0052Port::STM1 => {
+                let stm_control = match &mut self.rxpdo.stm_control {
+                    Some(value) => value,
+                    None => return Err(anyhow!("stm_control is None")),
+                };
+
+                let pos_control = match &mut self.rxpdo.pos_control {
+                    Some(value) => value,
+                    None => return Err(anyhow!("pos_control is None")),
+                };
+
+                self.position_interface
+                    .set_output(stm_control, pos_control, &value);
+                Ok(())
+            }
+            _ => Err(anyhow!(
+                "Port {:?} is not supported for stepper position",
+                port
+            )),
+        }
+    }
+
+    fn get_input(
+        &self,
+        port: EL7041_0052Port,
+    ) -> Result<StepperPositionEL70x1Input, anyhow::Error> {
+        self.check_position_controller()?;
+
+        match port {
+            EL7041_0052Port::STM1 => {
+                let stm_status = match &self.txpdo.stm_status {
+                    Some(value) => value,
+                    None => return Err(anyhow!("stm_status is None")),
+                };
+
+                let pos_status = match &self.txpdo.pos_status {
+                    Some(value) => value,
+                    None => return Err(anyhow!("pos_status is None")),
+                };
+
+                let enc_status = match &self.txpdo.enc_status {
+                    Some(value) => value,
+                    None => return Err(anyhow!("enc_status is None")),
+                };
+
+                Ok(EL70x1PositionInterface::get_input(
+                    stm_status, pos_status, enc_status,
+                ))
+            }
+            _ => Err(anyhow!(
+                "Port {:?} is not supported for stepper position",
+                port
+            )),
+        }
+    }
+
+    fn get_output(
+        &self,
+        port: EL7041_0052Port,
+    ) -> Result<StepperPositionEL70x1Output, anyhow::Error> {
+        self.check_position_controller()?;
+
+        match port {
+            EL7041_0052Port::STM1 => {
+                let stm_control = match &self.rxpdo.stm_control {
+                    Some(value) => value,
+                    None => return Err(anyhow!("stm_control is None")),
+                };
+
+                let pos_control = match &self.rxpdo.pos_control {
+                    Some(value) => value,
+                    None => return Err(anyhow!("pos_control is None")),
+                };
+
+                Ok(self.position_interface.get_output(stm_control, pos_control))
+            }
+            _ => Err(anyhow!(
+                "Port {:?} is not supported for stepper position",
+                port
+            )),
+        }
+    }
+
+    fn get_speed_range(
+        &self,
+        _port: EL7041_0052Port,
+    ) -> crate::shared_config::el70x1::EL70x1SpeedRange {
+        self.configuration.stm_features.speed_range
+    }
+}
+
+impl EL7041_0052 {
+    fn check_position_controller(&self) -> Result<(), anyhow::Error> {
+        if self.configuration.stm_features.operation_mode != EL70x1OperationMode::PositionController
+        {
+            return Err(anyhow!(
+                "Operation mode is not position controller, but {:?}",
+                self.configuration.stm_features.operation_mode
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl EL70x1MotorConfigurableDevice for EL7041_0052 {
+    fn get_motor_configuration(&self) -> EL70x1MotorConfiguration {
+        EL70x1MotorConfiguration {
+            encoder: self.configuration.encoder.clone(),
+            stm_motor: self.configuration.stm_motor.clone(),
+            stm_controller_1: self.configuration.stm_controller_1.clone(),
+            stm_controller_2: self.configuration.stm_controller_2.clone(),
+        }
+    }
+
+    fn set_motor_configuration(&mut self, configuration: EL70x1MotorConfiguration) {
+        self.configuration.encoder = configuration.encoder;
+        self.configuration.stm_motor = configuration.stm_motor;
+        self.configuration.stm_controller_1 = configuration.stm_controller_1;
+        self.configuration.stm_controller_2 = configuration.stm_controller_2;
+    }
+
+    fn is_motor_enabled(&self) -> bool {
+        self.rxpdo
+            .stm_control
+            .as_ref()
+            .is_some_and(|stm_control| stm_control.enable)
+    }
+}
+
 impl DigitalInputDevice<EL7041_0052Port> for EL7041_0052 {
     fn get_input(&self, port: EL7041_0052Port) -> Result<DigitalInputInput, anyhow::Error> {
         let error1 = anyhow::anyhow!("stm_status is None");
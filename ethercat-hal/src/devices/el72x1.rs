@@ -0,0 +1,298 @@
+use super::{EthercatDeviceProcessing, NewEthercatDevice, SubDeviceIdentityTuple};
+use crate::{
+    coe::{ConfigurableDevice, Configuration},
+    helpers::{
+        cia402::{Cia402Controller, Cia402ModeOfOperation, Cia402State},
+        ethercrab_types::EthercrabSubDevicePreoperational,
+    },
+    io::cia402_drive::{Cia402DriveDevice, Cia402DriveInput, Cia402DriveOutput},
+    pdo::{
+        PredefinedPdoAssignment, RxPdo, TxPdo,
+        cia402::{
+            DrvControlword, DrvModesOfOperation, DrvModesOfOperationDisplay, DrvStatusword,
+            DrvTargetPosition, DrvTargetVelocity, DrvVelocityActualValue, FbPosition,
+        },
+    },
+};
+use anyhow::anyhow;
+use ethercat_hal_derive::{EthercatDevice, RxPdo, TxPdo};
+
+/// EL7211/EL7221 servo motor terminal with the CiA 402 drive profile
+#[derive(EthercatDevice)]
+pub struct EL72x1 {
+    pub txpdo: EL72x1TxPdo,
+    pub rxpdo: EL72x1RxPdo,
+    pub configuration: EL72x1Configuration,
+    /// Requested by the io layer, applied in [`EthercatDeviceProcessing::output_pre_process`]
+    output: Cia402DriveOutput,
+    controller: Cia402Controller,
+    is_used: bool,
+}
+
+impl EthercatDeviceProcessing for EL72x1 {
+    fn output_pre_process(&mut self) -> Result<(), anyhow::Error> {
+        let statusword = match &self.txpdo.drv_statusword {
+            Some(value) => value.statusword,
+            None => return Err(anyhow!("drv_statusword is None")),
+        };
+
+        // the fault reset request is done once the fault is gone
+        if Cia402State::from_statusword(statusword) != Cia402State::Fault {
+            self.output.fault_reset = false;
+        }
+
+        let controlword = self
+            .controller
+            .controlword(statusword, &self.output.request());
+        self.output.new_setpoint = false;
+
+        match &mut self.rxpdo.drv_controlword {
+            Some(drv_controlword) => drv_controlword.controlword = controlword,
+            None => return Err(anyhow!("drv_controlword is None")),
+        }
+        if let Some(drv_target_velocity) = &mut self.rxpdo.drv_target_velocity {
+            drv_target_velocity.target_velocity = self.output.target_velocity;
+        }
+        if let Some(drv_target_position) = &mut self.rxpdo.drv_target_position {
+            drv_target_position.target_position = self.output.target_position;
+        }
+        if let Some(drv_modes_of_operation) = &mut self.rxpdo.drv_modes_of_operation {
+            drv_modes_of_operation.modes_of_operation = self.output.mode_of_operation.value();
+        }
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for EL72x1 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EL72x1")
+    }
+}
+
+impl NewEthercatDevice for EL72x1 {
+    fn new() -> Self {
+        let configuration = EL72x1Configuration::default();
+        Self {
+            txpdo: configuration.pdo_assignment.txpdo_assignment(),
+            rxpdo: configuration.pdo_assignment.rxpdo_assignment(),
+            output: Cia402DriveOutput {
+                mode_of_operation: configuration.mode_of_operation,
+                ..Default::default()
+            },
+            configuration,
+            controller: Cia402Controller::new(),
+            is_used: false,
+        }
+    }
+}
+
+impl Cia402DriveDevice<EL72x1Port> for EL72x1 {
+    fn set_output(
+        &mut self,
+        port: EL72x1Port,
+        value: Cia402DriveOutput,
+    ) -> Result<(), anyhow::Error> {
+        match port {
+            EL72x1Port::DRV1 => {
+                // without the modes of operation PDO the mode is fixed by the configuration
+                if self.rxpdo.drv_modes_of_operation.is_none()
+                    && value.mode_of_operation != self.configuration.mode_of_operation
+                {
+                    return Err(anyhow!(
+                        "[{}::EL72x1::set_output] Mode {:?} can't be set, the PDO assignment only allows {:?}",
+                        module_path!(),
+                        value.mode_of_operation,
+                        self.configuration.mode_of_operation
+                    ));
+                }
+                self.output = value;
+                Ok(())
+            }
+        }
+    }
+
+    fn get_output(&self, port: EL72x1Port) -> Result<Cia402DriveOutput, anyhow::Error> {
+        match port {
+            EL72x1Port::DRV1 => Ok(self.output.clone()),
+        }
+    }
+
+    fn get_input(&self, port: EL72x1Port) -> Result<Cia402DriveInput, anyhow::Error> {
+        match port {
+            EL72x1Port::DRV1 => {
+                let statusword = match &self.txpdo.drv_statusword {
+                    Some(value) => value.statusword,
+                    None => return Err(anyhow!("drv_statusword is None")),
+                };
+                let position = match &self.txpdo.fb_position {
+                    Some(value) => value.position,
+                    None => return Err(anyhow!("fb_position is None")),
+                };
+                Ok(Cia402DriveInput {
+                    statusword,
+                    position,
+                    velocity: self
+                        .txpdo
+                        .drv_velocity_actual_value
+                        .as_ref()
+                        .map(|value| value.velocity),
+                    mode_of_operation_display: self
+                        .txpdo
+                        .drv_modes_of_operation_display
+                        .as_ref()
+                        .and_then(|value| {
+                            Cia402ModeOfOperation::from_value(value.modes_of_operation_display)
+                        }),
+                })
+            }
+        }
+    }
+}
+
+impl ConfigurableDevice<EL72x1Configuration> for EL72x1 {
+    async fn write_config<'maindevice>(
+        &mut self,
+        device: &EthercrabSubDevicePreoperational<'maindevice>,
+        config: &EL72x1Configuration,
+    ) -> Result<(), anyhow::Error> {
+        config.write_config(device).await?;
+        self.configuration = config.clone();
+        self.txpdo = config.pdo_assignment.txpdo_assignment();
+        self.rxpdo = config.pdo_assignment.rxpdo_assignment();
+        self.output.mode_of_operation = config.mode_of_operation;
+        Ok(())
+    }
+
+    fn get_config(&self) -> EL72x1Configuration {
+        self.configuration.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum EL72x1Port {
+    DRV1,
+}
+
+#[derive(Debug, Clone, TxPdo)]
+pub struct EL72x1TxPdo {
+    #[pdo_object_index(0x1A00)]
+    pub fb_position: Option<FbPosition>,
+    #[pdo_object_index(0x1A01)]
+    pub drv_statusword: Option<DrvStatusword>,
+    #[pdo_object_index(0x1A02)]
+    pub drv_velocity_actual_value: Option<DrvVelocityActualValue>,
+    #[pdo_object_index(0x1A0E)]
+    pub drv_modes_of_operation_display: Option<DrvModesOfOperationDisplay>,
+}
+
+#[derive(Debug, Clone, RxPdo)]
+pub struct EL72x1RxPdo {
+    #[pdo_object_index(0x1600)]
+    pub drv_controlword: Option<DrvControlword>,
+    #[pdo_object_index(0x1601)]
+    pub drv_target_velocity: Option<DrvTargetVelocity>,
+    #[pdo_object_index(0x1606)]
+    pub drv_target_position: Option<DrvTargetPosition>,
+    #[pdo_object_index(0x1608)]
+    pub drv_modes_of_operation: Option<DrvModesOfOperation>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EL72x1Configuration {
+    pub pdo_assignment: EL72x1PredefinedPdoAssignment,
+    /// Mode after startup, with [`EL72x1PredefinedPdoAssignment::Cia402`] it can be changed while running
+    pub mode_of_operation: Cia402ModeOfOperation,
+}
+
+impl Configuration for EL72x1Configuration {
+    async fn write_config<'a>(
+        &self,
+        device: &EthercrabSubDevicePreoperational<'a>,
+    ) -> Result<(), anyhow::Error> {
+        // 0x7010:03 Modes of operation
+        device
+            .sdo_write(0x7010, 0x03, self.mode_of_operation.value())
+            .await?;
+        self.pdo_assignment
+            .txpdo_assignment()
+            .write_config(device)
+            .await?;
+        self.pdo_assignment
+            .rxpdo_assignment()
+            .write_config(device)
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub enum EL72x1PredefinedPdoAssignment {
+    /// All objects, the mode can be switched at runtime
+    #[default]
+    Cia402,
+    /// Only velocity objects, the mode has to be CSV
+    CyclicSynchronousVelocity,
+    /// Only position objects, the mode has to be CSP or PP
+    CyclicSynchronousPosition,
+}
+
+impl PredefinedPdoAssignment<EL72x1TxPdo, EL72x1RxPdo> for EL72x1PredefinedPdoAssignment {
+    fn txpdo_assignment(&self) -> EL72x1TxPdo {
+        match self {
+            Self::Cia402 => EL72x1TxPdo {
+                fb_position: Some(FbPosition::default()),
+                drv_statusword: Some(DrvStatusword::default()),
+                drv_velocity_actual_value: Some(DrvVelocityActualValue::default()),
+                drv_modes_of_operation_display: Some(DrvModesOfOperationDisplay::default()),
+            },
+            Self::CyclicSynchronousVelocity | Self::CyclicSynchronousPosition => EL72x1TxPdo {
+                fb_position: Some(FbPosition::default()),
+                drv_statusword: Some(DrvStatusword::default()),
+                drv_velocity_actual_value: None,
+                drv_modes_of_operation_display: None,
+            },
+        }
+    }
+
+    fn rxpdo_assignment(&self) -> EL72x1RxPdo {
+        match self {
+            Self::Cia402 => EL72x1RxPdo {
+                drv_controlword: Some(DrvControlword::default()),
+                drv_target_velocity: Some(DrvTargetVelocity::default()),
+                drv_target_position: Some(DrvTargetPosition::default()),
+                drv_modes_of_operation: Some(DrvModesOfOperation::default()),
+            },
+            Self::CyclicSynchronousVelocity => EL72x1RxPdo {
+                drv_controlword: Some(DrvControlword::default()),
+                drv_target_velocity: Some(DrvTargetVelocity::default()),
+                drv_target_position: None,
+                drv_modes_of_operation: None,
+            },
+            Self::CyclicSynchronousPosition => EL72x1RxPdo {
+                drv_controlword: Some(DrvControlword::default()),
+                drv_target_velocity: None,
+                drv_target_position: Some(DrvTargetPosition::default()),
+                drv_modes_of_operation: None,
+            },
+        }
+    }
+}
+
+pub const EL7211_VENDOR_ID: u32 = 0x2;
+pub const EL7211_PRODUCT_ID: u32 = 0x1c2b3052;
+pub const EL7211_REVISION_A: u32 = 0x00100000;
+pub const EL7211_REVISION_B: u32 = 0x00110000;
+pub const EL7211_IDENTITY_A: SubDeviceIdentityTuple =
+    (EL7211_VENDOR_ID, EL7211_PRODUCT_ID, EL7211_REVISION_A);
+pub const EL7211_IDENTITY_B: SubDeviceIdentityTuple =
+    (EL7211_VENDOR_ID, EL7211_PRODUCT_ID, EL7211_REVISION_B);
+
+pub const EL7221_VENDOR_ID: u32 = 0x2;
+pub const EL7221_PRODUCT_ID: u32 = 0x1c353052;
+pub const EL7221_REVISION_A: u32 = 0x00100000;
+pub const EL7221_REVISION_B: u32 = 0x00110000;
+pub const EL7221_IDENTITY_A: SubDeviceIdentityTuple =
+    (EL7221_VENDOR_ID, EL7221_PRODUCT_ID, EL7221_REVISION_A);
+pub const EL7221_IDENTITY_B: SubDeviceIdentityTuple =
+    (EL7221_VENDOR_ID, EL7221_PRODUCT_ID, EL7221_REVISION_B);
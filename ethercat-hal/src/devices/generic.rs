@@ -0,0 +1,758 @@
+use super::{
+    EthercatDevice, EthercatDeviceProcessing, EthercatDeviceUsed, Module, NewEthercatDevice,
+    SubDeviceIdentityTuple, subdevice_identity_to_tuple,
+};
+use crate::{
+    coe::{RX_PDO_ASSIGNMENT_REG, TX_PDO_ASSIGNMENT_REG},
+    esi::{EsiDataType, EsiDevice, EsiPdo, read_esi},
+    helpers::ethercrab_types::EthercrabSubDevicePreoperational,
+    io::{
+        analog_input::{AnalogInputDevice, AnalogInputInput, physical::AnalogInputRange},
+        analog_output::{AnalogOutputDevice, AnalogOutputOutput},
+        digital_input::{DigitalInputDevice, DigitalInputInput},
+        digital_output::{DigitalOutputDevice, DigitalOutputOutput},
+    },
+};
+use bitvec::prelude::*;
+use ethercrab::MainDevice;
+use std::path::Path;
+use units::{electric_potential::volt, f64::ElectricPotential};
+
+/// Bytes of the EEPROM read to find the PDO categories
+const SII_IMAGE_SIZE: usize = 2048;
+/// Word address of the first SII category
+const SII_CATEGORIES_WORD: usize = 0x40;
+const SII_CATEGORY_STRINGS: u16 = 10;
+const SII_CATEGORY_TXPDO: u16 = 50;
+const SII_CATEGORY_RXPDO: u16 = 51;
+const SII_CATEGORY_END: u16 = 0xFFFF;
+/// PDOs with this sync manager are not assigned by default
+const SII_SM_UNASSIGNED: u8 = 0xFF;
+
+/// A process data object of a [`GenericPdoLayout`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenericPdoEntry {
+    /// `PDO name.Entry name`, or `0x6000:11` if the source has no names
+    pub name: String,
+    pub index: u16,
+    pub sub_index: u8,
+    /// Offset in the input or output process image of the subdevice
+    pub bit_offset: usize,
+    pub bit_len: usize,
+    pub data_type: EsiDataType,
+}
+
+/// Where the layout of a [`GenericEthercatDevice`] was loaded from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GenericPdoLayoutSource {
+    /// Default assignment of a matching device in an ESI file
+    Esi,
+    /// PDO assignment and mapping objects, named from the SII if possible
+    Coe,
+    /// TxPDO and RxPDO categories of the SII
+    Sii,
+}
+
+/// Process image layout of a subdevice, padding entries only advance the offset
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GenericPdoLayout {
+    /// TxPDO entries
+    pub inputs: Vec<GenericPdoEntry>,
+    /// RxPDO entries
+    pub outputs: Vec<GenericPdoEntry>,
+    pub input_bits: usize,
+    pub output_bits: usize,
+}
+
+/// Collects the entries of one process image
+#[derive(Debug, Default)]
+struct ImageBuilder {
+    entries: Vec<GenericPdoEntry>,
+    bits: usize,
+}
+
+impl ImageBuilder {
+    fn push(
+        &mut self,
+        name: String,
+        index: u16,
+        sub_index: u8,
+        bit_len: usize,
+        data_type: EsiDataType,
+    ) {
+        if index != 0 {
+            self.entries.push(GenericPdoEntry {
+                name,
+                index,
+                sub_index,
+                bit_offset: self.bits,
+                bit_len,
+                data_type,
+            });
+        }
+        self.bits += bit_len;
+    }
+
+    fn push_esi_pdo(&mut self, pdo: &EsiPdo) {
+        for entry in &pdo.entries {
+            self.push(
+                format!("{}.{}", pdo.name, entry.name),
+                entry.index,
+                entry.sub_index,
+                entry.bit_len,
+                entry.data_type.clone(),
+            );
+        }
+    }
+}
+
+impl GenericPdoLayout {
+    fn from_images(inputs: ImageBuilder, outputs: ImageBuilder) -> Self {
+        Self {
+            inputs: inputs.entries,
+            outputs: outputs.entries,
+            input_bits: inputs.bits,
+            output_bits: outputs.bits,
+        }
+    }
+
+    /// Layout of the default PDO assignment of an ESI device
+    pub fn from_esi(device: &EsiDevice) -> Self {
+        let mut inputs = ImageBuilder::default();
+        let mut outputs = ImageBuilder::default();
+        for index in device.default_pdos() {
+            if let Some(pdo) = device.tx_pdos.iter().find(|pdo| pdo.index == index) {
+                inputs.push_esi_pdo(pdo);
+            }
+            if let Some(pdo) = device.rx_pdos.iter().find(|pdo| pdo.index == index) {
+                outputs.push_esi_pdo(pdo);
+            }
+        }
+        Self::from_images(inputs, outputs)
+    }
+
+    /// Layout of the PDOs the SII assigns to a sync manager
+    ///
+    /// `image` is the EEPROM content starting at word 0.
+    pub fn from_sii(image: &[u8]) -> Result<Self, anyhow::Error> {
+        let read_u16 = |offset: usize| {
+            image
+                .get(offset..offset + 2)
+                .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+        };
+
+        let mut categories = vec![];
+        let mut offset = SII_CATEGORIES_WORD * 2;
+        loop {
+            let (Some(category), Some(words)) = (read_u16(offset), read_u16(offset + 2)) else {
+                return Err(anyhow::anyhow!(
+                    "[{}::GenericPdoLayout::from_sii] SII ends without an end category",
+                    module_path!()
+                ));
+            };
+            if category == SII_CATEGORY_END {
+                break;
+            }
+            let start = offset + 4;
+            let end = start + words as usize * 2;
+            let data = image.get(start..end).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "[{}::GenericPdoLayout::from_sii] Category {} exceeds the SII",
+                    module_path!(),
+                    category
+                )
+            })?;
+            categories.push((category, data));
+            offset = end;
+        }
+
+        let strings = categories
+            .iter()
+            .find(|(category, _)| *category == SII_CATEGORY_STRINGS)
+            .map(|(_, data)| sii_strings(data))
+            .unwrap_or_default();
+        // string indices start at 1, 0 is no string
+        let string = |index: u8| {
+            (index as usize)
+                .checked_sub(1)
+                .and_then(|index| strings.get(index))
+                .cloned()
+                .unwrap_or_default()
+        };
+
+        let mut inputs = ImageBuilder::default();
+        let mut outputs = ImageBuilder::default();
+        for (category, data) in &categories {
+            let builder = match *category {
+                SII_CATEGORY_TXPDO => &mut inputs,
+                SII_CATEGORY_RXPDO => &mut outputs,
+                _ => continue,
+            };
+            // 8 byte PDO header followed by 8 bytes per entry
+            let mut data: &[u8] = data;
+            while let Some((header, rest)) = data.split_at_checked(8) {
+                let entry_count = header[2] as usize;
+                let sm = header[3];
+                let pdo_name = string(header[5]);
+                let Some((entries, rest)) = rest.split_at_checked(entry_count * 8) else {
+                    break;
+                };
+                data = rest;
+                if sm == SII_SM_UNASSIGNED {
+                    continue;
+                }
+                for entry in entries.chunks_exact(8) {
+                    let index = u16::from_le_bytes([entry[0], entry[1]]);
+                    let bit_len = entry[5] as usize;
+                    builder.push(
+                        format!("{}.{}", pdo_name, string(entry[3])),
+                        index,
+                        entry[2],
+                        bit_len,
+                        data_type_from_coe(entry[4], bit_len),
+                    );
+                }
+            }
+        }
+        Ok(Self::from_images(inputs, outputs))
+    }
+
+    /// Reads the layout from the PDO assignment and mapping objects, only works on CoE subdevices
+    pub async fn read_coe(
+        subdevice: &EthercrabSubDevicePreoperational<'_>,
+    ) -> Result<Self, anyhow::Error> {
+        let inputs = read_coe_image(subdevice, TX_PDO_ASSIGNMENT_REG).await?;
+        let outputs = read_coe_image(subdevice, RX_PDO_ASSIGNMENT_REG).await?;
+        Ok(Self::from_images(inputs, outputs))
+    }
+
+    /// Takes names and data types from entries of `other` with the same object
+    pub fn describe_from(&mut self, other: &Self) {
+        let describe = |entries: &mut Vec<GenericPdoEntry>, others: &[GenericPdoEntry]| {
+            for entry in entries.iter_mut() {
+                if let Some(other) = others.iter().find(|other| {
+                    other.index == entry.index
+                        && other.sub_index == entry.sub_index
+                        && other.bit_len == entry.bit_len
+                }) {
+                    entry.name = other.name.clone();
+                    entry.data_type = other.data_type.clone();
+                }
+            }
+        };
+        describe(&mut self.inputs, &other.inputs);
+        describe(&mut self.outputs, &other.outputs);
+    }
+}
+
+fn sii_strings(data: &[u8]) -> Vec<String> {
+    let mut strings = vec![];
+    let Some((&count, mut rest)) = data.split_first() else {
+        return strings;
+    };
+    for _ in 0..count {
+        let Some((&len, tail)) = rest.split_first() else {
+            break;
+        };
+        let Some((string, tail)) = tail.split_at_checked(len as usize) else {
+            break;
+        };
+        // SII strings are ISO-8859-1
+        strings.push(string.iter().map(|byte| *byte as char).collect());
+        rest = tail;
+    }
+    strings
+}
+
+/// Maps CoE base data type codes (ETG.1000.6) as used in the SII
+fn data_type_from_coe(code: u8, bit_len: usize) -> EsiDataType {
+    match code {
+        0x00 => EsiDataType::Padding,
+        0x01 if bit_len == 1 => EsiDataType::Bool,
+        0x02..=0x04 | 0x10 | 0x12..=0x15 => EsiDataType::Signed(bit_len),
+        0x05..=0x07 | 0x16 | 0x18..=0x1B | 0x30..=0x37 => EsiDataType::Unsigned(bit_len),
+        0x08 if bit_len == 32 => EsiDataType::Real,
+        0x11 if bit_len == 64 => EsiDataType::LReal,
+        other => EsiDataType::Other(format!("{:#04x}", other)),
+    }
+}
+
+async fn read_coe_image(
+    subdevice: &EthercrabSubDevicePreoperational<'_>,
+    assignment_index: u16,
+) -> Result<ImageBuilder, anyhow::Error> {
+    let mut image = ImageBuilder::default();
+    let pdo_count = subdevice.sdo_read::<u8>(assignment_index, 0).await?;
+    for pdo_sub_index in 1..=pdo_count {
+        let pdo_index = subdevice
+            .sdo_read::<u16>(assignment_index, pdo_sub_index)
+            .await?;
+        let entry_count = subdevice.sdo_read::<u8>(pdo_index, 0).await?;
+        for entry_sub_index in 1..=entry_count {
+            // index << 16 | sub index << 8 | bit length
+            let mapping = subdevice
+                .sdo_read::<u32>(pdo_index, entry_sub_index)
+                .await?;
+            let index = (mapping >> 16) as u16;
+            let sub_index = (mapping >> 8) as u8;
+            let bit_len = (mapping & 0xFF) as usize;
+            let data_type = match bit_len {
+                1 => EsiDataType::Bool,
+                _ => EsiDataType::Unsigned(bit_len),
+            };
+            image.push(
+                format!("{:#06x}:{:02x}", index, sub_index),
+                index,
+                sub_index,
+                bit_len,
+                data_type,
+            );
+        }
+    }
+    Ok(image)
+}
+
+/// Searches the `.xml` files in `dir` for a device with this exact identity, a missing directory finds nothing
+pub fn find_esi_device(
+    dir: &Path,
+    identity: SubDeviceIdentityTuple,
+) -> Result<Option<EsiDevice>, anyhow::Error> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(anyhow::anyhow!(
+                "[{}::find_esi_device] Failed to read {}: {}",
+                module_path!(),
+                dir.display(),
+                e
+            ));
+        }
+    };
+
+    let (vendor_id, product_id, revision) = identity;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("xml"))
+        {
+            continue;
+        }
+        let file = match read_esi(&path) {
+            Ok(file) => file,
+            Err(e) => {
+                tracing::warn!("Skipping ESI file {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        if file.vendor_id != vendor_id {
+            continue;
+        }
+        if let Some(device) = file.device(product_id, revision) {
+            return Ok(Some(device.clone()));
+        }
+    }
+    Ok(None)
+}
+
+/// An input object of a [`GenericEthercatDevice`], see [`GenericEthercatDevice::input_port`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GenericInputPort(usize);
+
+/// An output object of a [`GenericEthercatDevice`], see [`GenericEthercatDevice::output_port`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GenericOutputPort(usize);
+
+/// Device for terminals without a driver
+///
+/// The PDO layout is loaded at runtime with [`GenericEthercatDevice::load_layout`].
+/// Objects are addressed by name and read as digital, analog or integer values.
+pub struct GenericEthercatDevice {
+    pub layout: GenericPdoLayout,
+    pub layout_source: Option<GenericPdoLayoutSource>,
+    /// Used for all analog inputs, defaults to ±10V
+    pub analog_input_range: AnalogInputRange,
+    input_image: BitVec<u8, Lsb0>,
+    output_image: BitVec<u8, Lsb0>,
+    is_used: bool,
+}
+
+impl std::fmt::Debug for GenericEthercatDevice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "GenericEthercatDevice")
+    }
+}
+
+impl NewEthercatDevice for GenericEthercatDevice {
+    fn new() -> Self {
+        Self {
+            layout: GenericPdoLayout::default(),
+            layout_source: None,
+            analog_input_range: AnalogInputRange::Potential {
+                min: ElectricPotential::new::<volt>(-10.0),
+                max: ElectricPotential::new::<volt>(10.0),
+                min_raw: i16::MIN,
+                max_raw: i16::MAX,
+            },
+            input_image: BitVec::new(),
+            output_image: BitVec::new(),
+            is_used: false,
+        }
+    }
+}
+
+impl EthercatDeviceProcessing for GenericEthercatDevice {}
+
+impl EthercatDeviceUsed for GenericEthercatDevice {
+    fn is_used(&self) -> bool {
+        self.is_used
+    }
+
+    fn set_used(&mut self, used: bool) {
+        self.is_used = used;
+    }
+}
+
+impl EthercatDevice for GenericEthercatDevice {
+    fn input(&mut self, input: &BitSlice<u8, Lsb0>) -> Result<(), anyhow::Error> {
+        let len = self.input_image.len().min(input.len());
+        self.input_image[..len].copy_from_bitslice(&input[..len]);
+        Ok(())
+    }
+
+    fn input_len(&self) -> usize {
+        self.layout.input_bits
+    }
+
+    fn output(&self, output: &mut BitSlice<u8, Lsb0>) -> Result<(), anyhow::Error> {
+        let len = self.output_image.len().min(output.len());
+        output[..len].copy_from_bitslice(&self.output_image[..len]);
+        Ok(())
+    }
+
+    fn output_len(&self) -> usize {
+        self.layout.output_bits
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+
+    fn is_module(&self) -> bool {
+        false
+    }
+
+    fn get_module(&self) -> Option<Module> {
+        None
+    }
+
+    fn set_module(&mut self, _module: Module) {}
+}
+
+impl GenericEthercatDevice {
+    pub fn set_layout(&mut self, layout: GenericPdoLayout, source: GenericPdoLayoutSource) {
+        self.input_image = bitvec![u8, Lsb0; 0; layout.input_bits];
+        self.output_image = bitvec![u8, Lsb0; 0; layout.output_bits];
+        self.layout = layout;
+        self.layout_source = Some(source);
+    }
+
+    /// Loads the layout from a matching ESI file in `esi_dir`, otherwise from the CoE mapping or the SII
+    ///
+    /// Has to be called in preoperational state before the process image is mapped.
+    pub async fn load_layout(
+        &mut self,
+        subdevice: &EthercrabSubDevicePreoperational<'_>,
+        maindevice: &MainDevice<'_>,
+        esi_dir: &Path,
+    ) -> Result<GenericPdoLayoutSource, anyhow::Error> {
+        let identity = subdevice_identity_to_tuple(&subdevice.identity());
+        if let Some(device) = find_esi_device(esi_dir, identity)? {
+            self.set_layout(
+                GenericPdoLayout::from_esi(&device),
+                GenericPdoLayoutSource::Esi,
+            );
+            return Ok(GenericPdoLayoutSource::Esi);
+        }
+
+        let mut image = vec![0u8; SII_IMAGE_SIZE];
+        let sii = match subdevice.eeprom_read_raw(maindevice, 0, &mut image).await {
+            Ok(_) => GenericPdoLayout::from_sii(&image),
+            Err(e) => Err(anyhow::anyhow!(
+                "[{}::GenericEthercatDevice::load_layout] Failed to read the SII: {:?}",
+                module_path!(),
+                e
+            )),
+        };
+
+        let (layout, source) = match GenericPdoLayout::read_coe(subdevice).await {
+            Ok(mut layout) => {
+                if let Ok(sii) = &sii {
+                    layout.describe_from(sii);
+                }
+                (layout, GenericPdoLayoutSource::Coe)
+            }
+            Err(_) => (sii?, GenericPdoLayoutSource::Sii),
+        };
+        self.set_layout(layout, source);
+        Ok(source)
+    }
+
+    pub fn input_port(&self, name: &str) -> Result<GenericInputPort, anyhow::Error> {
+        self.layout
+            .inputs
+            .iter()
+            .position(|entry| entry.name == name)
+            .map(GenericInputPort)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "[{}::GenericEthercatDevice::input_port] No input named {:?}",
+                    module_path!(),
+                    name
+                )
+            })
+    }
+
+    pub fn output_port(&self, name: &str) -> Result<GenericOutputPort, anyhow::Error> {
+        self.layout
+            .outputs
+            .iter()
+            .position(|entry| entry.name == name)
+            .map(GenericOutputPort)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "[{}::GenericEthercatDevice::output_port] No output named {:?}",
+                    module_path!(),
+                    name
+                )
+            })
+    }
+
+    /// Reads an integer or boolean input, signed types are sign extended
+    pub fn get_integer(&self, port: GenericInputPort) -> Result<i64, anyhow::Error> {
+        let entry = &self.layout.inputs[port.0];
+        read_integer(entry, &self.input_image)
+    }
+
+    /// Reads an input as a float, integers are converted
+    pub fn get_real(&self, port: GenericInputPort) -> Result<f64, anyhow::Error> {
+        let entry = &self.layout.inputs[port.0];
+        read_real(entry, &self.input_image)
+    }
+
+    pub fn get_output_integer(&self, port: GenericOutputPort) -> Result<i64, anyhow::Error> {
+        let entry = &self.layout.outputs[port.0];
+        read_integer(entry, &self.output_image)
+    }
+
+    /// Writes an integer or boolean output, values are truncated to the bit length
+    pub fn set_integer(
+        &mut self,
+        port: GenericOutputPort,
+        value: i64,
+    ) -> Result<(), anyhow::Error> {
+        let entry = &self.layout.outputs[port.0];
+        let bits = integer_bits_mut(entry, &mut self.output_image)?;
+        bits.store_le(value as u64 & mask(entry.bit_len));
+        Ok(())
+    }
+}
+
+/// Largest unsigned value with `bit_len` bits
+const fn mask(bit_len: usize) -> u64 {
+    match bit_len {
+        0 => 0,
+        1..=63 => (1 << bit_len) - 1,
+        _ => u64::MAX,
+    }
+}
+
+fn integer_bits<'a>(
+    entry: &GenericPdoEntry,
+    image: &'a BitSlice<u8, Lsb0>,
+) -> Result<&'a BitSlice<u8, Lsb0>, anyhow::Error> {
+    match entry.data_type {
+        EsiDataType::Bool | EsiDataType::Unsigned(1..=64) | EsiDataType::Signed(1..=64) => image
+            .get(entry.bit_offset..entry.bit_offset + entry.bit_len)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "[{}::integer_bits] {} is outside of the process image",
+                    module_path!(),
+                    entry.name
+                )
+            }),
+        _ => Err(anyhow::anyhow!(
+            "[{}::integer_bits] {} is not an integer but {:?}",
+            module_path!(),
+            entry.name,
+            entry.data_type
+        )),
+    }
+}
+
+fn integer_bits_mut<'a>(
+    entry: &GenericPdoEntry,
+    image: &'a mut BitSlice<u8, Lsb0>,
+) -> Result<&'a mut BitSlice<u8, Lsb0>, anyhow::Error> {
+    integer_bits(entry, image)?;
+    Ok(&mut image[entry.bit_offset..entry.bit_offset + entry.bit_len])
+}
+
+fn read_integer(entry: &GenericPdoEntry, image: &BitSlice<u8, Lsb0>) -> Result<i64, anyhow::Error> {
+    let raw = integer_bits(entry, image)?.load_le::<u64>();
+    Ok(match entry.data_type {
+        // shift the sign bit to the top and back
+        EsiDataType::Signed(bit_len) => ((raw << (64 - bit_len)) as i64) >> (64 - bit_len),
+        _ => raw as i64,
+    })
+}
+
+fn read_real(entry: &GenericPdoEntry, image: &BitSlice<u8, Lsb0>) -> Result<f64, anyhow::Error> {
+    let bits = image.get(entry.bit_offset..entry.bit_offset + entry.bit_len);
+    match (&entry.data_type, bits) {
+        (EsiDataType::Real, Some(bits)) => Ok(f64::from(f32::from_bits(bits.load_le::<u32>()))),
+        (EsiDataType::LReal, Some(bits)) => Ok(f64::from_bits(bits.load_le::<u64>())),
+        _ => Ok(read_integer(entry, image)? as f64),
+    }
+}
+
+/// Scales integers to -1.0 to 1.0 for signed and 0.0 to 1.0 for unsigned types
+fn normalized(entry: &GenericPdoEntry, value: f64) -> f64 {
+    match entry.data_type {
+        EsiDataType::Signed(bit_len) => value / mask(bit_len.saturating_sub(1)) as f64,
+        EsiDataType::Bool | EsiDataType::Unsigned(_) => value / mask(entry.bit_len) as f64,
+        _ => value,
+    }
+}
+
+impl DigitalInputDevice<GenericInputPort> for GenericEthercatDevice {
+    fn get_input(&self, port: GenericInputPort) -> Result<DigitalInputInput, anyhow::Error> {
+        Ok(DigitalInputInput {
+            value: self.get_integer(port)? != 0,
+        })
+    }
+}
+
+impl AnalogInputDevice<GenericInputPort> for GenericEthercatDevice {
+    fn get_input(&self, port: GenericInputPort) -> AnalogInputInput {
+        let entry = &self.layout.inputs[port.0];
+        read_real(entry, &self.input_image).map_or(
+            AnalogInputInput {
+                normalized: 0.0,
+                wiring_error: true,
+            },
+            |value| AnalogInputInput {
+                normalized: normalized(entry, value) as f32,
+                wiring_error: false,
+            },
+        )
+    }
+
+    fn analog_input_range(&self) -> AnalogInputRange {
+        self.analog_input_range.clone()
+    }
+}
+
+impl DigitalOutputDevice<GenericOutputPort> for GenericEthercatDevice {
+    fn set_output(&mut self, port: GenericOutputPort, value: DigitalOutputOutput) {
+        let _ = self.set_integer(port, i64::from(value.0));
+    }
+
+    fn get_output(&self, port: GenericOutputPort) -> DigitalOutputOutput {
+        DigitalOutputOutput(self.get_output_integer(port).is_ok_and(|value| value != 0))
+    }
+}
+
+impl AnalogOutputDevice<GenericOutputPort> for GenericEthercatDevice {
+    fn set_output(&mut self, port: GenericOutputPort, value: AnalogOutputOutput) {
+        let entry = &self.layout.outputs[port.0];
+        let value = match entry.data_type {
+            EsiDataType::Signed(bit_len) => {
+                (value.0.clamp(-1.0, 1.0) as f64 * mask(bit_len.saturating_sub(1)) as f64) as i64
+            }
+            _ => (value.0.clamp(0.0, 1.0) as f64 * mask(entry.bit_len) as f64) as i64,
+        };
+        let _ = self.set_integer(port, value);
+    }
+
+    fn get_output(&self, port: GenericOutputPort) -> AnalogOutputOutput {
+        let entry = &self.layout.outputs[port.0];
+        let value = self.get_output_integer(port).unwrap_or_default();
+        AnalogOutputOutput(normalized(entry, value as f64) as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::esi::{parse_esi, tests::EL3001_ESI};
+
+    const EL2004_SII: &[u8] = include_bytes!("../../../ethercat-eeprom-dump/dumps/EL2004.bin");
+    const EL3021_SII: &[u8] = include_bytes!("../../../ethercat-eeprom-dump/dumps/EL3021.bin");
+
+    #[test]
+    fn test_layout_from_sii() {
+        let layout = GenericPdoLayout::from_sii(EL3021_SII).unwrap();
+        // the compact PDO is not assigned
+        assert_eq!(layout.input_bits, 32);
+        assert_eq!(layout.output_bits, 0);
+        let value = layout.inputs.last().unwrap();
+        assert_eq!(value.name, "AI Standard.Value");
+        assert_eq!((value.index, value.sub_index), (0x6000, 17));
+        assert_eq!(value.bit_offset, 16);
+        assert_eq!(value.data_type, EsiDataType::Signed(16));
+
+        let layout = GenericPdoLayout::from_sii(EL2004_SII).unwrap();
+        assert_eq!(layout.output_bits, 4);
+        assert_eq!(layout.outputs[2].name, "Channel 3.Output");
+        assert_eq!(layout.outputs[2].bit_offset, 2);
+        assert_eq!(layout.outputs[2].data_type, EsiDataType::Bool);
+    }
+
+    #[test]
+    fn test_generic_device_io() {
+        let file = parse_esi(EL3001_ESI).unwrap();
+        let mut device = GenericEthercatDevice::new();
+        device.set_layout(
+            GenericPdoLayout::from_esi(&file.devices[0]),
+            GenericPdoLayoutSource::Esi,
+        );
+        assert_eq!(device.input_len(), 32);
+        assert!(device.input_port("AI Compact Channel 1.Value").is_err());
+
+        let underrange = device
+            .input_port("AI Standard Channel 1.Status__Underrange")
+            .unwrap();
+        let value = device.input_port("AI Standard Channel 1.Value").unwrap();
+        let mut input = bitvec![u8, Lsb0; 0; 32];
+        input.set(0, true);
+        input[16..32].store_le(-16384i16 as u16);
+        device.input(&input).unwrap();
+
+        assert!(
+            DigitalInputDevice::get_input(&device, underrange)
+                .unwrap()
+                .value
+        );
+        assert_eq!(device.get_integer(value).unwrap(), -16384);
+        let analog = AnalogInputDevice::get_input(&device, value);
+        assert!((analog.normalized + 0.5).abs() < 0.001);
+
+        // a CoE layout only has object names until it is described from the SII
+        let sii = GenericPdoLayout::from_sii(EL2004_SII).unwrap();
+        let mut layout = sii.clone();
+        layout.outputs[2].name = "0x7020:01".to_string();
+        layout.describe_from(&sii);
+        device.set_layout(layout, GenericPdoLayoutSource::Coe);
+        let channel3 = device.output_port("Channel 3.Output").unwrap();
+        DigitalOutputDevice::set_output(&mut device, channel3, DigitalOutputOutput(true));
+        let mut output = bitvec![u8, Lsb0; 0; 8];
+        device.output(&mut output).unwrap();
+        assert_eq!(output.load_le::<u8>(), 0b0100);
+    }
+}
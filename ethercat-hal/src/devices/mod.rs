@@ -5,6 +5,7 @@ pub mod el2002;
 pub mod el2004;
 pub mod el2008;
 pub mod el2024;
+pub mod el2502;
 pub mod el2521;
 pub mod el2522;
 pub mod el2634;
@@ -14,12 +15,17 @@ pub mod el3021;
 pub mod el3024;
 pub mod el3062_0030;
 pub mod el3204;
+pub mod el3314;
+pub mod el3356;
+pub mod el3443;
 pub mod el4002;
 pub mod el5152;
 pub mod el6021;
 pub mod el7031;
 pub mod el7031_0030;
 pub mod el7041_0052;
+pub mod el72x1;
+pub mod generic;
 pub mod wago_750_354;
 pub mod wago_modules;
 
@@ -36,6 +42,7 @@ use el1008::EL1008_IDENTITY_A;
 use el2002::{EL2002, EL2002_IDENTITY_A, EL2002_IDENTITY_B};
 use el2004::{EL2004, EL2004_IDENTITY_A, EL2004_IDENTITY_B};
 use el2008::{EL2008, EL2008_IDENTITY_A, EL2008_IDENTITY_B, EL2008_IDENTITY_C};
+use el2502::{EL2502, EL2502_IDENTITY_A};
 use el2521::{EL2521_IDENTITY_0000_A, EL2521_IDENTITY_0000_B, EL2521_IDENTITY_0024_A};
 use el2522::{EL2522, EL2522_IDENTITY_A};
 use el3001::EL3001_IDENTITY_A;
@@ -44,13 +51,20 @@ use el3024::EL3024_IDENTITY_A;
 use el3062_0030::EL3062_0030_IDENTITY_A;
 use el3204::EL3204_IDENTITY_A;
 use el3204::EL3204_IDENTITY_B;
+use el3314::{EL3314, EL3314_IDENTITY_A};
+use el3356::{EL3356, EL3356_IDENTITY_A};
+use el3443::{EL3443, EL3443_IDENTITY_A};
 use el4002::EL4002_IDENTITY_A;
 use el5152::{EL5152, EL5152_IDENTITY_A};
 use el6021::{EL6021_IDENTITY_A, EL6021_IDENTITY_B, EL6021_IDENTITY_C, EL6021_IDENTITY_D};
 use el7031::{EL7031_IDENTITY_A, EL7031_IDENTITY_B};
 use el7031_0030::EL7031_0030_IDENTITY_A;
 use el7041_0052::EL7041_0052_IDENTITY_A;
+use el72x1::{
+    EL72x1, EL7211_IDENTITY_A, EL7211_IDENTITY_B, EL7221_IDENTITY_A, EL7221_IDENTITY_B,
+};
 use ethercrab::{MainDevice, SubDeviceIdentity};
+use generic::GenericEthercatDevice;
 use smol::lock::RwLock;
 use std::{any::Any, fmt::Debug, sync::Arc};
 use wago_750_354::{WAGO_750_354_IDENTITY_A, Wago750_354};
@@ -88,21 +102,10 @@ where
     /// The accepted length of the input data
     fn input_len(&self) -> usize;
 
-    /// automatically validate input length, then calls input
+    /// Calls input
+    ///
+    /// The input length is not checked every cycle, it is validated once on startup with [`validate_pdo_lengths`]
     fn input_checked(&mut self, input: &BitSlice<u8, Lsb0>) -> Result<(), anyhow::Error> {
-        // validate input has correct length
-        /* let expected = self.input_len();
-        let actual = input.len();
-        if actual != expected {
-            return Err(anyhow::anyhow!(
-                "[{}::Device::input_checked] Input length is {} ({} bytes) and must be {} bits ({} bytes)",
-                module_path!(),
-                actual,
-                actual / 8,
-                expected,
-                expected / 8
-            ));
-        }*/
         self.input(input)
     }
 
@@ -113,24 +116,11 @@ where
     /// The accepted length of the output data
     fn output_len(&self) -> usize;
 
+    /// Calls output
+    ///
+    /// The output length is not checked every cycle, it is validated once on startup with [`validate_pdo_lengths`]
     fn output_checked(&self, output: &mut BitSlice<u8, Lsb0>) -> Result<(), anyhow::Error> {
-        self.output(output)?;
-
-        // validate input has correct length
-        /*let expected = self.output_len();
-        let actual = output.len();
-        if output.len() != expected {
-            return Err(anyhow::anyhow!(
-                "[{}::Device::output_checked] Output length is {} ({} bytes) and must be {} bits ({} bytes)",
-                module_path!(),
-                actual,
-                actual / 8,
-                expected,
-                expected / 8
-            ));
-        }*/
-
-        Ok(())
+        self.output(output)
     }
 
     fn as_any(&self) -> &dyn Any;
@@ -222,6 +212,7 @@ pub fn device_from_subdevice_identity_tuple(
         EL2002_IDENTITY_A | EL2002_IDENTITY_B => Ok(Arc::new(RwLock::new(EL2002::new()))),
         EL2004_IDENTITY_A | EL2004_IDENTITY_B => Ok(Arc::new(RwLock::new(EL2004::new()))),
         EL2008_IDENTITY_A | EL2008_IDENTITY_B | EL2008_IDENTITY_C => Ok(Arc::new(RwLock::new(EL2008::new()))),
+        EL2502_IDENTITY_A => Ok(Arc::new(RwLock::new(EL2502::new()))),
         EL2522_IDENTITY_A => Ok(Arc::new(RwLock::new(EL2522::new()))),
         EL3001_IDENTITY_A => Ok(Arc::new(RwLock::new(el3001::EL3001::new()))),
         EL3021_IDENTITY_A => Ok(Arc::new(RwLock::new(el3021::EL3021::new()))),
@@ -233,9 +224,15 @@ pub fn device_from_subdevice_identity_tuple(
             Ok(Arc::new(RwLock::new(el6021::EL6021::new())))
         }
         EL3204_IDENTITY_A | EL3204_IDENTITY_B => Ok(Arc::new(RwLock::new(el3204::EL3204::new()))),
+        EL3314_IDENTITY_A => Ok(Arc::new(RwLock::new(EL3314::new()))),
+        EL3356_IDENTITY_A => Ok(Arc::new(RwLock::new(EL3356::new()))),
+        EL3443_IDENTITY_A => Ok(Arc::new(RwLock::new(EL3443::new()))),
         EL7031_IDENTITY_A | EL7031_IDENTITY_B => Ok(Arc::new(RwLock::new(el7031::EL7031::new()))),
         EL7031_0030_IDENTITY_A => Ok(Arc::new(RwLock::new(el7031_0030::EL7031_0030::new()))),
         EL7041_0052_IDENTITY_A => Ok(Arc::new(RwLock::new(el7041_0052::EL7041_0052::new()))),
+        EL7211_IDENTITY_A | EL7211_IDENTITY_B | EL7221_IDENTITY_A | EL7221_IDENTITY_B => {
+            Ok(Arc::new(RwLock::new(EL72x1::new())))
+        }
         EL2521_IDENTITY_0000_A | EL2521_IDENTITY_0000_B | EL2521_IDENTITY_0024_A => {
             Ok(Arc::new(RwLock::new(EL2521::new())))
         }
@@ -259,15 +256,20 @@ pub fn device_from_subdevice_identity(
 }
 
 /// Array equivalent of [`device_from_subdevice`]
+///
+/// Subdevices without a driver become a [`GenericEthercatDevice`], its layout is loaded with [`GenericEthercatDevice::load_layout`]
 pub fn devices_from_subdevices<'maindevice, const MAX_SUBDEVICES: usize, const PDI_LEN: usize>(
     group: &mut EthercrabSubDeviceGroupPreoperational<MAX_SUBDEVICES, PDI_LEN>,
     maindevice: &MainDevice,
 ) -> Result<Vec<Arc<RwLock<dyn EthercatDevice>>>, anyhow::Error> {
-    group
+    Ok(group
         .iter(maindevice)
         .map(|subdevice| subdevice.identity())
-        .map(|subdevice_identity| device_from_subdevice_identity(&subdevice_identity))
-        .collect::<Result<Vec<_>, anyhow::Error>>()
+        .map(|subdevice_identity| {
+            device_from_subdevice_identity(&subdevice_identity)
+                .unwrap_or_else(|_| Arc::new(RwLock::new(GenericEthercatDevice::new())))
+        })
+        .collect())
 }
 
 /// Casts a `dyn Device` from an array into a specific device type using [`downcast_device`]
@@ -285,6 +287,62 @@ pub async fn specific_device_from_devices<DEVICE: EthercatDevice>(
     .await
 }
 
+/// Difference between the PDO lengths a device expects and the process image of its subdevice
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PdoLengthMismatch {
+    /// [`EthercatDevice::input_len`] in bits
+    pub expected_input_bits: usize,
+    /// Size of the input process image in bits
+    pub actual_input_bits: usize,
+    /// [`EthercatDevice::output_len`] in bits
+    pub expected_output_bits: usize,
+    /// Size of the output process image in bits
+    pub actual_output_bits: usize,
+}
+
+impl std::fmt::Display for PdoLengthMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "inputs: expected {} bits ({} bytes) got {} bits ({} bytes), outputs: expected {} bits ({} bytes) got {} bits ({} bytes)",
+            self.expected_input_bits,
+            self.expected_input_bits.div_ceil(8),
+            self.actual_input_bits,
+            self.actual_input_bits.div_ceil(8),
+            self.expected_output_bits,
+            self.expected_output_bits.div_ceil(8),
+            self.actual_output_bits,
+            self.actual_output_bits.div_ceil(8),
+        )
+    }
+}
+
+/// Compares [`EthercatDevice::input_len`] and [`EthercatDevice::output_len`] with the process image sizes of the subdevice
+///
+/// Has to be called after the PDO assignment was written and the process image was mapped.
+/// The lengths are compared byte wise since the process image is always reserved in full bytes.
+pub fn validate_pdo_lengths(
+    device: &dyn EthercatDevice,
+    actual_input_bits: usize,
+    actual_output_bits: usize,
+) -> Result<(), PdoLengthMismatch> {
+    let expected_input_bits = device.input_len();
+    let expected_output_bits = device.output_len();
+
+    if expected_input_bits.div_ceil(8) == actual_input_bits.div_ceil(8)
+        && expected_output_bits.div_ceil(8) == actual_output_bits.div_ceil(8)
+    {
+        return Ok(());
+    }
+
+    Err(PdoLengthMismatch {
+        expected_input_bits,
+        actual_input_bits,
+        expected_output_bits,
+        actual_output_bits,
+    })
+}
+
 pub type SubDeviceIdentityTuple = (u32, u32, u32);
 
 // Is vendor id at 0, and prodid at 1
@@ -294,3 +352,34 @@ pub type SubDeviceProductTuple = (u32, u32);
 pub const fn subdevice_identity_to_tuple(identity: &SubDeviceIdentity) -> SubDeviceIdentityTuple {
     (identity.vendor_id, identity.product_id, identity.revision)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::devices::{el2002::EL2002, el3204::EL3204};
+
+    #[test]
+    fn test_validate_pdo_lengths_matching() {
+        // 2 output bits are padded to a full byte
+        let el2002 = EL2002::new();
+        assert_eq!(validate_pdo_lengths(&el2002, 0, 8), Ok(()));
+
+        // 4 channels with 32 bits each
+        let el3204 = EL3204::new();
+        assert_eq!(validate_pdo_lengths(&el3204, 128, 0), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_pdo_lengths_mismatch() {
+        let el3204 = EL3204::new();
+        assert_eq!(
+            validate_pdo_lengths(&el3204, 64, 0),
+            Err(PdoLengthMismatch {
+                expected_input_bits: 128,
+                actual_input_bits: 64,
+                expected_output_bits: 0,
+                actual_output_bits: 0,
+            })
+        );
+    }
+}
@@ -7,7 +7,11 @@ use crate::{
     devices::{
         DynamicEthercatDevice, Module,
         wago_modules::{
+            wago_750_404::{WAGO_750_404_MODULE_IDENT, WAGO_750_404_PRODUCT_ID},
+            wago_750_455::{WAGO_750_455_MODULE_IDENT, WAGO_750_455_PRODUCT_ID},
+            wago_750_464::{WAGO_750_464_MODULE_IDENT, WAGO_750_464_PRODUCT_ID},
             wago_750_501::{WAGO_750_501_MODULE_IDENT, WAGO_750_501_PRODUCT_ID},
+            wago_750_559::{WAGO_750_559_MODULE_IDENT, WAGO_750_559_PRODUCT_ID},
             wago_750_652::{WAGO_750_652_MODULE_IDENT, WAGO_750_652_PRODUCT_ID},
             wago_750_1506::{WAGO_750_1506_MODULE_IDENT, WAGO_750_1506_PRODUCT_ID},
         },
@@ -119,7 +123,28 @@ impl EthercatDeviceUsed for Wago750_354 {
     }
 }
 
-impl EthercatDeviceProcessing for Wago750_354 {}
+impl EthercatDeviceProcessing for Wago750_354 {
+    fn input_post_process(&mut self) -> Result<(), anyhow::Error> {
+        for slot_device in &self.slot_devices {
+            match slot_device {
+                // Modules are not in the device list of the loop, so their processing is done here
+                Some(device) => device.write_blocking().input_post_process()?,
+                None => break,
+            }
+        }
+        Ok(())
+    }
+
+    fn output_pre_process(&mut self) -> Result<(), anyhow::Error> {
+        for slot_device in &self.slot_devices {
+            match slot_device {
+                Some(device) => device.write_blocking().output_pre_process()?,
+                None => break,
+            }
+        }
+        Ok(())
+    }
+}
 
 impl NewEthercatDevice for Wago750_354 {
     fn new() -> Self {
@@ -173,10 +198,13 @@ impl Wago750_354 {
             }
         }
 
+        // the cumulative offset after the last PDO is the size of the whole process image
         if get_tx {
             self.tx_offsets = vec;
+            self.tx_size = bit_offset;
         } else {
             self.rx_offsets = vec;
+            self.rx_size = bit_offset;
         }
         Ok(())
     }
@@ -235,6 +263,18 @@ impl Wago750_354 {
                     module.has_tx = true;
                     module.has_rx = true;
                 }
+                WAGO_750_455_PRODUCT_ID | WAGO_750_464_PRODUCT_ID => {
+                    module.has_tx = true;
+                    module.has_rx = false;
+                }
+                WAGO_750_559_PRODUCT_ID => {
+                    module.has_tx = false;
+                    module.has_rx = true;
+                }
+                WAGO_750_404_PRODUCT_ID => {
+                    module.has_tx = true;
+                    module.has_rx = true;
+                }
                 _ => println!(
                     "Wago-750-354 found Unknown/Unimplemented Module: {}",
                     ident_iom
@@ -276,6 +316,18 @@ impl Wago750_354 {
                         WAGO_750_652_MODULE_IDENT => {
                             Arc::new(RwLock::new(wago_750_652::Wago750_652::new()))
                         }
+                        WAGO_750_455_MODULE_IDENT => {
+                            Arc::new(RwLock::new(wago_750_455::Wago750_455::new()))
+                        }
+                        WAGO_750_559_MODULE_IDENT => {
+                            Arc::new(RwLock::new(wago_750_559::Wago750_559::new()))
+                        }
+                        WAGO_750_464_MODULE_IDENT => {
+                            Arc::new(RwLock::new(wago_750_464::Wago750_464::new()))
+                        }
+                        WAGO_750_404_MODULE_IDENT => {
+                            Arc::new(RwLock::new(wago_750_404::Wago750_404::new()))
+                        }
                         _ => {
                             println!(
                                 "{} Missing Implementation for Module Identification: vendor_id: {:?}, module ident: {:?} !",
@@ -297,17 +349,12 @@ impl Wago750_354 {
                         rx_index += 1;
                     }
 
-                    let mut dev_guard = dev.write_blocking();
-                    match tx_pdo_offset {
-                        Some(offset) => dev_guard.set_tx_offset(*offset),
-                        None => (),
-                    }
-
-                    match rx_pdo_offset {
-                        Some(offset) => dev_guard.set_rx_offset(*offset),
-                        None => (),
-                    }
-                    drop(dev_guard);
+                    // The module keeps its slot and offsets, the slot is needed to address its settings
+                    dev.write_blocking().set_module(Module {
+                        tx_offset: tx_pdo_offset.copied().unwrap_or(0),
+                        rx_offset: rx_pdo_offset.copied().unwrap_or(0),
+                        ..m
+                    });
                     self.slot_devices[self.dev_count] = Some(dev);
                     self.dev_count += 1;
                 }
@@ -334,10 +334,13 @@ impl IP20EcDi8Do8 {
             }
         }
 
+        // the cumulative offset after the last PDO is the size of the whole process image
         if get_tx {
             self.tx_offsets = vec;
+            self.tx_size = bit_offset;
         } else {
             self.rx_offsets = vec;
+            self.rx_size = bit_offset;
         }
         Ok(())
     }
@@ -1,4 +1,8 @@
 pub mod ip20_ec_di8_do8;
 pub mod wago_750_1506;
+pub mod wago_750_404;
+pub mod wago_750_455;
+pub mod wago_750_464;
 pub mod wago_750_501;
+pub mod wago_750_559;
 pub mod wago_750_652;
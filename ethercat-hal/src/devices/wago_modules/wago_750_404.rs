@@ -0,0 +1,252 @@
+use anyhow::anyhow;
+use bitvec::field::BitField;
+
+use crate::devices::{
+    DynamicEthercatDevice, EthercatDevice, EthercatDeviceProcessing, EthercatDeviceUsed,
+    EthercatDynamicPDO, Module, NewEthercatDevice, SubDeviceProductTuple,
+};
+use crate::io::encoder_input::{
+    EncoderInputCounter, EncoderInputDevice, EncoderInputFrequency, EncoderInputPeriod,
+};
+
+/// Status/control byte, reserved byte and the 32 bit counter
+const PROCESS_IMAGE_BITS: usize = 48;
+
+/// Wago 750-404 up/down counter
+#[derive(Clone)]
+pub struct Wago750_404 {
+    is_used: bool,
+    tx_bit_offset: usize,
+    rx_bit_offset: usize,
+    module: Option<Module>,
+    pub txpdo: Wago750_404TxPdo,
+    pub rxpdo: Wago750_404RxPdo,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Wago750_404Port {
+    CNT1,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Wago750_404TxPdo {
+    /// S.0 - Clock input
+    pub clock_input: bool,
+    /// S.1 - Up/down input
+    pub up_down_input: bool,
+    /// S.4 - The counter is blocked
+    pub counter_blocked: bool,
+    /// S.5 - The counter was set to the value of the output
+    pub counter_set: bool,
+    /// D0 to D3
+    pub counter_value: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Wago750_404RxPdo {
+    /// C.4 - Stop counting
+    pub block_counter: bool,
+    /// C.5 - Set the counter to `set_value`, held until the module confirms it
+    pub set_counter: bool,
+    /// D0 to D3
+    pub set_value: u32,
+}
+
+impl EncoderInputDevice<Wago750_404Port> for Wago750_404 {
+    fn get_counter_value(
+        &self,
+        _port: Wago750_404Port,
+    ) -> Result<EncoderInputCounter, anyhow::Error> {
+        Ok(EncoderInputCounter {
+            value: self.txpdo.counter_value,
+        })
+    }
+
+    fn get_frequency(
+        &self,
+        _port: Wago750_404Port,
+    ) -> Result<Option<EncoderInputFrequency>, anyhow::Error> {
+        Ok(None)
+    }
+
+    fn get_period(
+        &self,
+        _port: Wago750_404Port,
+    ) -> Result<Option<EncoderInputPeriod>, anyhow::Error> {
+        Ok(None)
+    }
+
+    fn set_counter(&mut self, _port: Wago750_404Port, value: u32) -> Result<(), anyhow::Error> {
+        self.rxpdo.set_value = value;
+        self.rxpdo.set_counter = true;
+        Ok(())
+    }
+}
+
+impl EthercatDeviceUsed for Wago750_404 {
+    fn is_used(&self) -> bool {
+        self.is_used
+    }
+
+    fn set_used(&mut self, used: bool) {
+        self.is_used = used;
+    }
+}
+
+impl DynamicEthercatDevice for Wago750_404 {}
+
+impl EthercatDynamicPDO for Wago750_404 {
+    fn get_tx_offset(&self) -> usize {
+        self.tx_bit_offset
+    }
+
+    fn get_rx_offset(&self) -> usize {
+        self.rx_bit_offset
+    }
+
+    fn set_tx_offset(&mut self, offset: usize) {
+        self.tx_bit_offset = offset
+    }
+
+    fn set_rx_offset(&mut self, offset: usize) {
+        self.rx_bit_offset = offset
+    }
+}
+
+impl EthercatDevice for Wago750_404 {
+    fn input(
+        &mut self,
+        input: &bitvec::prelude::BitSlice<u8, bitvec::prelude::Lsb0>,
+    ) -> Result<(), anyhow::Error> {
+        let bits = input
+            .get(self.tx_bit_offset..self.tx_bit_offset + PROCESS_IMAGE_BITS)
+            .ok_or_else(|| {
+                anyhow!(
+                    "[{}::Wago750_404::input] Process image out of bounds",
+                    module_path!()
+                )
+            })?;
+        self.txpdo.clock_input = bits[0];
+        self.txpdo.up_down_input = bits[1];
+        self.txpdo.counter_blocked = bits[4];
+        self.txpdo.counter_set = bits[5];
+        self.txpdo.counter_value = bits[16..48].load_le();
+        Ok(())
+    }
+
+    fn input_len(&self) -> usize {
+        PROCESS_IMAGE_BITS
+    }
+
+    fn output(
+        &self,
+        output: &mut bitvec::prelude::BitSlice<u8, bitvec::prelude::Lsb0>,
+    ) -> Result<(), anyhow::Error> {
+        let bits = output
+            .get_mut(self.rx_bit_offset..self.rx_bit_offset + PROCESS_IMAGE_BITS)
+            .ok_or_else(|| {
+                anyhow!(
+                    "[{}::Wago750_404::output] Process image out of bounds",
+                    module_path!()
+                )
+            })?;
+        bits[0..16].store_le(0u16);
+        bits.set(4, self.rxpdo.block_counter);
+        bits.set(5, self.rxpdo.set_counter);
+        bits[16..48].store_le(self.rxpdo.set_value);
+        Ok(())
+    }
+
+    fn output_len(&self) -> usize {
+        PROCESS_IMAGE_BITS
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+
+    fn is_module(&self) -> bool {
+        true
+    }
+
+    fn get_module(&self) -> Option<Module> {
+        self.module
+    }
+
+    fn set_module(&mut self, module: Module) {
+        self.tx_bit_offset = module.tx_offset;
+        self.rx_bit_offset = module.rx_offset;
+        self.module = Some(module);
+    }
+}
+
+impl EthercatDeviceProcessing for Wago750_404 {
+    /// Releases the set request once the module confirmed it
+    fn input_post_process(&mut self) -> Result<(), anyhow::Error> {
+        if self.rxpdo.set_counter && self.txpdo.counter_set {
+            self.rxpdo.set_counter = false;
+        }
+        Ok(())
+    }
+}
+
+impl NewEthercatDevice for Wago750_404 {
+    fn new() -> Self {
+        Self {
+            is_used: false,
+            tx_bit_offset: 0,
+            rx_bit_offset: 0,
+            module: None,
+            txpdo: Wago750_404TxPdo::default(),
+            rxpdo: Wago750_404RxPdo::default(),
+        }
+    }
+}
+
+impl std::fmt::Debug for Wago750_404 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Wago750_404")
+    }
+}
+
+pub const WAGO_750_404_VENDOR_ID: u32 = 0x00000021;
+pub const WAGO_750_404_PRODUCT_ID: u32 = 0x04041772;
+pub const WAGO_750_404_MODULE_IDENT: SubDeviceProductTuple =
+    (WAGO_750_404_VENDOR_ID, WAGO_750_404_PRODUCT_ID);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitvec::prelude::*;
+
+    #[test]
+    fn test_set_counter_handshake() {
+        let mut device = Wago750_404::new();
+        device.set_tx_offset(8);
+        device.set_rx_offset(8);
+        device.set_counter(Wago750_404Port::CNT1, 1000).unwrap();
+
+        let mut output: BitVec<u8, Lsb0> = BitVec::repeat(false, 8 + PROCESS_IMAGE_BITS);
+        device.output(&mut output).unwrap();
+        assert_eq!(output[8..16].load_le::<u8>(), 0b0010_0000);
+        assert_eq!(output[24..56].load_le::<u32>(), 1000);
+
+        let mut input: BitVec<u8, Lsb0> = BitVec::repeat(false, 8 + PROCESS_IMAGE_BITS);
+        input.set(8 + 5, true);
+        input[24..56].store_le(1000u32);
+        device.input(&input).unwrap();
+        device.input_post_process().unwrap();
+        assert!(!device.rxpdo.set_counter);
+        assert_eq!(
+            device
+                .get_counter_value(Wago750_404Port::CNT1)
+                .unwrap()
+                .value,
+            1000
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use anyhow::anyhow;
+use bitvec::field::BitField;
+use units::electric_current::milliampere;
+use units::f64::ElectricCurrent;
+
+use crate::devices::{
+    DynamicEthercatDevice, EthercatDevice, EthercatDeviceProcessing, EthercatDeviceUsed,
+    EthercatDynamicPDO, Module, NewEthercatDevice, SubDeviceProductTuple,
+};
+use crate::io::analog_input::{AnalogInputDevice, AnalogInputInput, physical::AnalogInputRange};
+
+const CHANNELS: usize = 4;
+
+/// Full scale of the 15 bit value, the 3 low bits are status bits
+const FULL_SCALE: u16 = 0x7FF8;
+
+/// Wago 750-455 4-channel analog input 4-20 mA
+#[derive(Clone)]
+pub struct Wago750_455 {
+    is_used: bool,
+    tx_bit_offset: usize,
+    rx_bit_offset: usize,
+    module: Option<Module>,
+    pub txpdo: Wago750_455TxPdo,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Wago750_455Port {
+    AI1,
+    AI2,
+    AI3,
+    AI4,
+}
+
+impl From<Wago750_455Port> for usize {
+    fn from(value: Wago750_455Port) -> Self {
+        match value {
+            Wago750_455Port::AI1 => 0,
+            Wago750_455Port::AI2 => 1,
+            Wago750_455Port::AI3 => 2,
+            Wago750_455Port::AI4 => 3,
+        }
+    }
+}
+
+/// One 16 bit word per channel
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Wago750_455Channel {
+    /// Bits 3 to 15
+    pub value: u16,
+    /// Bit 0 - Over 20 mA
+    pub overrange: bool,
+    /// Bit 1 - Under 4 mA, wire break
+    pub error: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Wago750_455TxPdo {
+    pub channels: [Wago750_455Channel; CHANNELS],
+}
+
+impl AnalogInputDevice<Wago750_455Port> for Wago750_455 {
+    fn get_input(&self, port: Wago750_455Port) -> AnalogInputInput {
+        let channel = &self.txpdo.channels[usize::from(port)];
+        AnalogInputInput {
+            normalized: f32::from(channel.value) / f32::from(FULL_SCALE),
+            wiring_error: channel.error,
+        }
+    }
+
+    fn analog_input_range(&self) -> AnalogInputRange {
+        AnalogInputRange::Current {
+            min: ElectricCurrent::new::<milliampere>(4.0),
+            max: ElectricCurrent::new::<milliampere>(20.0),
+            min_raw: 0,
+            max_raw: FULL_SCALE as i16,
+        }
+    }
+}
+
+impl EthercatDeviceUsed for Wago750_455 {
+    fn is_used(&self) -> bool {
+        self.is_used
+    }
+
+    fn set_used(&mut self, used: bool) {
+        self.is_used = used;
+    }
+}
+
+impl DynamicEthercatDevice for Wago750_455 {}
+
+impl EthercatDynamicPDO for Wago750_455 {
+    fn get_tx_offset(&self) -> usize {
+        self.tx_bit_offset
+    }
+
+    fn get_rx_offset(&self) -> usize {
+        self.rx_bit_offset
+    }
+
+    fn set_tx_offset(&mut self, offset: usize) {
+        self.tx_bit_offset = offset
+    }
+
+    fn set_rx_offset(&mut self, offset: usize) {
+        self.rx_bit_offset = offset
+    }
+}
+
+impl EthercatDevice for Wago750_455 {
+    fn input(
+        &mut self,
+        input: &bitvec::prelude::BitSlice<u8, bitvec::prelude::Lsb0>,
+    ) -> Result<(), anyhow::Error> {
+        let bits = input
+            .get(self.tx_bit_offset..self.tx_bit_offset + self.input_len())
+            .ok_or_else(|| {
+                anyhow!(
+                    "[{}::Wago750_455::input] Process image out of bounds",
+                    module_path!()
+                )
+            })?;
+        for (channel, word) in self.txpdo.channels.iter_mut().zip(bits.chunks_exact(16)) {
+            let raw = word.load_le::<u16>();
+            channel.value = raw & FULL_SCALE;
+            channel.overrange = raw & 0b001 != 0;
+            channel.error = raw & 0b010 != 0;
+        }
+        Ok(())
+    }
+
+    fn input_len(&self) -> usize {
+        CHANNELS * 16
+    }
+
+    fn output(
+        &self,
+        _output: &mut bitvec::prelude::BitSlice<u8, bitvec::prelude::Lsb0>,
+    ) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+
+    fn output_len(&self) -> usize {
+        0
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+
+    fn is_module(&self) -> bool {
+        true
+    }
+
+    fn get_module(&self) -> Option<Module> {
+        self.module
+    }
+
+    fn set_module(&mut self, module: Module) {
+        self.tx_bit_offset = module.tx_offset;
+        self.rx_bit_offset = module.rx_offset;
+        self.module = Some(module);
+    }
+}
+
+impl EthercatDeviceProcessing for Wago750_455 {}
+
+impl NewEthercatDevice for Wago750_455 {
+    fn new() -> Self {
+        Self {
+            is_used: false,
+            tx_bit_offset: 0,
+            rx_bit_offset: 0,
+            module: None,
+            txpdo: Wago750_455TxPdo::default(),
+        }
+    }
+}
+
+impl std::fmt::Debug for Wago750_455 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Wago750_455")
+    }
+}
+
+pub const WAGO_750_455_VENDOR_ID: u32 = 0x00000021;
+pub const WAGO_750_455_PRODUCT_ID: u32 = 0x04551772;
+pub const WAGO_750_455_MODULE_IDENT: SubDeviceProductTuple =
+    (WAGO_750_455_VENDOR_ID, WAGO_750_455_PRODUCT_ID);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitvec::prelude::*;
+
+    #[test]
+    fn test_input_at_offset() {
+        let mut device = Wago750_455::new();
+        device.set_tx_offset(8);
+
+        let mut input: BitVec<u8, Lsb0> = BitVec::repeat(false, 8 + 64);
+        input[8..24].store_le(FULL_SCALE / 2);
+        input[24..40].store_le(0b010u16);
+        input[56..72].store_le(FULL_SCALE | 0b001);
+        device.input(&input).unwrap();
+
+        let ai1 = device.get_input(Wago750_455Port::AI1);
+        assert!((ai1.normalized - 0.5).abs() < 0.001);
+        assert!(!ai1.wiring_error);
+        assert!(device.get_input(Wago750_455Port::AI2).wiring_error);
+        assert_eq!(device.get_input(Wago750_455Port::AI4).normalized, 1.0);
+        assert!(device.txpdo.channels[3].overrange);
+    }
+}
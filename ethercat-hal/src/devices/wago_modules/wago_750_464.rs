@@ -0,0 +1,205 @@
+use anyhow::anyhow;
+use bitvec::field::BitField;
+
+use crate::devices::{
+    DynamicEthercatDevice, EthercatDevice, EthercatDeviceProcessing, EthercatDeviceUsed,
+    EthercatDynamicPDO, Module, NewEthercatDevice, SubDeviceProductTuple,
+};
+use crate::io::temperature_input::{TemperatureInputDevice, TemperatureInputInput};
+use crate::pdo::basic::Limit;
+
+const CHANNELS: usize = 4;
+
+/// Value of a channel with a wire break
+const WIRE_BREAK: i16 = i16::MAX;
+/// Value of a channel with a short circuit
+const SHORT_CIRCUIT: i16 = i16::MIN;
+
+/// Wago 750-464 4-channel temperature input
+///
+/// PT100 / PT1000 (RTD), the sensor type is set with WAGO-I/O-CHECK
+#[derive(Clone)]
+pub struct Wago750_464 {
+    is_used: bool,
+    tx_bit_offset: usize,
+    rx_bit_offset: usize,
+    module: Option<Module>,
+    pub txpdo: Wago750_464TxPdo,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Wago750_464Port {
+    T1,
+    T2,
+    T3,
+    T4,
+}
+
+impl From<Wago750_464Port> for usize {
+    fn from(value: Wago750_464Port) -> Self {
+        match value {
+            Wago750_464Port::T1 => 0,
+            Wago750_464Port::T2 => 1,
+            Wago750_464Port::T3 => 2,
+            Wago750_464Port::T4 => 3,
+        }
+    }
+}
+
+/// One signed 16 bit word per channel in 0.1 °C
+#[derive(Debug, Clone, Default)]
+pub struct Wago750_464TxPdo {
+    pub values: [i16; CHANNELS],
+}
+
+impl TemperatureInputDevice<Wago750_464Port> for Wago750_464 {
+    fn get_input(&self, port: Wago750_464Port) -> TemperatureInputInput {
+        let value = self.txpdo.values[usize::from(port)];
+        TemperatureInputInput {
+            temperature: f32::from(value) / 10.0,
+            undervoltage: value == SHORT_CIRCUIT,
+            overvoltage: value == WIRE_BREAK,
+            limit1: Limit::NotActive,
+            limit2: Limit::NotActive,
+            error: value == SHORT_CIRCUIT || value == WIRE_BREAK,
+            txpdo_state: false,
+            txpdo_toggle: true,
+        }
+    }
+}
+
+impl EthercatDeviceUsed for Wago750_464 {
+    fn is_used(&self) -> bool {
+        self.is_used
+    }
+
+    fn set_used(&mut self, used: bool) {
+        self.is_used = used;
+    }
+}
+
+impl DynamicEthercatDevice for Wago750_464 {}
+
+impl EthercatDynamicPDO for Wago750_464 {
+    fn get_tx_offset(&self) -> usize {
+        self.tx_bit_offset
+    }
+
+    fn get_rx_offset(&self) -> usize {
+        self.rx_bit_offset
+    }
+
+    fn set_tx_offset(&mut self, offset: usize) {
+        self.tx_bit_offset = offset
+    }
+
+    fn set_rx_offset(&mut self, offset: usize) {
+        self.rx_bit_offset = offset
+    }
+}
+
+impl EthercatDevice for Wago750_464 {
+    fn input(
+        &mut self,
+        input: &bitvec::prelude::BitSlice<u8, bitvec::prelude::Lsb0>,
+    ) -> Result<(), anyhow::Error> {
+        let bits = input
+            .get(self.tx_bit_offset..self.tx_bit_offset + self.input_len())
+            .ok_or_else(|| {
+                anyhow!(
+                    "[{}::Wago750_464::input] Process image out of bounds",
+                    module_path!()
+                )
+            })?;
+        for (value, word) in self.txpdo.values.iter_mut().zip(bits.chunks_exact(16)) {
+            *value = word.load_le::<i16>();
+        }
+        Ok(())
+    }
+
+    fn input_len(&self) -> usize {
+        CHANNELS * 16
+    }
+
+    fn output(
+        &self,
+        _output: &mut bitvec::prelude::BitSlice<u8, bitvec::prelude::Lsb0>,
+    ) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+
+    fn output_len(&self) -> usize {
+        0
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+
+    fn is_module(&self) -> bool {
+        true
+    }
+
+    fn get_module(&self) -> Option<Module> {
+        self.module
+    }
+
+    fn set_module(&mut self, module: Module) {
+        self.tx_bit_offset = module.tx_offset;
+        self.rx_bit_offset = module.rx_offset;
+        self.module = Some(module);
+    }
+}
+
+impl EthercatDeviceProcessing for Wago750_464 {}
+
+impl NewEthercatDevice for Wago750_464 {
+    fn new() -> Self {
+        Self {
+            is_used: false,
+            tx_bit_offset: 0,
+            rx_bit_offset: 0,
+            module: None,
+            txpdo: Wago750_464TxPdo::default(),
+        }
+    }
+}
+
+impl std::fmt::Debug for Wago750_464 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Wago750_464")
+    }
+}
+
+pub const WAGO_750_464_VENDOR_ID: u32 = 0x00000021;
+pub const WAGO_750_464_PRODUCT_ID: u32 = 0x04641772;
+pub const WAGO_750_464_MODULE_IDENT: SubDeviceProductTuple =
+    (WAGO_750_464_VENDOR_ID, WAGO_750_464_PRODUCT_ID);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitvec::prelude::*;
+
+    #[test]
+    fn test_input_at_offset() {
+        let mut device = Wago750_464::new();
+        device.set_tx_offset(16);
+
+        let mut input: BitVec<u8, Lsb0> = BitVec::repeat(false, 16 + 64);
+        input[16..32].store_le(2215i16);
+        input[32..48].store_le(-125i16);
+        input[48..64].store_le(WIRE_BREAK);
+        device.input(&input).unwrap();
+
+        assert_eq!(device.get_input(Wago750_464Port::T1).temperature, 221.5);
+        assert_eq!(device.get_input(Wago750_464Port::T2).temperature, -12.5);
+        let t3 = device.get_input(Wago750_464Port::T3);
+        assert!(t3.error && t3.overvoltage);
+        assert!(!device.get_input(Wago750_464Port::T4).error);
+    }
+}
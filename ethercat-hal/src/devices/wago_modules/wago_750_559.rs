@@ -0,0 +1,194 @@
+use anyhow::anyhow;
+use bitvec::field::BitField;
+
+use crate::devices::{
+    DynamicEthercatDevice, EthercatDevice, EthercatDeviceProcessing, EthercatDeviceUsed,
+    EthercatDynamicPDO, Module, NewEthercatDevice, SubDeviceProductTuple,
+};
+use crate::io::analog_output::{AnalogOutputDevice, AnalogOutputOutput};
+
+const CHANNELS: usize = 4;
+
+/// Wago 750-559 4-channel analog output 0-10 V
+#[derive(Clone)]
+pub struct Wago750_559 {
+    is_used: bool,
+    tx_bit_offset: usize,
+    rx_bit_offset: usize,
+    module: Option<Module>,
+    pub rxpdo: Wago750_559RxPdo,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Wago750_559Port {
+    AO1,
+    AO2,
+    AO3,
+    AO4,
+}
+
+impl From<Wago750_559Port> for usize {
+    fn from(value: Wago750_559Port) -> Self {
+        match value {
+            Wago750_559Port::AO1 => 0,
+            Wago750_559Port::AO2 => 1,
+            Wago750_559Port::AO3 => 2,
+            Wago750_559Port::AO4 => 3,
+        }
+    }
+}
+
+/// One 16 bit word per channel, 0 is 0 V and 0x7FFF is 10 V
+#[derive(Debug, Clone, Default)]
+pub struct Wago750_559RxPdo {
+    pub values: [i16; CHANNELS],
+}
+
+impl AnalogOutputDevice<Wago750_559Port> for Wago750_559 {
+    /// The value is the voltage from 0 to 10 V
+    fn set_output(&mut self, port: Wago750_559Port, value: AnalogOutputOutput) {
+        let normalized = value.0.clamp(0.0, 10.0) / 10.0;
+        self.rxpdo.values[usize::from(port)] = (normalized * f32::from(i16::MAX)) as i16;
+    }
+
+    fn get_output(&self, port: Wago750_559Port) -> AnalogOutputOutput {
+        let value = self.rxpdo.values[usize::from(port)];
+        AnalogOutputOutput(f32::from(value) / f32::from(i16::MAX) * 10.0)
+    }
+}
+
+impl EthercatDeviceUsed for Wago750_559 {
+    fn is_used(&self) -> bool {
+        self.is_used
+    }
+
+    fn set_used(&mut self, used: bool) {
+        self.is_used = used;
+    }
+}
+
+impl DynamicEthercatDevice for Wago750_559 {}
+
+impl EthercatDynamicPDO for Wago750_559 {
+    fn get_tx_offset(&self) -> usize {
+        self.tx_bit_offset
+    }
+
+    fn get_rx_offset(&self) -> usize {
+        self.rx_bit_offset
+    }
+
+    fn set_tx_offset(&mut self, offset: usize) {
+        self.tx_bit_offset = offset
+    }
+
+    fn set_rx_offset(&mut self, offset: usize) {
+        self.rx_bit_offset = offset
+    }
+}
+
+impl EthercatDevice for Wago750_559 {
+    fn input(
+        &mut self,
+        _input: &bitvec::prelude::BitSlice<u8, bitvec::prelude::Lsb0>,
+    ) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+
+    fn input_len(&self) -> usize {
+        0
+    }
+
+    fn output(
+        &self,
+        output: &mut bitvec::prelude::BitSlice<u8, bitvec::prelude::Lsb0>,
+    ) -> Result<(), anyhow::Error> {
+        let bits = output
+            .get_mut(self.rx_bit_offset..self.rx_bit_offset + self.output_len())
+            .ok_or_else(|| {
+                anyhow!(
+                    "[{}::Wago750_559::output] Process image out of bounds",
+                    module_path!()
+                )
+            })?;
+        for (word, value) in bits.chunks_exact_mut(16).zip(self.rxpdo.values) {
+            word.store_le(value as u16);
+        }
+        Ok(())
+    }
+
+    fn output_len(&self) -> usize {
+        CHANNELS * 16
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+
+    fn is_module(&self) -> bool {
+        true
+    }
+
+    fn get_module(&self) -> Option<Module> {
+        self.module
+    }
+
+    fn set_module(&mut self, module: Module) {
+        self.tx_bit_offset = module.tx_offset;
+        self.rx_bit_offset = module.rx_offset;
+        self.module = Some(module);
+    }
+}
+
+impl EthercatDeviceProcessing for Wago750_559 {}
+
+impl NewEthercatDevice for Wago750_559 {
+    fn new() -> Self {
+        Self {
+            is_used: false,
+            tx_bit_offset: 0,
+            rx_bit_offset: 0,
+            module: None,
+            rxpdo: Wago750_559RxPdo::default(),
+        }
+    }
+}
+
+impl std::fmt::Debug for Wago750_559 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Wago750_559")
+    }
+}
+
+pub const WAGO_750_559_VENDOR_ID: u32 = 0x00000021;
+pub const WAGO_750_559_PRODUCT_ID: u32 = 0x05591772;
+pub const WAGO_750_559_MODULE_IDENT: SubDeviceProductTuple =
+    (WAGO_750_559_VENDOR_ID, WAGO_750_559_PRODUCT_ID);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitvec::prelude::*;
+
+    #[test]
+    fn test_output_at_offset() {
+        let mut device = Wago750_559::new();
+        device.set_rx_offset(8);
+        device.set_output(Wago750_559Port::AO1, AnalogOutputOutput(10.0));
+        device.set_output(Wago750_559Port::AO3, AnalogOutputOutput(5.0));
+        device.set_output(Wago750_559Port::AO4, AnalogOutputOutput(-1.0));
+
+        let mut output: BitVec<u8, Lsb0> = BitVec::repeat(false, 8 + 64);
+        device.output(&mut output).unwrap();
+        assert_eq!(output[0..8].load_le::<u8>(), 0);
+        assert_eq!(output[8..24].load_le::<u16>(), 0x7FFF);
+        assert_eq!(output[24..40].load_le::<u16>(), 0);
+        assert_eq!(output[40..56].load_le::<u16>(), 0x3FFF);
+        assert_eq!(output[56..72].load_le::<u16>(), 0);
+        assert!((device.get_output(Wago750_559Port::AO3).0 - 5.0).abs() < 0.001);
+    }
+}
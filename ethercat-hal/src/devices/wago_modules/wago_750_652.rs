@@ -1,15 +1,221 @@
+use std::collections::VecDeque;
+
+use anyhow::{Error, anyhow};
+use bitvec::field::BitField;
+
+use crate::devices::el6021::convert_serial_encoding;
 use crate::devices::{
     DynamicEthercatDevice, EthercatDevice, EthercatDeviceUsed, EthercatDynamicPDO, Module,
     SubDeviceProductTuple,
 };
 use crate::devices::{EthercatDeviceProcessing, NewEthercatDevice};
+use crate::helpers::ethercrab_types::EthercrabSubDevicePreoperational;
+use crate::io::serial_interface::{SerialEncoding, SerialInterfaceDevice};
 
+/// Data bytes in the 6 byte process image (control/status byte and 5 data bytes)
+const DATA_BYTES: usize = 5;
+const PROCESS_IMAGE_BITS: usize = (1 + DATA_BYTES) * 8;
+
+/// Wago 750-652 serial interface (RS-232/RS-485) on the Wago 750-354 coupler
+///
+/// Messages are longer than the process image, so they are split into chunks of 5 bytes.
+/// One chunk is handed over per handshake and the module sends from its own buffer,
+/// received chunks are collected until the message is read.
 #[derive(Clone)]
 pub struct Wago750_652 {
     is_used: bool,
     tx_bit_offset: usize,
     rx_bit_offset: usize,
     module: Option<Module>,
+    pub configuration: Wago750_652Configuration,
+    pub txpdo: Wago750_652TxPdo,
+    pub rxpdo: Wago750_652RxPdo,
+    /// Init request was sent to the module
+    pub initialized: bool,
+    /// Initialization is done and data can be exchanged
+    pub ready: bool,
+    transmit_buffer: VecDeque<u8>,
+    receive_buffer: Vec<u8>,
+}
+
+#[derive(Clone)]
+pub enum Wago750_652Port {
+    SI1,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wago750_652Baudrate {
+    B1200 = 3,
+    B2400 = 4,
+    B4800 = 5,
+    /// default of the module
+    B9600 = 6,
+    B19200 = 7,
+    B38400 = 8,
+    B57600 = 9,
+    B115200 = 10,
+}
+
+impl From<Wago750_652Baudrate> for u8 {
+    fn from(baudrate: Wago750_652Baudrate) -> Self {
+        baudrate as Self
+    }
+}
+
+impl From<Wago750_652Baudrate> for u32 {
+    fn from(value: Wago750_652Baudrate) -> Self {
+        match value {
+            Wago750_652Baudrate::B1200 => 1200,
+            Wago750_652Baudrate::B2400 => 2400,
+            Wago750_652Baudrate::B4800 => 4800,
+            Wago750_652Baudrate::B9600 => 9600,
+            Wago750_652Baudrate::B19200 => 19200,
+            Wago750_652Baudrate::B38400 => 38400,
+            Wago750_652Baudrate::B57600 => 57600,
+            Wago750_652Baudrate::B115200 => 115200,
+        }
+    }
+}
+
+/// Settings of the module in the object dictionary of the coupler
+///
+/// The index is `0x8000 + slot * 0x10`, the values are coded like the EL6021.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Wago750_652Configuration {
+    /// # 80n0:11 - Baud rate
+    pub baud_rate: Wago750_652Baudrate,
+
+    /// # 80n0:15 - Data bits, parity and stop bits
+    /// Space and mark parity are not supported by the module
+    pub data_frame: SerialEncoding,
+}
+
+impl Default for Wago750_652Configuration {
+    fn default() -> Self {
+        Self {
+            baud_rate: Wago750_652Baudrate::B19200,
+            data_frame: SerialEncoding::Coding8E1,
+        }
+    }
+}
+
+impl Wago750_652Configuration {
+    pub async fn write_slot_config<'a>(
+        &self,
+        device: &EthercrabSubDevicePreoperational<'a>,
+        slot: u16,
+    ) -> Result<(), Error> {
+        if matches!(
+            self.data_frame,
+            SerialEncoding::Coding8S1 | SerialEncoding::Coding8M1
+        ) {
+            return Err(anyhow!(
+                "[{}::Wago750_652Configuration::write_slot_config] {:?} is not supported",
+                module_path!(),
+                self.data_frame
+            ));
+        }
+        let index = 0x8000 + slot * 0x10;
+        device
+            .sdo_write(index, 0x11, u8::from(self.baud_rate))
+            .await?;
+        device
+            .sdo_write(index, 0x15, convert_serial_encoding(self.data_frame))
+            .await?;
+        Ok(())
+    }
+}
+
+/// # Status byte S0
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Wago750_652Status {
+    /// S0.0 - Toggles when the module took the data of the transmit request
+    pub transmit_accepted: bool,
+    /// S0.1 - Toggles when the module has received data
+    pub receive_request: bool,
+    /// S0.2 - Initialization is done
+    pub init_accepted: bool,
+    /// S0.3 - Receive buffer of the module is full
+    pub buffer_full: bool,
+    /// S0.4 to S0.6 - Number of valid data bytes
+    pub length: u8,
+}
+
+/// # Control byte C0
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Wago750_652Control {
+    /// C0.0 - Toggle to hand over the data bytes
+    pub transmit_request: bool,
+    /// C0.1 - Toggle to acknowledge the received data bytes
+    pub receive_accepted: bool,
+    /// C0.2 - Requests the initialization, blocks sending and receiving
+    pub init_request: bool,
+    /// C0.4 to C0.6 - Number of valid data bytes
+    pub length: u8,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Wago750_652TxPdo {
+    pub status: Wago750_652Status,
+    pub data: [u8; DATA_BYTES],
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Wago750_652RxPdo {
+    pub control: Wago750_652Control,
+    pub data: [u8; DATA_BYTES],
+}
+
+impl Wago750_652TxPdo {
+    fn read(&mut self, bits: &bitvec::prelude::BitSlice<u8, bitvec::prelude::Lsb0>) {
+        self.status.transmit_accepted = bits[0];
+        self.status.receive_request = bits[1];
+        self.status.init_accepted = bits[2];
+        self.status.buffer_full = bits[3];
+        self.status.length = bits[4..7].load_le::<u8>();
+        for (i, byte) in bits[8..].chunks_exact(8).enumerate() {
+            self.data[i] = byte.load_le();
+        }
+    }
+}
+
+impl Wago750_652RxPdo {
+    fn write(&self, buffer: &mut bitvec::prelude::BitSlice<u8, bitvec::prelude::Lsb0>) {
+        buffer.set(0, self.control.transmit_request);
+        buffer.set(1, self.control.receive_accepted);
+        buffer.set(2, self.control.init_request);
+        buffer.set(3, false);
+        buffer[4..7].store_le(self.control.length);
+        buffer.set(7, false);
+        for (i, &byte) in self.data.iter().enumerate() {
+            buffer[(8 + i * 8)..(8 + (i + 1) * 8)].store_le(byte);
+        }
+    }
+}
+
+impl Wago750_652 {
+    /// Write the settings to the coupler, the module has to be assigned to a slot
+    ///
+    /// Not [`crate::coe::ConfigurableDevice`] because the settings are addressed by the slot.
+    pub async fn write_config<'maindevice>(
+        &mut self,
+        device: &EthercrabSubDevicePreoperational<'maindevice>,
+        config: &Wago750_652Configuration,
+    ) -> Result<(), Error> {
+        let module = self.module.ok_or_else(|| {
+            anyhow!(
+                "[{}::Wago750_652::write_config] Module is not assigned to a slot",
+                module_path!()
+            )
+        })?;
+        config.write_slot_config(device, module.slot).await?;
+        self.configuration = config.clone();
+        Ok(())
+    }
+
+    pub fn get_config(&self) -> Wago750_652Configuration {
+        self.configuration.clone()
+    }
 }
 
 impl EthercatDeviceUsed for Wago750_652 {
@@ -48,24 +254,42 @@ impl EthercatDevice for Wago750_652 {
     */
     fn input(
         &mut self,
-        _input: &bitvec::prelude::BitSlice<u8, bitvec::prelude::Lsb0>,
+        input: &bitvec::prelude::BitSlice<u8, bitvec::prelude::Lsb0>,
     ) -> Result<(), anyhow::Error> {
+        let bits = input
+            .get(self.tx_bit_offset..self.tx_bit_offset + PROCESS_IMAGE_BITS)
+            .ok_or_else(|| {
+                anyhow!(
+                    "[{}::Wago750_652::input] Process image out of bounds",
+                    module_path!()
+                )
+            })?;
+        self.txpdo.read(bits);
         Ok(())
     }
 
     fn input_len(&self) -> usize {
-        0
+        PROCESS_IMAGE_BITS
     }
 
     fn output(
         &self,
-        _output: &mut bitvec::prelude::BitSlice<u8, bitvec::prelude::Lsb0>,
+        output: &mut bitvec::prelude::BitSlice<u8, bitvec::prelude::Lsb0>,
     ) -> Result<(), anyhow::Error> {
+        let bits = output
+            .get_mut(self.rx_bit_offset..self.rx_bit_offset + PROCESS_IMAGE_BITS)
+            .ok_or_else(|| {
+                anyhow!(
+                    "[{}::Wago750_652::output] Process image out of bounds",
+                    module_path!()
+                )
+            })?;
+        self.rxpdo.write(bits);
         Ok(())
     }
 
     fn output_len(&self) -> usize {
-        0
+        PROCESS_IMAGE_BITS
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
@@ -124,7 +348,7 @@ impl EthercatDevice for Wago750_652 {
     }
 
     fn get_module(&self) -> Option<Module> {
-        self.module.clone()
+        self.module
     }
 
     fn set_module(&mut self, module: Module) {
@@ -134,7 +358,120 @@ impl EthercatDevice for Wago750_652 {
     }
 }
 
-impl EthercatDeviceProcessing for Wago750_652 {}
+impl EthercatDeviceProcessing for Wago750_652 {
+    /// Collects the received data bytes and acknowledges them
+    fn input_post_process(&mut self) -> Result<(), anyhow::Error> {
+        if !self.ready {
+            return Ok(());
+        }
+        let status = &self.txpdo.status;
+        if status.receive_request != self.rxpdo.control.receive_accepted {
+            let length = (status.length as usize).min(DATA_BYTES);
+            self.receive_buffer
+                .extend_from_slice(&self.txpdo.data[..length]);
+            self.rxpdo.control.receive_accepted = status.receive_request;
+        }
+        Ok(())
+    }
+
+    /// Hands over the next chunk once the module accepted the last one
+    fn output_pre_process(&mut self) -> Result<(), anyhow::Error> {
+        if !self.ready
+            || self.rxpdo.control.transmit_request != self.txpdo.status.transmit_accepted
+            || self.transmit_buffer.is_empty()
+        {
+            return Ok(());
+        }
+        let length = self.transmit_buffer.len().min(DATA_BYTES);
+        let mut data = [0u8; DATA_BYTES];
+        for (byte, value) in data.iter_mut().zip(self.transmit_buffer.drain(..length)) {
+            *byte = value;
+        }
+        self.rxpdo.data = data;
+        self.rxpdo.control.length = length as u8;
+        self.rxpdo.control.transmit_request = !self.rxpdo.control.transmit_request;
+        Ok(())
+    }
+}
+
+impl SerialInterfaceDevice<Wago750_652Port> for Wago750_652 {
+    fn serial_interface_has_messages(&mut self, _port: Wago750_652Port) -> bool {
+        !self.receive_buffer.is_empty()
+    }
+
+    /// Returns all bytes received since the last read
+    fn serial_interface_read_message(&mut self, port: Wago750_652Port) -> Option<Vec<u8>> {
+        if !self.serial_interface_has_messages(port) {
+            return None;
+        }
+        Some(std::mem::take(&mut self.receive_buffer))
+    }
+
+    fn serial_interface_write_message(
+        &mut self,
+        _port: Wago750_652Port,
+        message: Vec<u8>,
+    ) -> Result<bool, Error> {
+        // If we write a message of len zero, then this means we are waiting for our write_message to finish
+        if message.is_empty() {
+            return Ok(self.transmit_buffer.is_empty()
+                && self.rxpdo.control.transmit_request == self.txpdo.status.transmit_accepted);
+        }
+        if !self.ready {
+            return Err(anyhow!(
+                "[{}::Wago750_652::serial_interface_write_message] Not initialized",
+                module_path!()
+            ));
+        }
+        self.transmit_buffer.extend(message);
+        Ok(true)
+    }
+
+    fn get_baudrate(&self, _port: Wago750_652Port) -> Option<u32> {
+        Some(self.configuration.baud_rate.into())
+    }
+
+    fn get_serial_encoding(&self, _port: Wago750_652Port) -> Option<SerialEncoding> {
+        Some(self.configuration.data_frame)
+    }
+
+    /// Same handshake as the EL6021, returns true when it is finished
+    /// Every step of the init has to be done in an EtherCatCycle
+    fn serial_interface_initialize(&mut self, _port: Wago750_652Port) -> bool {
+        let control = &mut self.rxpdo.control;
+        let status = &self.txpdo.status;
+
+        // Initialization was accepted by the module
+        if control.init_request && status.init_accepted {
+            control.init_request = false;
+            return false;
+        }
+
+        // Initial state, request the initialization
+        if !control.init_request && !status.init_accepted && !self.initialized {
+            control.init_request = true;
+            self.initialized = true;
+            return false;
+        }
+
+        // Waiting for the module to be ready again
+        if !control.init_request && status.init_accepted {
+            return false;
+        }
+
+        if !control.init_request && !status.init_accepted && self.initialized {
+            // The module resets its toggle bits on init
+            control.transmit_request = status.transmit_accepted;
+            control.receive_accepted = status.receive_request;
+            self.transmit_buffer.clear();
+            self.receive_buffer.clear();
+            self.ready = true;
+            return true;
+        }
+
+        false
+    }
+}
 
 impl NewEthercatDevice for Wago750_652 {
     fn new() -> Self {
@@ -143,6 +480,13 @@ impl NewEthercatDevice for Wago750_652 {
             tx_bit_offset: 0,
             rx_bit_offset: 0,
             module: None,
+            configuration: Wago750_652Configuration::default(),
+            txpdo: Wago750_652TxPdo::default(),
+            rxpdo: Wago750_652RxPdo::default(),
+            initialized: false,
+            ready: false,
+            transmit_buffer: VecDeque::new(),
+            receive_buffer: vec![],
         }
     }
 }
@@ -157,3 +501,93 @@ pub const WAGO_750_652_VENDOR_ID: u32 = 0x00000021;
 pub const WAGO_750_652_PRODUCT_ID: u32 = 106043250;
 pub const WAGO_750_652_MODULE_IDENT: SubDeviceProductTuple =
     (WAGO_750_652_VENDOR_ID, WAGO_750_652_PRODUCT_ID);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitvec::prelude::*;
+
+    #[test]
+    fn test_process_image_at_offset() {
+        let mut device = Wago750_652::new();
+        device.set_tx_offset(32);
+        device.set_rx_offset(32);
+
+        let mut input: BitVec<u8, Lsb0> = BitVec::repeat(false, 32 + PROCESS_IMAGE_BITS);
+        input.set(32 + 1, true); // receive request
+        input[32 + 4..32 + 7].store_le(3u8); // length
+        for i in 0..DATA_BYTES {
+            input[(32 + 8 + i * 8)..(32 + 16 + i * 8)].store_le((i + 1) as u8);
+        }
+        device.input(&input).unwrap();
+        assert!(device.txpdo.status.receive_request);
+        assert_eq!(device.txpdo.status.length, 3);
+        assert_eq!(device.txpdo.data, [1, 2, 3, 4, 5]);
+
+        device.rxpdo.control.transmit_request = true;
+        device.rxpdo.control.length = 2;
+        device.rxpdo.data = [0xAA, 0xBB, 0, 0, 0];
+        let mut output: BitVec<u8, Lsb0> = BitVec::repeat(false, 32 + PROCESS_IMAGE_BITS);
+        device.output(&mut output).unwrap();
+        assert_eq!(output[0..32].load_le::<u32>(), 0);
+        assert_eq!(output[32..40].load_le::<u8>(), 0b0010_0001);
+        assert_eq!(output[40..48].load_le::<u8>(), 0xAA);
+        assert_eq!(output[48..56].load_le::<u8>(), 0xBB);
+    }
+
+    #[test]
+    fn test_message_is_sent_and_received_in_chunks() {
+        let mut device = Wago750_652::new();
+        device.ready = true;
+
+        let message: Vec<u8> = (1..=8).collect();
+        assert!(
+            device
+                .serial_interface_write_message(Wago750_652Port::SI1, message)
+                .unwrap()
+        );
+
+        device.output_pre_process().unwrap();
+        assert_eq!(device.rxpdo.control.length, 5);
+        assert_eq!(device.rxpdo.data, [1, 2, 3, 4, 5]);
+
+        // the next chunk waits for the module to accept the first one
+        device.output_pre_process().unwrap();
+        assert_eq!(device.rxpdo.data, [1, 2, 3, 4, 5]);
+        device.txpdo.status.transmit_accepted = true;
+        device.output_pre_process().unwrap();
+        assert_eq!(device.rxpdo.control.length, 3);
+        assert_eq!(device.rxpdo.data[..3], [6, 7, 8]);
+        assert!(
+            !device
+                .serial_interface_write_message(Wago750_652Port::SI1, vec![])
+                .unwrap()
+        );
+        device.txpdo.status.transmit_accepted = false;
+        assert!(
+            device
+                .serial_interface_write_message(Wago750_652Port::SI1, vec![])
+                .unwrap()
+        );
+
+        device.txpdo.status.receive_request = true;
+        device.txpdo.status.length = 5;
+        device.txpdo.data = [1, 2, 3, 4, 5];
+        device.input_post_process().unwrap();
+        // the same chunk is not collected twice
+        device.input_post_process().unwrap();
+        device.txpdo.status.receive_request = false;
+        device.txpdo.status.length = 2;
+        device.txpdo.data = [6, 7, 0, 0, 0];
+        device.input_post_process().unwrap();
+
+        assert_eq!(
+            device.serial_interface_read_message(Wago750_652Port::SI1),
+            Some(vec![1, 2, 3, 4, 5, 6, 7])
+        );
+        assert_eq!(
+            device.serial_interface_read_message(Wago750_652Port::SI1),
+            None
+        );
+    }
+}
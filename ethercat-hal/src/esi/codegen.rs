@@ -0,0 +1,529 @@
+//! Generates the identity constants, PDO objects, `TxPdo`/`RxPdo` structs and predefined PDO assignments of a device
+//!
+//! The output follows [docs/pdo.md](../../../docs/pdo.md) and compiles as a module in `ethercat_hal::devices`.
+//! The device struct, ports and the io trait implementations still have to be written by hand.
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use super::{EsiDataType, EsiDevice, EsiFile, EsiPdo, EsiPdoEntry};
+
+/// Generates the module for `type_name`, the PDOs are taken from `revision` or the newest revision
+pub fn generate_device_module(
+    file: &EsiFile,
+    type_name: &str,
+    revision: Option<u32>,
+    source: &str,
+) -> Result<String, anyhow::Error> {
+    let revisions = file.revisions(type_name);
+    let device = revision
+        .map_or_else(
+            || revisions.last(),
+            |revision| revisions.iter().find(|device| device.revision == revision),
+        )
+        .copied()
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "[{}::generate_device_module] Device {} {}not found",
+                module_path!(),
+                type_name,
+                revision
+                    .map(|revision| format!("with revision {:#010x} ", revision))
+                    .unwrap_or_default()
+            )
+        })?;
+
+    let prefix = const_name(&device.type_name);
+    let mut out = String::new();
+
+    writeln!(
+        out,
+        "//! {}\n//!\n//! Generated from `{}` with `ethercat-eeprom-dump esi-codegen`, review the names before using it.\n",
+        device.name, source
+    )?;
+
+    let objects = device
+        .tx_pdos
+        .iter()
+        .map(|pdo| (pdo, true))
+        .chain(device.rx_pdos.iter().map(|pdo| (pdo, false)))
+        .collect::<Vec<_>>();
+    let uses_bitfield = objects.iter().any(|(pdo, _)| {
+        pdo.entries
+            .iter()
+            .any(|entry| !matches!(entry.data_type, EsiDataType::Bool | EsiDataType::Padding))
+    });
+
+    writeln!(out, "use super::SubDeviceIdentityTuple;")?;
+    writeln!(
+        out,
+        "use crate::helpers::ethercrab_types::EthercrabSubDevicePreoperational;"
+    )?;
+    let mut pdo_traits = vec!["PredefinedPdoAssignment"];
+    if !device.rx_pdos.is_empty() {
+        pdo_traits.push("RxPdoObject");
+    }
+    if !device.tx_pdos.is_empty() {
+        pdo_traits.push("TxPdoObject");
+    }
+    writeln!(out, "use crate::pdo::{{{}}};", pdo_traits.join(", "))?;
+    if !objects.is_empty() {
+        match uses_bitfield {
+            true => writeln!(
+                out,
+                "use bitvec::{{field::BitField, order::Lsb0, slice::BitSlice}};"
+            )?,
+            false => writeln!(out, "use bitvec::{{order::Lsb0, slice::BitSlice}};")?,
+        }
+        writeln!(out, "use ethercat_hal_derive::{{PdoObject, RxPdo, TxPdo}};")?;
+    } else {
+        writeln!(out, "use ethercat_hal_derive::{{RxPdo, TxPdo}};")?;
+    }
+    writeln!(out)?;
+
+    // identities of all revisions in the file
+    writeln!(
+        out,
+        "pub const {}_VENDOR_ID: u32 = {:#x};",
+        prefix, file.vendor_id
+    )?;
+    writeln!(
+        out,
+        "pub const {}_PRODUCT_ID: u32 = {:#010x};",
+        prefix, device.product_code
+    )?;
+    for (position, revision) in revisions.iter().enumerate() {
+        writeln!(
+            out,
+            "pub const {}_REVISION_{}: u32 = {:#010x};",
+            prefix,
+            revision_suffix(position),
+            revision.revision
+        )?;
+    }
+    for position in 0..revisions.len() {
+        let suffix = revision_suffix(position);
+        writeln!(
+            out,
+            "pub const {prefix}_IDENTITY_{suffix}: SubDeviceIdentityTuple =\n    ({prefix}_VENDOR_ID, {prefix}_PRODUCT_ID, {prefix}_REVISION_{suffix});"
+        )?;
+    }
+
+    // PDO objects
+    let mut object_names = HashSet::new();
+    let mut object_types = vec![];
+    for (pdo, is_tx) in objects.iter() {
+        let name = unique_name(
+            format!("{}{}", prefix, type_name_part(&pdo.name, pdo.index)),
+            &mut object_names,
+        );
+        writeln!(out)?;
+        write_pdo_object(&mut out, &name, pdo, *is_tx)?;
+        object_types.push((pdo.index, name));
+    }
+
+    // TxPdo / RxPdo
+    let mut field_names = HashSet::new();
+    let fields = objects
+        .iter()
+        .map(|(pdo, _)| {
+            (
+                pdo.index,
+                unique_name(field_name(&pdo.name, pdo.index), &mut field_names),
+            )
+        })
+        .collect::<Vec<_>>();
+    for (suffix, pdos) in [("TxPdo", &device.tx_pdos), ("RxPdo", &device.rx_pdos)] {
+        writeln!(out)?;
+        writeln!(out, "#[derive(Debug, Clone, {})]", suffix)?;
+        if pdos.is_empty() {
+            writeln!(out, "pub struct {}{} {{}}", prefix, suffix)?;
+            continue;
+        }
+        writeln!(out, "pub struct {}{} {{", prefix, suffix)?;
+        for pdo in pdos.iter() {
+            writeln!(out, "    #[pdo_object_index({:#06X})]", pdo.index)?;
+            writeln!(
+                out,
+                "    pub {}: Option<{}>,",
+                lookup(&fields, pdo.index),
+                lookup(&object_types, pdo.index)
+            )?;
+        }
+        writeln!(out, "}}")?;
+    }
+
+    write_predefined_pdo_assignment(&mut out, &prefix, device, &fields, &object_types)?;
+
+    Ok(out)
+}
+
+/// Lists the devices of a file as `type revision name`
+pub fn list_devices(file: &EsiFile) -> Vec<String> {
+    file.devices
+        .iter()
+        .map(|device| {
+            format!(
+                "{} product {:#010x} revision {:#010x} {}",
+                device.type_name, device.product_code, device.revision, device.name
+            )
+        })
+        .collect()
+}
+
+fn write_pdo_object(
+    out: &mut String,
+    name: &str,
+    pdo: &EsiPdo,
+    is_tx: bool,
+) -> Result<(), anyhow::Error> {
+    let mut field_names = HashSet::new();
+    let mut fields = vec![];
+    let mut bit_offset = 0;
+    for entry in pdo.entries.iter() {
+        if let Some(rust_type) = rust_type(entry) {
+            let field = unique_name(field_name(&entry.name, entry.index), &mut field_names);
+            fields.push((field, rust_type, entry, bit_offset));
+        }
+        bit_offset += entry.bit_len;
+    }
+
+    writeln!(out, "/// {:#06X} {}", pdo.index, pdo.name)?;
+    writeln!(
+        out,
+        "#[derive(Debug, Clone, Default, PdoObject, PartialEq)]"
+    )?;
+    writeln!(out, "#[pdo_object(bits = {})]", pdo.bit_len())?;
+    writeln!(out, "pub struct {} {{", name)?;
+    for (field, rust_type, _, _) in fields.iter() {
+        writeln!(out, "    pub {}: {},", field, rust_type)?;
+    }
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+
+    match is_tx {
+        true => {
+            writeln!(out, "impl TxPdoObject for {} {{", name)?;
+            writeln!(
+                out,
+                "    fn read(&mut self, buffer: &BitSlice<u8, Lsb0>) {{"
+            )?;
+            for (field, rust_type, entry, offset) in fields.iter() {
+                let range = bit_range(*offset, entry.bit_len);
+                let value = match entry.data_type {
+                    EsiDataType::Bool => format!("buffer[{}]", offset),
+                    EsiDataType::Real => {
+                        format!("f32::from_bits(buffer[{}].load_le::<u32>())", range)
+                    }
+                    EsiDataType::LReal => {
+                        format!("f64::from_bits(buffer[{}].load_le::<u64>())", range)
+                    }
+                    _ => format!("buffer[{}].load_le::<{}>()", range, rust_type),
+                };
+                writeln!(out, "        self.{} = {};", field, value)?;
+            }
+            writeln!(out, "    }}")?;
+        }
+        false => {
+            writeln!(out, "impl RxPdoObject for {} {{", name)?;
+            writeln!(
+                out,
+                "    fn write(&self, buffer: &mut BitSlice<u8, Lsb0>) {{"
+            )?;
+            for (field, _, entry, offset) in fields.iter() {
+                let range = bit_range(*offset, entry.bit_len);
+                let statement = match entry.data_type {
+                    EsiDataType::Bool => format!("buffer.set({}, self.{});", offset, field),
+                    EsiDataType::Real | EsiDataType::LReal => {
+                        format!("buffer[{}].store_le(self.{}.to_bits());", range, field)
+                    }
+                    _ => format!("buffer[{}].store_le(self.{});", range, field),
+                };
+                writeln!(out, "        {}", statement)?;
+            }
+            writeln!(out, "    }}")?;
+        }
+    }
+    writeln!(out, "}}")?;
+
+    // entries the generator can't represent are left out, but keep their space in the object
+    for entry in pdo
+        .entries
+        .iter()
+        .filter(|entry| !entry.is_padding() && rust_type(entry).is_none())
+    {
+        writeln!(
+            out,
+            "// skipped {:#06X}:{} {} ({:?}, {} bits)",
+            entry.index, entry.sub_index, entry.name, entry.data_type, entry.bit_len
+        )?;
+    }
+    Ok(())
+}
+
+fn write_predefined_pdo_assignment(
+    out: &mut String,
+    prefix: &str,
+    device: &EsiDevice,
+    fields: &[(u16, String)],
+    object_types: &[(u16, String)],
+) -> Result<(), anyhow::Error> {
+    // without predefined assignments the default assignment is the only one
+    let mut variant_names = HashSet::new();
+    let assignments = match device.pdo_assignments.is_empty() {
+        true => vec![("Default".to_string(), device.default_pdos())],
+        false => {
+            let mut assignments = device.pdo_assignments.iter().collect::<Vec<_>>();
+            // the default assignment first, it becomes the `Default` of the enum
+            assignments.sort_by_key(|assignment| !assignment.default);
+            assignments
+                .into_iter()
+                .map(|assignment| {
+                    (
+                        unique_name(type_name_part(&assignment.name, 0), &mut variant_names),
+                        assignment.pdos.clone(),
+                    )
+                })
+                .collect()
+        }
+    };
+
+    writeln!(out)?;
+    writeln!(out, "#[derive(Debug, Clone)]")?;
+    writeln!(out, "pub enum {}PredefinedPdoAssignment {{", prefix)?;
+    for (variant, _) in assignments.iter() {
+        writeln!(out, "    {},", variant)?;
+    }
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+    writeln!(out, "impl Default for {}PredefinedPdoAssignment {{", prefix)?;
+    writeln!(out, "    fn default() -> Self {{")?;
+    writeln!(out, "        Self::{}", assignments[0].0)?;
+    writeln!(out, "    }}")?;
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "impl PredefinedPdoAssignment<{prefix}TxPdo, {prefix}RxPdo> for {prefix}PredefinedPdoAssignment {{"
+    )?;
+    for (function, suffix, pdos) in [
+        ("txpdo_assignment", "TxPdo", &device.tx_pdos),
+        ("rxpdo_assignment", "RxPdo", &device.rx_pdos),
+    ] {
+        if function == "rxpdo_assignment" {
+            writeln!(out)?;
+        }
+        writeln!(out, "    fn {}(&self) -> {}{} {{", function, prefix, suffix)?;
+        writeln!(out, "        match self {{")?;
+        for (variant, assigned) in assignments.iter() {
+            if pdos.is_empty() {
+                writeln!(
+                    out,
+                    "            Self::{} => {}{} {{}},",
+                    variant, prefix, suffix
+                )?;
+                continue;
+            }
+            writeln!(
+                out,
+                "            Self::{} => {}{} {{",
+                variant, prefix, suffix
+            )?;
+            for pdo in pdos.iter() {
+                let value = match assigned.contains(&pdo.index) {
+                    true => format!("Some({}::default())", lookup(object_types, pdo.index)),
+                    false => "None".to_string(),
+                };
+                writeln!(
+                    out,
+                    "                {}: {},",
+                    lookup(fields, pdo.index),
+                    value
+                )?;
+            }
+            writeln!(out, "            }},")?;
+        }
+        writeln!(out, "        }}")?;
+        writeln!(out, "    }}")?;
+    }
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
+fn lookup(names: &[(u16, String)], index: u16) -> &str {
+    names
+        .iter()
+        .find(|(i, _)| *i == index)
+        .map(|(_, name)| name.as_str())
+        .unwrap_or_default()
+}
+
+/// Rust type of an entry, `None` for padding and entries that can't be represented
+fn rust_type(entry: &EsiPdoEntry) -> Option<&'static str> {
+    let bits = |bits: usize, types: [&'static str; 4]| match bits {
+        1..=8 => Some(types[0]),
+        9..=16 => Some(types[1]),
+        17..=32 => Some(types[2]),
+        33..=64 => Some(types[3]),
+        _ => None,
+    };
+    match entry.data_type {
+        EsiDataType::Bool => Some("bool"),
+        EsiDataType::Unsigned(bits_len) => bits(bits_len, ["u8", "u16", "u32", "u64"]),
+        EsiDataType::Signed(bits_len) => bits(bits_len, ["i8", "i16", "i32", "i64"]),
+        EsiDataType::Real => Some("f32"),
+        EsiDataType::LReal => Some("f64"),
+        EsiDataType::Padding | EsiDataType::Other(_) => None,
+    }
+}
+
+fn bit_range(offset: usize, bit_len: usize) -> String {
+    match offset {
+        0 => format!("0..{}", bit_len),
+        _ => format!("{}..{} + {}", offset, offset, bit_len),
+    }
+}
+
+/// `A`, `B`, ..., `Z`, `AA`, ... like the hand written identities
+fn revision_suffix(position: usize) -> String {
+    let mut position = position;
+    let mut suffix = String::new();
+    loop {
+        suffix.insert(0, (b'A' + (position % 26) as u8) as char);
+        if position < 26 {
+            return suffix;
+        }
+        position = position / 26 - 1;
+    }
+}
+
+/// Splits ESI names like `Status__Underrange` or `AI Standard Channel 1` into words
+fn words(name: &str) -> Vec<String> {
+    let mut words = vec![];
+    let mut word = String::new();
+    let mut previous_lowercase = false;
+    for c in name.chars() {
+        if !c.is_ascii_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            previous_lowercase = false;
+            continue;
+        }
+        if c.is_ascii_uppercase() && previous_lowercase {
+            words.push(std::mem::take(&mut word));
+        }
+        previous_lowercase = c.is_ascii_lowercase() || c.is_ascii_digit();
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// `EL7031-0030` becomes `EL7031_0030`
+fn const_name(type_name: &str) -> String {
+    type_name
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c.to_ascii_uppercase(),
+            false => '_',
+        })
+        .collect()
+}
+
+/// Upper camel case part of a type name, `AI Standard` becomes `AiStandard`
+fn type_name_part(name: &str, index: u16) -> String {
+    let part = words(name)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().map(|c| c.to_ascii_uppercase());
+            let rest = chars.as_str().to_ascii_lowercase();
+            first.into_iter().collect::<String>() + &rest
+        })
+        .collect::<String>();
+    match part.is_empty() {
+        true => format!("Pdo{:04X}", index),
+        false => part,
+    }
+}
+
+/// Snake case field name, `Status__Limit 1` becomes `status_limit_1`
+fn field_name(name: &str, index: u16) -> String {
+    let name = words(name)
+        .iter()
+        .map(|word| word.to_ascii_lowercase())
+        .collect::<Vec<_>>()
+        .join("_");
+    match name.chars().next() {
+        None => format!("object_{:04x}", index),
+        Some(c) if c.is_ascii_digit() => format!("value_{}", name),
+        Some(_)
+            if matches!(
+                name.as_str(),
+                "type" | "mod" | "fn" | "match" | "ref" | "loop" | "move"
+            ) =>
+        {
+            format!("{}_", name)
+        }
+        Some(_) => name,
+    }
+}
+
+fn unique_name(name: String, taken: &mut HashSet<String>) -> String {
+    let mut candidate = name.clone();
+    let mut counter = 2;
+    while !taken.insert(candidate.clone()) {
+        candidate = format!("{}_{}", name, counter);
+        counter += 1;
+    }
+    candidate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::esi::{parse_esi, tests::EL3001_ESI};
+
+    #[test]
+    fn test_names() {
+        assert_eq!(field_name("Status__Limit 1", 0), "status_limit_1");
+        assert_eq!(field_name("TxPDO Toggle", 0), "tx_pdo_toggle");
+        assert_eq!(field_name("1 Channel", 0), "value_1_channel");
+        assert_eq!(field_name("", 0x1A00), "object_1a00");
+        assert_eq!(
+            type_name_part("AI Standard Channel 1", 0),
+            "AiStandardChannel1"
+        );
+        assert_eq!(const_name("EL7031-0030"), "EL7031_0030");
+        assert_eq!(revision_suffix(0), "A");
+        assert_eq!(revision_suffix(27), "AB");
+    }
+
+    #[test]
+    fn test_generate_device_module() {
+        let file = parse_esi(EL3001_ESI).unwrap();
+        let module = generate_device_module(&file, "EL3001", None, "Beckhoff EL3xxx.xml").unwrap();
+
+        // both revisions get an identity, the newest one is B
+        assert!(module.contains("pub const EL3001_REVISION_A: u32 = 0x00140000;"));
+        assert!(module.contains("pub const EL3001_REVISION_B: u32 = 0x00150000;"));
+        assert!(module.contains("pub const EL3001_IDENTITY_B: SubDeviceIdentityTuple ="));
+
+        assert!(module.contains("#[pdo_object(bits = 32)]\npub struct EL3001AiStandardChannel1 {"));
+        assert!(module.contains("self.status_limit_1 = buffer[2..2 + 2].load_le::<u8>();"));
+        // the 12 padding bits are skipped
+        assert!(module.contains("self.value = buffer[16..16 + 16].load_le::<i16>();"));
+        assert!(module.contains(
+            "    #[pdo_object_index(0x1A01)]\n    pub ai_compact_channel_1: Option<EL3001AiCompactChannel1>,"
+        ));
+        assert!(module.contains("    Standard,\n    Compact,\n"));
+        assert!(module.contains("        Self::Standard\n"));
+        assert!(module.contains("Self::Compact => EL3001RxPdo {},"));
+
+        assert!(generate_device_module(&file, "EL3002", None, "").is_err());
+        assert!(generate_device_module(&file, "EL3001", Some(0x00100000), "").is_err());
+    }
+}
@@ -0,0 +1,456 @@
+//! Parser for EtherCAT Slave Information (ESI) XML files as published by Beckhoff, WAGO and others
+//!
+//! Only the parts needed for process data are read: identity, PDOs with their entries and the
+//! predefined PDO assignments TwinCAT offers.
+
+pub mod codegen;
+
+use std::path::Path;
+
+/// Contents of an ESI file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EsiFile {
+    pub vendor_id: u32,
+    pub vendor_name: String,
+    /// One entry per device and revision
+    pub devices: Vec<EsiDevice>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EsiDevice {
+    /// Order number like `EL3001` or `EL7031-0030`
+    pub type_name: String,
+    /// Human readable description
+    pub name: String,
+    pub product_code: u32,
+    pub revision: u32,
+    pub rx_pdos: Vec<EsiPdo>,
+    pub tx_pdos: Vec<EsiPdo>,
+    /// Predefined PDO assignments, empty if the file has none
+    pub pdo_assignments: Vec<EsiPdoAssignment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EsiPdo {
+    pub index: u16,
+    pub name: String,
+    /// The entries of the PDO can't be changed
+    pub fixed: bool,
+    /// The PDO has to be assigned
+    pub mandatory: bool,
+    /// Sync manager the PDO is assigned to by default, `None` if it is not assigned by default
+    pub sm: Option<u8>,
+    /// PDOs that can't be assigned together with this one
+    pub excludes: Vec<u16>,
+    pub entries: Vec<EsiPdoEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EsiPdoEntry {
+    /// Object index, `0` for padding
+    pub index: u16,
+    pub sub_index: u8,
+    pub bit_len: usize,
+    pub name: String,
+    pub data_type: EsiDataType,
+}
+
+/// Data type of a PDO entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EsiDataType {
+    Bool,
+    /// `BITn`, `USINT`, `UINT`, `UDINT`, `ULINT` and their aliases with the bit length
+    Unsigned(usize),
+    /// `SINT`, `INT`, `DINT`, `LINT` with the bit length
+    Signed(usize),
+    Real,
+    LReal,
+    /// Padding entries have no data type
+    Padding,
+    /// Strings, arrays and vendor specific types
+    Other(String),
+}
+
+/// A predefined PDO assignment (`AlternativeSmMapping`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EsiPdoAssignment {
+    pub name: String,
+    pub default: bool,
+    /// Assigned PDOs of all sync managers
+    pub pdos: Vec<u16>,
+}
+
+impl EsiPdoEntry {
+    pub const fn is_padding(&self) -> bool {
+        self.index == 0
+    }
+}
+
+impl EsiPdo {
+    pub fn bit_len(&self) -> usize {
+        self.entries.iter().map(|entry| entry.bit_len).sum()
+    }
+}
+
+impl EsiDataType {
+    pub fn from_esi(data_type: &str, bit_len: usize) -> Self {
+        match data_type.trim().to_ascii_uppercase().as_str() {
+            "BOOL" | "BIT" if bit_len == 1 => Self::Bool,
+            "USINT" | "BYTE" | "UINT" | "WORD" | "UDINT" | "DWORD" | "ULINT" | "LWORD" => {
+                Self::Unsigned(bit_len)
+            }
+            "SINT" | "INT" | "DINT" | "LINT" => Self::Signed(bit_len),
+            "REAL" | "FLOAT" if bit_len == 32 => Self::Real,
+            "LREAL" | "DOUBLE" if bit_len == 64 => Self::LReal,
+            other if other.starts_with("BIT") && bit_len <= 64 => Self::Unsigned(bit_len),
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+impl EsiDevice {
+    pub const fn identity(&self, vendor_id: u32) -> (u32, u32, u32) {
+        (vendor_id, self.product_code, self.revision)
+    }
+
+    pub fn pdo(&self, index: u16) -> Option<&EsiPdo> {
+        self.rx_pdos
+            .iter()
+            .chain(self.tx_pdos.iter())
+            .find(|pdo| pdo.index == index)
+    }
+
+    /// PDOs assigned when the device starts, from the default predefined assignment or the `Sm` attributes
+    pub fn default_pdos(&self) -> Vec<u16> {
+        if let Some(assignment) = self
+            .pdo_assignments
+            .iter()
+            .find(|assignment| assignment.default)
+        {
+            return assignment.pdos.clone();
+        }
+        self.rx_pdos
+            .iter()
+            .chain(self.tx_pdos.iter())
+            .filter(|pdo| pdo.sm.is_some() || pdo.mandatory)
+            .map(|pdo| pdo.index)
+            .collect()
+    }
+}
+
+impl EsiFile {
+    /// All revisions of a device type, oldest first
+    pub fn revisions(&self, type_name: &str) -> Vec<&EsiDevice> {
+        let mut devices = self
+            .devices
+            .iter()
+            .filter(|device| device.type_name.eq_ignore_ascii_case(type_name))
+            .collect::<Vec<_>>();
+        devices.sort_by_key(|device| device.revision);
+        devices
+    }
+
+    /// Finds the device with the given identity
+    pub fn device(&self, product_code: u32, revision: u32) -> Option<&EsiDevice> {
+        self.devices
+            .iter()
+            .find(|device| device.product_code == product_code && device.revision == revision)
+    }
+}
+
+/// Parses numbers in ESI notation, `#x1A00` is hexadecimal, everything else decimal
+pub fn parse_esi_number(value: &str) -> Option<u32> {
+    let value = value.trim();
+    if let Some(hex) = value
+        .strip_prefix("#x")
+        .or_else(|| value.strip_prefix("#X"))
+        .or_else(|| value.strip_prefix("0x"))
+    {
+        return u32::from_str_radix(hex, 16).ok();
+    }
+    // some files write negative numbers for product codes above i32::MAX
+    value
+        .parse::<u32>()
+        .ok()
+        .or_else(|| value.parse::<i32>().ok().map(|value| value as u32))
+}
+
+/// Reads an ESI file, most vendors encode them as ISO-8859-1
+pub fn read_esi<P: AsRef<Path>>(path: P) -> Result<EsiFile, anyhow::Error> {
+    let bytes = std::fs::read(&path).map_err(|e| {
+        anyhow::anyhow!(
+            "[{}::read_esi] Failed to read {}: {}",
+            module_path!(),
+            path.as_ref().display(),
+            e
+        )
+    })?;
+    let content = match String::from_utf8(bytes) {
+        Ok(content) => content,
+        // every byte of ISO-8859-1 maps to the unicode code point with the same value
+        Err(e) => e.into_bytes().iter().map(|byte| *byte as char).collect(),
+    };
+    parse_esi(&content)
+}
+
+pub fn parse_esi(content: &str) -> Result<EsiFile, anyhow::Error> {
+    let document = roxmltree::Document::parse(content)
+        .map_err(|e| anyhow::anyhow!("[{}::parse_esi] Invalid XML: {}", module_path!(), e))?;
+    let root = document.root_element();
+    if !root.has_tag_name("EtherCATInfo") {
+        return Err(anyhow::anyhow!(
+            "[{}::parse_esi] Expected EtherCATInfo root element, found {}",
+            module_path!(),
+            root.tag_name().name()
+        ));
+    }
+
+    let vendor = child(root, "Vendor")
+        .ok_or_else(|| anyhow::anyhow!("[{}::parse_esi] Missing Vendor element", module_path!()))?;
+    let vendor_id = child_number(vendor, "Id")
+        .ok_or_else(|| anyhow::anyhow!("[{}::parse_esi] Missing vendor id", module_path!()))?;
+    let vendor_name = child_text(vendor, "Name").unwrap_or_default();
+
+    let devices = root
+        .descendants()
+        .filter(|node| node.has_tag_name("Device"))
+        // modules and groups can also contain Device elements, only the ones with a Type are devices
+        .filter(|node| child(*node, "Type").is_some())
+        .map(parse_device)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(EsiFile {
+        vendor_id,
+        vendor_name,
+        devices,
+    })
+}
+
+fn parse_device(node: roxmltree::Node) -> Result<EsiDevice, anyhow::Error> {
+    let type_node = child(node, "Type").expect("filtered by caller");
+    let type_name = type_node.text().unwrap_or_default().trim().to_string();
+    let product_code = type_node
+        .attribute("ProductCode")
+        .and_then(parse_esi_number)
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "[{}::parse_device] Device {} has no product code",
+                module_path!(),
+                type_name
+            )
+        })?;
+    let revision = type_node
+        .attribute("RevisionNo")
+        .and_then(parse_esi_number)
+        .unwrap_or(0);
+
+    let rx_pdos = children(node, "RxPdo")
+        .map(parse_pdo)
+        .collect::<Result<Vec<_>, _>>()?;
+    let tx_pdos = children(node, "TxPdo")
+        .map(parse_pdo)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let pdo_assignments = node
+        .descendants()
+        .filter(|node| node.has_tag_name("AlternativeSmMapping"))
+        .map(|mapping| EsiPdoAssignment {
+            name: child_text(mapping, "Name").unwrap_or_default(),
+            default: mapping
+                .attribute("Default")
+                .is_some_and(|default| default == "1" || default == "true"),
+            pdos: children(mapping, "Sm")
+                .flat_map(|sm| children(sm, "Pdo"))
+                .filter_map(|pdo| pdo.text().and_then(parse_esi_number))
+                .map(|index| index as u16)
+                .collect(),
+        })
+        .collect();
+
+    Ok(EsiDevice {
+        name: child_text(node, "Name").unwrap_or_else(|| type_name.clone()),
+        type_name,
+        product_code,
+        revision,
+        rx_pdos,
+        tx_pdos,
+        pdo_assignments,
+    })
+}
+
+fn parse_pdo(node: roxmltree::Node) -> Result<EsiPdo, anyhow::Error> {
+    let index = child_number(node, "Index")
+        .ok_or_else(|| anyhow::anyhow!("[{}::parse_pdo] PDO without index", module_path!()))?
+        as u16;
+
+    let entries = children(node, "Entry")
+        .map(|entry| {
+            let entry_index = child_number(entry, "Index").unwrap_or(0) as u16;
+            let bit_len = child_number(entry, "BitLen").ok_or_else(|| {
+                anyhow::anyhow!(
+                    "[{}::parse_pdo] Entry of PDO {:#06x} has no bit length",
+                    module_path!(),
+                    index
+                )
+            })? as usize;
+            let data_type = match (entry_index, child_text(entry, "DataType")) {
+                (0, _) | (_, None) => EsiDataType::Padding,
+                (_, Some(data_type)) => EsiDataType::from_esi(&data_type, bit_len),
+            };
+            Ok(EsiPdoEntry {
+                index: entry_index,
+                sub_index: child_number(entry, "SubIndex").unwrap_or(0) as u8,
+                bit_len,
+                name: child_text(entry, "Name").unwrap_or_default(),
+                data_type,
+            })
+        })
+        .collect::<Result<Vec<_>, anyhow::Error>>()?;
+
+    Ok(EsiPdo {
+        index,
+        name: child_text(node, "Name").unwrap_or_default(),
+        fixed: flag(node, "Fixed"),
+        mandatory: flag(node, "Mandatory"),
+        sm: node
+            .attribute("Sm")
+            .and_then(parse_esi_number)
+            .map(|sm| sm as u8),
+        excludes: children(node, "Exclude")
+            .filter_map(|exclude| exclude.text().and_then(parse_esi_number))
+            .map(|index| index as u16)
+            .collect(),
+        entries,
+    })
+}
+
+fn children<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    name: &'static str,
+) -> impl Iterator<Item = roxmltree::Node<'a, 'input>> {
+    node.children()
+        .filter(move |child| child.has_tag_name(name))
+}
+
+fn child<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    name: &'static str,
+) -> Option<roxmltree::Node<'a, 'input>> {
+    children(node, name).next()
+}
+
+/// Text of a child element, names are localized so English (`LcId` 1033) is preferred
+fn child_text(node: roxmltree::Node, name: &'static str) -> Option<String> {
+    children(node, name)
+        .find(|child| child.attribute("LcId").is_none_or(|lcid| lcid == "1033"))
+        .or_else(|| child(node, name))
+        .and_then(|child| child.text())
+        .map(|text| text.trim().to_string())
+}
+
+fn child_number(node: roxmltree::Node, name: &'static str) -> Option<u32> {
+    child(node, name)
+        .and_then(|child| child.text())
+        .and_then(parse_esi_number)
+}
+
+fn flag(node: roxmltree::Node, name: &str) -> bool {
+    node.attribute(name)
+        .is_some_and(|value| value == "1" || value == "true")
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Trimmed down EL3001 description with two revisions
+    pub const EL3001_ESI: &str = r##"<?xml version="1.0" encoding="ISO8859-1"?>
+<EtherCATInfo>
+  <Vendor>
+    <Id>2</Id>
+    <Name>Beckhoff Automation GmbH &amp; Co. KG</Name>
+  </Vendor>
+  <Descriptions>
+    <Devices>
+      <Device Physics="YY">
+        <Type ProductCode="#x0bb93052" RevisionNo="#x00150000">EL3001</Type>
+        <Name LcId="1031">EL3001 1K. Ana. Eingang +/-10V</Name>
+        <Name LcId="1033">EL3001 1Ch. Ana. Input +/-10V</Name>
+        <TxPdo Fixed="1" Sm="3">
+          <Index>#x1a00</Index>
+          <Name>AI Standard Channel 1</Name>
+          <Exclude>#x1a01</Exclude>
+          <Entry><Index>#x6000</Index><SubIndex>1</SubIndex><BitLen>1</BitLen><Name>Status__Underrange</Name><DataType>BOOL</DataType></Entry>
+          <Entry><Index>#x6000</Index><SubIndex>2</SubIndex><BitLen>1</BitLen><Name>Status__Overrange</Name><DataType>BOOL</DataType></Entry>
+          <Entry><Index>#x6000</Index><SubIndex>3</SubIndex><BitLen>2</BitLen><Name>Status__Limit 1</Name><DataType>BIT2</DataType></Entry>
+          <Entry><Index>#x0</Index><BitLen>12</BitLen></Entry>
+          <Entry><Index>#x6000</Index><SubIndex>17</SubIndex><BitLen>16</BitLen><Name>Value</Name><DataType>INT</DataType></Entry>
+        </TxPdo>
+        <TxPdo Fixed="1">
+          <Index>#x1a01</Index>
+          <Name>AI Compact Channel 1</Name>
+          <Exclude>#x1a00</Exclude>
+          <Entry><Index>#x6000</Index><SubIndex>17</SubIndex><BitLen>16</BitLen><Name>Value</Name><DataType>INT</DataType></Entry>
+        </TxPdo>
+        <VendorSpecific>
+          <TwinCAT>
+            <AlternativeSmMapping Default="1">
+              <Name>Standard</Name>
+              <Sm No="3"><Pdo>#x1a00</Pdo></Sm>
+            </AlternativeSmMapping>
+            <AlternativeSmMapping>
+              <Name>Compact</Name>
+              <Sm No="3"><Pdo>#x1a01</Pdo></Sm>
+            </AlternativeSmMapping>
+          </TwinCAT>
+        </VendorSpecific>
+      </Device>
+      <Device Physics="YY">
+        <Type ProductCode="#x0bb93052" RevisionNo="#x00140000">EL3001</Type>
+        <Name LcId="1033">EL3001 1Ch. Ana. Input +/-10V</Name>
+      </Device>
+    </Devices>
+  </Descriptions>
+</EtherCATInfo>
+"##;
+
+    #[test]
+    fn test_parse_esi_number() {
+        assert_eq!(parse_esi_number("#x1a00"), Some(0x1A00));
+        assert_eq!(parse_esi_number(" 42 "), Some(42));
+        assert_eq!(parse_esi_number("-1"), Some(u32::MAX));
+        assert_eq!(parse_esi_number("#xzz"), None);
+    }
+
+    #[test]
+    fn test_parse_esi() {
+        let file = parse_esi(EL3001_ESI).unwrap();
+        assert_eq!(file.vendor_id, 2);
+        assert_eq!(file.devices.len(), 2);
+
+        let revisions = file.revisions("el3001");
+        assert_eq!(
+            revisions
+                .iter()
+                .map(|device| device.revision)
+                .collect::<Vec<_>>(),
+            vec![0x00140000, 0x00150000]
+        );
+
+        let device = file.device(0x0bb93052, 0x00150000).unwrap();
+        assert_eq!(device.name, "EL3001 1Ch. Ana. Input +/-10V");
+        assert!(device.rx_pdos.is_empty());
+        assert_eq!(device.tx_pdos.len(), 2);
+
+        let standard = device.pdo(0x1A00).unwrap();
+        assert_eq!(standard.bit_len(), 32);
+        assert_eq!(standard.sm, Some(3));
+        assert_eq!(standard.excludes, vec![0x1A01]);
+        assert_eq!(standard.entries[2].data_type, EsiDataType::Unsigned(2));
+        assert!(standard.entries[3].is_padding());
+        assert_eq!(standard.entries[4].data_type, EsiDataType::Signed(16));
+
+        assert_eq!(device.pdo_assignments.len(), 2);
+        assert_eq!(device.default_pdos(), vec![0x1A00]);
+    }
+}
@@ -0,0 +1,323 @@
+//! CiA 402 (DS402) drive profile state machine
+
+/// Bits 0 to 3 and 7 of the controlword select the state transition
+pub const CONTROLWORD_SHUTDOWN: u16 = 0x0006;
+pub const CONTROLWORD_SWITCH_ON: u16 = 0x0007;
+pub const CONTROLWORD_DISABLE_OPERATION: u16 = 0x0007;
+pub const CONTROLWORD_ENABLE_OPERATION: u16 = 0x000F;
+pub const CONTROLWORD_DISABLE_VOLTAGE: u16 = 0x0000;
+pub const CONTROLWORD_QUICK_STOP: u16 = 0x0002;
+/// Resets a fault on the rising edge
+pub const CONTROLWORD_FAULT_RESET: u16 = 0x0080;
+/// Profile position: start a move to the target position on the rising edge
+pub const CONTROLWORD_NEW_SETPOINT: u16 = 1 << 4;
+/// Profile position: abort the current move instead of finishing it first
+pub const CONTROLWORD_CHANGE_SET_IMMEDIATELY: u16 = 1 << 5;
+pub const CONTROLWORD_HALT: u16 = 1 << 8;
+
+pub const STATUSWORD_WARNING: u16 = 1 << 7;
+pub const STATUSWORD_TARGET_REACHED: u16 = 1 << 10;
+pub const STATUSWORD_INTERNAL_LIMIT_ACTIVE: u16 = 1 << 11;
+/// Profile position: the new set-point was accepted, CSP/CSV: the drive follows the target
+pub const STATUSWORD_SETPOINT_ACKNOWLEDGE: u16 = 1 << 12;
+/// Profile position and CSP: the following error exceeded the window
+pub const STATUSWORD_FOLLOWING_ERROR: u16 = 1 << 13;
+
+/// State of the drive decoded from the statusword
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cia402State {
+    NotReadyToSwitchOn,
+    SwitchOnDisabled,
+    ReadyToSwitchOn,
+    SwitchedOn,
+    OperationEnabled,
+    QuickStopActive,
+    FaultReactionActive,
+    Fault,
+}
+
+impl Cia402State {
+    pub const fn from_statusword(statusword: u16) -> Self {
+        match (statusword & 0x004F, statusword & 0x006F) {
+            (0x0040, _) => Self::SwitchOnDisabled,
+            (_, 0x0021) => Self::ReadyToSwitchOn,
+            (_, 0x0023) => Self::SwitchedOn,
+            (_, 0x0027) => Self::OperationEnabled,
+            (_, 0x0007) => Self::QuickStopActive,
+            (0x000F, _) => Self::FaultReactionActive,
+            (0x0008, _) => Self::Fault,
+            _ => Self::NotReadyToSwitchOn,
+        }
+    }
+
+    /// Statusword bits 0 to 6 of the state, as written by a drive
+    pub const fn statusword(&self) -> u16 {
+        match self {
+            Self::NotReadyToSwitchOn => 0x0000,
+            Self::SwitchOnDisabled => 0x0040,
+            Self::ReadyToSwitchOn => 0x0021,
+            Self::SwitchedOn => 0x0023,
+            Self::OperationEnabled => 0x0027,
+            Self::QuickStopActive => 0x0007,
+            Self::FaultReactionActive => 0x000F,
+            Self::Fault => 0x0008,
+        }
+    }
+
+    /// State a drive changes to when it receives the controlword
+    pub const fn transition(&self, controlword: u16) -> Self {
+        let command = controlword & 0x008F;
+        let fault_reset = command & CONTROLWORD_FAULT_RESET != 0;
+        let enable_voltage = command & 0x0002 != 0;
+        let quick_stop = command & 0x0004 == 0;
+        let switch_on = command & 0x0001 != 0;
+        let enable_operation = command & 0x0008 != 0;
+        match self {
+            Self::NotReadyToSwitchOn => Self::SwitchOnDisabled,
+            Self::FaultReactionActive => Self::Fault,
+            Self::Fault if fault_reset => Self::SwitchOnDisabled,
+            Self::Fault => Self::Fault,
+            _ if !enable_voltage => Self::SwitchOnDisabled,
+            Self::OperationEnabled if quick_stop => Self::QuickStopActive,
+            Self::QuickStopActive => Self::QuickStopActive,
+            _ if quick_stop => Self::SwitchOnDisabled,
+            Self::SwitchOnDisabled => Self::ReadyToSwitchOn,
+            _ if !switch_on => Self::ReadyToSwitchOn,
+            Self::ReadyToSwitchOn => Self::SwitchedOn,
+            _ if enable_operation => Self::OperationEnabled,
+            _ => Self::SwitchedOn,
+        }
+    }
+}
+
+/// Modes of operation (0x6060) we support
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Cia402ModeOfOperation {
+    /// PP, moves to a target position with the drive's own profile
+    ProfilePosition,
+    /// CSP, follows a position set every cycle
+    CyclicSynchronousPosition,
+    /// CSV, follows a velocity set every cycle
+    #[default]
+    CyclicSynchronousVelocity,
+}
+
+impl Cia402ModeOfOperation {
+    pub const fn value(&self) -> i8 {
+        match self {
+            Self::ProfilePosition => 1,
+            Self::CyclicSynchronousPosition => 8,
+            Self::CyclicSynchronousVelocity => 9,
+        }
+    }
+
+    pub const fn from_value(value: i8) -> Option<Self> {
+        match value {
+            1 => Some(Self::ProfilePosition),
+            8 => Some(Self::CyclicSynchronousPosition),
+            9 => Some(Self::CyclicSynchronousVelocity),
+            _ => None,
+        }
+    }
+}
+
+/// What the application wants from the drive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Cia402Request {
+    /// Bring the drive to operation enabled, otherwise to ready to switch on
+    pub enable: bool,
+    /// Stop with the quick stop ramp and stay in quick stop active
+    pub quick_stop: bool,
+    /// Reset a fault, the drive stays disabled until the request is cleared
+    pub fault_reset: bool,
+    pub halt: bool,
+    /// Profile position: start a move to the current target position
+    pub new_setpoint: bool,
+}
+
+/// Walks the drive through the state machine, one transition per cycle
+#[derive(Debug, Clone, Default)]
+pub struct Cia402Controller {
+    /// A new set-point was requested and not yet acknowledged
+    setpoint_pending: bool,
+    /// The new set-point bit is set in the controlword
+    setpoint_bit: bool,
+    /// The fault reset bit was set last cycle, it only works on the rising edge
+    fault_reset_bit: bool,
+}
+
+impl Cia402Controller {
+    pub const fn new() -> Self {
+        Self {
+            setpoint_pending: false,
+            setpoint_bit: false,
+            fault_reset_bit: false,
+        }
+    }
+
+    /// Whether a requested set-point was not yet acknowledged
+    pub const fn setpoint_pending(&self) -> bool {
+        self.setpoint_pending
+    }
+
+    /// Controlword for the next cycle based on the last statusword
+    pub const fn controlword(&mut self, statusword: u16, request: &Cia402Request) -> u16 {
+        let state = Cia402State::from_statusword(statusword);
+
+        if request.new_setpoint {
+            self.setpoint_pending = true;
+        }
+        // set-point handshake, the bit is cleared after the drive acknowledged it
+        let acknowledged = statusword & STATUSWORD_SETPOINT_ACKNOWLEDGE != 0;
+        if self.setpoint_bit && acknowledged {
+            self.setpoint_pending = false;
+            self.setpoint_bit = false;
+        } else if self.setpoint_pending && !acknowledged {
+            self.setpoint_bit = matches!(state, Cia402State::OperationEnabled);
+        }
+
+        let fault_reset_bit = self.fault_reset_bit;
+        self.fault_reset_bit = false;
+
+        match state {
+            Cia402State::Fault if request.fault_reset && !fault_reset_bit => {
+                self.fault_reset_bit = true;
+                CONTROLWORD_FAULT_RESET
+            }
+            Cia402State::Fault
+            | Cia402State::FaultReactionActive
+            | Cia402State::NotReadyToSwitchOn => CONTROLWORD_DISABLE_VOLTAGE,
+            Cia402State::OperationEnabled if request.quick_stop => CONTROLWORD_QUICK_STOP,
+            Cia402State::QuickStopActive if request.quick_stop => CONTROLWORD_QUICK_STOP,
+            Cia402State::QuickStopActive => CONTROLWORD_DISABLE_VOLTAGE,
+            _ if request.quick_stop || request.fault_reset => CONTROLWORD_DISABLE_VOLTAGE,
+            Cia402State::OperationEnabled if request.enable => {
+                let mut controlword = CONTROLWORD_ENABLE_OPERATION;
+                if self.setpoint_bit {
+                    controlword |= CONTROLWORD_NEW_SETPOINT | CONTROLWORD_CHANGE_SET_IMMEDIATELY;
+                }
+                if request.halt {
+                    controlword |= CONTROLWORD_HALT;
+                }
+                controlword
+            }
+            Cia402State::OperationEnabled => CONTROLWORD_DISABLE_OPERATION,
+            Cia402State::SwitchedOn if request.enable => CONTROLWORD_ENABLE_OPERATION,
+            Cia402State::ReadyToSwitchOn if request.enable => CONTROLWORD_SWITCH_ON,
+            Cia402State::SwitchOnDisabled
+            | Cia402State::ReadyToSwitchOn
+            | Cia402State::SwitchedOn => CONTROLWORD_SHUTDOWN,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_from_statusword() {
+        let states = [
+            Cia402State::NotReadyToSwitchOn,
+            Cia402State::SwitchOnDisabled,
+            Cia402State::ReadyToSwitchOn,
+            Cia402State::SwitchedOn,
+            Cia402State::OperationEnabled,
+            Cia402State::QuickStopActive,
+            Cia402State::FaultReactionActive,
+            Cia402State::Fault,
+        ];
+        for state in states {
+            // the other bits don't matter
+            let statusword = state.statusword() | STATUSWORD_TARGET_REACHED | 0x0010;
+            assert_eq!(Cia402State::from_statusword(statusword), state);
+        }
+    }
+
+    #[test]
+    fn test_controller_enable_and_disable() {
+        let mut controller = Cia402Controller::new();
+        let mut state = Cia402State::SwitchOnDisabled;
+        let enable = Cia402Request {
+            enable: true,
+            ..Default::default()
+        };
+
+        let mut states = vec![];
+        for _ in 0..4 {
+            state = state.transition(controller.controlword(state.statusword(), &enable));
+            states.push(state);
+        }
+        assert_eq!(
+            states,
+            vec![
+                Cia402State::ReadyToSwitchOn,
+                Cia402State::SwitchedOn,
+                Cia402State::OperationEnabled,
+                Cia402State::OperationEnabled,
+            ]
+        );
+
+        // disabling goes back through switched on
+        let disable = Cia402Request::default();
+        state = state.transition(controller.controlword(state.statusword(), &disable));
+        assert_eq!(state, Cia402State::SwitchedOn);
+        state = state.transition(controller.controlword(state.statusword(), &disable));
+        assert_eq!(state, Cia402State::ReadyToSwitchOn);
+    }
+
+    #[test]
+    fn test_controller_fault_reset_edge() {
+        let mut controller = Cia402Controller::new();
+        let reset = Cia402Request {
+            enable: true,
+            fault_reset: true,
+            ..Default::default()
+        };
+        let fault = Cia402State::Fault.statusword();
+        assert_eq!(
+            controller.controlword(fault, &reset),
+            CONTROLWORD_FAULT_RESET
+        );
+        // the bit has to drop before the next rising edge
+        assert_eq!(
+            controller.controlword(fault, &reset),
+            CONTROLWORD_DISABLE_VOLTAGE
+        );
+        assert_eq!(
+            controller.controlword(fault, &reset),
+            CONTROLWORD_FAULT_RESET
+        );
+        // while the reset is requested the drive is not enabled
+        let disabled = Cia402State::SwitchOnDisabled.statusword();
+        assert_eq!(
+            controller.controlword(disabled, &reset),
+            CONTROLWORD_DISABLE_VOLTAGE
+        );
+    }
+
+    #[test]
+    fn test_controller_setpoint_handshake() {
+        let mut controller = Cia402Controller::new();
+        let enabled = Cia402State::OperationEnabled.statusword();
+        let request = Cia402Request {
+            enable: true,
+            new_setpoint: true,
+            ..Default::default()
+        };
+        let controlword = controller.controlword(enabled, &request);
+        assert_ne!(controlword & CONTROLWORD_NEW_SETPOINT, 0);
+
+        let request = Cia402Request {
+            enable: true,
+            ..Default::default()
+        };
+        // still set until the drive acknowledges
+        let controlword = controller.controlword(enabled, &request);
+        assert_ne!(controlword & CONTROLWORD_NEW_SETPOINT, 0);
+        let controlword =
+            controller.controlword(enabled | STATUSWORD_SETPOINT_ACKNOWLEDGE, &request);
+        assert_eq!(controlword & CONTROLWORD_NEW_SETPOINT, 0);
+        assert!(!controller.setpoint_pending());
+    }
+}
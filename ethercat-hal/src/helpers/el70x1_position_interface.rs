@@ -0,0 +1,199 @@
+//! Travel command handshake of the EL70x1 positioning interface
+
+use crate::{
+    io::stepper_position_el70x1::{StepperPositionEL70x1Input, StepperPositionEL70x1Output},
+    pdo::el70x1::{EncControl, EncStatus, PosControl, PosStatus, StmControl, StmStatus},
+    shared_config::el70x1::StartType,
+};
+
+/// Maps [`StepperPositionEL70x1Output`] onto the positioning interface PDOs
+///
+/// The terminal only starts a travel command on the rising edge of `execute`. Starting a new
+/// command while the last one is still latched holds `execute` low for one cycle first.
+#[derive(Debug, Default)]
+pub struct EL70x1PositionInterface {
+    /// A travel command is waiting for its rising edge
+    restart: bool,
+    /// `execute` was low for one cycle
+    restart_low_sent: bool,
+    /// Counter value to set in the next cycle
+    set_counter: Option<u32>,
+}
+
+impl EL70x1PositionInterface {
+    pub const fn new() -> Self {
+        Self {
+            restart: false,
+            restart_low_sent: false,
+            set_counter: None,
+        }
+    }
+
+    pub fn set_output(
+        &mut self,
+        stm_control: &mut StmControl,
+        pos_control: &mut PosControl,
+        value: &StepperPositionEL70x1Output,
+    ) {
+        stm_control.enable = value.enable;
+        stm_control.reduce_torque = value.reduce_torque;
+        stm_control.reset = value.reset;
+
+        if value.start {
+            if pos_control.execute || self.restart {
+                pos_control.execute = false;
+                self.restart = true;
+                self.restart_low_sent = false;
+            } else {
+                pos_control.execute = true;
+            }
+        } else if !value.execute {
+            // falling edge aborts the travel command
+            pos_control.execute = false;
+            self.restart = false;
+        }
+
+        pos_control.emergency_stop = value.emergency_stop;
+        pos_control.target_position = value.target_position as u32;
+        pos_control.target_velocity = value.target_velocity;
+        pos_control.start_type = u16::from(value.start_type);
+        pos_control.acceleration = value.acceleration;
+        pos_control.deceleration = value.deceleration;
+
+        if let Some(counter) = value.set_counter {
+            self.set_counter = Some(counter as u32);
+        }
+    }
+
+    pub fn get_output(
+        &self,
+        stm_control: &StmControl,
+        pos_control: &PosControl,
+    ) -> StepperPositionEL70x1Output {
+        StepperPositionEL70x1Output {
+            enable: stm_control.enable,
+            reduce_torque: stm_control.reduce_torque,
+            reset: stm_control.reset,
+            start: false,
+            execute: pos_control.execute || self.restart,
+            emergency_stop: pos_control.emergency_stop,
+            target_position: pos_control.target_position as i32,
+            target_velocity: pos_control.target_velocity,
+            start_type: StartType::try_from(pos_control.start_type).unwrap_or(StartType::Idle),
+            acceleration: pos_control.acceleration,
+            deceleration: pos_control.deceleration,
+            set_counter: self.set_counter.map(|counter| counter as i32),
+        }
+    }
+
+    pub const fn get_input(
+        stm_status: &StmStatus,
+        pos_status: &PosStatus,
+        enc_status: &EncStatus,
+    ) -> StepperPositionEL70x1Input {
+        StepperPositionEL70x1Input {
+            counter_value: enc_status.counter_value as i32,
+            actual_position: pos_status.actual_position as i32,
+            actual_velocity: pos_status.actual_velocity,
+            ready_to_enable: stm_status.ready_to_enable,
+            ready: stm_status.ready,
+            warning: stm_status.warning,
+            error: stm_status.error,
+            busy: pos_status.busy,
+            in_target: pos_status.in_target,
+            pos_warning: pos_status.warning,
+            pos_error: pos_status.error,
+            calibrated: pos_status.calibrated,
+            accelerate: pos_status.accelerate,
+            decelerate: pos_status.decelerate,
+        }
+    }
+
+    /// Call once per cycle before the outputs are sent
+    pub const fn output_pre_process(
+        &mut self,
+        pos_control: &mut PosControl,
+        enc_control: &mut EncControl,
+    ) {
+        if self.restart {
+            if self.restart_low_sent {
+                pos_control.execute = true;
+                self.restart = false;
+                self.restart_low_sent = false;
+            } else {
+                self.restart_low_sent = true;
+            }
+        }
+
+        // set counter for one cycle
+        match self.set_counter.take() {
+            Some(counter) => {
+                enc_control.set_counter = true;
+                enc_control.set_counter_value = counter;
+            }
+            None => {
+                enc_control.set_counter = false;
+                enc_control.set_counter_value = 0;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn travel(target_position: i32) -> StepperPositionEL70x1Output {
+        StepperPositionEL70x1Output {
+            enable: true,
+            reduce_torque: false,
+            reset: false,
+            start: true,
+            execute: true,
+            emergency_stop: false,
+            target_position,
+            target_velocity: 1000,
+            start_type: StartType::Absolute,
+            acceleration: 500,
+            deceleration: 500,
+            set_counter: None,
+        }
+    }
+
+    #[test]
+    fn test_restart_active_travel_command() {
+        let mut interface = EL70x1PositionInterface::new();
+        let mut stm_control = StmControl::default();
+        let mut pos_control = PosControl::default();
+        let mut enc_control = EncControl::default();
+
+        // first command starts on the first cycle
+        interface.set_output(&mut stm_control, &mut pos_control, &travel(1000));
+        interface.output_pre_process(&mut pos_control, &mut enc_control);
+        assert!(pos_control.execute);
+        assert_eq!(pos_control.target_position, 1000);
+
+        // other outputs don't restart the command
+        let mut output = interface.get_output(&stm_control, &pos_control);
+        output.reduce_torque = true;
+        interface.set_output(&mut stm_control, &mut pos_control, &output);
+        interface.output_pre_process(&mut pos_control, &mut enc_control);
+        assert!(pos_control.execute);
+
+        // a new command needs one cycle with execute low
+        interface.set_output(&mut stm_control, &mut pos_control, &travel(-200));
+        assert!(interface.get_output(&stm_control, &pos_control).execute);
+        interface.output_pre_process(&mut pos_control, &mut enc_control);
+        assert!(!pos_control.execute);
+        interface.output_pre_process(&mut pos_control, &mut enc_control);
+        assert!(pos_control.execute);
+        assert_eq!(pos_control.target_position, -200i32 as u32);
+
+        // stop
+        let mut output = interface.get_output(&stm_control, &pos_control);
+        output.execute = false;
+        interface.set_output(&mut stm_control, &mut pos_control, &output);
+        interface.output_pre_process(&mut pos_control, &mut enc_control);
+        assert!(!pos_control.execute);
+    }
+}
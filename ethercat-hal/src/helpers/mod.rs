@@ -1,4 +1,6 @@
+pub mod cia402;
 pub mod counter_wrapper_u16_i128;
+pub mod el70x1_position_interface;
 pub mod el70xx_velocity_converter;
 pub mod ethercrab_types;
 pub mod signing_converter_u16;
@@ -0,0 +1,206 @@
+use std::{fmt, sync::Arc};
+
+use anyhow::Error;
+use smol::lock::RwLock;
+
+use crate::helpers::cia402::{
+    Cia402ModeOfOperation, Cia402Request, Cia402State, STATUSWORD_FOLLOWING_ERROR,
+    STATUSWORD_TARGET_REACHED, STATUSWORD_WARNING,
+};
+
+/// Servo drive with the CiA 402 (DS402) profile
+///
+/// The device walks the drive through the state machine every cycle, see [`crate::helpers::cia402::Cia402Controller`].
+/// Positions are in drive increments and velocities in increments per second.
+pub struct Cia402Drive {
+    /// Write to the drive
+    set_output: Box<dyn Fn(Cia402DriveOutput) -> Result<(), Error> + Send + Sync>,
+    /// Read the last written output
+    get_output: Box<dyn Fn() -> Result<Cia402DriveOutput, Error> + Send + Sync>,
+    /// Read the state of the drive
+    get_input: Box<dyn Fn() -> Result<Cia402DriveInput, Error> + Send + Sync>,
+}
+
+impl fmt::Debug for Cia402Drive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Cia402Drive")
+    }
+}
+
+impl Cia402Drive {
+    pub fn new<PORT>(device: Arc<RwLock<dyn Cia402DriveDevice<PORT>>>, port: PORT) -> Self
+    where
+        PORT: Clone + Send + Sync + 'static,
+    {
+        // build sync write closure
+        let port1 = port.clone();
+        let device1 = device.clone();
+        let set_output = Box::new(move |value: Cia402DriveOutput| -> Result<(), Error> {
+            let mut device = smol::block_on(device1.write());
+            device.set_output(port1.clone(), value)
+        });
+
+        // build sync get closures
+        let port2 = port.clone();
+        let device2 = device.clone();
+        let get_output = Box::new(move || -> Result<Cia402DriveOutput, Error> {
+            let device = smol::block_on(device2.read());
+            device.get_output(port2.clone())
+        });
+
+        let port3 = port;
+        let device3 = device;
+        let get_input = Box::new(move || -> Result<Cia402DriveInput, Error> {
+            let device = smol::block_on(device3.read());
+            device.get_input(port3.clone())
+        });
+
+        Self {
+            set_output,
+            get_output,
+            get_input,
+        }
+    }
+
+    /// Changes the output and keeps the other values
+    fn update_output(&self, update: impl FnOnce(&mut Cia402DriveOutput)) -> Result<(), Error> {
+        let mut output = (self.get_output)()?;
+        update(&mut output);
+        (self.set_output)(output)
+    }
+
+    /// Enables the drive, it takes a few cycles until the operation is enabled
+    pub fn set_enabled(&self, enabled: bool) -> Result<(), Error> {
+        self.update_output(|output| output.enable = enabled)
+    }
+
+    /// Whether the drive should be enabled, see [`Self::is_operation_enabled`] for the actual state
+    pub fn is_enabled(&self) -> Result<bool, Error> {
+        Ok((self.get_output)()?.enable)
+    }
+
+    /// Switching the mode is only possible if the device has the modes of operation PDO
+    pub fn set_mode(&self, mode: Cia402ModeOfOperation) -> Result<(), Error> {
+        self.update_output(|output| output.mode_of_operation = mode)
+    }
+
+    /// Target velocity for [`Cia402ModeOfOperation::CyclicSynchronousVelocity`]
+    pub fn set_target_velocity(&self, velocity: i32) -> Result<(), Error> {
+        self.update_output(|output| output.target_velocity = velocity)
+    }
+
+    /// Target position for [`Cia402ModeOfOperation::CyclicSynchronousPosition`]
+    pub fn set_target_position(&self, position: i32) -> Result<(), Error> {
+        self.update_output(|output| output.target_position = position)
+    }
+
+    /// Starts a move in [`Cia402ModeOfOperation::ProfilePosition`], a running move is replaced
+    pub fn move_to(&self, position: i32) -> Result<(), Error> {
+        self.update_output(|output| {
+            output.target_position = position;
+            output.new_setpoint = true;
+        })
+    }
+
+    /// Stops with the quick stop ramp until released, the drive has to be enabled again afterwards
+    pub fn set_quick_stop(&self, quick_stop: bool) -> Result<(), Error> {
+        self.update_output(|output| output.quick_stop = quick_stop)
+    }
+
+    /// Stops the motion but keeps the drive enabled
+    pub fn set_halt(&self, halt: bool) -> Result<(), Error> {
+        self.update_output(|output| output.halt = halt)
+    }
+
+    /// Resets a fault, the request is cleared by the device once the fault is gone
+    pub fn reset_fault(&self) -> Result<(), Error> {
+        self.update_output(|output| output.fault_reset = true)
+    }
+
+    pub fn get_input(&self) -> Result<Cia402DriveInput, Error> {
+        (self.get_input)()
+    }
+
+    pub fn state(&self) -> Result<Cia402State, Error> {
+        Ok(self.get_input()?.state())
+    }
+
+    pub fn is_operation_enabled(&self) -> Result<bool, Error> {
+        Ok(self.state()? == Cia402State::OperationEnabled)
+    }
+
+    pub fn has_fault(&self) -> Result<bool, Error> {
+        Ok(matches!(
+            self.state()?,
+            Cia402State::Fault | Cia402State::FaultReactionActive
+        ))
+    }
+
+    pub fn get_position(&self) -> Result<i32, Error> {
+        Ok(self.get_input()?.position)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cia402DriveInput {
+    pub statusword: u16,
+    /// Actual position in increments
+    pub position: i32,
+    /// Actual velocity in increments per second, `None` if it is not in the PDO assignment
+    pub velocity: Option<i32>,
+    /// `None` if it is not in the PDO assignment or the drive reports a mode we don't support
+    pub mode_of_operation_display: Option<Cia402ModeOfOperation>,
+}
+
+impl Cia402DriveInput {
+    pub const fn state(&self) -> Cia402State {
+        Cia402State::from_statusword(self.statusword)
+    }
+
+    /// PP: the move is finished, CSV/CSP: the drive reached the target
+    pub const fn target_reached(&self) -> bool {
+        self.statusword & STATUSWORD_TARGET_REACHED != 0
+    }
+
+    pub const fn following_error(&self) -> bool {
+        self.statusword & STATUSWORD_FOLLOWING_ERROR != 0
+    }
+
+    pub const fn warning(&self) -> bool {
+        self.statusword & STATUSWORD_WARNING != 0
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cia402DriveOutput {
+    pub enable: bool,
+    pub quick_stop: bool,
+    pub fault_reset: bool,
+    pub halt: bool,
+    pub mode_of_operation: Cia402ModeOfOperation,
+    pub target_position: i32,
+    pub target_velocity: i32,
+    /// PP: start a move to `target_position`, consumed by the device
+    pub new_setpoint: bool,
+}
+
+impl Cia402DriveOutput {
+    pub const fn request(&self) -> Cia402Request {
+        Cia402Request {
+            enable: self.enable,
+            quick_stop: self.quick_stop,
+            fault_reset: self.fault_reset,
+            halt: self.halt,
+            new_setpoint: self.new_setpoint,
+        }
+    }
+}
+
+pub trait Cia402DriveDevice<PORT>: Send + Sync
+where
+    PORT: Clone,
+{
+    fn set_output(&mut self, port: PORT, value: Cia402DriveOutput) -> Result<(), Error>;
+    fn get_output(&self, port: PORT) -> Result<Cia402DriveOutput, Error>;
+    fn get_input(&self, port: PORT) -> Result<Cia402DriveInput, Error>;
+}
@@ -0,0 +1,233 @@
+use std::time::Duration;
+
+use anyhow::Error;
+
+use super::cia402_drive::{Cia402DriveDevice, Cia402DriveInput, Cia402DriveOutput};
+use crate::helpers::cia402::{
+    CONTROLWORD_HALT, CONTROLWORD_NEW_SETPOINT, Cia402Controller, Cia402ModeOfOperation,
+    Cia402State, STATUSWORD_SETPOINT_ACKNOWLEDGE, STATUSWORD_TARGET_REACHED,
+};
+
+#[derive(Debug, Clone)]
+pub struct Cia402DriveSimulatedPort;
+
+/// Simulated CiA 402 drive without hardware
+///
+/// Runs the same controller as the real devices, [`Self::cycle`] has to be called like a cycle of the EtherCAT loop.
+#[derive(Debug)]
+pub struct Cia402DriveSimulated {
+    output: Cia402DriveOutput,
+    controller: Cia402Controller,
+    state: Cia402State,
+    mode_of_operation_display: Cia402ModeOfOperation,
+    /// Position in increments
+    position: f64,
+    /// Velocity in increments per second
+    velocity: f64,
+    /// Velocity used in [`Cia402ModeOfOperation::ProfilePosition`] in increments per second
+    pub profile_velocity: f64,
+    setpoint_acknowledge: bool,
+    target_reached: bool,
+    /// Target of the running profile position move
+    profile_target: i32,
+}
+
+impl Default for Cia402DriveSimulated {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cia402DriveSimulated {
+    pub fn new() -> Self {
+        Self {
+            output: Cia402DriveOutput::default(),
+            controller: Cia402Controller::new(),
+            state: Cia402State::NotReadyToSwitchOn,
+            mode_of_operation_display: Cia402ModeOfOperation::default(),
+            position: 0.0,
+            velocity: 0.0,
+            profile_velocity: 10_000.0,
+            setpoint_acknowledge: false,
+            target_reached: false,
+            profile_target: 0,
+        }
+    }
+
+    pub const fn statusword(&self) -> u16 {
+        let mut statusword = self.state.statusword();
+        if self.setpoint_acknowledge {
+            statusword |= STATUSWORD_SETPOINT_ACKNOWLEDGE;
+        }
+        if self.target_reached {
+            statusword |= STATUSWORD_TARGET_REACHED;
+        }
+        statusword
+    }
+
+    /// Puts the drive into fault like an overcurrent would
+    pub const fn set_fault(&mut self) {
+        self.state = Cia402State::FaultReactionActive;
+    }
+
+    /// One cycle: the controlword is sent, the drive changes its state and moves
+    pub fn cycle(&mut self, dt: Duration) {
+        let controlword = self
+            .controller
+            .controlword(self.statusword(), &self.output.request());
+        self.output.new_setpoint = false;
+
+        self.state = self.state.transition(controlword);
+        self.mode_of_operation_display = self.output.mode_of_operation;
+        if self.state != Cia402State::Fault {
+            self.output.fault_reset = false;
+        }
+
+        let halt = controlword & CONTROLWORD_HALT != 0;
+        if self.state != Cia402State::OperationEnabled || halt {
+            self.velocity = 0.0;
+            self.setpoint_acknowledge = false;
+            return;
+        }
+
+        let dt = dt.as_secs_f64();
+        match self.mode_of_operation_display {
+            Cia402ModeOfOperation::CyclicSynchronousVelocity => {
+                self.velocity = f64::from(self.output.target_velocity);
+                self.position += self.velocity * dt;
+                self.target_reached = true;
+            }
+            Cia402ModeOfOperation::CyclicSynchronousPosition => {
+                let target = f64::from(self.output.target_position);
+                self.velocity = (target - self.position) / dt;
+                self.position = target;
+                self.target_reached = true;
+            }
+            Cia402ModeOfOperation::ProfilePosition => {
+                let new_setpoint = controlword & CONTROLWORD_NEW_SETPOINT != 0;
+                if new_setpoint && !self.setpoint_acknowledge {
+                    self.profile_target = self.output.target_position;
+                    self.target_reached = false;
+                }
+                self.setpoint_acknowledge = new_setpoint;
+
+                let distance = f64::from(self.profile_target) - self.position;
+                let step = self.profile_velocity * dt;
+                if distance.abs() <= step {
+                    self.position = f64::from(self.profile_target);
+                    self.velocity = 0.0;
+                    self.target_reached = true;
+                } else {
+                    self.velocity = self.profile_velocity.copysign(distance);
+                    self.position += self.velocity * dt;
+                }
+            }
+        }
+    }
+}
+
+impl Cia402DriveDevice<Cia402DriveSimulatedPort> for Cia402DriveSimulated {
+    fn set_output(
+        &mut self,
+        _port: Cia402DriveSimulatedPort,
+        value: Cia402DriveOutput,
+    ) -> Result<(), Error> {
+        self.output = value;
+        Ok(())
+    }
+
+    fn get_output(&self, _port: Cia402DriveSimulatedPort) -> Result<Cia402DriveOutput, Error> {
+        Ok(self.output.clone())
+    }
+
+    fn get_input(&self, _port: Cia402DriveSimulatedPort) -> Result<Cia402DriveInput, Error> {
+        Ok(Cia402DriveInput {
+            statusword: self.statusword(),
+            position: self.position.round() as i32,
+            velocity: Some(self.velocity.round() as i32),
+            mode_of_operation_display: Some(self.mode_of_operation_display),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use smol::lock::RwLock;
+
+    use super::*;
+    use crate::io::cia402_drive::Cia402Drive;
+
+    const DT: Duration = Duration::from_millis(1);
+
+    fn simulated_drive() -> (Arc<RwLock<Cia402DriveSimulated>>, Cia402Drive) {
+        let device = Arc::new(RwLock::new(Cia402DriveSimulated::new()));
+        let drive = Cia402Drive::new(device.clone(), Cia402DriveSimulatedPort);
+        (device, drive)
+    }
+
+    fn run(device: &Arc<RwLock<Cia402DriveSimulated>>, cycles: usize) {
+        let mut device = smol::block_on(device.write());
+        for _ in 0..cycles {
+            device.cycle(DT);
+        }
+    }
+
+    #[test]
+    fn test_enable_and_velocity() {
+        let (device, drive) = simulated_drive();
+        drive.set_enabled(true).unwrap();
+        drive.set_target_velocity(1000).unwrap();
+        run(&device, 5);
+        assert!(drive.is_operation_enabled().unwrap());
+
+        let start = drive.get_position().unwrap();
+        run(&device, 100);
+        assert_eq!(drive.get_position().unwrap() - start, 100);
+        assert_eq!(drive.get_input().unwrap().velocity, Some(1000));
+
+        drive.set_enabled(false).unwrap();
+        run(&device, 2);
+        assert_eq!(drive.state().unwrap(), Cia402State::ReadyToSwitchOn);
+    }
+
+    #[test]
+    fn test_profile_position_move() {
+        let (device, drive) = simulated_drive();
+        drive
+            .set_mode(Cia402ModeOfOperation::ProfilePosition)
+            .unwrap();
+        drive.set_enabled(true).unwrap();
+        run(&device, 5);
+        assert!(drive.is_operation_enabled().unwrap());
+
+        drive.move_to(500).unwrap();
+        run(&device, 10);
+        assert!(!drive.get_input().unwrap().target_reached());
+
+        run(&device, 100);
+        let input = drive.get_input().unwrap();
+        assert!(input.target_reached());
+        assert_eq!(input.position, 500);
+        assert_eq!(
+            input.mode_of_operation_display,
+            Some(Cia402ModeOfOperation::ProfilePosition)
+        );
+    }
+
+    #[test]
+    fn test_fault_reset() {
+        let (device, drive) = simulated_drive();
+        drive.set_enabled(true).unwrap();
+        run(&device, 5);
+        smol::block_on(device.write()).set_fault();
+        run(&device, 2);
+        assert!(drive.has_fault().unwrap());
+
+        drive.reset_fault().unwrap();
+        run(&device, 6);
+        assert!(drive.is_operation_enabled().unwrap());
+        assert!(!smol::block_on(device.read()).output.fault_reset);
+    }
+}
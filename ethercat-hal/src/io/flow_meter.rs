@@ -0,0 +1,258 @@
+use std::time::{Duration, Instant};
+
+use anyhow::Error;
+use units::{
+    f64::{Frequency, Volume, VolumeRate},
+    frequency::{centihertz, hertz},
+    volume::liter,
+    volume_rate::liter_per_second,
+};
+
+use super::encoder_input::EncoderInput;
+
+/// Which value of the [`EncoderInput`] the flow rate is computed from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FlowMeterSignal {
+    /// Frequency value, `resolution` is the frequency of one LSB
+    Frequency { resolution: Frequency },
+    /// Period value, `resolution` is the duration of one LSB
+    Period { resolution: Duration },
+}
+
+impl Default for FlowMeterSignal {
+    /// EL5152 default frequency resolution of 0.01 Hz
+    fn default() -> Self {
+        Self::Frequency {
+            resolution: Frequency::new::<centihertz>(1.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlowMeterConfiguration {
+    /// Pulses per litre of the sensor
+    pub k_factor: f64,
+    /// Pulse frequency the characteristic `f = k_factor * q + zero_offset` has at zero flow
+    ///
+    /// Only applied while pulses are counted, a standing sensor is always zero flow.
+    pub zero_offset: Frequency,
+    pub signal: FlowMeterSignal,
+    /// Flow below is reported as zero, suppresses noise of a standing sensor
+    pub low_flow_cutoff: VolumeRate,
+}
+
+impl FlowMeterConfiguration {
+    /// Flow rate from the raw frequency or period value, zero below the cutoff
+    pub fn flow_rate(&self, value: u32) -> VolumeRate {
+        let pulse_frequency = match self.signal {
+            FlowMeterSignal::Frequency { resolution } => resolution.get::<hertz>() * value as f64,
+            FlowMeterSignal::Period { resolution } => {
+                let period = resolution.as_secs_f64() * value as f64;
+                if period > 0.0 { 1.0 / period } else { 0.0 }
+            }
+        };
+        if pulse_frequency <= 0.0 {
+            return VolumeRate::new::<liter_per_second>(0.0);
+        }
+        let flow_rate = VolumeRate::new::<liter_per_second>(
+            (pulse_frequency - self.zero_offset.get::<hertz>()) / self.k_factor,
+        );
+        if flow_rate.value.is_finite() && flow_rate >= self.low_flow_cutoff {
+            flow_rate
+        } else {
+            VolumeRate::new::<liter_per_second>(0.0)
+        }
+    }
+
+    /// Volume of a number of pulses counted within `duration`
+    pub fn volume(&self, pulses: u32, duration: Duration) -> Volume {
+        if pulses == 0 {
+            return Volume::new::<liter>(0.0);
+        }
+        let offset_pulses = self.zero_offset.get::<hertz>() * duration.as_secs_f64();
+        Volume::new::<liter>(((pulses as f64 - offset_pulses) / self.k_factor).max(0.0))
+    }
+}
+
+/// Pulse output flow meter on an [`EncoderInput`]
+///
+/// The flow rate is computed from the frequency or period value, the volume is totalized from the
+/// counter so no pulse is lost between cycles. [`FlowMeter::update`] has to be called once per
+/// `act` cycle.
+#[derive(Debug)]
+pub struct FlowMeter {
+    input: EncoderInput,
+    pub configuration: FlowMeterConfiguration,
+    flow_rate: VolumeRate,
+    total_volume: Volume,
+    last_counter: Option<(u32, Instant)>,
+}
+
+impl FlowMeter {
+    pub fn new(input: EncoderInput, configuration: FlowMeterConfiguration) -> Self {
+        Self {
+            input,
+            configuration,
+            flow_rate: VolumeRate::new::<liter_per_second>(0.0),
+            total_volume: Volume::new::<liter>(0.0),
+            last_counter: None,
+        }
+    }
+
+    /// Read the encoder, update flow rate and total volume
+    ///
+    /// On error the flow rate is zero and the total volume is kept.
+    pub fn update(&mut self) -> Result<VolumeRate, Error> {
+        self.flow_rate = VolumeRate::new::<liter_per_second>(0.0);
+
+        let counter = self.input.get_counter_value()?;
+        let now = Instant::now();
+        if let Some((last_counter, last_time)) = self.last_counter {
+            // the counter wraps around
+            self.total_volume += self.configuration.volume(
+                counter.wrapping_sub(last_counter),
+                now.duration_since(last_time),
+            );
+        }
+        self.last_counter = Some((counter, now));
+
+        let value = match self.configuration.signal {
+            FlowMeterSignal::Frequency { .. } => self.input.get_frequency_value()?,
+            FlowMeterSignal::Period { .. } => self.input.get_period_value()?,
+        }
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "[{}::FlowMeter::update] Encoder has no {:?} value, check the PDO assignment",
+                module_path!(),
+                self.configuration.signal
+            )
+        })?;
+        self.flow_rate = self.configuration.flow_rate(value);
+        Ok(self.flow_rate)
+    }
+
+    /// Flow rate of the last update
+    pub const fn get_flow_rate(&self) -> VolumeRate {
+        self.flow_rate
+    }
+
+    /// Volume since creation or the last reset
+    pub const fn get_total_volume(&self) -> Volume {
+        self.total_volume
+    }
+
+    pub fn reset_total_volume(&mut self) {
+        self.total_volume = Volume::new::<liter>(0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use smol::lock::RwLock;
+    use units::volume_rate::liter_per_minute;
+
+    use super::*;
+    use crate::io::encoder_input::{
+        EncoderInputCounter, EncoderInputDevice, EncoderInputFrequency, EncoderInputPeriod,
+    };
+
+    #[derive(Clone)]
+    struct TestPort;
+
+    struct TestEncoder {
+        counter: u32,
+        frequency: u32,
+    }
+
+    impl EncoderInputDevice<TestPort> for TestEncoder {
+        fn get_counter_value(&self, _port: TestPort) -> Result<EncoderInputCounter, Error> {
+            Ok(EncoderInputCounter {
+                value: self.counter,
+            })
+        }
+
+        fn get_frequency(&self, _port: TestPort) -> Result<Option<EncoderInputFrequency>, Error> {
+            Ok(Some(EncoderInputFrequency {
+                value: self.frequency,
+            }))
+        }
+
+        fn get_period(&self, _port: TestPort) -> Result<Option<EncoderInputPeriod>, Error> {
+            Ok(None)
+        }
+
+        fn set_counter(&mut self, _port: TestPort, value: u32) -> Result<(), Error> {
+            self.counter = value;
+            Ok(())
+        }
+    }
+
+    fn configuration(signal: FlowMeterSignal) -> FlowMeterConfiguration {
+        FlowMeterConfiguration {
+            k_factor: 500.0,
+            zero_offset: Frequency::new::<hertz>(0.0),
+            signal,
+            low_flow_cutoff: VolumeRate::new::<liter_per_minute>(0.5),
+        }
+    }
+
+    #[test]
+    fn test_flow_rate() {
+        let frequency = configuration(FlowMeterSignal::default());
+        // 50 Hz / 500 pulses per litre = 6 L/min
+        let flow = frequency.flow_rate(5000).get::<liter_per_minute>();
+        assert!((flow - 6.0).abs() < 1e-9);
+        // 2 Hz is 0.24 L/min, below the cutoff
+        assert_eq!(frequency.flow_rate(200).get::<liter_per_minute>(), 0.0);
+
+        let period = configuration(FlowMeterSignal::Period {
+            resolution: Duration::from_nanos(100),
+        });
+        // 20 ms period is 50 Hz
+        let flow = period.flow_rate(200_000).get::<liter_per_minute>();
+        assert!((flow - 6.0).abs() < 1e-9);
+        assert_eq!(period.flow_rate(0).get::<liter_per_minute>(), 0.0);
+
+        // f = 8.1 * q - 3 with q in L/min
+        let offset = FlowMeterConfiguration {
+            k_factor: 486.0,
+            zero_offset: Frequency::new::<hertz>(-3.0),
+            ..configuration(FlowMeterSignal::default())
+        };
+        // 37.5 Hz is 5 L/min
+        let flow = offset.flow_rate(3750).get::<liter_per_minute>();
+        assert!((flow - 5.0).abs() < 1e-9);
+        assert_eq!(offset.flow_rate(0).get::<liter_per_minute>(), 0.0);
+        // 45 pulses in 1 s are 48 offset corrected pulses
+        let volume = offset.volume(45, Duration::from_secs(1)).get::<liter>();
+        assert!((volume - 48.0 / 486.0).abs() < 1e-9);
+        assert_eq!(offset.volume(0, Duration::from_secs(1)).get::<liter>(), 0.0);
+    }
+
+    #[test]
+    fn test_totalizer() {
+        let device = Arc::new(RwLock::new(TestEncoder {
+            counter: u32::MAX - 99,
+            frequency: 5000,
+        }));
+        let mut flow_meter = FlowMeter::new(
+            EncoderInput::new(device.clone(), TestPort),
+            configuration(FlowMeterSignal::default()),
+        );
+
+        // first update only takes the counter as reference
+        flow_meter.update().unwrap();
+        assert_eq!(flow_meter.get_total_volume().get::<liter>(), 0.0);
+
+        // 500 pulses across the wrap around are one litre
+        smol::block_on(device.write()).counter = 400;
+        flow_meter.update().unwrap();
+        assert!((flow_meter.get_total_volume().get::<liter>() - 1.0).abs() < 1e-9);
+        assert!((flow_meter.get_flow_rate().get::<liter_per_minute>() - 6.0).abs() < 1e-9);
+
+        flow_meter.reset_total_volume();
+        assert_eq!(flow_meter.get_total_volume().get::<liter>(), 0.0);
+    }
+}
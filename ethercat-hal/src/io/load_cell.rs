@@ -0,0 +1,221 @@
+use std::{fmt, sync::Arc};
+
+use anyhow::{Error, anyhow};
+use smol::lock::RwLock;
+use units::{
+    f64::{Force, Mass},
+    force::newton,
+    mass::kilogram,
+};
+
+/// Standard gravity used to convert mass to force
+pub const STANDARD_GRAVITY: f64 = 9.80665;
+
+/// Load cell (strain gauge bridge) input
+///
+/// The device measures the mass, [`LoadCell::calibrate`] corrects it with a gain.
+pub struct LoadCell {
+    get_input: Box<dyn Fn() -> Result<LoadCellInput, Error> + Send + Sync>,
+    tare: Box<dyn Fn() -> Result<(), Error> + Send + Sync>,
+    get_gain: Box<dyn Fn() -> f64 + Send + Sync>,
+    set_gain: Box<dyn Fn(f64) + Send + Sync>,
+}
+
+impl fmt::Debug for LoadCell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "LoadCell")
+    }
+}
+
+impl LoadCell {
+    pub fn new<PORT>(device: Arc<RwLock<dyn LoadCellDevice<PORT>>>, port: PORT) -> Self
+    where
+        PORT: Clone + Send + Sync + 'static,
+    {
+        let port1 = port.clone();
+        let device1 = device.clone();
+        let get_input = Box::new(move || -> Result<LoadCellInput, Error> {
+            let device = smol::block_on(device1.read());
+            device.get_input(port1.clone())
+        });
+
+        let port2 = port.clone();
+        let device2 = device.clone();
+        let tare = Box::new(move || -> Result<(), Error> {
+            let mut device = smol::block_on(device2.write());
+            device.tare(port2.clone())
+        });
+
+        let port3 = port.clone();
+        let device3 = device.clone();
+        let get_gain = Box::new(move || -> f64 {
+            let device = smol::block_on(device3.read());
+            device.get_gain(port3.clone())
+        });
+
+        let port4 = port;
+        let device4 = device;
+        let set_gain = Box::new(move |gain: f64| {
+            let mut device = smol::block_on(device4.write());
+            device.set_gain(port4.clone(), gain);
+        });
+
+        Self {
+            get_input,
+            tare,
+            get_gain,
+            set_gain,
+        }
+    }
+
+    pub fn get_input(&self) -> Result<LoadCellInput, Error> {
+        (self.get_input)()
+    }
+
+    /// Calibrated mass, fails if the bridge is out of range or has an error
+    pub fn get_mass(&self) -> Result<Mass, Error> {
+        let input = self.get_input()?;
+        if input.error {
+            return Err(anyhow!("[{}::LoadCell::get_mass] Error", module_path!()));
+        }
+        if input.overrange || input.underrange {
+            return Err(anyhow!(
+                "[{}::LoadCell::get_mass] Out of range",
+                module_path!()
+            ));
+        }
+        Ok(input.mass * (self.get_gain)())
+    }
+
+    /// Calibrated weight force with [`STANDARD_GRAVITY`]
+    pub fn get_force(&self) -> Result<Force, Error> {
+        let mass = self.get_mass()?;
+        Ok(Force::new::<newton>(
+            mass.get::<kilogram>() * STANDARD_GRAVITY,
+        ))
+    }
+
+    /// If the value is settled, weighing should wait for it
+    pub fn is_steady(&self) -> Result<bool, Error> {
+        Ok(self.get_input()?.steady_state)
+    }
+
+    /// Takes the current load as zero
+    ///
+    /// The device needs a few cycles until the new zero is applied.
+    pub fn tare(&self) -> Result<(), Error> {
+        (self.tare)()
+    }
+
+    /// Corrects the gain with a reference mass on the load cell, has to be done after [`Self::tare`]
+    ///
+    /// Returns the new gain so it can be stored and restored with [`Self::set_calibration_gain`].
+    pub fn calibrate(&self, reference: Mass) -> Result<f64, Error> {
+        let input = self.get_input()?;
+        let measured = input.mass.get::<kilogram>();
+        if measured.abs() < f64::EPSILON {
+            return Err(anyhow!(
+                "[{}::LoadCell::calibrate] Nothing measured, is the reference on the load cell?",
+                module_path!()
+            ));
+        }
+        let gain = reference.get::<kilogram>() / measured;
+        if !gain.is_finite() || gain <= 0.0 {
+            return Err(anyhow!(
+                "[{}::LoadCell::calibrate] Invalid gain {}",
+                module_path!(),
+                gain
+            ));
+        }
+        (self.set_gain)(gain);
+        Ok(gain)
+    }
+
+    pub fn get_calibration_gain(&self) -> f64 {
+        (self.get_gain)()
+    }
+
+    pub fn set_calibration_gain(&self, gain: f64) {
+        (self.set_gain)(gain);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LoadCellInput {
+    /// Mass as measured by the device, without the calibration gain
+    pub mass: Mass,
+    pub steady_state: bool,
+    pub underrange: bool,
+    pub overrange: bool,
+    pub error: bool,
+}
+
+pub trait LoadCellDevice<PORT>: Send + Sync
+where
+    PORT: Clone,
+{
+    fn get_input(&self, port: PORT) -> Result<LoadCellInput, Error>;
+    /// Requests a tare, executed by the device in the next cycles
+    fn tare(&mut self, port: PORT) -> Result<(), Error>;
+    fn get_gain(&self, port: PORT) -> f64;
+    fn set_gain(&mut self, port: PORT, gain: f64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use units::mass::gram;
+
+    #[derive(Clone)]
+    struct TestPort;
+
+    struct TestLoadCell {
+        mass: Mass,
+        gain: f64,
+    }
+
+    impl LoadCellDevice<TestPort> for TestLoadCell {
+        fn get_input(&self, _port: TestPort) -> Result<LoadCellInput, Error> {
+            Ok(LoadCellInput {
+                mass: self.mass,
+                steady_state: true,
+                underrange: false,
+                overrange: false,
+                error: false,
+            })
+        }
+
+        fn tare(&mut self, _port: TestPort) -> Result<(), Error> {
+            self.mass = Mass::new::<kilogram>(0.0);
+            Ok(())
+        }
+
+        fn get_gain(&self, _port: TestPort) -> f64 {
+            self.gain
+        }
+
+        fn set_gain(&mut self, _port: TestPort, gain: f64) {
+            self.gain = gain;
+        }
+    }
+
+    #[test]
+    fn test_calibrate() {
+        let device = Arc::new(RwLock::new(TestLoadCell {
+            mass: Mass::new::<gram>(490.0),
+            gain: 1.0,
+        }));
+        let load_cell = LoadCell::new(device, TestPort);
+
+        let gain = load_cell.calibrate(Mass::new::<gram>(500.0)).unwrap();
+        assert!((gain - 500.0 / 490.0).abs() < 1e-9);
+        let mass = load_cell.get_mass().unwrap();
+        assert!((mass.get::<gram>() - 500.0).abs() < 1e-9);
+        let force = load_cell.get_force().unwrap();
+        let weight = 0.5 * STANDARD_GRAVITY;
+        assert!((force.get::<newton>() - weight).abs() < 1e-9);
+
+        load_cell.tare().unwrap();
+        assert!(load_cell.calibrate(Mass::new::<gram>(500.0)).is_err());
+    }
+}
@@ -1,10 +1,17 @@
 pub mod analog_input;
 pub mod analog_input_dummy;
 pub mod analog_output;
+pub mod cia402_drive;
+pub mod cia402_drive_simulated;
 pub mod digital_input;
 pub mod digital_output;
 pub mod encoder_input;
+pub mod flow_meter;
+pub mod load_cell;
+pub mod power_measurement;
 pub mod pulse_train_output;
+pub mod pwm_output;
 pub mod serial_interface;
+pub mod stepper_position_el70x1;
 pub mod stepper_velocity_el70x1;
 pub mod temperature_input;
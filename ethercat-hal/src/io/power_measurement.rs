@@ -0,0 +1,84 @@
+use std::{fmt, sync::Arc};
+
+use anyhow::{Error, anyhow};
+use smol::lock::RwLock;
+use units::{
+    electric_current::ampere,
+    electric_potential::volt,
+    energy::watt_hour,
+    f64::{ElectricCurrent, ElectricPotential, Energy, Power},
+    power::watt,
+};
+
+/// Power measurement of one phase
+pub struct PowerMeasurement {
+    get_input: Box<dyn Fn() -> Result<PowerMeasurementInput, Error> + Send + Sync>,
+}
+
+impl fmt::Debug for PowerMeasurement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PowerMeasurement")
+    }
+}
+
+impl PowerMeasurement {
+    pub fn new<PORT>(device: Arc<RwLock<dyn PowerMeasurementDevice<PORT>>>, port: PORT) -> Self
+    where
+        PORT: Clone + Send + Sync + 'static,
+    {
+        let get_input = Box::new(move || -> Result<PowerMeasurementInput, Error> {
+            let device = smol::block_on(device.read());
+            device.get_input(port.clone())
+        });
+        Self { get_input }
+    }
+
+    /// Fails if the device reports a measurement error
+    pub fn get_input(&self) -> Result<PowerMeasurementInput, Error> {
+        let input = (self.get_input)()?;
+        if input.error {
+            return Err(anyhow!(
+                "[{}::PowerMeasurement::get_input] Measurement error",
+                module_path!()
+            ));
+        }
+        Ok(input)
+    }
+
+    pub fn get_voltage(&self) -> Result<ElectricPotential, Error> {
+        Ok(ElectricPotential::new::<volt>(self.get_input()?.voltage))
+    }
+
+    pub fn get_current(&self) -> Result<ElectricCurrent, Error> {
+        Ok(ElectricCurrent::new::<ampere>(self.get_input()?.current))
+    }
+
+    pub fn get_active_power(&self) -> Result<Power, Error> {
+        Ok(Power::new::<watt>(self.get_input()?.active_power))
+    }
+
+    /// Energy counted by the device, it is not reset when the machine starts
+    pub fn get_active_energy(&self) -> Result<Energy, Error> {
+        Ok(Energy::new::<watt_hour>(self.get_input()?.active_energy))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PowerMeasurementInput {
+    /// RMS voltage in V
+    pub voltage: f64,
+    /// RMS current in A
+    pub current: f64,
+    /// Active power in W
+    pub active_power: f64,
+    /// Active energy in Wh
+    pub active_energy: f64,
+    pub error: bool,
+}
+
+pub trait PowerMeasurementDevice<PORT>: Send + Sync
+where
+    PORT: Clone,
+{
+    fn get_input(&self, port: PORT) -> Result<PowerMeasurementInput, Error>;
+}
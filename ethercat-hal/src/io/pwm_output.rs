@@ -0,0 +1,89 @@
+use std::{fmt, sync::Arc, time::Duration};
+
+use smol::lock::RwLock;
+
+/// Pulse width modulated output
+///
+/// The device generates the pulses, the duty cycle is in clip space (0 to 1).
+pub struct PwmOutput {
+    set_duty_cycle: Box<dyn Fn(f64) + Send + Sync>,
+    get_duty_cycle: Box<dyn Fn() -> f64 + Send + Sync>,
+    set_period: Box<dyn Fn(Duration) + Send + Sync>,
+    get_period: Box<dyn Fn() -> Duration + Send + Sync>,
+}
+
+impl fmt::Debug for PwmOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PwmOutput")
+    }
+}
+
+impl PwmOutput {
+    pub fn new<PORT>(device: Arc<RwLock<dyn PwmOutputDevice<PORT>>>, port: PORT) -> Self
+    where
+        PORT: Clone + Send + Sync + 'static,
+    {
+        let port1 = port.clone();
+        let device1 = device.clone();
+        let set_duty_cycle = Box::new(move |duty_cycle: f64| {
+            let mut device = smol::block_on(device1.write());
+            device.set_duty_cycle(port1.clone(), duty_cycle);
+        });
+
+        let port2 = port.clone();
+        let device2 = device.clone();
+        let get_duty_cycle = Box::new(move || -> f64 {
+            let device = smol::block_on(device2.read());
+            device.get_duty_cycle(port2.clone())
+        });
+
+        let port3 = port.clone();
+        let device3 = device.clone();
+        let set_period = Box::new(move |period: Duration| {
+            let mut device = smol::block_on(device3.write());
+            device.set_period(port3.clone(), period);
+        });
+
+        let port4 = port;
+        let device4 = device;
+        let get_period = Box::new(move || -> Duration {
+            let device = smol::block_on(device4.read());
+            device.get_period(port4.clone())
+        });
+
+        Self {
+            set_duty_cycle,
+            get_duty_cycle,
+            set_period,
+            get_period,
+        }
+    }
+
+    /// Set the duty cycle, clamped to 0 to 1
+    pub fn set_duty_cycle(&self, duty_cycle: f64) {
+        (self.set_duty_cycle)(duty_cycle.clamp(0.0, 1.0));
+    }
+
+    pub fn get_duty_cycle(&self) -> f64 {
+        (self.get_duty_cycle)()
+    }
+
+    /// Set the period, the device clamps it to the range it supports
+    pub fn set_period(&self, period: Duration) {
+        (self.set_period)(period);
+    }
+
+    pub fn get_period(&self) -> Duration {
+        (self.get_period)()
+    }
+}
+
+pub trait PwmOutputDevice<PORT>: Send + Sync
+where
+    PORT: Clone,
+{
+    fn set_duty_cycle(&mut self, port: PORT, duty_cycle: f64);
+    fn get_duty_cycle(&self, port: PORT) -> f64;
+    fn set_period(&mut self, port: PORT, period: Duration);
+    fn get_period(&self, port: PORT) -> Duration;
+}
@@ -0,0 +1,273 @@
+use std::{fmt, sync::Arc};
+
+use crate::{
+    helpers::el70xx_velocity_converter::EL70x1VelocityConverter,
+    shared_config::el70x1::{EL70x1SpeedRange, StartType},
+};
+use anyhow::Error;
+use smol::lock::RwLock;
+
+/// Stepper in position controller mode
+///
+/// Uses the travel command generator of the EL70x1 ("positioning interface"). A move is started
+/// with [`StepperPositionEL70x1::move_to`] and the terminal ramps to the target on its own.
+pub struct StepperPositionEL70x1 {
+    /// Write to the positioning interface
+    set_output: Box<dyn Fn(StepperPositionEL70x1Output) -> Result<(), Error> + Send + Sync>,
+    /// Read the last written state of the positioning interface
+    get_output: Box<dyn Fn() -> Result<StepperPositionEL70x1Output, Error> + Send + Sync>,
+    /// Read the state of the positioning interface
+    get_input: Box<dyn Fn() -> Result<StepperPositionEL70x1Input, Error> + Send + Sync>,
+    /// Get the speed range configuration
+    get_speed_range: Box<dyn Fn() -> EL70x1SpeedRange + Send + Sync>,
+}
+
+impl fmt::Debug for StepperPositionEL70x1 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "StepperPosition")
+    }
+}
+
+impl StepperPositionEL70x1 {
+    pub fn new<PORT, DEVICE>(device: Arc<RwLock<DEVICE>>, port: PORT) -> Self
+    where
+        PORT: Clone + Copy + Send + Sync + 'static,
+        DEVICE: StepperPositionEL70x1Device<PORT> + Send + Sync + 'static,
+    {
+        // build sync write closure
+        let device1 = device.clone();
+        let set_output = Box::new(
+            move |value: StepperPositionEL70x1Output| -> Result<(), Error> {
+                smol::block_on(async {
+                    let mut device = device1.write().await;
+                    device.set_output(port, value)
+                })
+            },
+        );
+
+        // build sync get closures
+        let device2 = device.clone();
+        let get_input = Box::new(move || -> Result<StepperPositionEL70x1Input, Error> {
+            smol::block_on(async {
+                let device = device2.read().await;
+                device.get_input(port)
+            })
+        });
+
+        let device3 = device.clone();
+        let get_output = Box::new(move || -> Result<StepperPositionEL70x1Output, Error> {
+            smol::block_on(async {
+                let device = device3.read().await;
+                device.get_output(port)
+            })
+        });
+
+        let device4 = device;
+        let get_speed_range = Box::new(move || -> EL70x1SpeedRange {
+            smol::block_on(async {
+                let device = device4.read().await;
+                device.get_speed_range(port)
+            })
+        });
+
+        Self {
+            set_output,
+            get_output,
+            get_input,
+            get_speed_range,
+        }
+    }
+
+    /// Enable or disable the stepper
+    pub fn set_enabled(&mut self, enabled: bool) -> Result<(), Error> {
+        let mut output = (self.get_output)()?;
+        output.enable = enabled;
+        (self.set_output)(output)
+    }
+
+    /// Get the enabled state of the stepper
+    pub fn is_enabled(&self) -> Result<bool, Error> {
+        Ok((self.get_output)()?.enable)
+    }
+
+    /// Start a travel command
+    ///
+    /// If a travel command is already active, the device restarts it with the new parameters.
+    pub fn move_to(&mut self, travel: StepperPositionEL70x1Travel) -> Result<(), Error> {
+        let mut output = (self.get_output)()?;
+
+        // the target velocity is always positive, the direction follows from the start type
+        let converter = EL70x1VelocityConverter::new(&(self.get_speed_range)());
+        let velocity = converter.steps_to_velocity(travel.steps_per_second.abs(), false);
+
+        output.start = true;
+        output.emergency_stop = false;
+        output.target_position = travel.target_position;
+        output.target_velocity = velocity;
+        output.start_type = travel.start_type;
+        output.acceleration = travel.acceleration;
+        output.deceleration = travel.deceleration;
+
+        (self.set_output)(output)
+    }
+
+    /// Abort the active travel command with the configured deceleration
+    pub fn stop(&mut self) -> Result<(), Error> {
+        let mut output = (self.get_output)()?;
+        output.execute = false;
+        (self.set_output)(output)
+    }
+
+    /// Abort the active travel command with the emergency deceleration
+    pub fn emergency_stop(&mut self) -> Result<(), Error> {
+        let mut output = (self.get_output)()?;
+        output.emergency_stop = true;
+        (self.set_output)(output)
+    }
+
+    /// Read the state of the positioning interface
+    pub fn get_input(&self) -> Result<StepperPositionEL70x1Input, Error> {
+        (self.get_input)()
+    }
+
+    /// A travel command is active
+    pub fn is_busy(&self) -> Result<bool, Error> {
+        Ok((self.get_input)()?.busy)
+    }
+
+    /// The last travel command has reached its target
+    pub fn is_in_target(&self) -> Result<bool, Error> {
+        Ok((self.get_input)()?.in_target)
+    }
+
+    /// The positioning interface or the motor stage reports an error
+    pub fn has_error(&self) -> Result<bool, Error> {
+        let input = (self.get_input)()?;
+        Ok(input.pos_error || input.error)
+    }
+
+    /// Get the current position of the stepper
+    pub fn get_position(&self) -> Result<i32, Error> {
+        Ok((self.get_input)()?.counter_value)
+    }
+
+    /// Set the position of the stepper
+    pub fn set_position(&mut self, position: i32) -> Result<(), Error> {
+        let mut output = (self.get_output)()?;
+        output.set_counter = Some(position);
+        (self.set_output)(output)
+    }
+}
+
+/// Parameters of a single travel command
+#[derive(Debug, Clone, Copy)]
+pub struct StepperPositionEL70x1Travel {
+    /// Target position in counter increments, interpreted according to `start_type`
+    pub target_position: i32,
+
+    /// How the target position is interpreted
+    pub start_type: StartType,
+
+    /// Maximum velocity of the travel command
+    pub steps_per_second: f64,
+
+    /// Acceleration time to the maximum velocity in ms
+    pub acceleration: u16,
+
+    /// Deceleration time from the maximum velocity in ms
+    pub deceleration: u16,
+}
+
+#[derive(Debug, Clone)]
+pub struct StepperPositionEL70x1Input {
+    /// `counter_value` from [`crate::pdo::el70x1::EncStatus`]
+    pub counter_value: i32,
+
+    /// `actual_position` from [`crate::pdo::el70x1::PosStatus`]
+    pub actual_position: i32,
+
+    /// `actual_velocity` from [`crate::pdo::el70x1::PosStatus`]
+    pub actual_velocity: i16,
+
+    /// `ready_to_enable` from [`crate::pdo::el70x1::StmStatus`]
+    pub ready_to_enable: bool,
+
+    /// `ready` from [`crate::pdo::el70x1::StmStatus`]
+    pub ready: bool,
+
+    /// `warning` from [`crate::pdo::el70x1::StmStatus`]
+    pub warning: bool,
+
+    /// `error` from [`crate::pdo::el70x1::StmStatus`]
+    pub error: bool,
+
+    /// `busy` from [`crate::pdo::el70x1::PosStatus`]
+    pub busy: bool,
+
+    /// `in_target` from [`crate::pdo::el70x1::PosStatus`]
+    pub in_target: bool,
+
+    /// `warning` from [`crate::pdo::el70x1::PosStatus`]
+    pub pos_warning: bool,
+
+    /// `error` from [`crate::pdo::el70x1::PosStatus`]
+    pub pos_error: bool,
+
+    /// `calibrated` from [`crate::pdo::el70x1::PosStatus`]
+    pub calibrated: bool,
+
+    /// `accelerate` from [`crate::pdo::el70x1::PosStatus`]
+    pub accelerate: bool,
+
+    /// `decelerate` from [`crate::pdo::el70x1::PosStatus`]
+    pub decelerate: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct StepperPositionEL70x1Output {
+    /// `enable` from [`crate::pdo::el70x1::StmControl`]
+    pub enable: bool,
+
+    /// `reduce_torque` from [`crate::pdo::el70x1::StmControl`]
+    pub reduce_torque: bool,
+
+    /// `reset` from [`crate::pdo::el70x1::StmControl`]
+    pub reset: bool,
+
+    /// Start a new travel command with these parameters, restarting an active one
+    pub start: bool,
+
+    /// `execute` from [`crate::pdo::el70x1::PosControl`], clearing it aborts the travel command
+    pub execute: bool,
+
+    /// `emergency_stop` from [`crate::pdo::el70x1::PosControl`]
+    pub emergency_stop: bool,
+
+    /// `target_position` from [`crate::pdo::el70x1::PosControl`]
+    pub target_position: i32,
+
+    /// `target_velocity` from [`crate::pdo::el70x1::PosControl`]
+    pub target_velocity: i16,
+
+    /// `start_type` from [`crate::pdo::el70x1::PosControl`]
+    pub start_type: StartType,
+
+    /// `acceleration` from [`crate::pdo::el70x1::PosControl`]
+    pub acceleration: u16,
+
+    /// `deceleration` from [`crate::pdo::el70x1::PosControl`]
+    pub deceleration: u16,
+
+    /// `set_counter` and `set_counter_value` from [`crate::pdo::el70x1::EncControl`]
+    pub set_counter: Option<i32>,
+}
+
+pub trait StepperPositionEL70x1Device<PORT>: Send + Sync
+where
+    PORT: Clone,
+{
+    fn set_output(&mut self, port: PORT, value: StepperPositionEL70x1Output) -> Result<(), Error>;
+    fn get_input(&self, port: PORT) -> Result<StepperPositionEL70x1Input, Error>;
+    fn get_output(&self, port: PORT) -> Result<StepperPositionEL70x1Output, Error>;
+    fn get_speed_range(&self, port: PORT) -> EL70x1SpeedRange;
+}
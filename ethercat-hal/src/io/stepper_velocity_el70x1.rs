@@ -1,6 +1,9 @@
 use std::{fmt, sync::Arc};
 
-use crate::helpers::el70xx_velocity_converter::EL70x1VelocityConverter;
+use crate::{
+    helpers::el70xx_velocity_converter::EL70x1VelocityConverter, pdo::el70x1::StmSynchronInfoData,
+    shared_config::el70x1::EL70x1InfoData,
+};
 use anyhow::Error;
 use smol::lock::RwLock;
 
@@ -127,6 +130,16 @@ impl<'device> StepperVelocityEL70x1 {
         input.counter_value
     }
 
+    /// Get an info data value if it is selected and mapped into the PDO
+    pub fn get_info_data(&self, selection: EL70x1InfoData) -> Option<u16> {
+        let input = (self.get_input)().ok()?;
+        [input.info_data_1, input.info_data_2]
+            .into_iter()
+            .flatten()
+            .find(|info_data| info_data.selection == selection)
+            .map(|info_data| info_data.value)
+    }
+
     /// Set the position of the stepper
     pub fn set_position(&mut self, position: i128) {
         // Get current state to preserve other output values
@@ -164,6 +177,42 @@ pub struct StepperVelocityEL70x1Input {
 
     /// `torque_reduced` from [`crate::pdo::el70x1::StmStatus`]
     pub torque_reduced: bool,
+
+    /// `info_data_1` from [`crate::pdo::el70x1::StmSynchronInfoData`] if mapped
+    pub info_data_1: Option<EL70x1InfoDataValue>,
+
+    /// `info_data_2` from [`crate::pdo::el70x1::StmSynchronInfoData`] if mapped
+    pub info_data_2: Option<EL70x1InfoDataValue>,
+}
+
+/// Info data value with the selection it was configured for
+#[derive(Debug, Clone, Copy)]
+pub struct EL70x1InfoDataValue {
+    pub selection: EL70x1InfoData,
+    pub value: u16,
+}
+
+impl EL70x1InfoDataValue {
+    /// Both info data values with the selections from `0x8012:11` and `0x8012:19`
+    pub const fn from_pdo(
+        info_data: Option<&StmSynchronInfoData>,
+        selection_1: EL70x1InfoData,
+        selection_2: EL70x1InfoData,
+    ) -> (Option<Self>, Option<Self>) {
+        match info_data {
+            Some(info_data) => (
+                Some(Self {
+                    selection: selection_1,
+                    value: info_data.info_data_1,
+                }),
+                Some(Self {
+                    selection: selection_2,
+                    value: info_data.info_data_2,
+                }),
+            ),
+            None => (None, None),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -1,6 +1,7 @@
 pub mod coe;
 pub mod debugging;
 pub mod devices;
+pub mod esi;
 pub mod helpers;
 pub mod io;
 pub mod pdo;
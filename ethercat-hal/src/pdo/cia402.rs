@@ -0,0 +1,139 @@
+use super::{RxPdoObject, TxPdoObject};
+use bitvec::prelude::*;
+use ethercat_hal_derive::PdoObject;
+
+/// # `DrvControlword`
+/// 16 bits / 2 bytes
+#[derive(Debug, Clone, Default, PdoObject, PartialEq, Eq)]
+#[pdo_object(bits = 16)]
+pub struct DrvControlword {
+    /// # 7010:01
+    /// CiA 402 controlword, see [`crate::helpers::cia402`].
+    pub controlword: u16,
+}
+
+impl RxPdoObject for DrvControlword {
+    fn write(&self, buffer: &mut BitSlice<u8, Lsb0>) {
+        // Offset 0.0
+        buffer[0..16].store_le(self.controlword);
+    }
+}
+
+/// # `DrvTargetVelocity`
+/// 32 bits / 4 bytes
+#[derive(Debug, Clone, Default, PdoObject, PartialEq, Eq)]
+#[pdo_object(bits = 32)]
+pub struct DrvTargetVelocity {
+    /// # 7010:06
+    /// Target velocity for CSV in increments per second.
+    pub target_velocity: i32,
+}
+
+impl RxPdoObject for DrvTargetVelocity {
+    fn write(&self, buffer: &mut BitSlice<u8, Lsb0>) {
+        // Offset 0.0
+        buffer[0..32].store_le(self.target_velocity);
+    }
+}
+
+/// # `DrvTargetPosition`
+/// 32 bits / 4 bytes
+#[derive(Debug, Clone, Default, PdoObject, PartialEq, Eq)]
+#[pdo_object(bits = 32)]
+pub struct DrvTargetPosition {
+    /// # 7010:05
+    /// Target position for CSP and PP in increments.
+    pub target_position: i32,
+}
+
+impl RxPdoObject for DrvTargetPosition {
+    fn write(&self, buffer: &mut BitSlice<u8, Lsb0>) {
+        // Offset 0.0
+        buffer[0..32].store_le(self.target_position);
+    }
+}
+
+/// # `DrvModesOfOperation`
+/// 16 bits / 2 bytes
+#[derive(Debug, Clone, Default, PdoObject, PartialEq, Eq)]
+#[pdo_object(bits = 16)]
+pub struct DrvModesOfOperation {
+    /// # 7010:03
+    /// See [`crate::helpers::cia402::Cia402ModeOfOperation`].
+    pub modes_of_operation: i8,
+}
+
+impl RxPdoObject for DrvModesOfOperation {
+    fn write(&self, buffer: &mut BitSlice<u8, Lsb0>) {
+        // Offset 0.0, followed by 8 bits padding
+        buffer[0..8].store_le(self.modes_of_operation);
+    }
+}
+
+/// # `FbPosition`
+/// 32 bits / 4 bytes
+#[derive(Debug, Clone, Default, PdoObject, PartialEq, Eq)]
+#[pdo_object(bits = 32)]
+pub struct FbPosition {
+    /// # 6000:11
+    /// Actual position of the feedback in increments.
+    pub position: i32,
+}
+
+impl TxPdoObject for FbPosition {
+    fn read(&mut self, bits: &BitSlice<u8, Lsb0>) {
+        // Offset 0.0
+        self.position = bits[0..32].load_le();
+    }
+}
+
+/// # `DrvStatusword`
+/// 16 bits / 2 bytes
+#[derive(Debug, Clone, Default, PdoObject, PartialEq, Eq)]
+#[pdo_object(bits = 16)]
+pub struct DrvStatusword {
+    /// # 6010:01
+    /// CiA 402 statusword, see [`crate::helpers::cia402`].
+    pub statusword: u16,
+}
+
+impl TxPdoObject for DrvStatusword {
+    fn read(&mut self, bits: &BitSlice<u8, Lsb0>) {
+        // Offset 0.0
+        self.statusword = bits[0..16].load_le();
+    }
+}
+
+/// # `DrvVelocityActualValue`
+/// 32 bits / 4 bytes
+#[derive(Debug, Clone, Default, PdoObject, PartialEq, Eq)]
+#[pdo_object(bits = 32)]
+pub struct DrvVelocityActualValue {
+    /// # 6010:07
+    /// Actual velocity in increments per second.
+    pub velocity: i32,
+}
+
+impl TxPdoObject for DrvVelocityActualValue {
+    fn read(&mut self, bits: &BitSlice<u8, Lsb0>) {
+        // Offset 0.0
+        self.velocity = bits[0..32].load_le();
+    }
+}
+
+/// # `DrvModesOfOperationDisplay`
+/// 16 bits / 2 bytes
+#[derive(Debug, Clone, Default, PdoObject, PartialEq, Eq)]
+#[pdo_object(bits = 16)]
+pub struct DrvModesOfOperationDisplay {
+    /// # 6010:03
+    /// The mode the drive is running in.
+    pub modes_of_operation_display: i8,
+}
+
+impl TxPdoObject for DrvModesOfOperationDisplay {
+    fn read(&mut self, bits: &BitSlice<u8, Lsb0>) {
+        // Offset 0.0, followed by 8 bits padding
+        self.modes_of_operation_display = bits[0..8].load_le();
+    }
+}
@@ -0,0 +1,39 @@
+use super::RxPdoObject;
+use bitvec::prelude::*;
+use ethercat_hal_derive::PdoObject;
+
+/// PDO Object for EL2502 devices
+///
+/// "PWM Output" with the period in the process data, 0x7000:11 and 0x7000:12
+#[derive(Debug, Clone, Default, PdoObject, PartialEq, Eq)]
+#[pdo_object(bits = 48)]
+pub struct PwmOutputWithPeriod {
+    /// Duty cycle, 0 to 0x7FFF is 0% to 100%
+    pub value: u16,
+    /// Period in µs
+    pub period: u32,
+}
+
+impl RxPdoObject for PwmOutputWithPeriod {
+    fn write(&self, bits: &mut BitSlice<u8, Lsb0>) {
+        bits[0..16].store_le(self.value);
+        bits[16..48].store_le(self.period);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pwm_output_with_period_write() {
+        let output = PwmOutputWithPeriod {
+            value: 0x4000,
+            period: 1_000_000,
+        };
+        let mut buffer = [0u8; 6];
+        output.write(&mut buffer.view_bits_mut::<Lsb0>()[0..48]);
+
+        assert_eq!(buffer, [0x00, 0x40, 0x40, 0x42, 0x0F, 0x00]);
+    }
+}
@@ -51,3 +51,8 @@ impl TxPdoObject for RtdInput {
         self.txpdo_state = bits[8 + 6];
     }
 }
+
+/// PDO Object for EL33xx (thermocouple) devices
+///
+/// The "TC Input" has the same layout as the [`RtdInput`].
+pub type TcInput = RtdInput;
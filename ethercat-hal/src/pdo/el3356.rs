@@ -0,0 +1,134 @@
+use bitvec::prelude::*;
+use ethercat_hal_derive::PdoObject;
+
+use super::{RxPdoObject, TxPdoObject};
+
+/// # `RMB Status`
+/// 16 bits / 2 bytes
+#[derive(Debug, Clone, Default, PdoObject, PartialEq, Eq)]
+#[pdo_object(bits = 16)]
+pub struct RmbStatus {
+    /// # 6000:01
+    /// The bridge voltage is below the measuring range
+    pub underrange: bool,
+
+    /// # 6000:02
+    /// The bridge voltage is above the measuring range
+    pub overrange: bool,
+
+    /// # 6000:04
+    /// The value can't be used, for example while the terminal starts
+    pub data_invalid: bool,
+
+    /// # 6000:07
+    /// Wire break or supply error
+    pub error: bool,
+
+    /// # 6000:08
+    /// The terminal is calibrating itself and doesn't measure
+    pub calibration_in_progress: bool,
+
+    /// # 6000:09
+    /// The value didn't change more than the configured tolerance for the configured time
+    pub steady_state: bool,
+
+    /// # 6000:0E
+    pub sync_error: bool,
+
+    /// # 6000:10
+    /// If the PDO objects data has changed since the last read
+    pub txpdo_toggle: bool,
+}
+
+impl TxPdoObject for RmbStatus {
+    fn read(&mut self, bits: &BitSlice<u8, Lsb0>) {
+        self.underrange = bits[0];
+        self.overrange = bits[1];
+        self.data_invalid = bits[3];
+        self.error = bits[6];
+        self.calibration_in_progress = bits[7];
+        self.steady_state = bits[8];
+        self.sync_error = bits[13];
+        self.txpdo_toggle = bits[15];
+    }
+}
+
+/// # `RMB Value (INT32)`
+/// 32 bits / 4 bytes
+#[derive(Debug, Clone, Default, PdoObject, PartialEq, Eq)]
+#[pdo_object(bits = 32)]
+pub struct RmbValue {
+    /// # 6000:11
+    /// Load in the unit of the nominal load multiplied by the scale factor
+    pub value: i32,
+}
+
+impl TxPdoObject for RmbValue {
+    fn read(&mut self, bits: &BitSlice<u8, Lsb0>) {
+        self.value = bits[0..32].load_le();
+    }
+}
+
+/// # `RMB Control`
+/// 16 bits / 2 bytes
+#[derive(Debug, Clone, Default, PdoObject, PartialEq, Eq)]
+#[pdo_object(bits = 16)]
+pub struct RmbControl {
+    /// # 7000:01
+    /// Starts a self calibration on the rising edge
+    pub start_calibration: bool,
+
+    /// # 7000:02
+    /// Suppresses the cyclic self calibration
+    pub disable_calibration: bool,
+
+    /// # 7000:03
+    /// Keeps the last value
+    pub input_freeze: bool,
+
+    /// # 7000:04
+    /// Switches to the second (faster) filter
+    pub sample_mode: bool,
+
+    /// # 7000:05
+    /// Sets the current load as zero on the rising edge, lost when the terminal restarts
+    pub tare: bool,
+}
+
+impl RxPdoObject for RmbControl {
+    fn write(&self, buffer: &mut BitSlice<u8, Lsb0>) {
+        buffer.set(0, self.start_calibration);
+        buffer.set(1, self.disable_calibration);
+        buffer.set(2, self.input_freeze);
+        buffer.set(3, self.sample_mode);
+        buffer.set(4, self.tare);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rmb_status_read() {
+        let mut status = RmbStatus::default();
+        // steady state, underrange and toggle
+        status.read([0b0000_0001, 0b1000_0001].view_bits::<Lsb0>());
+        assert!(status.underrange);
+        assert!(!status.overrange);
+        assert!(status.steady_state);
+        assert!(status.txpdo_toggle);
+        assert!(!status.error);
+    }
+
+    #[test]
+    fn test_rmb_control_write() {
+        let control = RmbControl {
+            tare: true,
+            ..Default::default()
+        };
+        let mut buffer = [0u8; 2];
+        control.write(buffer.view_bits_mut::<Lsb0>());
+        assert_eq!(buffer, [0b0001_0000, 0]);
+    }
+}
@@ -0,0 +1,90 @@
+use bitvec::prelude::*;
+use ethercat_hal_derive::PdoObject;
+
+use super::TxPdoObject;
+
+/// # `PMX Status`
+/// 16 bits / 2 bytes
+#[derive(Debug, Clone, Default, PdoObject, PartialEq, Eq)]
+#[pdo_object(bits = 16)]
+pub struct PmxStatus {
+    /// # 6000:07
+    /// Measurement error, for example a missing voltage
+    pub error: bool,
+
+    /// # 6000:0F
+    pub txpdo_state: bool,
+
+    /// # 6000:10
+    /// If the PDO objects data has changed since the last read
+    pub txpdo_toggle: bool,
+}
+
+impl TxPdoObject for PmxStatus {
+    fn read(&mut self, bits: &BitSlice<u8, Lsb0>) {
+        self.error = bits[6];
+        self.txpdo_state = bits[14];
+        self.txpdo_toggle = bits[15];
+    }
+}
+
+/// # `PMX Basic` of one phase
+/// 96 bits / 12 bytes
+#[derive(Debug, Clone, Default, PdoObject, PartialEq)]
+#[pdo_object(bits = 96)]
+pub struct PmxPhaseBasic {
+    /// # 6010:11 (L1), 6020:11 (L2), 6030:11 (L3)
+    /// RMS voltage against neutral in V
+    pub voltage: f32,
+
+    /// # 6010:12 (L1), 6020:12 (L2), 6030:12 (L3)
+    /// RMS current in A
+    pub current: f32,
+
+    /// # 6010:13 (L1), 6020:13 (L2), 6030:13 (L3)
+    /// Active power in W, negative if the energy is fed back
+    pub active_power: f32,
+}
+
+impl TxPdoObject for PmxPhaseBasic {
+    fn read(&mut self, bits: &BitSlice<u8, Lsb0>) {
+        self.voltage = f32::from_bits(bits[0..32].load_le());
+        self.current = f32::from_bits(bits[32..64].load_le());
+        self.active_power = f32::from_bits(bits[64..96].load_le());
+    }
+}
+
+/// # `PMX Energy` of one phase
+/// 64 bits / 8 bytes
+#[derive(Debug, Clone, Default, PdoObject, PartialEq, Eq)]
+#[pdo_object(bits = 64)]
+pub struct PmxPhaseEnergy {
+    /// # 6011:11 (L1), 6021:11 (L2), 6031:11 (L3)
+    /// Active energy in mWh, kept by the terminal over power cycles
+    pub active_energy: i64,
+}
+
+impl TxPdoObject for PmxPhaseEnergy {
+    fn read(&mut self, bits: &BitSlice<u8, Lsb0>) {
+        self.active_energy = bits[0..64].load_le();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pmx_phase_basic_read() {
+        let mut buffer = [0u8; 12];
+        buffer[0..4].copy_from_slice(&230.5f32.to_le_bytes());
+        buffer[4..8].copy_from_slice(&1.25f32.to_le_bytes());
+        buffer[8..12].copy_from_slice(&(-288.0f32).to_le_bytes());
+
+        let mut basic = PmxPhaseBasic::default();
+        basic.read(buffer.view_bits::<Lsb0>());
+        assert_eq!(basic.voltage, 230.5);
+        assert_eq!(basic.current, 1.25);
+        assert_eq!(basic.active_power, -288.0);
+    }
+}
@@ -1,7 +1,11 @@
 pub mod analog_input;
 pub mod basic;
+pub mod cia402;
+pub mod el2502;
 pub mod el252x;
 pub mod el32xx;
+pub mod el3356;
+pub mod el3443;
 pub mod el40xx;
 pub mod el5152;
 pub mod el70x1;
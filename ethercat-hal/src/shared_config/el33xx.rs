@@ -0,0 +1,104 @@
+use super::el30xx::EL30XXFilterSettings;
+use crate::helpers::ethercrab_types::EthercrabSubDevicePreoperational;
+
+impl EL33XXChannelConfiguration {
+    pub async fn write_channel_config<'a>(
+        &self,
+        device: &EthercrabSubDevicePreoperational<'a>,
+        base_index: u16,
+    ) -> Result<(), anyhow::Error> {
+        device
+            .sdo_write(base_index, 0x06, self.enable_filter)
+            .await?;
+        device
+            .sdo_write(base_index, 0x15, u16::from(self.filter_settings))
+            .await?;
+        device
+            .sdo_write(base_index, 0x19, u16::from(self.thermocouple_type))
+            .await?;
+        device
+            .sdo_write(base_index, 0x1A, u16::from(self.cold_junction_compensation))
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EL33XXChannelConfiguration {
+    // 80n0:06
+    // Enable filter, the setting of channel 1 applies to all channels
+    pub enable_filter: bool,
+
+    // 80n0:15
+    // Filter settings if the filter is enabled, the setting of channel 1 applies to all channels
+    pub filter_settings: EL30XXFilterSettings,
+
+    // 80n0:19
+    // Thermocouple element connected to the channel
+    pub thermocouple_type: EL33XXThermocoupleType,
+
+    // 80n0:1A
+    // Source of the cold junction temperature
+    pub cold_junction_compensation: EL33XXColdJunctionCompensation,
+}
+
+impl Default for EL33XXChannelConfiguration {
+    fn default() -> Self {
+        Self {
+            enable_filter: true,
+            filter_settings: EL30XXFilterSettings::FIR50Hz,
+            thermocouple_type: EL33XXThermocoupleType::K,
+            cold_junction_compensation: EL33XXColdJunctionCompensation::Internal,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EL33XXThermocoupleType {
+    K,
+    J,
+    L,
+    E,
+    T,
+    N,
+    U,
+    B,
+    R,
+    S,
+    C,
+}
+
+impl From<EL33XXThermocoupleType> for u16 {
+    fn from(thermocouple_type: EL33XXThermocoupleType) -> Self {
+        match thermocouple_type {
+            EL33XXThermocoupleType::K => 0,
+            EL33XXThermocoupleType::J => 1,
+            EL33XXThermocoupleType::L => 2,
+            EL33XXThermocoupleType::E => 3,
+            EL33XXThermocoupleType::T => 4,
+            EL33XXThermocoupleType::N => 5,
+            EL33XXThermocoupleType::U => 6,
+            EL33XXThermocoupleType::B => 7,
+            EL33XXThermocoupleType::R => 8,
+            EL33XXThermocoupleType::S => 9,
+            EL33XXThermocoupleType::C => 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EL33XXColdJunctionCompensation {
+    /// Temperature sensor in the terminal
+    Internal,
+    /// No compensation, for measurements against an external reference junction
+    None,
+}
+
+impl From<EL33XXColdJunctionCompensation> for u16 {
+    fn from(cold_junction_compensation: EL33XXColdJunctionCompensation) -> Self {
+        match cold_junction_compensation {
+            EL33XXColdJunctionCompensation::Internal => 0,
+            EL33XXColdJunctionCompensation::None => 1,
+        }
+    }
+}
@@ -1,8 +1,15 @@
+use std::ops::Deref;
+
 use anyhow;
+use ethercrab::{SubDevice, SubDeviceRef};
+use serde::{Deserialize, Serialize};
 
+use crate::devices::{
+    EthercatDevice, el7031::EL7031, el7031_0030::EL7031_0030, el7041_0052::EL7041_0052,
+};
 use crate::helpers::ethercrab_types::EthercrabSubDevicePreoperational;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EncConfiguration {
     /// # 8000:0E
     /// Activates reversion of rotation of the encoder.
@@ -21,18 +28,27 @@ impl Default for EncConfiguration {
 }
 
 impl EncConfiguration {
-    pub async fn write_config<'a>(
+    pub async fn write_config<S: Deref<Target = SubDevice>>(
         &self,
-        device: &EthercrabSubDevicePreoperational<'a>,
+        device: &SubDeviceRef<'_, S>,
     ) -> Result<(), anyhow::Error> {
         device
             .sdo_write(0x8000, 0x0E, self.reversion_of_rotation)
             .await?;
         Ok(())
     }
+
+    /// Reads back the objects written by [`Self::write_config`]
+    pub async fn read_config<S: Deref<Target = SubDevice>>(
+        &mut self,
+        device: &SubDeviceRef<'_, S>,
+    ) -> Result<(), anyhow::Error> {
+        self.reversion_of_rotation = device.sdo_read(0x8000, 0x0E).await?;
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct StmMotorConfiguration {
     /// # 0x8010:01
     /// Maximum current (unit: 1 mA)
@@ -107,9 +123,9 @@ impl Default for StmMotorConfiguration {
 }
 
 impl StmMotorConfiguration {
-    pub async fn write_config<'a>(
+    pub async fn write_config<S: Deref<Target = SubDevice>>(
         &self,
-        device: &EthercrabSubDevicePreoperational<'a>,
+        device: &SubDeviceRef<'_, S>,
     ) -> Result<(), anyhow::Error> {
         device.sdo_write(0x8010, 0x01, self.max_current).await?;
         device.sdo_write(0x8010, 0x02, self.reduced_current).await?;
@@ -130,9 +146,26 @@ impl StmMotorConfiguration {
             .await?;
         Ok(())
     }
+
+    /// Reads back the objects written by [`Self::write_config`]
+    pub async fn read_config<S: Deref<Target = SubDevice>>(
+        &mut self,
+        device: &SubDeviceRef<'_, S>,
+    ) -> Result<(), anyhow::Error> {
+        self.max_current = device.sdo_read(0x8010, 0x01).await?;
+        self.reduced_current = device.sdo_read(0x8010, 0x02).await?;
+        self.nominal_voltage = device.sdo_read(0x8010, 0x03).await?;
+        self.motor_coil_resistance = device.sdo_read(0x8010, 0x04).await?;
+        self.motor_emf = device.sdo_read(0x8010, 0x05).await?;
+        self.motor_full_steps = device.sdo_read(0x8010, 0x06).await?;
+        self.start_velocity = device.sdo_read(0x8010, 0x09).await?;
+        self.drive_on_delay_time = device.sdo_read(0x8010, 0x10).await?;
+        self.drive_off_delay_time = device.sdo_read(0x8010, 0x11).await?;
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct StmControllerConfiguration {
     /// # 8011:01 / 8013:01
     /// Kp control factor (proportional component) for the current controll (unit: 0.001)
@@ -193,9 +226,9 @@ impl Default for StmControllerConfiguration {
 }
 
 impl StmControllerConfiguration {
-    pub async fn write_config<'a>(
+    pub async fn write_config<S: Deref<Target = SubDevice>>(
         &self,
-        device: &EthercrabSubDevicePreoperational<'a>,
+        device: &SubDeviceRef<'_, S>,
         base_index: u16,
     ) -> Result<(), anyhow::Error> {
         device.sdo_write(base_index, 0x01, self.kp_factor).await?;
@@ -213,6 +246,98 @@ impl StmControllerConfiguration {
         // device.sdo_write(base_index, 0x08, self.kd_factor).await?;
         Ok(())
     }
+
+    /// Reads back the objects written by [`Self::write_config`]
+    pub async fn read_config<S: Deref<Target = SubDevice>>(
+        &mut self,
+        device: &SubDeviceRef<'_, S>,
+        base_index: u16,
+    ) -> Result<(), anyhow::Error> {
+        self.kp_factor = device.sdo_read(base_index, 0x01).await?;
+        self.ki_factor = device.sdo_read(base_index, 0x02).await?;
+        self.inner_window = device.sdo_read(base_index, 0x03).await?;
+        Ok(())
+    }
+}
+
+/// Motor dependent part of the EL70x1 configuration
+///
+/// Unlike the rest of the configuration it can be changed while the terminal is operational, as
+/// long as the motor is disabled.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EL70x1MotorConfiguration {
+    /// Encoder configuration
+    pub encoder: EncConfiguration,
+
+    /// STM motor configuration
+    pub stm_motor: StmMotorConfiguration,
+
+    /// STM controller configuration
+    pub stm_controller_1: StmControllerConfiguration,
+
+    /// STM controller configuration
+    pub stm_controller_2: StmControllerConfiguration,
+}
+
+impl EL70x1MotorConfiguration {
+    pub async fn write_config<S: Deref<Target = SubDevice>>(
+        &self,
+        device: &SubDeviceRef<'_, S>,
+    ) -> Result<(), anyhow::Error> {
+        self.encoder.write_config(device).await?;
+        self.stm_motor.write_config(device).await?;
+        self.stm_controller_1.write_config(device, 0x8011).await?;
+        self.stm_controller_2.write_config(device, 0x8013).await?;
+        Ok(())
+    }
+
+    /// Reads the motor configuration from the terminal
+    ///
+    /// Objects that [`Self::write_config`] doesn't write keep their value from `self`.
+    pub async fn read_config<S: Deref<Target = SubDevice>>(
+        &mut self,
+        device: &SubDeviceRef<'_, S>,
+    ) -> Result<(), anyhow::Error> {
+        self.encoder.read_config(device).await?;
+        self.stm_motor.read_config(device).await?;
+        self.stm_controller_1.read_config(device, 0x8011).await?;
+        self.stm_controller_2.read_config(device, 0x8013).await?;
+        Ok(())
+    }
+}
+
+/// EL70x1 terminal whose motor configuration can be changed at runtime
+pub trait EL70x1MotorConfigurableDevice {
+    /// The motor configuration the terminal was last configured with
+    fn get_motor_configuration(&self) -> EL70x1MotorConfiguration;
+
+    /// Remember the motor configuration after it was written to the terminal
+    fn set_motor_configuration(&mut self, configuration: EL70x1MotorConfiguration);
+
+    /// The driver stage is enabled, the motor configuration must not be changed
+    fn is_motor_enabled(&self) -> bool;
+}
+
+/// Get the runtime motor configuration of an EL70x1 terminal
+///
+/// Returns `None` if the device isn't an EL70x1 stepper terminal.
+pub fn as_el70x1_motor_configurable_mut(
+    device: &mut dyn EthercatDevice,
+) -> Option<&mut dyn EL70x1MotorConfigurableDevice> {
+    let device = device.as_any_mut();
+    if device.is::<EL7031>() {
+        return device
+            .downcast_mut::<EL7031>()
+            .map(|device| device as &mut dyn EL70x1MotorConfigurableDevice);
+    }
+    if device.is::<EL7031_0030>() {
+        return device
+            .downcast_mut::<EL7031_0030>()
+            .map(|device| device as &mut dyn EL70x1MotorConfigurableDevice);
+    }
+    device
+        .downcast_mut::<EL7041_0052>()
+        .map(|device| device as &mut dyn EL70x1MotorConfigurableDevice)
 }
 
 #[derive(Debug, Clone)]
@@ -801,6 +926,8 @@ pub enum EL70x1InfoData {
     DutyCycleCoilB = 6,
     /// Current velocity (value range +/- 10000)
     CurrentVelocity = 7,
+    /// Motor load angle, rises with the load until the motor stalls
+    LoadAngle = 8,
     /// Internal temperature of the driver card
     InternalTemperature = 101,
     /// Control voltage
@@ -828,6 +955,7 @@ impl std::fmt::Debug for EL70x1InfoData {
             Self::DutyCycleCoilA => write!(f, "DutyCycleCoilA (5)"),
             Self::DutyCycleCoilB => write!(f, "DutyCycleCoilB (6)"),
             Self::CurrentVelocity => write!(f, "CurrentVelocity (7)"),
+            Self::LoadAngle => write!(f, "LoadAngle (8)"),
             Self::InternalTemperature => write!(f, "InternalTemperature (101)"),
             Self::ControlVoltage => write!(f, "ControlVoltage (103)"),
             Self::MotorSupplyVoltage => write!(f, "MotorSupplyVoltage (104)"),
@@ -852,6 +980,7 @@ impl TryFrom<u8> for EL70x1InfoData {
             5 => Ok(Self::DutyCycleCoilA),
             6 => Ok(Self::DutyCycleCoilB),
             7 => Ok(Self::CurrentVelocity),
+            8 => Ok(Self::LoadAngle),
             101 => Ok(Self::InternalTemperature),
             103 => Ok(Self::ControlVoltage),
             104 => Ok(Self::MotorSupplyVoltage),
@@ -1,3 +1,4 @@
 pub mod el30xx;
+pub mod el33xx;
 pub mod el40xx;
 pub mod el70x1;
@@ -1,3 +1,5 @@
+use crate::hardware_descriptor::{EthercatRole, MachineHardwareDescriptor};
+use ethercat_hal::devices::el3021::EL3021_IDENTITY_A;
 use std::time::Instant;
 
 use control_core::socketio::{event::Event, namespace::NamespaceCacheingLogic};
@@ -46,6 +48,15 @@ impl AnalogInputTestMachine {
         machine: ANALOG_INPUT_TEST_MACHINE,
     };
 
+    /// Hardware the machine expects, checked before the machine is created
+    pub const HARDWARE_DESCRIPTOR: MachineHardwareDescriptor = MachineHardwareDescriptor {
+        ethercat_roles: &[EthercatRole::required(
+            1,
+            "Analog input",
+            &[EL3021_IDENTITY_A],
+        )],
+    };
+
     pub fn emit_measurement(&mut self, value: f64, unix_timestamp_ms: u128) {
         let event = MeasurementEvent::Measurement(value, unix_timestamp_ms.to_string());
         self.namespace
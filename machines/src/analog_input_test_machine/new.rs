@@ -2,7 +2,7 @@ use std::time::Instant;
 
 use anyhow::Error;
 use ethercat_hal::{
-    devices::el3021::{EL3021, EL3021_IDENTITY_A, EL3021Port},
+    devices::el3021::{EL3021, EL3021Port},
     io::analog_input::AnalogInput,
 };
 use smol::{block_on, channel::unbounded};
@@ -35,10 +35,14 @@ impl MachineNewTrait for AnalogInputTestMachine {
         };
 
         block_on(async {
-            let el3021 =
-                get_ethercat_device::<EL3021>(hardware, params, 1, [EL3021_IDENTITY_A].to_vec())
-                    .await?
-                    .0;
+            let el3021 = get_ethercat_device::<EL3021>(
+                hardware,
+                params,
+                1,
+                Self::HARDWARE_DESCRIPTOR.identities(1),
+            )
+            .await?
+            .0;
 
             let ai1 = AnalogInput::new(el3021.clone(), EL3021Port::AI1);
 
@@ -1,11 +1,12 @@
 use crate::aquapath1::VolumeRate;
 use crate::aquapath1::{Flow, Temperature};
 use control_core::controllers::pid::PidController;
-use ethercat_hal::io::encoder_input::EncoderInput;
+use control_core::helpers::heater_output::HeaterOutput;
+use ethercat_hal::io::flow_meter::FlowMeter;
 use ethercat_hal::io::{
     analog_output::AnalogOutput, digital_output::DigitalOutput, temperature_input::TemperatureInput,
 };
-use std::time::{Duration, Instant};
+use std::time::Instant;
 use units::f64::ThermodynamicTemperature;
 use units::thermodynamic_temperature::degree_celsius;
 use units::volume_rate::liter_per_minute;
@@ -14,8 +15,6 @@ use units::volume_rate::liter_per_minute;
 pub struct Controller {
     pub pid: PidController,
     temperature_pid_output: f64,
-    window_start: Instant,
-    pwm_period: Duration,
 
     pub temperature: Temperature,
     pub target_temperature: ThermodynamicTemperature,
@@ -27,7 +26,7 @@ pub struct Controller {
     pub cooling_controller: AnalogOutput,
     pub cooling_relais: DigitalOutput,
 
-    pub heating_relais_1: DigitalOutput,
+    pub heater: HeaterOutput,
     pub temperature_sensor_in: TemperatureInput,
     pub temperature_sensor_out: TemperatureInput,
 
@@ -37,7 +36,7 @@ pub struct Controller {
     pub flow: Flow,
     pump_relais: DigitalOutput,
     pub should_pump: bool,
-    pub flow_sensor: EncoderInput,
+    pub flow_sensor: FlowMeter,
     pub pump_allowed: bool,
     pub current_flow: VolumeRate,
     pub max_flow: VolumeRate,
@@ -48,24 +47,21 @@ impl Controller {
         kp: f64,
         ki: f64,
         kd: f64,
-        pwm_duration: Duration,
         temp: Temperature,
         target_tempetature: ThermodynamicTemperature,
         cooling_controller: AnalogOutput,
         cooling_relais: DigitalOutput,
-        heating_relais_1: DigitalOutput,
+        heater: HeaterOutput,
         temp_sensor_in: TemperatureInput,
         temp_sensor_out: TemperatureInput,
 
         flow: Flow,
         pump_relais: DigitalOutput,
-        flow_sensor: EncoderInput,
+        flow_sensor: FlowMeter,
     ) -> Self {
         Self {
             pid: PidController::new(kp, ki, kd),
             temperature_pid_output: 0.0,
-            window_start: Instant::now(),
-            pwm_period: pwm_duration,
             target_temperature: target_tempetature,
             current_temperature: ThermodynamicTemperature::new::<degree_celsius>(25.0),
             temp_reservoir: ThermodynamicTemperature::new::<degree_celsius>(25.0),
@@ -75,7 +71,7 @@ impl Controller {
             temperature: temp,
             cooling_controller: cooling_controller,
             cooling_relais: cooling_relais,
-            heating_relais_1: heating_relais_1,
+            heater: heater,
             cooling_allowed: false,
             heating_allowed: false,
             temperature_sensor_in: temp_sensor_in,
@@ -184,12 +180,12 @@ impl Controller {
     }
 
     pub fn turn_heating_on(&mut self) {
-        self.heating_relais_1.set(true);
+        self.heater.on();
         self.temperature.heating = true;
     }
 
     pub fn turn_heating_off(&mut self) {
-        self.heating_relais_1.set(false);
+        self.heater.off();
         self.temperature.heating = false;
     }
 
@@ -202,25 +198,9 @@ impl Controller {
     }
 
     pub fn get_flow(&mut self) -> VolumeRate {
-        let value = match self.flow_sensor.get_frequency_value() {
-            Ok(val) => val,
-            Err(_e) => {
-                return VolumeRate::new::<liter_per_minute>(0.0);
-            }
-        };
-
-        match value {
-            Some(val) => {
-                if val == 0 {
-                    return VolumeRate::new::<liter_per_minute>(0.0);
-                }
-                // Formula: f = 8.1*q - 3, so q = (f + 3) / 8.1
-                let actual_flow = ((val / 100) as f32 + 3.0) / 8.1;
-                VolumeRate::new::<liter_per_minute>(actual_flow.into())
-            }
-            None => {
-                return VolumeRate::new::<liter_per_minute>(0.0);
-            }
+        match self.flow_sensor.update() {
+            Ok(flow) => flow,
+            Err(_e) => VolumeRate::new::<liter_per_minute>(0.0),
         }
     }
 
@@ -253,11 +233,6 @@ impl Controller {
         let control = self.pid.update(error, now);
         self.temperature_pid_output = control;
 
-        let elapsed = now.duration_since(self.window_start);
-        if elapsed >= self.pwm_period {
-            self.window_start = now;
-        }
-
         // Decide whether to heat or cool based on error
         if error > 0.0 {
             // Need heating (current < target)
@@ -267,13 +242,7 @@ impl Controller {
             if self.heating_allowed && current_flow > VolumeRate::new::<liter_per_minute>(0.0) {
                 // Only start heating if pump is on
                 let duty = control.clamp(0.0, 1.0);
-                let on_time = self.pwm_period.mul_f64(duty);
-                let on = elapsed < on_time;
-                if on && !self.temperature.heating {
-                    self.turn_heating_on();
-                } else if !on && self.temperature.heating {
-                    self.turn_heating_off();
-                }
+                self.temperature.heating = self.heater.set_duty_cycle(now, duty);
             } else {
                 // Pump is off or heating not allowed - don't heat
                 if self.temperature.heating {
@@ -1,4 +1,12 @@
+use crate::hardware_descriptor::{EthercatRole, MachineHardwareDescriptor};
 use control_core::socketio::namespace::NamespaceCacheingLogic;
+use ethercat_hal::devices::{
+    ek1100::EK1100_IDENTITY_A,
+    el2008::{EL2008_IDENTITY_A, EL2008_IDENTITY_B},
+    el3204::{EL3204_IDENTITY_A, EL3204_IDENTITY_B},
+    el4002::EL4002_IDENTITY_A,
+    el5152::EL5152_IDENTITY_A,
+};
 use serde::{Deserialize, Serialize};
 use std::time::Instant;
 use units::f64::*;
@@ -100,6 +108,25 @@ impl AquaPathV1 {
         vendor: VENDOR_QITECH,
         machine: MACHINE_AQUAPATH_V1,
     };
+
+    /// Hardware the machine expects, checked before the machine is created
+    pub const HARDWARE_DESCRIPTOR: MachineHardwareDescriptor = MachineHardwareDescriptor {
+        ethercat_roles: &[
+            EthercatRole::optional(0, "Bus coupler", &[EK1100_IDENTITY_A]),
+            EthercatRole::required(
+                1,
+                "Digital outputs",
+                &[EL2008_IDENTITY_A, EL2008_IDENTITY_B],
+            ),
+            EthercatRole::required(2, "Analog outputs", &[EL4002_IDENTITY_A]),
+            EthercatRole::required(
+                3,
+                "Temperature sensors",
+                &[EL3204_IDENTITY_A, EL3204_IDENTITY_B],
+            ),
+            EthercatRole::required(4, "Flow meters", &[EL5152_IDENTITY_A]),
+        ],
+    };
 }
 
 impl std::fmt::Display for AquaPathV1 {
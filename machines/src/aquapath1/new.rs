@@ -8,25 +8,31 @@ use super::{
     AquaPathV1, AquaPathV1Mode, Flow, Temperature, api::AquaPathV1Namespace, controller::Controller,
 };
 use anyhow::Error;
+use control_core::helpers::heater_output::HeaterOutput;
 use ethercat_hal::{
     coe::ConfigurableDevice,
     devices::{
-        ek1100::{EK1100, EK1100_IDENTITY_A},
-        el2008::{EL2008, EL2008_IDENTITY_A, EL2008_IDENTITY_B, EL2008Port},
-        el3204::{EL3204, EL3204_IDENTITY_A, EL3204_IDENTITY_B, EL3204Port},
-        el4002::{EL4002, EL4002_IDENTITY_A, EL4002Port},
-        el5152::{
-            EL5152, EL5152_IDENTITY_A, EL5152Configuration, EL5152Port,
-            EL5152PredefinedPdoAssignment,
-        },
+        ek1100::EK1100,
+        el2008::{EL2008, EL2008Port},
+        el3204::{EL3204, EL3204Port},
+        el4002::{EL4002, EL4002Port},
+        el5152::{EL5152, EL5152Configuration, EL5152Port, EL5152PredefinedPdoAssignment},
     },
     io::{
-        analog_output::AnalogOutput, digital_output::DigitalOutput, encoder_input::EncoderInput,
+        analog_output::AnalogOutput,
+        digital_output::DigitalOutput,
+        encoder_input::EncoderInput,
+        flow_meter::{FlowMeter, FlowMeterConfiguration, FlowMeterSignal},
         temperature_input::TemperatureInput,
     },
 };
 use std::time::{Duration, Instant};
 use units::thermodynamic_temperature::{ThermodynamicTemperature, degree_celsius};
+use units::{
+    f64::{Frequency, VolumeRate},
+    frequency::hertz,
+    volume_rate::liter_per_minute,
+};
 
 impl MachineNewTrait for AquaPathV1 {
     fn new<'maindevice>(params: &MachineNewParams) -> Result<Self, Error> {
@@ -51,38 +57,50 @@ impl MachineNewTrait for AquaPathV1 {
 
         smol::block_on(async {
             // Role 0 - Buscoupler EK1100
-            let _ek1100 =
-                get_ethercat_device::<EK1100>(hardware, params, 0, [EK1100_IDENTITY_A].to_vec());
+            let _ek1100 = get_ethercat_device::<EK1100>(
+                hardware,
+                params,
+                0,
+                Self::HARDWARE_DESCRIPTOR.identities(0),
+            );
 
             // Role 1 - EL2008 Digital Output Module
             let el2008 = get_ethercat_device::<EL2008>(
                 hardware,
                 params,
                 1,
-                [EL2008_IDENTITY_A, EL2008_IDENTITY_B].to_vec(),
+                Self::HARDWARE_DESCRIPTOR.identities(1),
             )
             .await?
             .0;
 
             // Role 2 - EL4002 Analog Output Module
-            let el4002 =
-                get_ethercat_device::<EL4002>(hardware, params, 2, [EL4002_IDENTITY_A].to_vec())
-                    .await?
-                    .0;
+            let el4002 = get_ethercat_device::<EL4002>(
+                hardware,
+                params,
+                2,
+                Self::HARDWARE_DESCRIPTOR.identities(2),
+            )
+            .await?
+            .0;
 
             let el3204 = get_ethercat_device::<EL3204>(
                 hardware,
                 params,
                 3,
-                [EL3204_IDENTITY_A, EL3204_IDENTITY_B].to_vec(),
+                Self::HARDWARE_DESCRIPTOR.identities(3),
             )
             .await?
             .0;
 
-            let el5152 =
-                get_ethercat_device::<EL5152>(hardware, params, 4, [EL5152_IDENTITY_A].to_vec())
-                    .await?
-                    .0;
+            let el5152 = get_ethercat_device::<EL5152>(
+                hardware,
+                params,
+                4,
+                Self::HARDWARE_DESCRIPTOR.identities(4),
+            )
+            .await?
+            .0;
 
             let config = EL5152Configuration {
                 pdo_assignment: EL5152PredefinedPdoAssignment::Frequency,
@@ -95,9 +113,22 @@ impl MachineNewTrait for AquaPathV1 {
                 .write_config(&subdevice, &config)
                 .await?;
 
-            let enc1 = EncoderInput::new(el5152.clone(), EL5152Port::ENC1);
+            // Flow sensor: f = 8.1 * q - 3 with q in L/min, 486 pulses per litre
+            let flow_meter_configuration = FlowMeterConfiguration {
+                k_factor: 486.0,
+                zero_offset: Frequency::new::<hertz>(-3.0),
+                signal: FlowMeterSignal::default(),
+                low_flow_cutoff: VolumeRate::new::<liter_per_minute>(0.1),
+            };
+            let enc1 = FlowMeter::new(
+                EncoderInput::new(el5152.clone(), EL5152Port::ENC1),
+                flow_meter_configuration,
+            );
 
-            let enc2 = EncoderInput::new(el5152.clone(), EL5152Port::ENC2);
+            let enc2 = FlowMeter::new(
+                EncoderInput::new(el5152.clone(), EL5152Port::ENC2),
+                flow_meter_configuration,
+            );
             //after heating
             let t1 = TemperatureInput::new(el3204.clone(), EL3204Port::T1);
             //in reservoir
@@ -131,12 +162,11 @@ impl MachineNewTrait for AquaPathV1 {
                 0.10,
                 0.0,
                 0.015,
-                Duration::from_millis(500),
                 Temperature::default(),
                 ThermodynamicTemperature::new::<degree_celsius>(25.0),
                 ao1,
                 do4,
-                do2,
+                HeaterOutput::software(do2, Duration::from_millis(500)),
                 t1,
                 t2,
                 Flow::default(),
@@ -148,12 +178,11 @@ impl MachineNewTrait for AquaPathV1 {
                 0.10,
                 0.0,
                 0.015,
-                Duration::from_millis(500),
                 Temperature::default(),
                 ThermodynamicTemperature::new::<degree_celsius>(25.0),
                 ao2,
                 do8,
-                do6,
+                HeaterOutput::software(do6, Duration::from_millis(500)),
                 t3,
                 t4,
                 Flow::default(),
@@ -1,4 +1,4 @@
-use super::BufferV1;
+use super::{BufferV1, BufferV1Mode};
 use crate::{MachineAct, MachineMessage};
 use std::time::{Duration, Instant};
 
@@ -11,6 +11,17 @@ impl MachineAct for BufferV1 {
             }
             Err(_) => (),
         };
+
+        // stop if the tower stepper stalls
+        if let Some(reason) = self.buffer_tower_controller.check_stall(now) {
+            tracing::error!(
+                "[{}::act] Buffer stepper stalled ({:?}), switching to standby",
+                module_path!(),
+                reason
+            );
+            self.set_mode_state(BufferV1Mode::Standby);
+        }
+
         // if last measurement is older than 1 second, emit a new measurement
         if now.duration_since(self.last_measurement_emit) > Duration::from_secs_f64(1.0 / 30.0) {
             // Emit live values at 30 FPS
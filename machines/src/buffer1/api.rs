@@ -1,5 +1,6 @@
 use super::{BufferV1, BufferV1Mode};
 use crate::{MachineApi, MachineMessage, machine_identification::MachineIdentificationUnique};
+use control_core::helpers::stall_detector::StallReason;
 use control_core::socketio::{
     event::{Event, GenericEvent},
     namespace::{CacheFn, CacheableEvents, Namespace, NamespaceCacheingLogic, cache_one_event},
@@ -23,6 +24,8 @@ impl LiveValuesEvent {
 pub struct StateEvent {
     /// mode state
     pub mode_state: ModeState, // connected machine state
+    /// tower stepper stall alarm
+    pub stall_state: StallState,
 }
 
 impl StateEvent {
@@ -41,6 +44,12 @@ pub struct ModeState {
     pub mode: BufferV1Mode,
 }
 
+/// Latched stall of the tower stepper, cleared when the buffer is started again
+#[derive(Serialize, Debug, Clone)]
+pub struct StallState {
+    pub stalled: Option<StallReason>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub enum Mode {
     Standby,
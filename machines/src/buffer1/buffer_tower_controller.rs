@@ -1,10 +1,26 @@
+use std::time::{Duration, Instant};
+
+use control_core::helpers::stall_detector::{StallDetector, StallReason};
 use ethercat_hal::io::stepper_velocity_el70x1::StepperVelocityEL70x1;
 
+use crate::update_stepper_stall_detector;
+
+/// Raw EL70x1 load angle above which the tower stepper counts as stalling
+const STALL_LOAD_ANGLE_LIMIT: u16 = 900;
+
+/// Full steps the tower stepper may fall behind the commanded position
+const STALL_FOLLOWING_ERROR_LIMIT: f64 = 50.0;
+
+/// How long a stall condition has to last before the buffer stops
+const STALL_DEBOUNCE: Duration = Duration::from_millis(500);
+
 #[derive(Debug)]
 pub struct BufferTowerController {
     enabled: bool,
     /// Stepper driver. Controls buffer stepper motor
     pub stepper_driver: StepperVelocityEL70x1,
+    /// Detects a blocked tower
+    stall_detector: StallDetector,
 }
 
 impl BufferTowerController {
@@ -12,11 +28,18 @@ impl BufferTowerController {
         Self {
             enabled: false,
             stepper_driver: driver,
+            stall_detector: StallDetector::new(
+                Some(STALL_LOAD_ANGLE_LIMIT),
+                Some(STALL_FOLLOWING_ERROR_LIMIT),
+                STALL_DEBOUNCE,
+            ),
         }
     }
     pub fn set_enabled(&mut self, enabled: bool) {
         self.enabled = enabled;
         if enabled {
+            // enabling again acknowledges a stall
+            self.stall_detector.reset();
             self.stepper_driver.set_enabled(true);
             let _ = self.stepper_driver.set_speed(10.0);
         } else {
@@ -24,4 +47,17 @@ impl BufferTowerController {
             let _ = self.stepper_driver.set_speed(0.0);
         }
     }
+
+    /// Update the stall detector, returns the reason once the tower stalled
+    pub fn check_stall(&mut self, now: Instant) -> Option<StallReason> {
+        if !self.enabled {
+            return None;
+        }
+        update_stepper_stall_detector(&mut self.stall_detector, &self.stepper_driver, now)
+    }
+
+    /// The latched stall, if any
+    pub const fn stalled(&self) -> Option<StallReason> {
+        self.stall_detector.stalled()
+    }
 }
@@ -4,12 +4,17 @@ pub mod buffer_tower_controller;
 pub mod new;
 
 use super::machine_identification::{MachineIdentification, MachineIdentificationUnique};
+use crate::hardware_descriptor::{EthercatRole, MachineHardwareDescriptor};
 use crate::{AsyncThreadMessage, Machine, MachineMessage};
 #[cfg(not(feature = "mock-machine"))]
 use crate::{MACHINE_BUFFER_V1, VENDOR_QITECH};
-use api::{Buffer1Namespace, BufferV1Events, LiveValuesEvent, ModeState, StateEvent};
+use api::{Buffer1Namespace, BufferV1Events, LiveValuesEvent, ModeState, StallState, StateEvent};
 use buffer_tower_controller::BufferTowerController;
 use control_core::socketio::namespace::NamespaceCacheingLogic;
+use ethercat_hal::devices::{
+    ek1100::EK1100_IDENTITY_A, el7031_0030::EL7031_0030_IDENTITY_A,
+    el7041_0052::EL7041_0052_IDENTITY_A,
+};
 use serde::{Deserialize, Serialize};
 use smol::channel::{Receiver, Sender};
 use std::time::Instant;
@@ -48,6 +53,16 @@ impl BufferV1 {
         vendor: VENDOR_QITECH,
         machine: MACHINE_BUFFER_V1,
     };
+
+    /// Hardware the machine expects, checked before the machine is created
+    pub const HARDWARE_DESCRIPTOR: MachineHardwareDescriptor = MachineHardwareDescriptor {
+        ethercat_roles: &[
+            EthercatRole::required(0, "Bus coupler", &[EK1100_IDENTITY_A]),
+            EthercatRole::required(1, "Buffer stepper", &[EL7041_0052_IDENTITY_A]),
+            EthercatRole::required(2, "Puller stepper", &[EL7031_0030_IDENTITY_A]),
+        ],
+    };
+
     pub fn emit_live_values(&mut self) {
         let live_values = LiveValuesEvent {};
 
@@ -59,6 +74,9 @@ impl BufferV1 {
             mode_state: ModeState {
                 mode: self.mode.clone(),
             },
+            stall_state: StallState {
+                stalled: self.buffer_tower_controller.stalled(),
+            },
             // connected_machine_state: self.connected_winder.to_state(),
         };
 
@@ -6,20 +6,24 @@ use ethercat_hal::{
     devices::el7031_0030::coe::EL7031_0030Configuration,
     devices::el7031_0030::pdo::EL7031_0030PredefinedPdoAssignment,
     devices::el7041_0052::coe::EL7041_0052Configuration,
+    devices::el7041_0052::pdo::EL7041_0052PredefinedPdoAssignment,
     devices::{
         EthercatDeviceUsed,
-        ek1100::{EK1100, EK1100_IDENTITY_A},
-        el7031_0030::{EL7031_0030, EL7031_0030_IDENTITY_A},
-        el7041_0052::{EL7041_0052, EL7041_0052_IDENTITY_A, EL7041_0052Port},
+        ek1100::EK1100,
+        el7031_0030::EL7031_0030,
+        el7041_0052::{EL7041_0052, EL7041_0052Port},
     },
     io::stepper_velocity_el70x1::StepperVelocityEL70x1,
     shared_config,
-    shared_config::el70x1::{EL70x1OperationMode, StmMotorConfiguration},
+    shared_config::el70x1::{
+        EL70x1InfoData, EL70x1MotorConfiguration, EL70x1OperationMode, StmMotorConfiguration,
+    },
 };
 
 use crate::{
     MachineNewHardware, MachineNewHardwareEthercat, MachineNewParams, MachineNewTrait,
-    buffer1::BufferV1Mode, get_ethercat_device, validate_same_machine_identification_unique,
+    buffer1::BufferV1Mode, get_ethercat_device, motor_configuration::load_motor_configuration,
+    validate_same_machine_identification_unique,
 };
 use crate::{buffer1::buffer_tower_controller::BufferTowerController, validate_no_role_dublicates};
 
@@ -46,29 +50,49 @@ impl MachineNewTrait for BufferV1 {
 
         smol::block_on(async {
             // Role 0 - Buscoupler EK1100
-            let _ek1100 =
-                get_ethercat_device::<EK1100>(hardware, params, 0, [EK1100_IDENTITY_A].to_vec())
-                    .await?
-                    .0;
+            let _ek1100 = get_ethercat_device::<EK1100>(
+                hardware,
+                params,
+                0,
+                Self::HARDWARE_DESCRIPTOR.identities(0),
+            )
+            .await?
+            .0;
 
             // Role 1 - Stepper Buffer EL7041-0052
             let (el7041, subdevice) = get_ethercat_device::<EL7041_0052>(
                 hardware,
                 params,
                 1,
-                [EL7041_0052_IDENTITY_A].to_vec(),
+                Self::HARDWARE_DESCRIPTOR.identities(1),
             )
             .await?;
 
+            // motor configuration can be changed at runtime
+            let motor_configuration = load_motor_configuration(
+                &params.get_machine_identification_unique(),
+                1,
+                EL70x1MotorConfiguration {
+                    stm_motor: StmMotorConfiguration {
+                        max_current: 6000,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            );
+
             let el7041_config = EL7041_0052Configuration {
                 stm_features: shared_config::el70x1::StmFeatures {
                     operation_mode: EL70x1OperationMode::DirectVelocity,
+                    select_info_data_1: EL70x1InfoData::LoadAngle,
                     ..Default::default()
                 },
-                stm_motor: StmMotorConfiguration {
-                    max_current: 6000,
-                    ..Default::default()
-                },
+                encoder: motor_configuration.encoder,
+                stm_motor: motor_configuration.stm_motor,
+                stm_controller_1: motor_configuration.stm_controller_1,
+                stm_controller_2: motor_configuration.stm_controller_2,
+                pdo_assignment:
+                    EL7041_0052PredefinedPdoAssignment::VelocityControlCompactWithInfoData,
                 ..Default::default()
             };
 
@@ -87,10 +111,22 @@ impl MachineNewTrait for BufferV1 {
                 hardware,
                 params,
                 2,
-                [EL7031_0030_IDENTITY_A].to_vec(),
+                Self::HARDWARE_DESCRIPTOR.identities(2),
             )
             .await?;
 
+            let motor_configuration = load_motor_configuration(
+                &params.get_machine_identification_unique(),
+                2,
+                EL70x1MotorConfiguration {
+                    stm_motor: StmMotorConfiguration {
+                        max_current: 1500,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            );
+
             let el7031_config = EL7031_0030Configuration {
                 stm_features: ethercat_hal::devices::el7031_0030::coe::StmFeatures {
                     operation_mode: EL70x1OperationMode::DirectVelocity,
@@ -99,10 +135,10 @@ impl MachineNewTrait for BufferV1 {
                     speed_range: shared_config::el70x1::EL70x1SpeedRange::Steps1000,
                     ..Default::default()
                 },
-                stm_motor: StmMotorConfiguration {
-                    max_current: 1500,
-                    ..Default::default()
-                },
+                encoder: motor_configuration.encoder,
+                stm_motor: motor_configuration.stm_motor,
+                stm_controller_1: motor_configuration.stm_controller_1,
+                stm_controller_2: motor_configuration.stm_controller_2,
                 pdo_assignment: EL7031_0030PredefinedPdoAssignment::VelocityControlCompact,
                 ..Default::default()
             };
@@ -8,6 +8,7 @@ use crate::{MachineMessage, extruder1::HeatingType};
 
 #[cfg(not(feature = "mock-machine"))]
 use crate::MachineApi;
+use control_core::helpers::analog_signal::{CalibrationPoint, TwoPointCalibration};
 use control_core::socketio::{
     event::{Event, GenericEvent},
     namespace::{
@@ -57,6 +58,8 @@ pub struct LiveValuesEvent {
     pub motor_status: MotorStatusValues,
     /// pressure in bar
     pub pressure: f64,
+    /// pressure before calibration in bar
+    pub uncalibrated_pressure: f64,
     /// nozzle temperature in celsius
     pub nozzle_temperature: f64,
     /// front temperature in celsius
@@ -127,6 +130,7 @@ pub struct RegulationState {
 pub struct PressureState {
     pub target_bar: f64,
     pub wiring_error: bool,
+    pub calibration: TwoPointCalibration,
 }
 
 #[derive(Serialize, Debug, Clone, PartialEq)]
@@ -235,6 +239,10 @@ pub enum Mutation {
     SetPressurePidSettings(PidSettings),
     SetTemperaturePidSettings(TemperaturePid),
 
+    // Pressure Sensor Calibration
+    SetPressureSensorCalibration([CalibrationPoint; 2]),
+    ResetPressureSensorCalibration(bool),
+
     // Reset
     ResetInverter(bool),
 }
@@ -317,6 +325,11 @@ impl MachineApi for ExtruderV2 {
             Mutation::SetTemperaturePidSettings(settings) => {
                 self.configure_temperature_pid(settings);
             }
+
+            Mutation::SetPressureSensorCalibration(points) => {
+                self.set_pressure_sensor_calibration(points)?;
+            }
+            Mutation::ResetPressureSensorCalibration(_) => self.reset_pressure_sensor_calibration(),
         }
         Ok(())
     }
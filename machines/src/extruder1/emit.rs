@@ -9,6 +9,8 @@ use crate::extruder1::{
     },
 };
 #[cfg(not(feature = "mock-machine"))]
+use control_core::helpers::analog_signal::{CalibrationPoint, TwoPointCalibration};
+#[cfg(not(feature = "mock-machine"))]
 use control_core::helpers::hasher_serializer::hash_with_serde_model;
 #[cfg(not(feature = "mock-machine"))]
 use control_core::socketio::event::BuildEvent;
@@ -45,6 +47,9 @@ impl ExtruderV2 {
                     .get_target_pressure()
                     .get::<bar>(),
                 wiring_error: self.screw_speed_controller.get_wiring_error(),
+                calibration: self
+                    .screw_speed_controller
+                    .get_pressure_sensor_calibration(),
             },
             screw_state: ScrewState {
                 target_rpm: self
@@ -177,6 +182,10 @@ impl ExtruderV2 {
         let live_values = LiveValuesEvent {
             motor_status: self.screw_speed_controller.get_motor_status().into(),
             pressure: self.screw_speed_controller.get_pressure().get::<bar>(),
+            uncalibrated_pressure: self
+                .screw_speed_controller
+                .get_uncalibrated_pressure()
+                .get::<bar>(),
             nozzle_temperature: self
                 .temperature_controller_nozzle
                 .heating
@@ -231,6 +240,23 @@ impl ExtruderV2 {
         self.emit_state();
     }
 
+    pub fn set_pressure_sensor_calibration(
+        &mut self,
+        points: [CalibrationPoint; 2],
+    ) -> Result<(), anyhow::Error> {
+        let calibration = TwoPointCalibration::from_points(points[0], points[1])?;
+        self.screw_speed_controller
+            .set_pressure_sensor_calibration(calibration);
+        self.emit_state();
+        Ok(())
+    }
+
+    pub fn reset_pressure_sensor_calibration(&mut self) {
+        self.screw_speed_controller
+            .set_pressure_sensor_calibration(TwoPointCalibration::default());
+        self.emit_state();
+    }
+
     pub fn enable_heating(&mut self) {
         self.temperature_controller_back.allow_heating();
         self.temperature_controller_front.allow_heating();
@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use crate::{
+    MachineNewHardwareEthercat, MachineNewParams, get_device_identification_by_role,
+    get_ethercat_device, hardware_descriptor::MachineHardwareDescriptor,
+};
+use anyhow::Error;
+use control_core::helpers::heater_output::HeaterOutput;
+use ethercat_hal::{
+    coe::ConfigurableDevice,
+    devices::{
+        el2004::{EL2004, EL2004Port},
+        el2502::{EL2502, EL2502Configuration, EL2502Port},
+    },
+    io::{digital_output::DigitalOutput, pwm_output::PwmOutput},
+};
+
+/// PWM period of the heaters, software and hardware PWM
+const HEATER_PWM_PERIOD: Duration = Duration::from_millis(500);
+
+/// Heater outputs of the front, middle, back and nozzle zone
+///
+/// The heaters are switched by the EL2004 with the role `relay_role`. If an EL2502 is present for
+/// one of the `pwm_roles`, its two zones (front and middle, back and nozzle) are driven with
+/// hardware PWM instead.
+pub async fn get_heater_outputs<
+    'maindevice,
+    'subdevices,
+    'device_identifications_identified,
+    'ethercat_devices,
+    'machine_new_hardware_etehrcat,
+    'machine_new_hardware_serial,
+    'machine_new_hardware,
+>(
+    hardware: &&MachineNewHardwareEthercat<'maindevice, 'subdevices, 'ethercat_devices>,
+    params: &MachineNewParams<
+        'maindevice,
+        'subdevices,
+        'device_identifications_identified,
+        'ethercat_devices,
+        'machine_new_hardware_etehrcat,
+        'machine_new_hardware_serial,
+        'machine_new_hardware,
+    >,
+    hardware_descriptor: &MachineHardwareDescriptor,
+    relay_role: u16,
+    pwm_roles: [u16; 2],
+) -> Result<[HeaterOutput; 4], Error> {
+    let el2004 = get_ethercat_device::<EL2004>(
+        hardware,
+        params,
+        relay_role,
+        hardware_descriptor.identities(relay_role),
+    )
+    .await?
+    .0;
+
+    let mut pwm_outputs: [Option<PwmOutput>; 4] = Default::default();
+    for (index, role) in pwm_roles.into_iter().enumerate() {
+        if get_device_identification_by_role(params.device_group, role).is_err() {
+            continue;
+        }
+        let (el2502, subdevice) = get_ethercat_device::<EL2502>(
+            hardware,
+            params,
+            role,
+            hardware_descriptor.identities(role),
+        )
+        .await?;
+        el2502
+            .write()
+            .await
+            .write_config(subdevice, &EL2502Configuration::default())
+            .await?;
+        pwm_outputs[2 * index] = Some(PwmOutput::new(el2502.clone(), EL2502Port::PWM1));
+        pwm_outputs[2 * index + 1] = Some(PwmOutput::new(el2502, EL2502Port::PWM2));
+    }
+
+    let relay_ports = [
+        EL2004Port::DO1,
+        EL2004Port::DO2,
+        EL2004Port::DO3,
+        EL2004Port::DO4,
+    ];
+    Ok(std::array::from_fn(|index| {
+        match pwm_outputs[index].take() {
+            Some(pwm_output) => HeaterOutput::hardware(pwm_output, HEATER_PWM_PERIOD),
+            None => HeaterOutput::software(
+                DigitalOutput::new(el2004.clone(), relay_ports[index].clone()),
+                HEATER_PWM_PERIOD,
+            ),
+        }
+    }))
+}
@@ -41,6 +41,11 @@ impl MachineApi for ExtruderV2 {
             Mutation::SetTemperaturePidSettings(settings) => {
                 self.configure_temperature_pid(settings);
             }
+
+            Mutation::SetPressureSensorCalibration(points) => {
+                self.set_pressure_sensor_calibration(points)?;
+            }
+            Mutation::ResetPressureSensorCalibration(_) => self.reset_pressure_sensor_calibration(),
         }
         Ok(())
     }
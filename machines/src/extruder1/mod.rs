@@ -1,4 +1,15 @@
 #[cfg(not(feature = "mock-machine"))]
+use crate::hardware_descriptor::{EthercatRole, MachineHardwareDescriptor};
+#[cfg(not(feature = "mock-machine"))]
+use ethercat_hal::devices::{
+    ek1100::EK1100_IDENTITY_A,
+    el1002::EL1002_IDENTITY_A,
+    el2004::EL2004_IDENTITY_A,
+    el2502::EL2502_IDENTITY_A,
+    el3021::EL3021_IDENTITY_A,
+    el6021::{EL6021_IDENTITY_A, EL6021_IDENTITY_B, EL6021_IDENTITY_C, EL6021_IDENTITY_D},
+};
+#[cfg(not(feature = "mock-machine"))]
 use std::time::Instant;
 
 #[cfg(not(feature = "mock-machine"))]
@@ -25,6 +36,7 @@ use crate::{
     extruder1::{
         api::ExtruderV2Namespace, screw_speed_controller::ScrewSpeedController,
         temperature_controller::TemperatureController,
+        temperature_inputs::TEMPERATURE_INPUT_IDENTITIES,
     },
     machine_identification::{MachineIdentification, MachineIdentificationUnique},
 };
@@ -32,11 +44,15 @@ use crate::{
 pub mod act;
 pub mod api;
 pub mod emit;
+#[cfg(not(feature = "mock-machine"))]
+pub mod heater_outputs;
 pub mod mitsubishi_cs80;
 pub mod mock;
 pub mod new;
 pub mod screw_speed_controller;
 pub mod temperature_controller;
+#[cfg(not(feature = "mock-machine"))]
+pub mod temperature_inputs;
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 pub enum ExtruderV2Mode {
@@ -124,6 +140,29 @@ impl ExtruderV2 {
         vendor: VENDOR_QITECH,
         machine: MACHINE_EXTRUDER_V1,
     };
+
+    /// Hardware the machine expects, checked before the machine is created
+    pub const HARDWARE_DESCRIPTOR: MachineHardwareDescriptor = MachineHardwareDescriptor {
+        ethercat_roles: &[
+            EthercatRole::optional(0, "Bus coupler", &[EK1100_IDENTITY_A]),
+            EthercatRole::required(1, "Digital inputs", &[EL1002_IDENTITY_A]),
+            EthercatRole::required(
+                2,
+                "Serial interface",
+                &[
+                    EL6021_IDENTITY_A,
+                    EL6021_IDENTITY_B,
+                    EL6021_IDENTITY_C,
+                    EL6021_IDENTITY_D,
+                ],
+            ),
+            EthercatRole::required(3, "Heater outputs", &[EL2004_IDENTITY_A]),
+            EthercatRole::required(4, "Pressure sensor", &[EL3021_IDENTITY_A]),
+            EthercatRole::required(5, "Temperature sensors", TEMPERATURE_INPUT_IDENTITIES),
+            EthercatRole::optional(6, "PWM heaters front, middle", &[EL2502_IDENTITY_A]),
+            EthercatRole::optional(7, "PWM heaters back, nozzle", &[EL2502_IDENTITY_A]),
+        ],
+    };
 }
 
 #[cfg(not(feature = "mock-machine"))]
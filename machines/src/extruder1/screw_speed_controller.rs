@@ -2,12 +2,13 @@ use std::time::Instant;
 
 use control_core::{
     controllers::clamping_timeagnostic_pid::ClampingTimeagnosticPidController,
-    helpers::interpolation::normalize,
+    helpers::analog_signal::{
+        AnalogFilter, AnalogSensorSignal, AnalogSignalChain, SensorScaling, TwoPointCalibration,
+    },
     transmission::{Transmission, fixed::FixedTransmission},
 };
 use ethercat_hal::io::analog_input::AnalogInput;
 use units::angular_velocity::revolution_per_minute;
-use units::electric_current::milliampere;
 use units::f64::*;
 use units::frequency::{cycle_per_minute, hertz};
 use units::pressure::bar;
@@ -22,7 +23,7 @@ pub struct ScrewSpeedController {
     pub target_pressure: Pressure,
     pub target_rpm: AngularVelocity,
     pub inverter: MitsubishiCS80,
-    pressure_sensor: AnalogInput,
+    pressure_sensor: AnalogSignalChain,
     last_update: Instant,
     uses_rpm: bool,
     forward_rotation: bool,
@@ -51,7 +52,12 @@ impl ScrewSpeedController {
             last_update: now,
             target_pressure,
             target_rpm,
-            pressure_sensor,
+            // Our pressure sensor has a range of Up to 350 Bar
+            pressure_sensor: AnalogSignalChain::new(
+                pressure_sensor,
+                SensorScaling::new(AnalogSensorSignal::Current4To20mA, 0.0, 350.0),
+                AnalogFilter::None,
+            ),
             uses_rpm: true,
             forward_rotation: true,
             transmission: transmission,
@@ -193,28 +199,34 @@ impl ScrewSpeedController {
         }
     }
 
+    pub const fn get_pressure_sensor_calibration(&self) -> TwoPointCalibration {
+        self.pressure_sensor.calibration()
+    }
+
+    pub fn set_pressure_sensor_calibration(&mut self, calibration: TwoPointCalibration) {
+        self.pressure_sensor.set_calibration(calibration);
+    }
+
+    /// Pressure before calibration, the `measured` value of a calibration point
+    pub fn get_uncalibrated_pressure(&self) -> Pressure {
+        Pressure::new::<bar>(self.pressure_sensor.uncalibrated_value().unwrap_or(0.0))
+    }
+
     pub const fn reset_pid(&mut self) {
         self.pid.reset()
     }
 
-    pub fn get_pressure(&mut self) -> Pressure {
-        let current_result = self.get_sensor_current();
-        let current = match current_result {
-            Ok(current) => current.get::<milliampere>(),
-            Err(_) => {
-                tracing::error!("cant get pressure sensor reading");
-                return Pressure::new::<bar>(0.0);
-            }
-        };
-        let normalized = normalize(current, 4.0, 20.0);
-        // Our pressure sensor has a range of Up to 350 Bar
-        let actual_pressure = (normalized) * 350.0;
-        Pressure::new::<bar>(actual_pressure)
+    /// Calibrated pressure of the last [`Self::update`]
+    pub fn get_pressure(&self) -> Pressure {
+        Pressure::new::<bar>(self.pressure_sensor.value().unwrap_or(0.0))
     }
 
     pub fn update(&mut self, now: Instant, is_extruding: bool) {
         // TODO: move this logic elsewhere or make non async
         smol::block_on(self.inverter.act(now));
+        if let Err(e) = self.pressure_sensor.update(now) {
+            tracing::error!("cant get pressure sensor reading: {}", e);
+        }
         let measured_pressure = self.get_pressure();
         if !self.uses_rpm && !is_extruding && self.motor_on {
             let frequency = Frequency::new::<hertz>(0.0);
@@ -1,7 +1,8 @@
 use super::Heating;
 use control_core::controllers::pid::PidController;
-use ethercat_hal::io::{digital_output::DigitalOutput, temperature_input::TemperatureInput};
-use std::time::{Duration, Instant};
+use control_core::helpers::heater_output::HeaterOutput;
+use ethercat_hal::io::temperature_input::TemperatureInput;
+use std::time::Instant;
 use units::f64::*;
 use units::thermodynamic_temperature::degree_celsius;
 
@@ -10,12 +11,10 @@ use units::thermodynamic_temperature::degree_celsius;
 pub struct TemperatureController {
     pub pid: PidController,
     temperature_sensor: TemperatureInput,
-    relais: DigitalOutput,
+    heater: HeaterOutput,
     pub heating: Heating,
     pub target_temp: ThermodynamicTemperature,
-    window_start: Instant,
     heating_allowed: bool,
-    pwm_period: Duration,
     max_temperature: ThermodynamicTemperature,
     temperature_pid_output: f64,
     heating_element_wattage: f64,
@@ -24,7 +23,7 @@ pub struct TemperatureController {
 
 impl TemperatureController {
     pub fn disable(&mut self) {
-        self.relais.set(false);
+        self.heater.off();
         self.heating.heating = false;
         self.disallow_heating();
     }
@@ -36,21 +35,18 @@ impl TemperatureController {
         target_temp: ThermodynamicTemperature,
         max_temperature: ThermodynamicTemperature,
         temperature_sensor: TemperatureInput,
-        relais: DigitalOutput,
+        heater: HeaterOutput,
         heating: Heating,
-        pwm_duration: Duration,
         heating_element_wattage: f64,
         max_clamp: f64,
     ) -> Self {
         Self {
             pid: PidController::new(kp, ki, kd),
             target_temp,
-            window_start: Instant::now(),
             temperature_sensor,
-            relais,
+            heater,
             heating,
             heating_allowed: false,
-            pwm_period: pwm_duration,
             max_temperature,
             temperature_pid_output: 0.0,
             heating_element_wattage,
@@ -86,8 +82,8 @@ impl TemperatureController {
         self.heating.temperature = temperature_celsius;
 
         if self.heating.temperature > self.max_temperature {
-            // disable the relais and return
-            self.relais.set(false);
+            // disable the heater and return
+            self.heater.off();
             self.heating.heating = false;
             return;
         }
@@ -102,19 +98,7 @@ impl TemperatureController {
 
             self.temperature_pid_output = duty;
 
-            let elapsed = now.duration_since(self.window_start);
-
-            // Restart window if needed
-            if elapsed >= self.pwm_period {
-                self.window_start = now;
-            }
-            // Compare duty cycle to elapsed time
-            let on_time = self.pwm_period.mul_f64(duty);
-
-            // Relay is ON if within duty cycle window
-            let on = elapsed < on_time;
-            self.relais.set(on);
-            self.heating.heating = on;
+            self.heating.heating = self.heater.set_duty_cycle(now, duty);
         }
     }
 }
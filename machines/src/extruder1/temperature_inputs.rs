@@ -0,0 +1,81 @@
+use crate::{
+    MachineNewHardwareEthercat, MachineNewParams, get_ethercat_device,
+    get_ethercat_device_identity, hardware_descriptor::MachineHardwareDescriptor,
+};
+use anyhow::Error;
+use ethercat_hal::{
+    coe::ConfigurableDevice,
+    devices::{
+        SubDeviceIdentityTuple,
+        el3204::{EL3204, EL3204_IDENTITY_A, EL3204_IDENTITY_B, EL3204Port},
+        el3314::{EL3314, EL3314_IDENTITY_A, EL3314Configuration, EL3314Port},
+    },
+    io::temperature_input::TemperatureInput,
+};
+
+/// Terminals the temperature zones can be wired to, RTD (EL3204) or thermocouple (EL3314)
+pub const TEMPERATURE_INPUT_IDENTITIES: &[SubDeviceIdentityTuple] =
+    &[EL3204_IDENTITY_A, EL3204_IDENTITY_B, EL3314_IDENTITY_A];
+
+/// Temperature inputs T1 to T4 of the terminal with the role
+pub async fn get_temperature_inputs<
+    'maindevice,
+    'subdevices,
+    'device_identifications_identified,
+    'ethercat_devices,
+    'machine_new_hardware_etehrcat,
+    'machine_new_hardware_serial,
+    'machine_new_hardware,
+>(
+    hardware: &&MachineNewHardwareEthercat<'maindevice, 'subdevices, 'ethercat_devices>,
+    params: &MachineNewParams<
+        'maindevice,
+        'subdevices,
+        'device_identifications_identified,
+        'ethercat_devices,
+        'machine_new_hardware_etehrcat,
+        'machine_new_hardware_serial,
+        'machine_new_hardware,
+    >,
+    hardware_descriptor: &MachineHardwareDescriptor,
+    role: u16,
+) -> Result<[TemperatureInput; 4], Error> {
+    match get_ethercat_device_identity(hardware, params, role).await? {
+        EL3314_IDENTITY_A => {
+            let (el3314, subdevice) = get_ethercat_device::<EL3314>(
+                hardware,
+                params,
+                role,
+                hardware_descriptor.identities(role),
+            )
+            .await?;
+            el3314
+                .write()
+                .await
+                .write_config(subdevice, &EL3314Configuration::default())
+                .await?;
+            Ok([
+                TemperatureInput::new(el3314.clone(), EL3314Port::T1),
+                TemperatureInput::new(el3314.clone(), EL3314Port::T2),
+                TemperatureInput::new(el3314.clone(), EL3314Port::T3),
+                TemperatureInput::new(el3314, EL3314Port::T4),
+            ])
+        }
+        _ => {
+            let el3204 = get_ethercat_device::<EL3204>(
+                hardware,
+                params,
+                role,
+                hardware_descriptor.identities(role),
+            )
+            .await?
+            .0;
+            Ok([
+                TemperatureInput::new(el3204.clone(), EL3204Port::T1),
+                TemperatureInput::new(el3204.clone(), EL3204Port::T2),
+                TemperatureInput::new(el3204.clone(), EL3204Port::T3),
+                TemperatureInput::new(el3204, EL3204Port::T4),
+            ])
+        }
+    }
+}
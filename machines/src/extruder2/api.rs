@@ -9,6 +9,7 @@ use crate::extruder1::{
 };
 #[cfg(not(feature = "mock-machine"))]
 use crate::{MachineApi, MachineMessage};
+use control_core::helpers::analog_signal::CalibrationPoint;
 use control_core::socketio::{
     event::{Event, GenericEvent},
     namespace::{
@@ -61,6 +62,8 @@ pub struct LiveValuesEvent {
     pub motor_status: MotorStatusValues,
     /// pressure in bar
     pub pressure: f64,
+    /// pressure before calibration in bar
+    pub uncalibrated_pressure: f64,
     /// nozzle temperature in celsius
     pub nozzle_temperature: f64,
     /// front temperature in celsius
@@ -147,6 +150,10 @@ pub enum Mutation {
     SetPressurePidSettings(PidSettings),
     SetTemperaturePidSettings(TemperaturePid),
 
+    // Pressure Sensor Calibration
+    SetPressureSensorCalibration([CalibrationPoint; 2]),
+    ResetPressureSensorCalibration(bool),
+
     // Reset
     ResetInverter(bool),
 }
@@ -230,6 +237,11 @@ impl MachineApi for ExtruderV3 {
             Mutation::SetTemperaturePidSettings(settings) => {
                 self.configure_temperature_pid(settings);
             }
+
+            Mutation::SetPressureSensorCalibration(points) => {
+                self.set_pressure_sensor_calibration(points)?;
+            }
+            Mutation::ResetPressureSensorCalibration(_) => self.reset_pressure_sensor_calibration(),
         }
         Ok(())
     }
@@ -5,7 +5,10 @@ use crate::extruder1::{
 use crate::extruder2::mock::ExtruderV2;
 
 use control_core::{
-    helpers::hasher_serializer::hash_with_serde_model,
+    helpers::{
+        analog_signal::{CalibrationPoint, TwoPointCalibration},
+        hasher_serializer::hash_with_serde_model,
+    },
     socketio::{event::BuildEvent, namespace::NamespaceCacheingLogic},
 };
 
@@ -55,6 +58,7 @@ impl ExtruderV2 {
         let live_values = LiveValuesEvent {
             motor_status: self.motor_status.clone(),
             pressure: self.pressure,
+            uncalibrated_pressure: self.pressure,
             nozzle_temperature: self.nozzle_temperature,
             front_temperature: self.front_temperature,
             back_temperature: self.back_temperature,
@@ -81,6 +85,20 @@ impl ExtruderV2 {
         self.emit_state();
     }
 
+    pub fn set_pressure_sensor_calibration(
+        &mut self,
+        points: [CalibrationPoint; 2],
+    ) -> Result<(), anyhow::Error> {
+        self.pressure_state.calibration = TwoPointCalibration::from_points(points[0], points[1])?;
+        self.emit_state();
+        Ok(())
+    }
+
+    pub fn reset_pressure_sensor_calibration(&mut self) {
+        self.pressure_state.calibration = TwoPointCalibration::default();
+        self.emit_state();
+    }
+
     pub fn enable_heating(&mut self) {
         self.back_heating_allowed = true;
         self.front_heating_allowed = true;
@@ -1,3 +1,5 @@
+use control_core::helpers::analog_signal::TwoPointCalibration;
+
 use crate::{
     MachineNewHardware, MachineNewParams, MachineNewTrait,
     extruder1::{
@@ -58,6 +60,7 @@ impl MachineNewTrait for ExtruderV2 {
             pressure_state: PressureState {
                 target_bar: 0.0,
                 wiring_error: false,
+                calibration: TwoPointCalibration::default(),
             },
             screw_state: ScrewState { target_rpm: 0.0 },
             heating_states: HeatingStates {
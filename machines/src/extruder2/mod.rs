@@ -1,4 +1,17 @@
 #[cfg(not(feature = "mock-machine"))]
+use crate::hardware_descriptor::{EthercatRole, MachineHardwareDescriptor};
+#[cfg(not(feature = "mock-machine"))]
+use ethercat_hal::devices::{
+    ek1100::EK1100_IDENTITY_A,
+    el2004::EL2004_IDENTITY_A,
+    el2502::EL2502_IDENTITY_A,
+    el3021::EL3021_IDENTITY_A,
+    el3443::EL3443_IDENTITY_A,
+    el6021::{EL6021_IDENTITY_A, EL6021_IDENTITY_B, EL6021_IDENTITY_C, EL6021_IDENTITY_D},
+};
+#[cfg(not(feature = "mock-machine"))]
+use ethercat_hal::io::power_measurement::PowerMeasurement;
+#[cfg(not(feature = "mock-machine"))]
 use std::time::Instant;
 
 #[cfg(not(feature = "mock-machine"))]
@@ -26,7 +39,9 @@ use crate::{AsyncThreadMessage, Machine};
 use crate::{
     MachineMessage, VENDOR_QITECH,
     extruder1::{
-        screw_speed_controller::ScrewSpeedController, temperature_controller::TemperatureController,
+        screw_speed_controller::ScrewSpeedController,
+        temperature_controller::TemperatureController,
+        temperature_inputs::TEMPERATURE_INPUT_IDENTITIES,
     },
     machine_identification::{MachineIdentification, MachineIdentificationUnique},
 };
@@ -76,6 +91,11 @@ pub struct ExtruderV3 {
     total_energy_kwh: f64,
     last_energy_calculation_time: Option<Instant>,
 
+    /// Phases L1 to L3 of the optional power measurement, replaces the estimated power and energy
+    power_measurement: Option<[PowerMeasurement; 3]>,
+    /// Measured energy when the machine was created, the device counts its whole lifetime
+    measured_energy_offset_kwh: Option<f64>,
+
     /// will be initalized as false and set to true by `emit_state`
     /// This way we can signal to the client that the first state emission is a default state
     emitted_default_state: bool,
@@ -105,6 +125,29 @@ impl ExtruderV3 {
         vendor: VENDOR_QITECH,
         machine: MACHINE_EXTRUDER_V2,
     };
+
+    /// Hardware the machine expects, checked before the machine is created
+    pub const HARDWARE_DESCRIPTOR: MachineHardwareDescriptor = MachineHardwareDescriptor {
+        ethercat_roles: &[
+            EthercatRole::optional(0, "Bus coupler", &[EK1100_IDENTITY_A]),
+            EthercatRole::required(
+                1,
+                "Serial interface",
+                &[
+                    EL6021_IDENTITY_A,
+                    EL6021_IDENTITY_B,
+                    EL6021_IDENTITY_C,
+                    EL6021_IDENTITY_D,
+                ],
+            ),
+            EthercatRole::required(2, "Heater outputs", &[EL2004_IDENTITY_A]),
+            EthercatRole::required(3, "Pressure sensor", &[EL3021_IDENTITY_A]),
+            EthercatRole::required(4, "Temperature sensors", TEMPERATURE_INPUT_IDENTITIES),
+            EthercatRole::optional(5, "Power measurement", &[EL3443_IDENTITY_A]),
+            EthercatRole::optional(6, "PWM heaters front, middle", &[EL2502_IDENTITY_A]),
+            EthercatRole::optional(7, "PWM heaters back, nozzle", &[EL2502_IDENTITY_A]),
+        ],
+    };
 }
 
 #[cfg(not(feature = "mock-machine"))]
@@ -143,6 +186,37 @@ impl ExtruderV3 {
         self.last_energy_calculation_time = Some(now);
     }
 
+    /// Combined power in watts and total energy from the power measurement if there is one
+    ///
+    /// Falls back to the estimate of [`Self::calculate_combined_power`].
+    /// If the measurement fails the estimated power is returned and the energy is kept.
+    fn update_power_and_energy(&mut self, now: Instant) -> f64 {
+        let Some(phases) = &self.power_measurement else {
+            let combined_power = self.calculate_combined_power();
+            self.update_total_energy(combined_power, now);
+            return combined_power;
+        };
+
+        let measured = phases
+            .iter()
+            .try_fold((0.0, 0.0), |(power, energy_kwh), phase| {
+                phase.get_input().map(|input| {
+                    (
+                        power + input.active_power,
+                        energy_kwh + input.active_energy / 1000.0,
+                    )
+                })
+            });
+        let Ok((combined_power, energy_kwh)) = measured else {
+            return self.calculate_combined_power();
+        };
+
+        let offset_kwh = *self.measured_energy_offset_kwh.get_or_insert(energy_kwh);
+        self.total_energy_kwh = energy_kwh - offset_kwh;
+        self.last_energy_calculation_time = Some(now);
+        combined_power
+    }
+
     // Funktionen ohne emit_state bleiben hier
 
     // Set all relais to ZERO
@@ -8,17 +8,10 @@ use anyhow::Error;
 use ethercat_hal::coe::ConfigurableDevice;
 #[cfg(not(feature = "mock-machine"))]
 use ethercat_hal::devices::{
-    EthercatDeviceUsed,
-    ek1100::EK1100,
-    ek1100::EK1100_IDENTITY_A,
-    el6021::EL6021,
-    el6021::EL6021Configuration,
+    EthercatDeviceUsed, ek1100::EK1100, el6021::EL6021, el6021::EL6021Configuration,
     el6021::EL6021Port,
-    el6021::{EL6021_IDENTITY_A, EL6021_IDENTITY_B, EL6021_IDENTITY_C, EL6021_IDENTITY_D},
 };
 #[cfg(not(feature = "mock-machine"))]
-use std::time::Duration;
-#[cfg(not(feature = "mock-machine"))]
 use std::time::Instant;
 #[cfg(not(feature = "mock-machine"))]
 use units::angular_velocity::AngularVelocity;
@@ -32,20 +25,22 @@ use units::pressure::bar;
 #[cfg(not(feature = "mock-machine"))]
 use ethercat_hal::{
     devices::{
-        el2004::{EL2004, EL2004_IDENTITY_A, EL2004Port},
-        el3021::{EL3021, EL3021_IDENTITY_A, EL3021Port},
-        el3204::{EL3204, EL3204_IDENTITY_A, EL3204_IDENTITY_B, EL3204Port},
+        el3021::{EL3021, EL3021Port},
+        el3443::{EL3443, EL3443Port},
     },
     io::{
-        analog_input::AnalogInput, digital_output::DigitalOutput,
-        serial_interface::SerialInterface, temperature_input::TemperatureInput,
+        analog_input::AnalogInput, power_measurement::PowerMeasurement,
+        serial_interface::SerialInterface,
     },
 };
 #[cfg(not(feature = "mock-machine"))]
 use units::thermodynamic_temperature::{ThermodynamicTemperature, degree_celsius};
 
 #[cfg(not(feature = "mock-machine"))]
-use crate::extruder1::temperature_controller::TemperatureController;
+use crate::extruder1::{
+    heater_outputs::get_heater_outputs, temperature_controller::TemperatureController,
+    temperature_inputs::get_temperature_inputs,
+};
 
 #[cfg(not(feature = "mock-machine"))]
 use super::ExtruderV3;
@@ -56,8 +51,8 @@ impl MachineNewTrait for ExtruderV3 {
         // validate general stuff
 
         use crate::{
-            MachineNewHardware, MachineNewHardwareEthercat, validate_no_role_dublicates,
-            validate_same_machine_identification_unique,
+            MachineNewHardware, MachineNewHardwareEthercat, get_device_identification_by_role,
+            validate_no_role_dublicates, validate_same_machine_identification_unique,
         };
 
         let device_identification = params.device_group.to_vec();
@@ -90,17 +85,20 @@ impl MachineNewTrait for ExtruderV3 {
                 },
                 extruder2::{ExtruderV3Mode, api::ExtruderV3Namespace},
             };
-            let _ek1100 =
-                get_ethercat_device::<EK1100>(hardware, params, 0, [EK1100_IDENTITY_A].to_vec());
+            let _ek1100 = get_ethercat_device::<EK1100>(
+                hardware,
+                params,
+                0,
+                Self::HARDWARE_DESCRIPTOR.identities(0),
+            );
             let el6021 = {
-                let identities = [
-                    EL6021_IDENTITY_A,
-                    EL6021_IDENTITY_B,
-                    EL6021_IDENTITY_C,
-                    EL6021_IDENTITY_D,
-                ]
-                .to_vec();
-                let device = get_ethercat_device::<EL6021>(hardware, params, 1, identities).await?;
+                let device = get_ethercat_device::<EL6021>(
+                    hardware,
+                    params,
+                    1,
+                    Self::HARDWARE_DESCRIPTOR.identities(1),
+                )
+                .await?;
 
                 device
                     .0
@@ -115,35 +113,42 @@ impl MachineNewTrait for ExtruderV3 {
                 device.0
             };
 
-            let el2004 =
-                get_ethercat_device::<EL2004>(hardware, params, 2, [EL2004_IDENTITY_A].to_vec())
-                    .await?
-                    .0;
-
-            let el3021 =
-                get_ethercat_device::<EL3021>(hardware, params, 3, [EL3021_IDENTITY_A].to_vec())
-                    .await?
-                    .0;
-
-            let el3204 = get_ethercat_device::<EL3204>(
+            let el3021 = get_ethercat_device::<EL3021>(
                 hardware,
                 params,
-                4,
-                [EL3204_IDENTITY_A, EL3204_IDENTITY_B].to_vec(),
+                3,
+                Self::HARDWARE_DESCRIPTOR.identities(3),
             )
             .await?
             .0;
 
-            let t1 = TemperatureInput::new(el3204.clone(), EL3204Port::T1);
-            let t2 = TemperatureInput::new(el3204.clone(), EL3204Port::T2);
-            let t3 = TemperatureInput::new(el3204.clone(), EL3204Port::T3);
-            let t4 = TemperatureInput::new(el3204, EL3204Port::T4);
+            let [t1, t2, t3, t4] =
+                get_temperature_inputs(hardware, params, &Self::HARDWARE_DESCRIPTOR, 4).await?;
+
+            // Role 5 - optional power measurement of the machine supply
+            let power_measurement = match get_device_identification_by_role(params.device_group, 5)
+            {
+                Ok(_) => {
+                    let el3443 = get_ethercat_device::<EL3443>(
+                        hardware,
+                        params,
+                        5,
+                        Self::HARDWARE_DESCRIPTOR.identities(5),
+                    )
+                    .await?
+                    .0;
+                    Some([
+                        PowerMeasurement::new(el3443.clone(), EL3443Port::L1),
+                        PowerMeasurement::new(el3443.clone(), EL3443Port::L2),
+                        PowerMeasurement::new(el3443, EL3443Port::L3),
+                    ])
+                }
+                Err(_) => None,
+            };
 
-            // For the Relais
-            let digital_out_1 = DigitalOutput::new(el2004.clone(), EL2004Port::DO1);
-            let digital_out_2 = DigitalOutput::new(el2004.clone(), EL2004Port::DO2);
-            let digital_out_3 = DigitalOutput::new(el2004.clone(), EL2004Port::DO3);
-            let digital_out_4 = DigitalOutput::new(el2004.clone(), EL2004Port::DO4);
+            // Role 2 - heater relais, roles 6 and 7 - optional EL2502 for hardware PWM
+            let [heater_front, heater_middle, heater_back, heater_nozzle] =
+                get_heater_outputs(hardware, params, &Self::HARDWARE_DESCRIPTOR, 2, [6, 7]).await?;
 
             let pressure_sensor = AnalogInput::new(el3021, EL3021Port::AI1);
             // The Extruders temparature Controllers should disable the relais when the max_temperature is reached
@@ -156,9 +161,8 @@ impl MachineNewTrait for ExtruderV3 {
                 ThermodynamicTemperature::new::<degree_celsius>(150.0),
                 extruder_max_temperature,
                 t1,
-                digital_out_1,
+                heater_front,
                 Heating::default(),
-                Duration::from_millis(500),
                 700.0,
                 1.0,
             );
@@ -171,9 +175,8 @@ impl MachineNewTrait for ExtruderV3 {
                 ThermodynamicTemperature::new::<degree_celsius>(150.0),
                 extruder_max_temperature,
                 t2,
-                digital_out_2,
+                heater_middle,
                 Heating::default(),
-                Duration::from_millis(500),
                 700.0,
                 1.0,
             );
@@ -186,9 +189,8 @@ impl MachineNewTrait for ExtruderV3 {
                 ThermodynamicTemperature::new::<degree_celsius>(150.0),
                 extruder_max_temperature,
                 t3,
-                digital_out_3,
+                heater_back,
                 Heating::default(),
-                Duration::from_millis(500),
                 700.0,
                 1.0,
             );
@@ -201,9 +203,8 @@ impl MachineNewTrait for ExtruderV3 {
                 ThermodynamicTemperature::new::<degree_celsius>(150.0),
                 extruder_max_temperature,
                 t4,
-                digital_out_4,
+                heater_nozzle,
                 Heating::default(),
-                Duration::from_millis(500),
                 200.0,
                 0.95,
             );
@@ -234,6 +235,8 @@ impl MachineNewTrait for ExtruderV3 {
                 mode: ExtruderV3Mode::Standby,
                 total_energy_kwh: 0.0,
                 last_energy_calculation_time: None,
+                power_measurement,
+                measured_energy_offset_kwh: None,
                 temperature_controller_front,
                 temperature_controller_middle,
                 temperature_controller_back,
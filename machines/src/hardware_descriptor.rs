@@ -0,0 +1,274 @@
+use std::fmt;
+
+use ethercat_hal::devices::SubDeviceIdentityTuple;
+use serde::{Deserialize, Serialize};
+
+/// A role a machine expects on the EtherCAT bus and the terminals that can fill it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EthercatRole {
+    pub role: u16,
+    /// Shown in the commissioning checklist
+    pub name: &'static str,
+    pub identities: &'static [SubDeviceIdentityTuple],
+    /// The machine also runs without this role
+    pub optional: bool,
+}
+
+impl EthercatRole {
+    pub const fn required(
+        role: u16,
+        name: &'static str,
+        identities: &'static [SubDeviceIdentityTuple],
+    ) -> Self {
+        Self {
+            role,
+            name,
+            identities,
+            optional: false,
+        }
+    }
+
+    pub const fn optional(
+        role: u16,
+        name: &'static str,
+        identities: &'static [SubDeviceIdentityTuple],
+    ) -> Self {
+        Self {
+            role,
+            name,
+            identities,
+            optional: true,
+        }
+    }
+}
+
+/// Static description of the hardware a machine type needs, registered with the [`crate::registry::MachineRegistry`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MachineHardwareDescriptor {
+    /// EtherCAT roles in role order
+    pub ethercat_roles: &'static [EthercatRole],
+}
+
+/// A device of the machine's device group as found on the bus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HardwareCheckDevice {
+    pub role: u16,
+    pub subdevice_index: usize,
+    pub identity: SubDeviceIdentityTuple,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum HardwareCheckStatus {
+    Ok,
+    /// No subdevice is identified with this role
+    Missing,
+    /// The subdevice is not one of the allowed terminals
+    WrongIdentity {
+        identity: SubDeviceIdentityTuple,
+    },
+    /// Another subdevice already fills this role
+    Duplicate,
+    /// The machine has no such role
+    Unexpected,
+}
+
+/// One line of the commissioning checklist
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HardwareCheckItem {
+    pub role: u16,
+    pub name: String,
+    pub optional: bool,
+    pub subdevice_index: Option<usize>,
+    pub status: HardwareCheckStatus,
+}
+
+impl HardwareCheckItem {
+    /// Whether the item prevents the machine from being created
+    pub fn is_problem(&self) -> bool {
+        match self.status {
+            HardwareCheckStatus::Ok => false,
+            HardwareCheckStatus::Missing => !self.optional,
+            HardwareCheckStatus::WrongIdentity { .. }
+            | HardwareCheckStatus::Duplicate
+            | HardwareCheckStatus::Unexpected => true,
+        }
+    }
+}
+
+impl fmt::Display for HardwareCheckItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Role {} ({})", self.role, self.name)?;
+        if let Some(subdevice_index) = self.subdevice_index {
+            write!(f, " at subdevice {}", subdevice_index)?;
+        }
+        match self.status {
+            HardwareCheckStatus::Ok => write!(f, ": ok"),
+            HardwareCheckStatus::Missing if self.optional => write!(f, ": not present (optional)"),
+            HardwareCheckStatus::Missing => write!(f, ": missing"),
+            HardwareCheckStatus::WrongIdentity { identity } => {
+                write!(f, ": wrong terminal {:08x?}", identity)
+            }
+            HardwareCheckStatus::Duplicate => write!(f, ": role assigned more than once"),
+            HardwareCheckStatus::Unexpected => write!(f, ": machine has no such role"),
+        }
+    }
+}
+
+impl MachineHardwareDescriptor {
+    /// For machines without EtherCAT hardware
+    pub const NONE: Self = Self {
+        ethercat_roles: &[],
+    };
+
+    pub fn role(&self, role: u16) -> Option<&'static EthercatRole> {
+        self.ethercat_roles.iter().find(|r| r.role == role)
+    }
+
+    /// Allowed identities of a role, empty for unknown roles
+    pub fn identities(&self, role: u16) -> Vec<SubDeviceIdentityTuple> {
+        self.role(role)
+            .map(|r| r.identities.to_vec())
+            .unwrap_or_default()
+    }
+
+    /// Checks the devices of a machine against the descriptor
+    ///
+    /// Reports every role at once, so a partially wired machine shows everything that is missing or wrong.
+    pub fn check(&self, devices: &[HardwareCheckDevice]) -> Vec<HardwareCheckItem> {
+        let mut items = vec![];
+
+        for role in self.ethercat_roles {
+            let mut matching = devices.iter().filter(|device| device.role == role.role);
+            let item = |subdevice_index, status| HardwareCheckItem {
+                role: role.role,
+                name: role.name.to_string(),
+                optional: role.optional,
+                subdevice_index,
+                status,
+            };
+
+            let Some(first) = matching.next() else {
+                items.push(item(None, HardwareCheckStatus::Missing));
+                continue;
+            };
+            let status = match role.identities.contains(&first.identity) {
+                true => HardwareCheckStatus::Ok,
+                false => HardwareCheckStatus::WrongIdentity {
+                    identity: first.identity,
+                },
+            };
+            items.push(item(Some(first.subdevice_index), status));
+            for duplicate in matching {
+                items.push(item(
+                    Some(duplicate.subdevice_index),
+                    HardwareCheckStatus::Duplicate,
+                ));
+            }
+        }
+
+        for device in devices
+            .iter()
+            .filter(|device| self.role(device.role).is_none())
+        {
+            items.push(HardwareCheckItem {
+                role: device.role,
+                name: "Unknown".to_string(),
+                optional: false,
+                subdevice_index: Some(device.subdevice_index),
+                status: HardwareCheckStatus::Unexpected,
+            });
+        }
+
+        items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COUPLER: SubDeviceIdentityTuple = (0x2, 0x044c2c52, 0x00120000);
+    const OUTPUTS_A: SubDeviceIdentityTuple = (0x2, 0x07d43052, 0x00100000);
+    const OUTPUTS_B: SubDeviceIdentityTuple = (0x2, 0x07d43052, 0x00110000);
+    const INPUTS: SubDeviceIdentityTuple = (0x2, 0x03ea3052, 0x00100000);
+
+    const DESCRIPTOR: MachineHardwareDescriptor = MachineHardwareDescriptor {
+        ethercat_roles: &[
+            EthercatRole::optional(0, "Bus coupler", &[COUPLER]),
+            EthercatRole::required(1, "Digital outputs", &[OUTPUTS_A, OUTPUTS_B]),
+            EthercatRole::required(2, "Digital inputs", &[INPUTS]),
+            EthercatRole::required(3, "Stepper", &[INPUTS]),
+        ],
+    };
+
+    fn device(
+        role: u16,
+        subdevice_index: usize,
+        identity: SubDeviceIdentityTuple,
+    ) -> HardwareCheckDevice {
+        HardwareCheckDevice {
+            role,
+            subdevice_index,
+            identity,
+        }
+    }
+
+    #[test]
+    fn test_check_reports_all_problems() {
+        let items = DESCRIPTOR.check(&[
+            device(1, 1, OUTPUTS_B),
+            device(2, 2, OUTPUTS_A),
+            device(1, 3, OUTPUTS_A),
+            device(7, 4, INPUTS),
+        ]);
+
+        let statuses = items
+            .iter()
+            .map(|item| (item.role, item.subdevice_index, item.status))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            vec![
+                (0, None, HardwareCheckStatus::Missing),
+                (1, Some(1), HardwareCheckStatus::Ok),
+                (1, Some(3), HardwareCheckStatus::Duplicate),
+                (
+                    2,
+                    Some(2),
+                    HardwareCheckStatus::WrongIdentity {
+                        identity: OUTPUTS_A
+                    }
+                ),
+                (3, None, HardwareCheckStatus::Missing),
+                (7, Some(4), HardwareCheckStatus::Unexpected),
+            ]
+        );
+
+        // the missing coupler is optional
+        let problems = items
+            .iter()
+            .filter(|item| item.is_problem())
+            .map(|item| item.role)
+            .collect::<Vec<_>>();
+        assert_eq!(problems, vec![1, 2, 3, 7]);
+    }
+
+    #[test]
+    fn test_check_complete() {
+        let items = DESCRIPTOR.check(&[
+            device(0, 0, COUPLER),
+            device(1, 1, OUTPUTS_A),
+            device(2, 2, INPUTS),
+            device(3, 3, INPUTS),
+        ]);
+        assert_eq!(items.len(), 4);
+        assert!(
+            items
+                .iter()
+                .all(|item| item.status == HardwareCheckStatus::Ok)
+        );
+        assert_eq!(DESCRIPTOR.identities(1), vec![OUTPUTS_A, OUTPUTS_B]);
+        assert!(DESCRIPTOR.identities(9).is_empty());
+    }
+}
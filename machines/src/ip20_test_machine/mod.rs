@@ -1,7 +1,9 @@
+use crate::hardware_descriptor::{EthercatRole, MachineHardwareDescriptor};
 use crate::ip20_test_machine::api::{IP20TestMachineEvents, LiveValuesEvent, StateEvent};
 use crate::machine_identification::{MachineIdentification, MachineIdentificationUnique};
 use crate::{AsyncThreadMessage, Machine, MachineMessage};
 use control_core::socketio::namespace::NamespaceCacheingLogic;
+use ethercat_hal::devices::wago_modules::ip20_ec_di8_do8::IP20_EC_DI8_DO8_IDENTITY;
 use ethercat_hal::io::digital_input::DigitalInput;
 use ethercat_hal::io::digital_output::DigitalOutput;
 use smol::channel::{Receiver, Sender};
@@ -42,6 +44,15 @@ impl IP20TestMachine {
         vendor: VENDOR_QITECH,
         machine: IP20_TEST_MACHINE,
     };
+
+    /// Hardware the machine expects, checked before the machine is created
+    pub const HARDWARE_DESCRIPTOR: MachineHardwareDescriptor = MachineHardwareDescriptor {
+        ethercat_roles: &[EthercatRole::required(
+            0,
+            "Digital inputs and outputs",
+            &[IP20_EC_DI8_DO8_IDENTITY],
+        )],
+    };
 }
 
 impl IP20TestMachine {
@@ -10,7 +10,7 @@ use crate::{
 
 use anyhow::Error;
 use ethercat_hal::devices::wago_modules::ip20_ec_di8_do8::{
-    IP20_EC_DI8_DO8_IDENTITY, IP20EcDi8Do8, IP20EcDi8Do8InputPort, IP20EcDi8Do8OutputPort,
+    IP20EcDi8Do8, IP20EcDi8Do8InputPort, IP20EcDi8Do8OutputPort,
 };
 use ethercat_hal::io::digital_input::DigitalInput;
 use ethercat_hal::io::digital_output::DigitalOutput;
@@ -41,7 +41,7 @@ impl MachineNewTrait for IP20TestMachine {
                 hardware,
                 params,
                 0,
-                [IP20_EC_DI8_DO8_IDENTITY].to_vec(),
+                Self::HARDWARE_DESCRIPTOR.identities(0),
             )
             .await?
             .0;
@@ -21,10 +21,12 @@ pub mod aquapath1;
 pub mod buffer1;
 pub mod extruder1;
 pub mod extruder2;
+pub mod hardware_descriptor;
 pub mod ip20_test_machine;
 pub mod laser;
 pub mod machine_identification;
 pub mod mock;
+pub mod motor_configuration;
 pub mod registry;
 pub mod serial;
 pub mod test_machine;
@@ -342,6 +344,64 @@ async fn get_device_ident<
     return Ok(device_hardware_identification_ethercat.clone());
 }
 
+/// Identity of the subdevice with the role
+///
+/// Used to pick the driver if a role accepts different devices.
+#[cfg(not(feature = "mock-machine"))]
+async fn get_ethercat_device_identity<
+    'maindevice,
+    'subdevices,
+    'device_identifications_identified,
+    'ethercat_devices,
+    'machine_new_hardware_etehrcat,
+    'machine_new_hardware_serial,
+    'machine_new_hardware,
+>(
+    hardware: &&MachineNewHardwareEthercat<'maindevice, 'subdevices, 'ethercat_devices>,
+    params: &MachineNewParams<
+        'maindevice,
+        'subdevices,
+        'device_identifications_identified,
+        'ethercat_devices,
+        'machine_new_hardware_etehrcat,
+        'machine_new_hardware_serial,
+        'machine_new_hardware,
+    >,
+    role: u16,
+) -> Result<SubDeviceIdentityTuple, anyhow::Error> {
+    let device_hardware_identification_ethercat = get_device_ident(params, role).await?;
+    let subdevice = get_subdevice_by_index(
+        hardware.subdevices,
+        device_hardware_identification_ethercat.subdevice_index,
+    )?;
+    Ok(subdevice_identity_to_tuple(&subdevice.identity()))
+}
+
+/// Microsteps per full step of the EL70x1 counter
+#[cfg(not(feature = "mock-machine"))]
+const EL70X1_MICROSTEPS: f64 = 64.0;
+
+/// Update a stall detector from an EL70x1 stepper in velocity mode
+///
+/// A disabled stepper counts as standing still, so the detector only follows its position.
+#[cfg(not(feature = "mock-machine"))]
+pub fn update_stepper_stall_detector(
+    detector: &mut control_core::helpers::stall_detector::StallDetector,
+    stepper: &ethercat_hal::io::stepper_velocity_el70x1::StepperVelocityEL70x1,
+    now: Instant,
+) -> Option<control_core::helpers::stall_detector::StallReason> {
+    let steps_per_second = match stepper.is_enabled() {
+        true => f64::from(stepper.get_speed()),
+        false => 0.0,
+    };
+    detector.update(
+        now,
+        steps_per_second,
+        stepper.get_position() as f64 / EL70X1_MICROSTEPS,
+        stepper.get_info_data(ethercat_hal::shared_config::el70x1::EL70x1InfoData::LoadAngle),
+    )
+}
+
 async fn get_ethercat_device<
     'maindevice,
     'subdevices,
@@ -122,6 +122,7 @@ use ethercat_hal::devices::el1008::EL1008_IDENTITY_A;
 use ethercat_hal::devices::el2002::EL2002_IDENTITY_A;
 use ethercat_hal::devices::el2002::EL2002_IDENTITY_B;
 use ethercat_hal::devices::el2008::{EL2008_IDENTITY_A, EL2008_IDENTITY_B, EL2008_IDENTITY_C};
+use ethercat_hal::devices::el2502::EL2502_IDENTITY_A;
 use ethercat_hal::devices::el2521::{
     EL2521_IDENTITY_0000_A, EL2521_IDENTITY_0000_B, EL2521_IDENTITY_0024_A,
 };
@@ -132,6 +133,9 @@ use ethercat_hal::devices::el3024::EL3024_IDENTITY_A;
 use ethercat_hal::devices::el3062_0030::EL3062_0030_IDENTITY_A;
 use ethercat_hal::devices::el3204::EL3204_IDENTITY_A;
 use ethercat_hal::devices::el3204::EL3204_IDENTITY_B;
+use ethercat_hal::devices::el3314::EL3314_IDENTITY_A;
+use ethercat_hal::devices::el3356::EL3356_IDENTITY_A;
+use ethercat_hal::devices::el3443::EL3443_IDENTITY_A;
 use ethercat_hal::devices::el4002::EL4002_IDENTITY_A;
 use ethercat_hal::devices::el5152::EL5152_IDENTITY_A;
 use ethercat_hal::devices::el6021::{
@@ -140,12 +144,17 @@ use ethercat_hal::devices::el6021::{
 use ethercat_hal::devices::el7031::{EL7031_IDENTITY_A, EL7031_IDENTITY_B};
 use ethercat_hal::devices::el7031_0030::EL7031_0030_IDENTITY_A;
 use ethercat_hal::devices::el7041_0052::EL7041_0052_IDENTITY_A;
+use ethercat_hal::devices::el72x1::{
+    EL7211_IDENTITY_A, EL7211_IDENTITY_B, EL7221_IDENTITY_A, EL7221_IDENTITY_B,
+};
 use ethercat_hal::devices::subdevice_identity_to_tuple;
 use ethercat_hal::helpers::ethercrab_types::{
     EthercrabSubDeviceOperational, EthercrabSubDevicePreoperational,
 };
 use ethercrab::MainDevice;
 use ethercrab::SubDeviceIdentity;
+use ethercrab::{SubDevice, SubDeviceRef};
+use std::ops::Deref;
 
 #[derive(Debug)]
 pub struct MachineIdentificationAddresses {
@@ -171,6 +180,56 @@ impl MachineIdentificationAddresses {
     }
 }
 
+impl MachineIdentificationAddresses {
+    /// EEPROM word addresses with the values to write, in the order [`write_machine_device_identification`] writes them
+    pub const fn encode(&self, device_identification: &DeviceMachineIdentification) -> [(u16, u16); 4] {
+        [
+            (
+                self.vendor_word,
+                device_identification
+                    .machine_identification_unique
+                    .machine_identification
+                    .vendor,
+            ),
+            (
+                self.serial_word,
+                device_identification.machine_identification_unique.serial,
+            ),
+            (
+                self.machine_word,
+                device_identification
+                    .machine_identification_unique
+                    .machine_identification
+                    .machine,
+            ),
+            (self.role_word, device_identification.role),
+        ]
+    }
+
+    /// Decodes the identification from an EEPROM image as dumped byte by byte
+    ///
+    /// Returns `None` if the image is too short.
+    pub fn decode_from_image(&self, image: &[u8]) -> Option<DeviceMachineIdentification> {
+        let word = |address: u16| {
+            let offset = address as usize * 2;
+            image
+                .get(offset..offset + 2)
+                .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+        };
+
+        Some(DeviceMachineIdentification {
+            machine_identification_unique: MachineIdentificationUnique {
+                machine_identification: MachineIdentification {
+                    vendor: word(self.vendor_word)?,
+                    machine: word(self.machine_word)?,
+                },
+                serial: word(self.serial_word)?,
+            },
+            role: word(self.role_word)?,
+        })
+    }
+}
+
 impl Default for MachineIdentificationAddresses {
     fn default() -> Self {
         Self {
@@ -198,10 +257,15 @@ pub async fn read_device_identifications<'maindevice>(
 }
 
 /// Reads the machine device identification from the EEPROM
-pub async fn machine_device_identification<'maindevice>(
-    subdevice: &'maindevice EthercrabSubDevicePreoperational<'maindevice>,
+///
+/// Works in every group state, so the loop can read it back after writing.
+pub async fn machine_device_identification<S>(
+    subdevice: &SubDeviceRef<'_, S>,
     maindevice: &MainDevice<'_>,
-) -> Result<DeviceMachineIdentification, Error> {
+) -> Result<DeviceMachineIdentification, Error>
+where
+    S: Deref<Target = SubDevice>,
+{
     let addresses = match get_identification_addresses(&subdevice.identity(), subdevice.name()) {
         Ok(x) => x,
         Err(e) => {
@@ -303,36 +367,11 @@ pub async fn write_machine_device_identification<'maindevice, const MAX_PDI: usi
         addresses.role_word + 1,
     );
 
-    subdevice
-        .eeprom_write_dangerously(
-            maindevice,
-            addresses.vendor_word,
-            device_identification
-                .machine_identification_unique
-                .machine_identification
-                .vendor,
-        )
-        .await?;
-    subdevice
-        .eeprom_write_dangerously(
-            maindevice,
-            addresses.serial_word,
-            device_identification.machine_identification_unique.serial,
-        )
-        .await?;
-    subdevice
-        .eeprom_write_dangerously(
-            maindevice,
-            addresses.machine_word,
-            device_identification
-                .machine_identification_unique
-                .machine_identification
-                .machine,
-        )
-        .await?;
-    subdevice
-        .eeprom_write_dangerously(maindevice, addresses.role_word, device_identification.role)
-        .await?;
+    for (word, value) in addresses.encode(device_identification) {
+        subdevice
+            .eeprom_write_dangerously(maindevice, word, value)
+            .await?;
+    }
     Ok(())
 }
 
@@ -353,6 +392,7 @@ pub fn get_identification_addresses(
         EL2002_IDENTITY_A | EL2002_IDENTITY_B => MachineIdentificationAddresses::default(),
         ethercat_hal::devices::el2004::EL2004_IDENTITY_A | ethercat_hal::devices::el2004::EL2004_IDENTITY_B => MachineIdentificationAddresses::default(),
         EL2008_IDENTITY_A | EL2008_IDENTITY_B | EL2008_IDENTITY_C => MachineIdentificationAddresses::default(),
+        EL2502_IDENTITY_A => MachineIdentificationAddresses::default(),
         EL2521_IDENTITY_0000_A | EL2521_IDENTITY_0000_B | EL2521_IDENTITY_0024_A => {
             MachineIdentificationAddresses::default()
         }
@@ -362,6 +402,9 @@ pub fn get_identification_addresses(
         EL3024_IDENTITY_A => MachineIdentificationAddresses::default(),
         EL3062_0030_IDENTITY_A => MachineIdentificationAddresses::default(),
         EL3204_IDENTITY_A | EL3204_IDENTITY_B => MachineIdentificationAddresses::default(),
+        EL3314_IDENTITY_A => MachineIdentificationAddresses::default(),
+        EL3356_IDENTITY_A => MachineIdentificationAddresses::default(),
+        EL3443_IDENTITY_A => MachineIdentificationAddresses::default(),
         EL4002_IDENTITY_A => MachineIdentificationAddresses::default(),
         EL5152_IDENTITY_A => MachineIdentificationAddresses::default(),
         EL6021_IDENTITY_A | EL6021_IDENTITY_B | EL6021_IDENTITY_C | EL6021_IDENTITY_D => {
@@ -370,6 +413,9 @@ pub fn get_identification_addresses(
         EL7031_IDENTITY_A | EL7031_IDENTITY_B => MachineIdentificationAddresses::default(),
         EL7031_0030_IDENTITY_A => MachineIdentificationAddresses::default(),
         EL7041_0052_IDENTITY_A => MachineIdentificationAddresses::default(),
+        EL7211_IDENTITY_A | EL7211_IDENTITY_B | EL7221_IDENTITY_A | EL7221_IDENTITY_B => {
+            MachineIdentificationAddresses::default()
+        }
 
         _ => {
             // block_on(u16dump(&subdevice, maindevice, 0x00, 0xff))?;
@@ -388,4 +434,33 @@ pub fn get_identification_addresses(
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    #[test]
+    fn test_encode_decode_roundtrip() {
+        let addresses = MachineIdentificationAddresses::default();
+        let device_identification = DeviceMachineIdentification {
+            machine_identification_unique: MachineIdentificationUnique {
+                machine_identification: MachineIdentification {
+                    vendor: 0x0001,
+                    machine: 0x0002,
+                },
+                serial: 0x1234,
+            },
+            role: 3,
+        };
+
+        let mut image = vec![0u8; 128];
+        for (word, value) in addresses.encode(&device_identification) {
+            let offset = word as usize * 2;
+            image[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+        }
+
+        assert_eq!(image[0x50], 0x01);
+        assert_eq!(&image[0x54..0x56], &[0x34, 0x12]);
+        assert_eq!(
+            addresses.decode_from_image(&image),
+            Some(device_identification)
+        );
+        assert_eq!(addresses.decode_from_image(&image[..0x50]), None);
+    }
 }
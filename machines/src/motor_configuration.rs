@@ -0,0 +1,163 @@
+use std::path::Path;
+
+use anyhow::Error;
+use ethercat_hal::shared_config::el70x1::EL70x1MotorConfiguration;
+use serde::{Deserialize, Serialize};
+
+use crate::machine_identification::MachineIdentificationUnique;
+
+/// Motor configurations changed at runtime, loaded again when the machines are created
+pub const MOTOR_CONFIGURATION_PATH: &str = "motor_configurations.json";
+
+/// Motor configuration of the stepper terminal with `role` in a machine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MotorConfigurationEntry {
+    pub machine_identification_unique: MachineIdentificationUnique,
+    pub role: u16,
+    pub configuration: EL70x1MotorConfiguration,
+}
+
+/// Content of [`MOTOR_CONFIGURATION_PATH`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MotorConfigurations {
+    pub entries: Vec<MotorConfigurationEntry>,
+}
+
+impl MotorConfigurations {
+    /// A missing file counts as empty
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Writes to a temporary file first, so a crash never leaves a half written file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let path = path.as_ref();
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(tmp_path, path)?;
+        Ok(())
+    }
+
+    pub fn get(
+        &self,
+        machine_identification_unique: &MachineIdentificationUnique,
+        role: u16,
+    ) -> Option<&EL70x1MotorConfiguration> {
+        self.entries
+            .iter()
+            .find(|entry| {
+                &entry.machine_identification_unique == machine_identification_unique
+                    && entry.role == role
+            })
+            .map(|entry| &entry.configuration)
+    }
+
+    pub fn set(
+        &mut self,
+        machine_identification_unique: &MachineIdentificationUnique,
+        role: u16,
+        configuration: EL70x1MotorConfiguration,
+    ) {
+        match self.entries.iter_mut().find(|entry| {
+            &entry.machine_identification_unique == machine_identification_unique
+                && entry.role == role
+        }) {
+            Some(entry) => entry.configuration = configuration,
+            None => self.entries.push(MotorConfigurationEntry {
+                machine_identification_unique: machine_identification_unique.clone(),
+                role,
+                configuration,
+            }),
+        }
+    }
+}
+
+/// Stored motor configuration of a stepper, `default` if it was never changed
+///
+/// Called from the machines `new`, a broken file falls back to `default`.
+pub fn load_motor_configuration(
+    machine_identification_unique: &MachineIdentificationUnique,
+    role: u16,
+    default: EL70x1MotorConfiguration,
+) -> EL70x1MotorConfiguration {
+    match MotorConfigurations::load(MOTOR_CONFIGURATION_PATH) {
+        Ok(configurations) => configurations
+            .get(machine_identification_unique, role)
+            .cloned()
+            .unwrap_or(default),
+        Err(e) => {
+            tracing::warn!(
+                "[{}::load_motor_configuration] Failed to read {}, using the default for role {}: {}",
+                module_path!(),
+                MOTOR_CONFIGURATION_PATH,
+                role,
+                e
+            );
+            default
+        }
+    }
+}
+
+/// Stores the motor configuration of a stepper in [`MOTOR_CONFIGURATION_PATH`]
+pub fn save_motor_configuration(
+    machine_identification_unique: &MachineIdentificationUnique,
+    role: u16,
+    configuration: EL70x1MotorConfiguration,
+) -> Result<(), Error> {
+    let mut configurations = MotorConfigurations::load(MOTOR_CONFIGURATION_PATH)?;
+    configurations.set(machine_identification_unique, role, configuration);
+    configurations.save(MOTOR_CONFIGURATION_PATH)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::machine_identification::MachineIdentification;
+
+    fn machine(serial: u16) -> MachineIdentificationUnique {
+        MachineIdentificationUnique {
+            machine_identification: MachineIdentification {
+                vendor: 1,
+                machine: 2,
+            },
+            serial,
+        }
+    }
+
+    fn configuration(max_current: u16) -> EL70x1MotorConfiguration {
+        let mut configuration = EL70x1MotorConfiguration::default();
+        configuration.stm_motor.max_current = max_current;
+        configuration
+    }
+
+    #[test]
+    fn test_set_replaces_entry_of_same_axis() {
+        let mut configurations = MotorConfigurations::default();
+        configurations.set(&machine(1), 2, configuration(1500));
+        configurations.set(&machine(1), 3, configuration(2700));
+        configurations.set(&machine(2), 2, configuration(1000));
+        configurations.set(&machine(1), 2, configuration(2800));
+
+        assert_eq!(configurations.entries.len(), 3);
+        assert_eq!(
+            configurations.get(&machine(1), 2),
+            Some(&configuration(2800))
+        );
+        assert_eq!(
+            configurations.get(&machine(2), 2),
+            Some(&configuration(1000))
+        );
+        assert_eq!(configurations.get(&machine(2), 3), None);
+
+        // survives a round trip through the file format
+        let content = serde_json::to_string(&configurations).unwrap();
+        assert_eq!(
+            serde_json::from_str::<MotorConfigurations>(&content).unwrap(),
+            configurations
+        );
+    }
+}
@@ -6,7 +6,8 @@ use crate::{
 
 use crate::{
     Machine, MachineNewParams, analog_input_test_machine::AnalogInputTestMachine,
-    ip20_test_machine::IP20TestMachine, machine_identification::MachineIdentification,
+    hardware_descriptor::MachineHardwareDescriptor, ip20_test_machine::IP20TestMachine,
+    machine_identification::MachineIdentification,
 };
 
 #[cfg(not(feature = "mock-machine"))]
@@ -29,7 +30,14 @@ pub type MachineNewClosure =
     Box<dyn Fn(&MachineNewParams) -> Result<Box<dyn Machine>, Error> + Send + Sync>;
 
 pub struct MachineRegistry {
-    type_map: HashMap<TypeId, (MachineIdentification, MachineNewClosure)>,
+    type_map: HashMap<
+        TypeId,
+        (
+            MachineIdentification,
+            MachineHardwareDescriptor,
+            MachineNewClosure,
+        ),
+    >,
 }
 
 impl Default for MachineRegistry {
@@ -48,11 +56,13 @@ impl MachineRegistry {
     pub fn register<T: Machine + 'static>(
         &mut self,
         machine_identficiation: MachineIdentification,
+        hardware_descriptor: MachineHardwareDescriptor,
     ) {
         self.type_map.insert(
             TypeId::of::<T>(),
             (
                 machine_identficiation.clone(),
+                hardware_descriptor,
                 // create a machine construction closure
                 Box::new(|machine_new_params| Ok(Box::new(T::new(machine_new_params)?))),
             ),
@@ -74,10 +84,10 @@ impl MachineRegistry {
                 ))?;
 
         // find machine new function by comparing MachineIdentification
-        let (_, machine_new_closure) = self
+        let (_, _, machine_new_closure) = self
             .type_map
             .values()
-            .find(|(mi, _)| {
+            .find(|(mi, _, _)| {
                 mi == &device_identification
                     .device_machine_identification
                     .machine_identification_unique
@@ -91,41 +101,68 @@ impl MachineRegistry {
         // call machine new function by reference
         (machine_new_closure)(machine_new_params)
     }
+
+    /// Hardware descriptor of a registered machine, without roles for machines without EtherCAT hardware
+    pub fn hardware_descriptor(
+        &self,
+        machine_identification: &MachineIdentification,
+    ) -> Option<MachineHardwareDescriptor> {
+        self.type_map
+            .values()
+            .find(|(mi, _, _)| mi == machine_identification)
+            .map(|(_, hardware_descriptor, _)| *hardware_descriptor)
+    }
 }
 
 lazy_static! {
     pub static ref MACHINE_REGISTRY: MachineRegistry = {
         let mut mc = MachineRegistry::new();
-        mc.register::<Winder2>(Winder2::MACHINE_IDENTIFICATION);
+        #[cfg(not(feature = "mock-machine"))]
+        mc.register::<Winder2>(Winder2::MACHINE_IDENTIFICATION, Winder2::HARDWARE_DESCRIPTOR);
 
         #[cfg(feature = "mock-machine")]
-        mc.register::<ExtruderV2Mock1>(ExtruderV2Mock1::MACHINE_IDENTIFICATION);
+        mc.register::<Winder2>(Winder2::MACHINE_IDENTIFICATION, MachineHardwareDescriptor::NONE);
 
         #[cfg(feature = "mock-machine")]
-        mc.register::<ExtruderV2Mock2>(ExtruderV2Mock2::MACHINE_IDENTIFICATION);
+        mc.register::<ExtruderV2Mock1>(ExtruderV2Mock1::MACHINE_IDENTIFICATION, MachineHardwareDescriptor::NONE);
+
+        #[cfg(feature = "mock-machine")]
+        mc.register::<ExtruderV2Mock2>(ExtruderV2Mock2::MACHINE_IDENTIFICATION, MachineHardwareDescriptor::NONE);
 
         #[cfg(not(feature = "mock-machine"))]
-        mc.register::<ExtruderV2>(ExtruderV2::MACHINE_IDENTIFICATION);
+        mc.register::<ExtruderV2>(ExtruderV2::MACHINE_IDENTIFICATION, ExtruderV2::HARDWARE_DESCRIPTOR);
 
         #[cfg(not(feature = "mock-machine"))]
-        mc.register::<ExtruderV3>(ExtruderV3::MACHINE_IDENTIFICATION);
+        mc.register::<ExtruderV3>(ExtruderV3::MACHINE_IDENTIFICATION, ExtruderV3::HARDWARE_DESCRIPTOR);
 
         #[cfg(feature = "mock-machine")]
-        mc.register::<MockMachine>(MockMachine::MACHINE_IDENTIFICATION);
+        mc.register::<MockMachine>(MockMachine::MACHINE_IDENTIFICATION, MachineHardwareDescriptor::NONE);
 
         #[cfg(not(feature = "mock-machine"))]
-        mc.register::<LaserMachine>(LaserMachine::MACHINE_IDENTIFICATION);
+        mc.register::<LaserMachine>(LaserMachine::MACHINE_IDENTIFICATION, MachineHardwareDescriptor::NONE);
 
         #[cfg(not(feature = "mock-machine"))]
-        mc.register::<BufferV1>(BufferV1::MACHINE_IDENTIFICATION);
+        mc.register::<BufferV1>(BufferV1::MACHINE_IDENTIFICATION, BufferV1::HARDWARE_DESCRIPTOR);
 
         #[cfg(not(feature = "mock-machine"))]
-        mc.register::<AquaPathV1>(AquaPathV1::MACHINE_IDENTIFICATION);
+        mc.register::<AquaPathV1>(AquaPathV1::MACHINE_IDENTIFICATION, AquaPathV1::HARDWARE_DESCRIPTOR);
 
-        mc.register::<TestMachine>(TestMachine::MACHINE_IDENTIFICATION);
-        mc.register::<IP20TestMachine>(IP20TestMachine::MACHINE_IDENTIFICATION);
-        mc.register::<AnalogInputTestMachine>(AnalogInputTestMachine::MACHINE_IDENTIFICATION);
-        mc.register::<TestEL2008Machine>(TestEL2008Machine::MACHINE_IDENTIFICATION);
+        mc.register::<TestMachine>(
+            TestMachine::MACHINE_IDENTIFICATION,
+            TestMachine::HARDWARE_DESCRIPTOR,
+        );
+        mc.register::<IP20TestMachine>(
+            IP20TestMachine::MACHINE_IDENTIFICATION,
+            IP20TestMachine::HARDWARE_DESCRIPTOR,
+        );
+        mc.register::<AnalogInputTestMachine>(
+            AnalogInputTestMachine::MACHINE_IDENTIFICATION,
+            AnalogInputTestMachine::HARDWARE_DESCRIPTOR,
+        );
+        mc.register::<TestEL2008Machine>(
+            TestEL2008Machine::MACHINE_IDENTIFICATION,
+            TestEL2008Machine::HARDWARE_DESCRIPTOR,
+        );
 
         mc
     };
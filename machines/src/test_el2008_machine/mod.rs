@@ -1,7 +1,9 @@
+use crate::hardware_descriptor::{EthercatRole, MachineHardwareDescriptor};
 use crate::machine_identification::{MachineIdentification, MachineIdentificationUnique};
 use crate::test_el2008_machine::api::{StateEvent, TestEL2008MachineEvents};
 use crate::{AsyncThreadMessage, Machine, MachineMessage};
 use control_core::socketio::namespace::NamespaceCacheingLogic;
+use ethercat_hal::devices::el2008::{EL2008_IDENTITY_A, EL2008_IDENTITY_B, EL2008_IDENTITY_C};
 use ethercat_hal::io::digital_output::DigitalOutput;
 use serde::{Deserialize, Serialize};
 use smol::channel::{Receiver, Sender};
@@ -67,6 +69,15 @@ impl TestEL2008Machine {
         vendor: VENDOR_QITECH,
         machine: TEST_EL2008_MACHINE,
     };
+
+    /// Hardware the machine expects, checked before the machine is created
+    pub const HARDWARE_DESCRIPTOR: MachineHardwareDescriptor = MachineHardwareDescriptor {
+        ethercat_roles: &[EthercatRole::required(
+            1,
+            "Digital outputs",
+            &[EL2008_IDENTITY_A, EL2008_IDENTITY_B, EL2008_IDENTITY_C],
+        )],
+    };
 }
 
 impl TestEL2008Machine {
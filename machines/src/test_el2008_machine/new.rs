@@ -10,7 +10,7 @@ use crate::{
 };
 
 use anyhow::Error;
-use ethercat_hal::devices::el2008::{EL2008, EL2008_IDENTITY_A, EL2008_IDENTITY_B, EL2008_IDENTITY_C, EL2008Port};
+use ethercat_hal::devices::el2008::{EL2008, EL2008Port};
 use ethercat_hal::io::digital_output::DigitalOutput;
 
 impl MachineNewTrait for TestEL2008Machine {
@@ -38,7 +38,7 @@ impl MachineNewTrait for TestEL2008Machine {
                 hardware,
                 params,
                 1,
-                [EL2008_IDENTITY_A, EL2008_IDENTITY_B, EL2008_IDENTITY_C].to_vec(),
+                Self::HARDWARE_DESCRIPTOR.identities(1),
             )
             .await?
             .0;
@@ -1,7 +1,12 @@
+use crate::hardware_descriptor::{EthercatRole, MachineHardwareDescriptor};
 use crate::machine_identification::{MachineIdentification, MachineIdentificationUnique};
 use crate::test_machine::api::{StateEvent, TestMachineEvents};
 use crate::{AsyncThreadMessage, Machine, MachineMessage};
 use control_core::socketio::namespace::NamespaceCacheingLogic;
+use ethercat_hal::devices::{
+    el2004::{EL2004_IDENTITY_A, EL2004_IDENTITY_B},
+    wago_750_354::WAGO_750_354_IDENTITY_A,
+};
 use ethercat_hal::io::digital_output::DigitalOutput;
 use smol::channel::{Receiver, Sender};
 use std::time::Instant;
@@ -37,6 +42,18 @@ impl TestMachine {
         vendor: VENDOR_QITECH,
         machine: TEST_MACHINE,
     };
+
+    /// Hardware the machine expects, checked before the machine is created
+    pub const HARDWARE_DESCRIPTOR: MachineHardwareDescriptor = MachineHardwareDescriptor {
+        ethercat_roles: &[
+            EthercatRole::optional(0, "Serial interface", &[WAGO_750_354_IDENTITY_A]),
+            EthercatRole::required(
+                1,
+                "Digital outputs",
+                &[EL2004_IDENTITY_A, EL2004_IDENTITY_B],
+            ),
+        ],
+    };
 }
 
 impl TestMachine {
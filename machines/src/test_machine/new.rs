@@ -9,12 +9,12 @@ use crate::{
 };
 
 use anyhow::Error;
-use ethercat_hal::devices::el2004::{EL2004, EL2004_IDENTITY_A, EL2004_IDENTITY_B, EL2004Port};
+use ethercat_hal::devices::el2004::{EL2004, EL2004Port};
 use ethercat_hal::io::digital_output::DigitalOutput;
 
 //Imports For Wago
 /*
-use ethercat_hal::devices::wago_750_354::{WAGO_750_354_IDENTITY_A, Wago750_354};
+use ethercat_hal::devices::wago_750_354::Wago750_354;
 use ethercat_hal::devices::{EthercatDevice, downcast_device};
 use smol::lock::RwLock;
 use std::sync::Arc;
@@ -47,7 +47,7 @@ impl MachineNewTrait for TestMachine {
                 hardware,
                 params,
                 0,
-                [WAGO_750_354_IDENTITY_A].to_vec(),
+                Self::HARDWARE_DESCRIPTOR.identities(0),
             )
             .await?;
 
@@ -74,7 +74,7 @@ impl MachineNewTrait for TestMachine {
                 hardware,
                 params,
                 1,
-                [EL2004_IDENTITY_A, EL2004_IDENTITY_B].to_vec(),
+                Self::HARDWARE_DESCRIPTOR.identities(1),
             )
             .await?
             .0;
@@ -25,6 +25,9 @@ impl MachineAct for Winder2 {
         // automatically stops or pulls after N Meters if enabled
         self.stop_or_pull_spool(now);
 
+        // stop if a stepper stalls
+        self.check_stall(now);
+
         if self.traverse_controller.did_change_state() {
             self.emit_state();
         }
@@ -51,6 +51,7 @@ pub use winder2_imports::*;
 #[cfg(not(feature = "mock-machine"))]
 use crate::{MachineApi, MachineMessage};
 use crate::{MachineCrossConnectionState, machine_identification::MachineIdentificationUnique};
+use control_core::helpers::stall_detector::StallReason;
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub enum Mode {
@@ -176,6 +177,8 @@ pub struct StateEvent {
     pub spool_speed_controller_state: SpoolSpeedControllerState,
     /// Is a Machine Connected?
     pub connected_machine_state: MachineCrossConnectionState,
+    /// stepper stall alarms
+    pub stall_state: StallState,
 }
 
 #[derive(Serialize, Debug, Clone, Default)]
@@ -240,6 +243,14 @@ pub struct SpoolAutomaticActionState {
     pub spool_automatic_action_mode: SpoolAutomaticActionMode,
 }
 
+/// Latched stall of each stepper, cleared when the winder is started again
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct StallState {
+    pub traverse: Option<StallReason>,
+    pub puller: Option<StallReason>,
+    pub spool: Option<StallReason>,
+}
+
 #[derive(Serialize, Debug, Clone, Default)]
 pub struct ModeState {
     /// mode
@@ -5,8 +5,8 @@ mod winder2_imports {
     pub use crate::buffer1::BufferV1;
     pub use api::{
         LiveValuesEvent, ModeState, PullerState, SpoolAutomaticActionMode,
-        SpoolAutomaticActionState, SpoolSpeedControllerState, StateEvent, TensionArmState,
-        TraverseState, Winder2Events,
+        SpoolAutomaticActionState, SpoolSpeedControllerState, StallState, StateEvent,
+        TensionArmState, TraverseState, Winder2Events,
     };
     pub use control_core::socketio::event::BuildEvent;
     pub use control_core::socketio::namespace::NamespaceCacheingLogic;
@@ -84,6 +84,13 @@ impl Winder2 {
         let should_update = *mode != Winder2Mode::Wind || self.can_wind();
 
         if should_update {
+            // starting again acknowledges a stall
+            if *mode != Winder2Mode::Standby {
+                self.traverse_stall_detector.reset();
+                self.puller_stall_detector.reset();
+                self.spool_stall_detector.reset();
+            }
+
             // all transitions are allowed
             self.mode = mode.clone();
 
@@ -301,6 +308,11 @@ impl Winder2 {
                 spool_automatic_action_mode: self.spool_automatic_action.mode.clone(),
             },
             connected_machine_state: cross_conn,
+            stall_state: StallState {
+                traverse: self.traverse_stall_detector.stalled(),
+                puller: self.puller_stall_detector.stalled(),
+                spool: self.spool_stall_detector.stalled(),
+            },
         }
     }
 
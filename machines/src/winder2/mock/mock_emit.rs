@@ -4,7 +4,9 @@ use super::Winder2;
 use crate::machine_identification::{MachineIdentification, MachineIdentificationUnique};
 use crate::winder2::Winder2Mode;
 use crate::winder2::api::LiveValuesEvent;
-use crate::winder2::api::{ModeState, SpoolAutomaticActionMode, StateEvent, Winder2Events};
+use crate::winder2::api::{
+    ModeState, SpoolAutomaticActionMode, StallState, StateEvent, Winder2Events,
+};
 use crate::winder2::puller_speed_controller::{GearRatio, PullerRegulationMode};
 use crate::winder2::spool_speed_controller::SpoolSpeedControllerType;
 use crate::{MACHINE_WINDER_V1, VENDOR_QITECH};
@@ -106,6 +108,7 @@ impl Winder2 {
             tension_arm_state: self.tension_arm_state.clone(),
             spool_speed_controller_state: self.spool_speed_controller_state.clone(),
             connected_machine_state: cross_conn,
+            stall_state: StallState::default(),
         }
     }
 
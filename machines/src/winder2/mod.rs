@@ -31,16 +31,21 @@ mod winder2_imports {
     pub use super::tension_arm::TensionArm;
     pub use super::traverse_controller::TraverseController;
     pub use control_core::converters::angular_step_converter::AngularStepConverter;
+    pub use control_core::helpers::stall_detector::StallDetector;
     pub use ethercat_hal::io::{
         digital_input::DigitalInput, digital_output::DigitalOutput,
         stepper_velocity_el70x1::StepperVelocityEL70x1,
     };
     pub use smol::channel::{Receiver, Sender};
     pub use smol::lock::RwLock;
-    pub use std::{fmt::Debug, sync::Weak, time::Instant};
+    pub use std::{
+        fmt::Debug,
+        sync::Weak,
+        time::{Duration, Instant},
+    };
 
     pub use crate::buffer1::BufferV1;
-    pub use crate::{AsyncThreadMessage, Machine};
+    pub use crate::{AsyncThreadMessage, Machine, update_stepper_stall_detector};
     pub use units::ConstZero;
     pub use units::f64::Length;
     pub use units::{length::meter, length::millimeter, velocity::meter_per_second};
@@ -51,8 +56,17 @@ pub use winder2_imports::*;
 #[cfg(not(feature = "mock-machine"))]
 use crate::{
     MACHINE_WINDER_V1, MachineConnection, MachineMessage, VENDOR_QITECH,
+    hardware_descriptor::{EthercatRole, MachineHardwareDescriptor},
     machine_identification::{MachineIdentification, MachineIdentificationUnique},
 };
+#[cfg(not(feature = "mock-machine"))]
+use ethercat_hal::devices::{
+    ek1100::EK1100_IDENTITY_A,
+    el2002::{EL2002_IDENTITY_A, EL2002_IDENTITY_B},
+    el7031::{EL7031_IDENTITY_A, EL7031_IDENTITY_B},
+    el7031_0030::EL7031_0030_IDENTITY_A,
+    el7041_0052::EL7041_0052_IDENTITY_A,
+};
 
 #[derive(Debug)]
 pub struct SpoolAutomaticAction {
@@ -125,6 +139,11 @@ pub struct Winder2 {
     // control circuit puller
     pub puller_speed_controller: PullerSpeedController,
 
+    // stall detection
+    pub traverse_stall_detector: StallDetector,
+    pub puller_stall_detector: StallDetector,
+    pub spool_stall_detector: StallDetector,
+
     /// Will be initialized as false and set to true by emit_state
     /// This way we can signal to the client that the first state emission is a default state
     emitted_default_state: bool,
@@ -137,6 +156,67 @@ impl Winder2 {
         machine: MACHINE_WINDER_V1,
     };
 
+    /// Hardware the machine expects, checked before the machine is created
+    pub const HARDWARE_DESCRIPTOR: MachineHardwareDescriptor = MachineHardwareDescriptor {
+        ethercat_roles: &[
+            EthercatRole::required(0, "Bus coupler", &[EK1100_IDENTITY_A]),
+            EthercatRole::required(
+                1,
+                "Digital outputs",
+                &[EL2002_IDENTITY_A, EL2002_IDENTITY_B],
+            ),
+            EthercatRole::required(2, "Spool stepper", &[EL7041_0052_IDENTITY_A]),
+            EthercatRole::required(
+                3,
+                "Traverse stepper",
+                &[EL7031_IDENTITY_A, EL7031_IDENTITY_B],
+            ),
+            EthercatRole::required(4, "Puller stepper", &[EL7031_0030_IDENTITY_A]),
+        ],
+    };
+
+    /// Raw EL70x1 load angle above which a stepper counts as stalling
+    const STALL_LOAD_ANGLE_LIMIT: u16 = 900;
+
+    /// Full steps the stepper may fall behind the commanded position
+    const STALL_FOLLOWING_ERROR_LIMIT: f64 = 50.0;
+
+    /// How long a stall condition has to last before the winder stops
+    const STALL_DEBOUNCE: Duration = Duration::from_millis(500);
+
+    pub const fn new_stall_detector() -> StallDetector {
+        StallDetector::new(
+            Some(Self::STALL_LOAD_ANGLE_LIMIT),
+            Some(Self::STALL_FOLLOWING_ERROR_LIMIT),
+            Self::STALL_DEBOUNCE,
+        )
+    }
+
+    /// Switch to standby if a stepper stalls instead of losing steps
+    /// called by `act`
+    pub fn check_stall(&mut self, now: Instant) {
+        if self.mode == Winder2Mode::Standby {
+            return;
+        }
+
+        let traverse =
+            update_stepper_stall_detector(&mut self.traverse_stall_detector, &self.traverse, now);
+        let puller =
+            update_stepper_stall_detector(&mut self.puller_stall_detector, &self.puller, now);
+        let spool = update_stepper_stall_detector(&mut self.spool_stall_detector, &self.spool, now);
+
+        if traverse.is_some() || puller.is_some() || spool.is_some() {
+            tracing::error!(
+                "[{}::check_stall] Stepper stalled (traverse: {:?}, puller: {:?}, spool: {:?}), switching to standby",
+                module_path!(),
+                traverse,
+                puller,
+                spool
+            );
+            self.set_mode(&Winder2Mode::Standby);
+        }
+    }
+
     /// Validates that traverse limits maintain proper constraints:
     /// - Inner limit must be smaller than outer limit
     /// - At least 0.9mm difference between inner and outer limits
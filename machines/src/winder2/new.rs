@@ -3,6 +3,7 @@ mod winder2_imports {
     pub use super::super::api::Winder2Namespace;
     pub use super::super::tension_arm::TensionArm;
     pub use super::super::{Winder2, Winder2Mode};
+    pub use crate::motor_configuration::load_motor_configuration;
     pub use crate::winder2::puller_speed_controller::PullerSpeedController;
     pub use crate::winder2::spool_speed_controller::SpoolSpeedController;
     pub use crate::winder2::traverse_controller::TraverseController;
@@ -16,29 +17,26 @@ mod winder2_imports {
 
     pub use ethercat_hal::coe::ConfigurableDevice;
     pub use ethercat_hal::devices::ek1100::EK1100;
-    pub use ethercat_hal::devices::el2002::{EL2002, EL2002_IDENTITY_B, EL2002Port};
+    pub use ethercat_hal::devices::el2002::{EL2002, EL2002Port};
     pub use ethercat_hal::devices::el7031::coe::EL7031Configuration;
     pub use ethercat_hal::devices::el7031::pdo::EL7031PredefinedPdoAssignment;
-    pub use ethercat_hal::devices::el7031::{
-        EL7031, EL7031_IDENTITY_A, EL7031_IDENTITY_B, EL7031DigitalInputPort, EL7031StepperPort,
-    };
+    pub use ethercat_hal::devices::el7031::{EL7031, EL7031DigitalInputPort, EL7031StepperPort};
     pub use ethercat_hal::devices::el7031_0030::coe::EL7031_0030Configuration;
     pub use ethercat_hal::devices::el7031_0030::pdo::EL7031_0030PredefinedPdoAssignment;
     pub use ethercat_hal::devices::el7031_0030::{
-        self, EL7031_0030, EL7031_0030_IDENTITY_A, EL7031_0030AnalogInputPort,
-        EL7031_0030StepperPort,
+        self, EL7031_0030, EL7031_0030AnalogInputPort, EL7031_0030StepperPort,
     };
     pub use ethercat_hal::devices::el7041_0052::coe::EL7041_0052Configuration;
-    pub use ethercat_hal::devices::el7041_0052::{
-        EL7041_0052, EL7041_0052_IDENTITY_A, EL7041_0052Port,
-    };
-    pub use ethercat_hal::devices::{ek1100::EK1100_IDENTITY_A, el2002::EL2002_IDENTITY_A};
+    pub use ethercat_hal::devices::el7041_0052::pdo::EL7041_0052PredefinedPdoAssignment;
+    pub use ethercat_hal::devices::el7041_0052::{EL7041_0052, EL7041_0052Port};
     pub use ethercat_hal::io::analog_input::AnalogInput;
     pub use ethercat_hal::io::digital_input::DigitalInput;
     pub use ethercat_hal::io::digital_output::DigitalOutput;
     pub use ethercat_hal::io::stepper_velocity_el70x1::StepperVelocityEL70x1;
     pub use ethercat_hal::shared_config;
-    pub use ethercat_hal::shared_config::el70x1::{EL70x1OperationMode, StmMotorConfiguration};
+    pub use ethercat_hal::shared_config::el70x1::{
+        EL70x1InfoData, EL70x1MotorConfiguration, EL70x1OperationMode, StmMotorConfiguration,
+    };
     pub use std::time::Instant;
     pub use units::ConstZero;
     pub use units::f64::*;
@@ -76,16 +74,23 @@ impl MachineNewTrait for Winder2 {
         // if its async the compiler thinks &subdevices is persisted in the future which might never execute
         // so we can't drop subdevices unless this machine is dropped, which is bad
         smol::block_on(async {
+            let machine_identification_unique = params.get_machine_identification_unique();
+
             // Role 0: Buscoupler EK1100
-            let _ek1100 =
-                get_ethercat_device::<EK1100>(hardware, params, 0, vec![EK1100_IDENTITY_A]).await?;
+            let _ek1100 = get_ethercat_device::<EK1100>(
+                hardware,
+                params,
+                0,
+                Self::HARDWARE_DESCRIPTOR.identities(0),
+            )
+            .await?;
 
             // Role 1: 2x Digital outputs EL2002
             let el2002 = get_ethercat_device::<EL2002>(
                 hardware,
                 params,
                 1,
-                vec![EL2002_IDENTITY_A, EL2002_IDENTITY_B],
+                Self::HARDWARE_DESCRIPTOR.identities(1),
             )
             .await?
             .0;
@@ -96,19 +101,35 @@ impl MachineNewTrait for Winder2 {
                     hardware,
                     params,
                     2,
-                    vec![EL7041_0052_IDENTITY_A],
+                    Self::HARDWARE_DESCRIPTOR.identities(2),
                 )
                 .await?;
 
+                // motor configuration can be changed at runtime
+                let motor_configuration = load_motor_configuration(
+                    &machine_identification_unique,
+                    2,
+                    EL70x1MotorConfiguration {
+                        stm_motor: StmMotorConfiguration {
+                            max_current: 2800,
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                );
+
                 let el7041_config = EL7041_0052Configuration {
                     stm_features: shared_config::el70x1::StmFeatures {
                         operation_mode: EL70x1OperationMode::DirectVelocity,
+                        select_info_data_1: EL70x1InfoData::LoadAngle,
                         ..Default::default()
                     },
-                    stm_motor: StmMotorConfiguration {
-                        max_current: 2800,
-                        ..Default::default()
-                    },
+                    encoder: motor_configuration.encoder,
+                    stm_motor: motor_configuration.stm_motor,
+                    stm_controller_1: motor_configuration.stm_controller_1,
+                    stm_controller_2: motor_configuration.stm_controller_2,
+                    pdo_assignment:
+                        EL7041_0052PredefinedPdoAssignment::VelocityControlCompactWithInfoData,
                     ..Default::default()
                 };
 
@@ -127,21 +148,36 @@ impl MachineNewTrait for Winder2 {
                     hardware,
                     params,
                     3,
-                    vec![EL7031_IDENTITY_A, EL7031_IDENTITY_B],
+                    Self::HARDWARE_DESCRIPTOR.identities(3),
                 )
                 .await?;
 
+                // motor configuration can be changed at runtime
+                let motor_configuration = load_motor_configuration(
+                    &machine_identification_unique,
+                    3,
+                    EL70x1MotorConfiguration {
+                        stm_motor: StmMotorConfiguration {
+                            max_current: 1500,
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                );
+
                 let el7031_config = EL7031Configuration {
                     stm_features: shared_config::el70x1::StmFeatures {
                         operation_mode: EL70x1OperationMode::DirectVelocity,
                         speed_range: shared_config::el70x1::EL70x1SpeedRange::Steps1000,
+                        select_info_data_1: EL70x1InfoData::LoadAngle,
                         ..Default::default()
                     },
-                    stm_motor: StmMotorConfiguration {
-                        max_current: 1500,
-                        ..Default::default()
-                    },
-                    pdo_assignment: EL7031PredefinedPdoAssignment::VelocityControlCompact,
+                    encoder: motor_configuration.encoder,
+                    stm_motor: motor_configuration.stm_motor,
+                    stm_controller_1: motor_configuration.stm_controller_1,
+                    stm_controller_2: motor_configuration.stm_controller_2,
+                    pdo_assignment:
+                        EL7031PredefinedPdoAssignment::VelocityControlCompactWithInfoData,
                     ..Default::default()
                 };
 
@@ -161,21 +197,36 @@ impl MachineNewTrait for Winder2 {
                     hardware,
                     params,
                     4,
-                    vec![EL7031_0030_IDENTITY_A],
+                    Self::HARDWARE_DESCRIPTOR.identities(4),
                 )
                 .await?;
 
+                // motor configuration can be changed at runtime
+                let motor_configuration = load_motor_configuration(
+                    &machine_identification_unique,
+                    4,
+                    EL70x1MotorConfiguration {
+                        stm_motor: StmMotorConfiguration {
+                            max_current: 2700,
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                );
+
                 let el7031_0030_config = EL7031_0030Configuration {
                     stm_features: el7031_0030::coe::StmFeatures {
                         operation_mode: EL70x1OperationMode::DirectVelocity,
                         speed_range: shared_config::el70x1::EL70x1SpeedRange::Steps1000,
+                        select_info_data_1: EL70x1InfoData::LoadAngle,
                         ..Default::default()
                     },
-                    stm_motor: StmMotorConfiguration {
-                        max_current: 2700,
-                        ..Default::default()
-                    },
-                    pdo_assignment: EL7031_0030PredefinedPdoAssignment::VelocityControlCompact,
+                    encoder: motor_configuration.encoder,
+                    stm_motor: motor_configuration.stm_motor,
+                    stm_controller_1: motor_configuration.stm_controller_1,
+                    stm_controller_2: motor_configuration.stm_controller_2,
+                    pdo_assignment:
+                        EL7031_0030PredefinedPdoAssignment::VelocityControlCompactWithInfoData,
                     ..Default::default()
                 };
                 device
@@ -237,6 +288,9 @@ impl MachineNewTrait for Winder2 {
                     Length::new::<millimeter>(92.0), // Default outer limit
                     64,                              // Microsteps
                 ),
+                traverse_stall_detector: Self::new_stall_detector(),
+                puller_stall_detector: Self::new_stall_detector(),
+                spool_stall_detector: Self::new_stall_detector(),
                 emitted_default_state: false,
                 spool_automatic_action: super::SpoolAutomaticAction {
                     progress: Length::ZERO,
@@ -1,4 +1,7 @@
 use crate::ethercat::config::{MAX_SUBDEVICES, PDI_LEN};
+use crate::ethercat::identification_backup::IdentificationBackup;
+use crate::ethercat::identification_mapping::IdentificationConflict;
+use crate::ethercat::topology::EthercatTopology;
 use crate::rest::handlers::write_machine_device_identification::MachineDeviceInfoRequest;
 use crate::socketio::main_namespace::MainNamespaceEvents;
 use crate::socketio::main_namespace::ethercat_devices_event::EthercatDevicesEventBuilder;
@@ -8,7 +11,9 @@ use control_core::socketio::event::GenericEvent;
 use ethercat_hal::devices::EthercatDevice;
 use ethercrab::SubDeviceRef;
 use ethercrab::{MainDevice, SubDeviceGroup, subdevice_group::Op};
-use machines::machine_identification::{DeviceIdentification, MachineIdentificationUnique};
+use machines::machine_identification::{
+    DeviceHardwareIdentificationEthercat, DeviceIdentification, MachineIdentificationUnique,
+};
 use machines::serial::registry::SERIAL_DEVICE_REGISTRY;
 use machines::{Machine, MachineMessage};
 use serde::{Deserialize, Serialize};
@@ -42,8 +47,30 @@ pub enum HotThreadMessage {
     NoMsg,
     AddMachines(Vec<Box<dyn Machine>>),
     AddEtherCatSetup(EthercatSetup),
-    WriteMachineDeviceInfo(MachineDeviceInfoRequest),
+    /// Replies with the backup entry holding the previous identification
+    WriteMachineDeviceInfo(
+        MachineDeviceInfoRequest,
+        Sender<Result<IdentificationBackup, anyhow::Error>>,
+    ),
+    /// Restores the identification from the last backup of a subdevice
+    UndoMachineDeviceInfo(
+        DeviceHardwareIdentificationEthercat,
+        Sender<Result<IdentificationBackup, anyhow::Error>>,
+    ),
+    /// Writes the identifications of all devices of a machine, rolls back all of them if one fails
+    AssignMachineDeviceInfo(
+        Vec<MachineDeviceInfoRequest>,
+        Sender<Result<Vec<IdentificationBackup>, anyhow::Error>>,
+    ),
     DeleteMachine(MachineIdentificationUnique),
+    /// Removes the EtherCAT setup together with the first machines, replies with it once the group isn't used anymore
+    ///
+    /// The outputs are switched off first, the second machines are paused until the next setup is added.
+    RemoveEthercatSetup(
+        Vec<MachineIdentificationUnique>,
+        Vec<MachineIdentificationUnique>,
+        Sender<Option<EthercatSetup>>,
+    ),
 }
 
 use crate::AsyncThreadMessage;
@@ -77,6 +104,33 @@ impl EtherCatDeviceMetaData {
     }
 }
 
+/// PDO length of a subdevice that doesn't match the process image ethercrab mapped for it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EtherCatPdoLengthMismatch {
+    pub subdevice_index: usize,
+    pub configured_address: u16,
+    pub name: String,
+    pub expected_input_bits: usize,
+    pub actual_input_bits: usize,
+    pub expected_output_bits: usize,
+    pub actual_output_bits: usize,
+}
+
+impl fmt::Display for EtherCatPdoLengthMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "PDO length mismatch on subdevice {} ({}): inputs expected {} bits, got {} bits; outputs expected {} bits, got {} bits",
+            self.subdevice_index,
+            self.name,
+            self.expected_input_bits,
+            self.actual_input_bits,
+            self.expected_output_bits,
+            self.actual_output_bits
+        )
+    }
+}
+
 pub struct SharedState {
     pub socketio_setup: SocketioSetup,
     pub api_machines: Mutex<HashMap<MachineIdentificationUnique, Sender<MachineMessage>>>,
@@ -84,6 +138,14 @@ pub struct SharedState {
     pub rt_machine_creation_channel: Sender<HotThreadMessage>,
     pub main_channel: Sender<AsyncThreadMessage>,
     pub ethercat_meta_data: RwLock<Vec<EtherCatDeviceMetaData>>,
+    /// PDO length mismatches found during the last EtherCAT setup
+    pub ethercat_pdo_length_mismatches: RwLock<Vec<EtherCatPdoLengthMismatch>>,
+    /// Chain topology as read during the last EtherCAT setup
+    pub ethercat_topology: RwLock<Option<EthercatTopology>>,
+    /// Conflicts between the identification mapping file and the EEPROMs found during the last EtherCAT setup
+    pub ethercat_identification_conflicts: RwLock<Vec<IdentificationConflict>>,
+    /// Handle of the setup the loop is running, `None` while the group is set up
+    pub ethercat_setup_handle: RwLock<Option<EthercatSetupHandle>>,
 }
 
 impl fmt::Debug for EthercatSetup {
@@ -102,24 +164,44 @@ pub struct EthercatSetup {
     /// All Ethercat devices
     /// Generic interface for all devices
     /// Needed to interface with the devices on an Ethercat level
-    pub group: SubDeviceGroup<MAX_SUBDEVICES, PDI_LEN, Op>,
+    pub group: Arc<SubDeviceGroup<MAX_SUBDEVICES, PDI_LEN, Op>>,
     /// The Ethercat main device
     /// Needed to interface with the devices
-    pub maindevice: MainDevice<'static>,
+    pub maindevice: Arc<MainDevice<'static>>,
+}
+
+/// Shared access to the group of the running setup outside the loop thread
+///
+/// Register reads and mailbox transfers don't touch the process image, so they run next to the
+/// cyclic exchange instead of blocking a cycle.
+#[derive(Clone)]
+pub struct EthercatSetupHandle {
+    /// Same devices as the setup, lock them only briefly since the loop locks them every cycle
+    pub devices: Vec<(DeviceIdentification, Arc<RwLock<dyn EthercatDevice>>)>,
+    pub group: Arc<SubDeviceGroup<MAX_SUBDEVICES, PDI_LEN, Op>>,
+    pub maindevice: Arc<MainDevice<'static>>,
 }
 
 impl EthercatSetup {
     pub fn new(
         devices: Vec<(DeviceIdentification, Arc<RwLock<dyn EthercatDevice>>)>,
         group: SubDeviceGroup<MAX_SUBDEVICES, PDI_LEN, Op>,
-        maindevice: MainDevice<'static>,
+        maindevice: Arc<MainDevice<'static>>,
     ) -> Self {
         Self {
             devices,
-            group,
+            group: Arc::new(group),
             maindevice,
         }
     }
+
+    pub fn handle(&self) -> EthercatSetupHandle {
+        EthercatSetupHandle {
+            devices: self.devices.clone(),
+            group: self.group.clone(),
+            maindevice: self.maindevice.clone(),
+        }
+    }
 }
 
 impl SharedState {
@@ -171,6 +253,10 @@ impl SharedState {
         Self {
             current_machines_meta: vec![].into(),
             ethercat_meta_data: vec![].into(),
+            ethercat_pdo_length_mismatches: vec![].into(),
+            ethercat_topology: RwLock::new(None),
+            ethercat_identification_conflicts: vec![].into(),
+            ethercat_setup_handle: RwLock::new(None),
             socketio_setup: SocketioSetup {
                 socketio: RwLock::new(None),
                 namespaces: RwLock::new(Namespaces::new(socket_queue_tx.clone())),
@@ -0,0 +1,421 @@
+use ethercat_hal::devices::SubDeviceIdentityTuple;
+use machines::hardware_descriptor::MachineHardwareDescriptor;
+use machines::machine_identification::{
+    DeviceHardwareIdentificationEthercat, DeviceMachineIdentification, MachineIdentification,
+    MachineIdentificationUnique, machine_device_identification,
+    write_machine_device_identification,
+};
+use machines::registry::MACHINE_REGISTRY;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::app_state::{EthercatSetup, HotThreadMessage, SharedState};
+use crate::ethercat::identification_backup::{
+    IdentificationBackup, write_machine_device_identification_with_backup,
+};
+use crate::ethercat::setup::rescan_ethercat_setup;
+use crate::rest::handlers::write_machine_device_identification::MachineDeviceInfoRequest;
+
+/// Assigns a set of subdevices to a new machine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MachineAssignmentRequest {
+    pub machine_identification: MachineIdentification,
+    pub serial: u16,
+    /// One subdevice per EtherCAT role of the machine, in role order, `None` leaves an optional role empty
+    pub subdevice_indices: Vec<Option<usize>>,
+}
+
+/// What the assignment needs to know about a subdevice on the bus
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssignmentSubDevice {
+    pub identity: SubDeviceIdentityTuple,
+    pub device_machine_identification: Option<DeviceMachineIdentification>,
+}
+
+/// Checks the subdevices against the hardware descriptor of the machine and returns the identifications to write
+///
+/// All problems are collected into one error, so a wrongly wired machine can be fixed in one go.
+pub fn validate_machine_assignment(
+    request: &MachineAssignmentRequest,
+    hardware_descriptor: &MachineHardwareDescriptor,
+    subdevices: &[AssignmentSubDevice],
+) -> Result<Vec<MachineDeviceInfoRequest>, anyhow::Error> {
+    let ethercat_roles = hardware_descriptor.ethercat_roles;
+    let machine_identification_unique = MachineIdentificationUnique {
+        machine_identification: request.machine_identification.clone(),
+        serial: request.serial,
+    };
+    if !machine_identification_unique.is_valid() {
+        return Err(anyhow::anyhow!(
+            "[{}::validate_machine_assignment] Invalid machine identification {}",
+            module_path!(),
+            machine_identification_unique
+        ));
+    }
+    if ethercat_roles.is_empty() {
+        return Err(anyhow::anyhow!(
+            "[{}::validate_machine_assignment] Machine {} has no EtherCAT devices",
+            module_path!(),
+            machine_identification_unique
+        ));
+    }
+    if request.subdevice_indices.len() != ethercat_roles.len() {
+        return Err(anyhow::anyhow!(
+            "[{}::validate_machine_assignment] Machine {} needs {} subdevices, got {}",
+            module_path!(),
+            machine_identification_unique,
+            ethercat_roles.len(),
+            request.subdevice_indices.len()
+        ));
+    }
+
+    let mut problems = vec![];
+    for (position, (role, subdevice_index)) in ethercat_roles
+        .iter()
+        .zip(request.subdevice_indices.iter())
+        .enumerate()
+    {
+        let Some(subdevice_index) = subdevice_index else {
+            if !role.optional {
+                problems.push(format!("role {} ({}) is required", role.role, role.name));
+            }
+            continue;
+        };
+        if request.subdevice_indices[..position].contains(&Some(*subdevice_index)) {
+            problems.push(format!("subdevice {} is used twice", subdevice_index));
+            continue;
+        }
+        let Some(subdevice) = subdevices.get(*subdevice_index) else {
+            problems.push(format!("subdevice {} doesn't exist", subdevice_index));
+            continue;
+        };
+        if !role.identities.contains(&subdevice.identity) {
+            problems.push(format!(
+                "subdevice {} has identity {:x?}, role {} ({}) expects one of {:x?}",
+                subdevice_index, subdevice.identity, role.role, role.name, role.identities
+            ));
+        }
+        if let Some(existing) = subdevice
+            .device_machine_identification
+            .as_ref()
+            .filter(|existing| existing.is_valid())
+            .filter(|existing| {
+                existing.machine_identification_unique != machine_identification_unique
+            })
+        {
+            problems.push(format!(
+                "subdevice {} is already assigned to machine {} role {}",
+                subdevice_index, existing.machine_identification_unique, existing.role
+            ));
+        }
+    }
+
+    // the same machine on other subdevices would end up with every role twice
+    for (subdevice_index, subdevice) in subdevices.iter().enumerate() {
+        if request.subdevice_indices.contains(&Some(subdevice_index)) {
+            continue;
+        }
+        if let Some(existing) =
+            subdevice
+                .device_machine_identification
+                .as_ref()
+                .filter(|existing| {
+                    existing.machine_identification_unique == machine_identification_unique
+                })
+        {
+            problems.push(format!(
+                "machine {} is already assigned to subdevice {} as role {}",
+                machine_identification_unique, subdevice_index, existing.role
+            ));
+        }
+    }
+
+    if !problems.is_empty() {
+        return Err(anyhow::anyhow!(
+            "[{}::validate_machine_assignment] Can't assign machine {}: {}",
+            module_path!(),
+            machine_identification_unique,
+            problems.join("; ")
+        ));
+    }
+
+    Ok(ethercat_roles
+        .iter()
+        .zip(request.subdevice_indices.iter())
+        .filter_map(|(role, subdevice_index)| {
+            Some(MachineDeviceInfoRequest {
+                device_machine_identification: DeviceMachineIdentification {
+                    machine_identification_unique: machine_identification_unique.clone(),
+                    role: role.role,
+                },
+                hardware_identification_ethercat: DeviceHardwareIdentificationEthercat {
+                    subdevice_index: (*subdevice_index)?,
+                },
+            })
+        })
+        .collect())
+}
+
+/// Writes all identifications, if one fails the previous identifications are written back to all of them
+///
+/// Rollbacks aren't logged as backups, so an undo restores the value from before the assignment.
+pub async fn write_machine_assignment(
+    ethercat_setup: &EthercatSetup,
+    requests: &[MachineDeviceInfoRequest],
+) -> Result<Vec<IdentificationBackup>, anyhow::Error> {
+    let maindevice = &ethercat_setup.maindevice;
+
+    // read everything up front, a failed write can't be rolled back without the previous values
+    let mut previous = vec![];
+    for request in requests {
+        let subdevice_index = request.hardware_identification_ethercat.subdevice_index;
+        let subdevice = ethercat_setup
+            .group
+            .subdevice(maindevice, subdevice_index)?;
+        previous.push(machine_device_identification(&subdevice, maindevice).await?);
+    }
+
+    let mut backups = vec![];
+    for (position, request) in requests.iter().enumerate() {
+        let subdevice_index = request.hardware_identification_ethercat.subdevice_index;
+        let result = match ethercat_setup.group.subdevice(maindevice, subdevice_index) {
+            Ok(subdevice) => {
+                write_machine_device_identification_with_backup(
+                    &subdevice,
+                    maindevice,
+                    subdevice_index,
+                    &request.device_machine_identification,
+                )
+                .await
+            }
+            Err(e) => Err(e.into()),
+        };
+
+        match result {
+            Ok(backup) => backups.push(backup),
+            Err(e) => {
+                // includes the failed subdevice, its EEPROM might hold a partial write
+                let mut failed_rollbacks = vec![];
+                for (request, previous) in requests[..=position].iter().zip(previous.iter()) {
+                    let subdevice_index = request.hardware_identification_ethercat.subdevice_index;
+                    let rollback = match ethercat_setup.group.subdevice(maindevice, subdevice_index)
+                    {
+                        Ok(subdevice) => {
+                            write_machine_device_identification(&subdevice, maindevice, previous)
+                                .await
+                        }
+                        Err(e) => Err(e.into()),
+                    };
+                    if let Err(rollback_error) = rollback {
+                        tracing::error!(
+                            "Failed to roll back identification of subdevice {}: {:?}",
+                            subdevice_index,
+                            rollback_error
+                        );
+                        failed_rollbacks.push(subdevice_index);
+                    }
+                }
+                if !failed_rollbacks.is_empty() {
+                    return Err(anyhow::anyhow!(
+                        "[{}::write_machine_assignment] Failed to write subdevice {} and to roll back subdevices {:?}, check their identifications: {}",
+                        module_path!(),
+                        subdevice_index,
+                        failed_rollbacks,
+                        e
+                    ));
+                }
+                return Err(anyhow::anyhow!(
+                    "[{}::write_machine_assignment] Failed to write subdevice {}, all writes were rolled back: {}",
+                    module_path!(),
+                    subdevice_index,
+                    e
+                ));
+            }
+        }
+    }
+
+    Ok(backups)
+}
+
+/// Validates the assignment, writes it to the EEPROMs and sets the EtherCAT group up again
+///
+/// The new machine is created by the setup, the server keeps running. Setting the group up again
+/// interrupts the other EtherCAT machines like a rescan, they continue with their devices.
+pub async fn assign_machine(
+    app_state: Arc<SharedState>,
+    request: MachineAssignmentRequest,
+) -> Result<Vec<IdentificationBackup>, anyhow::Error> {
+    let hardware_descriptor = MACHINE_REGISTRY
+        .hardware_descriptor(&request.machine_identification)
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "[{}::assign_machine] Unknown machine {:?}",
+                module_path!(),
+                request.machine_identification
+            )
+        })?;
+
+    // the meta data holds the subdevices first, followed by the coupler slot modules
+    let subdevice_count = app_state
+        .ethercat_topology
+        .read()
+        .await
+        .as_ref()
+        .map(|topology| topology.nodes.len())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "[{}::assign_machine] EtherCAT setup has not finished yet",
+                module_path!()
+            )
+        })?;
+    let subdevices = app_state
+        .ethercat_meta_data
+        .read()
+        .await
+        .iter()
+        .take(subdevice_count)
+        .map(|meta| AssignmentSubDevice {
+            identity: (meta.vendor_id, meta.product_id, meta.revision),
+            device_machine_identification: meta
+                .device_identification
+                .device_machine_identification
+                .clone(),
+        })
+        .collect::<Vec<_>>();
+
+    let info_requests = validate_machine_assignment(&request, &hardware_descriptor, &subdevices)?;
+
+    let (reply_tx, reply_rx) = smol::channel::bounded(1);
+    app_state
+        .rt_machine_creation_channel
+        .send(HotThreadMessage::AssignMachineDeviceInfo(
+            info_requests,
+            reply_tx,
+        ))
+        .await
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "[{}::assign_machine] Failed to send HotThreadMessage::AssignMachineDeviceInfo {}",
+                module_path!(),
+                e
+            )
+        })?;
+    // no timeout, the writes can't be taken back once the loop has the message
+    let backups = reply_rx.recv().await.map_err(|e| {
+        anyhow::anyhow!(
+            "[{}::assign_machine] Loop dropped the EEPROM writes {}",
+            module_path!(),
+            e
+        )
+    })??;
+
+    rescan_ethercat_setup(app_state).await.map_err(|e| {
+        anyhow::anyhow!(
+            "[{}::assign_machine] Identifications were written but the EtherCAT setup failed, restart the server: {}",
+            module_path!(),
+            e
+        )
+    })?;
+
+    Ok(backups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use machines::hardware_descriptor::EthercatRole;
+
+    const COUPLER: SubDeviceIdentityTuple = (0x2, 0x044c2c52, 0x00120000);
+    const OUTPUTS: SubDeviceIdentityTuple = (0x2, 0x07d43052, 0x00100000);
+
+    const DESCRIPTOR: &MachineHardwareDescriptor = &MachineHardwareDescriptor {
+        ethercat_roles: &[
+            EthercatRole::optional(0, "Bus coupler", &[COUPLER]),
+            EthercatRole::required(1, "Digital outputs", &[OUTPUTS]),
+        ],
+    };
+
+    fn request(subdevice_indices: Vec<Option<usize>>) -> MachineAssignmentRequest {
+        MachineAssignmentRequest {
+            machine_identification: MachineIdentification {
+                vendor: 1,
+                machine: 2,
+            },
+            serial: 7,
+            subdevice_indices,
+        }
+    }
+
+    fn subdevice(identity: SubDeviceIdentityTuple) -> AssignmentSubDevice {
+        AssignmentSubDevice {
+            identity,
+            device_machine_identification: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_machine_assignment() {
+        let subdevices = [subdevice(COUPLER), subdevice(OUTPUTS), subdevice(OUTPUTS)];
+
+        let requests =
+            validate_machine_assignment(&request(vec![Some(0), Some(2)]), DESCRIPTOR, &subdevices)
+                .unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].device_machine_identification.role, 1);
+        assert_eq!(
+            requests[1].hardware_identification_ethercat.subdevice_index,
+            2
+        );
+
+        // wrong identity for role 0
+        assert!(
+            validate_machine_assignment(&request(vec![Some(1), Some(2)]), DESCRIPTOR, &subdevices)
+                .is_err()
+        );
+        // missing role
+        assert!(
+            validate_machine_assignment(&request(vec![Some(0)]), DESCRIPTOR, &subdevices).is_err()
+        );
+        assert!(
+            validate_machine_assignment(&request(vec![Some(0), None]), DESCRIPTOR, &subdevices)
+                .is_err()
+        );
+        // the coupler is optional
+        let requests =
+            validate_machine_assignment(&request(vec![None, Some(1)]), DESCRIPTOR, &subdevices)
+                .unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].device_machine_identification.role, 1);
+        // out of range
+        assert!(
+            validate_machine_assignment(&request(vec![Some(0), Some(3)]), DESCRIPTOR, &subdevices)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_validate_machine_assignment_already_assigned() {
+        let mut subdevices = [subdevice(COUPLER), subdevice(OUTPUTS), subdevice(OUTPUTS)];
+        subdevices[2].device_machine_identification = Some(DeviceMachineIdentification {
+            machine_identification_unique: MachineIdentificationUnique {
+                machine_identification: MachineIdentification {
+                    vendor: 1,
+                    machine: 2,
+                },
+                serial: 7,
+            },
+            role: 1,
+        });
+
+        // the machine already owns subdevice 2
+        assert!(
+            validate_machine_assignment(&request(vec![Some(0), Some(1)]), DESCRIPTOR, &subdevices)
+                .is_err()
+        );
+        // reassigning the same subdevice to the same machine is fine
+        assert!(
+            validate_machine_assignment(&request(vec![Some(0), Some(2)]), DESCRIPTOR, &subdevices)
+                .is_ok()
+        );
+    }
+}
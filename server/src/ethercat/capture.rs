@@ -0,0 +1,511 @@
+use std::collections::VecDeque;
+use std::ffi::CString;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// EtherType of EtherCAT frames
+pub const ETHERTYPE_ETHERCAT: u16 = 0x88A4;
+
+/// Default number of frames kept in the ring
+///
+/// With two frames per cycle (sent and returned) this covers roughly one second at a 700us cycle.
+pub const DEFAULT_CAPTURE_CAPACITY: usize = 3000;
+
+/// Frames captured after a dump was requested before the ring is written to disk
+const POST_TRIGGER_FRAMES: usize = 500;
+
+/// Maximum time to wait for the post trigger frames, the bus might be completely silent
+const POST_TRIGGER_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Environment variable to start the capture as soon as the EtherCAT interface is found
+///
+/// The value is the ring capacity in frames.
+pub const CAPTURE_ENV_VAR: &str = "ETHERCAT_CAPTURE_FRAMES";
+
+/// pcapng link type for Ethernet, Wireshark dissects EtherType 0x88A4 as EtherCAT
+const LINKTYPE_ETHERNET: u16 = 1;
+
+/// Largest Ethernet frame we expect on the EtherCAT interface
+const SNAPLEN: u32 = 1518;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedFrame {
+    /// Nanoseconds since the unix epoch
+    pub timestamp_ns: u64,
+    pub data: Vec<u8>,
+}
+
+/// Bounded ring of captured frames, the oldest frame is dropped when full
+#[derive(Debug)]
+pub struct FrameRing {
+    frames: VecDeque<CapturedFrame>,
+    capacity: usize,
+    /// Number of frames dropped because the ring was full
+    dropped: u64,
+}
+
+impl FrameRing {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            frames: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+            dropped: 0,
+        }
+    }
+
+    pub fn push(&mut self, frame: CapturedFrame) {
+        if self.frames.len() >= self.capacity {
+            self.frames.pop_front();
+            self.dropped = self.dropped.saturating_add(1);
+        }
+        self.frames.push_back(frame);
+    }
+
+    pub fn frames(&self) -> impl Iterator<Item = &CapturedFrame> {
+        self.frames.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+}
+
+fn pad_to_4(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+fn write_option<W: Write>(writer: &mut W, code: u16, value: &[u8]) -> std::io::Result<()> {
+    writer.write_all(&code.to_le_bytes())?;
+    writer.write_all(&(value.len() as u16).to_le_bytes())?;
+    writer.write_all(value)?;
+    writer.write_all(&[0u8; 3][..pad_to_4(value.len()) - value.len()])
+}
+
+/// Writes the frames as a pcapng file with a single Ethernet interface
+///
+/// Timestamps are written with nanosecond resolution (`if_tsresol` = 9).
+pub fn write_pcapng<'a, W: Write>(
+    writer: &mut W,
+    iface: &str,
+    frames: impl Iterator<Item = &'a CapturedFrame>,
+) -> std::io::Result<()> {
+    // Section Header Block
+    let shb_len: u32 = 28;
+    writer.write_all(&0x0A0D0D0Au32.to_le_bytes())?;
+    writer.write_all(&shb_len.to_le_bytes())?;
+    writer.write_all(&0x1A2B3C4Du32.to_le_bytes())?;
+    writer.write_all(&1u16.to_le_bytes())?;
+    writer.write_all(&0u16.to_le_bytes())?;
+    writer.write_all(&(-1i64).to_le_bytes())?;
+    writer.write_all(&shb_len.to_le_bytes())?;
+
+    // Interface Description Block with if_name, if_tsresol and opt_endofopt
+    let name = iface.as_bytes();
+    let options_len = 4 + pad_to_4(name.len()) + 4 + 4 + 4;
+    let idb_len = (20 + options_len) as u32;
+    writer.write_all(&1u32.to_le_bytes())?;
+    writer.write_all(&idb_len.to_le_bytes())?;
+    writer.write_all(&LINKTYPE_ETHERNET.to_le_bytes())?;
+    writer.write_all(&0u16.to_le_bytes())?;
+    writer.write_all(&SNAPLEN.to_le_bytes())?;
+    write_option(writer, 2, name)?;
+    write_option(writer, 9, &[9])?;
+    write_option(writer, 0, &[])?;
+    writer.write_all(&idb_len.to_le_bytes())?;
+
+    // Enhanced Packet Blocks
+    for frame in frames {
+        let captured_len = frame.data.len().min(SNAPLEN as usize);
+        let epb_len = (32 + pad_to_4(captured_len)) as u32;
+        writer.write_all(&6u32.to_le_bytes())?;
+        writer.write_all(&epb_len.to_le_bytes())?;
+        writer.write_all(&0u32.to_le_bytes())?;
+        writer.write_all(&((frame.timestamp_ns >> 32) as u32).to_le_bytes())?;
+        writer.write_all(&(frame.timestamp_ns as u32).to_le_bytes())?;
+        writer.write_all(&(captured_len as u32).to_le_bytes())?;
+        writer.write_all(&(frame.data.len() as u32).to_le_bytes())?;
+        writer.write_all(&frame.data[..captured_len])?;
+        writer.write_all(&[0u8; 3][..pad_to_4(captured_len) - captured_len])?;
+        writer.write_all(&epb_len.to_le_bytes())?;
+    }
+
+    Ok(())
+}
+
+/// Returns true if the raw Ethernet frame carries EtherCAT
+pub fn is_ethercat_frame(frame: &[u8]) -> bool {
+    frame.len() >= 14 && u16::from_be_bytes([frame[12], frame[13]]) == ETHERTYPE_ETHERCAT
+}
+
+struct FrameCaptureState {
+    ring: Mutex<FrameRing>,
+    iface: Mutex<Option<String>>,
+    running: AtomicBool,
+    dump_requested: AtomicBool,
+    /// Incremented on every start and stop, a capture thread exits once it differs from its own
+    session: AtomicU64,
+    thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+static FRAME_CAPTURE: OnceLock<FrameCaptureState> = OnceLock::new();
+
+fn capture_state() -> &'static FrameCaptureState {
+    FRAME_CAPTURE.get_or_init(|| FrameCaptureState {
+        ring: Mutex::new(FrameRing::new(DEFAULT_CAPTURE_CAPACITY)),
+        iface: Mutex::new(None),
+        running: AtomicBool::new(false),
+        dump_requested: AtomicBool::new(false),
+        session: AtomicU64::new(0),
+        thread: Mutex::new(None),
+    })
+}
+
+/// Status of the frame capture for the REST API
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FrameCaptureStatus {
+    pub running: bool,
+    pub iface: Option<String>,
+    pub frames: usize,
+    pub capacity: usize,
+    pub dropped: u64,
+}
+
+pub fn frame_capture_status() -> FrameCaptureStatus {
+    let state = capture_state();
+    let ring = state.ring.lock().unwrap();
+    FrameCaptureStatus {
+        running: state.running.load(Ordering::Relaxed),
+        iface: state.iface.lock().unwrap().clone(),
+        frames: ring.len(),
+        capacity: ring.capacity(),
+        dropped: ring.dropped(),
+    }
+}
+
+pub fn is_frame_capture_running() -> bool {
+    capture_state().running.load(Ordering::Relaxed)
+}
+
+/// Starts capturing EtherCAT frames on `iface` into a fresh ring of `capacity` frames
+///
+/// Spawns a thread with its own raw socket, the EtherCAT loop itself is not touched.
+/// Blocks while the thread of a previous session exits, async callers use `smol::unblock`.
+pub fn start_frame_capture(iface: &str, capacity: usize) -> Result<(), anyhow::Error> {
+    let state = capture_state();
+    if state.running.swap(true, Ordering::SeqCst) {
+        return Err(anyhow::anyhow!(
+            "[{}::start_frame_capture] Capture is already running",
+            module_path!()
+        ));
+    }
+
+    // the thread of a stopped session might still be waiting in `recv`
+    let session = state.session.fetch_add(1, Ordering::SeqCst) + 1;
+    join_capture_thread();
+
+    let fd = match open_capture_socket(iface) {
+        Ok(fd) => fd,
+        Err(e) => {
+            state.running.store(false, Ordering::SeqCst);
+            return Err(e);
+        }
+    };
+
+    *state.ring.lock().unwrap() = FrameRing::new(capacity);
+    *state.iface.lock().unwrap() = Some(iface.to_string());
+    state.dump_requested.store(false, Ordering::SeqCst);
+
+    let res = std::thread::Builder::new()
+        .name("ethercat-capture".to_owned())
+        .spawn(move || capture_thread(fd, session));
+
+    let handle = match res {
+        Ok(handle) => handle,
+        Err(e) => {
+            unsafe { libc::close(fd) };
+            state.running.store(false, Ordering::SeqCst);
+            return Err(anyhow::anyhow!(
+                "[{}::start_frame_capture] Failed to spawn capture thread: {}",
+                module_path!(),
+                e
+            ));
+        }
+    };
+    *state.thread.lock().unwrap() = Some(handle);
+
+    tracing::info!(
+        "Started EtherCAT frame capture on {} with {} frames",
+        iface,
+        capacity
+    );
+    Ok(())
+}
+
+/// Starts the capture if [`CAPTURE_ENV_VAR`] is set
+pub fn start_frame_capture_from_env(iface: &str) {
+    let Ok(value) = std::env::var(CAPTURE_ENV_VAR) else {
+        return;
+    };
+    let capacity = value.parse::<usize>().unwrap_or(DEFAULT_CAPTURE_CAPACITY);
+    if let Err(e) = start_frame_capture(iface, capacity) {
+        tracing::error!("Failed to start EtherCAT frame capture: {:?}", e);
+    }
+}
+
+/// Stops the capture thread, the ring is kept so it can still be downloaded
+///
+/// Blocks until the thread has exited, at most the receive timeout of its socket, async callers
+/// use `smol::unblock`.
+pub fn stop_frame_capture() {
+    let state = capture_state();
+    state.session.fetch_add(1, Ordering::SeqCst);
+    join_capture_thread();
+    state.running.store(false, Ordering::SeqCst);
+}
+
+fn join_capture_thread() {
+    let handle = capture_state().thread.lock().unwrap().take();
+    if let Some(Err(_)) = handle.map(JoinHandle::join) {
+        tracing::error!("EtherCAT frame capture thread panicked");
+    }
+}
+
+/// Requests a dump of the ring once some more frames after the trigger were captured
+///
+/// Only sets a flag so it is cheap enough to call from the loop thread.
+pub fn request_frame_capture_dump() {
+    let state = capture_state();
+    if state.running.load(Ordering::Relaxed) {
+        state.dump_requested.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Returns the current ring as pcapng file
+pub fn frame_capture_pcapng() -> Vec<u8> {
+    let state = capture_state();
+    let iface = state.iface.lock().unwrap().clone().unwrap_or_default();
+    let ring = state.ring.lock().unwrap();
+    let mut buf = Vec::new();
+    // writing into a Vec can't fail
+    let _ = write_pcapng(&mut buf, &iface, ring.frames());
+    buf
+}
+
+/// Writes the current ring to `ethercat_capture_<reason>_<unix_ms>.pcapng` in the working directory
+pub fn dump_frame_capture(reason: &str) -> Result<PathBuf, anyhow::Error> {
+    let unix_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let path = PathBuf::from(format!("ethercat_capture_{reason}_{unix_ms}.pcapng"));
+
+    std::fs::write(&path, frame_capture_pcapng()).map_err(|e| {
+        anyhow::anyhow!(
+            "[{}::dump_frame_capture] Failed to write {}: {}",
+            module_path!(),
+            path.display(),
+            e
+        )
+    })?;
+
+    tracing::info!("Wrote EtherCAT frame capture to {}", path.display());
+    Ok(path)
+}
+
+fn open_capture_socket(iface: &str) -> Result<libc::c_int, anyhow::Error> {
+    let iface_c = CString::new(iface).map_err(|e| {
+        anyhow::anyhow!(
+            "[{}::open_capture_socket] Invalid interface name: {}",
+            module_path!(),
+            e
+        )
+    })?;
+
+    let ifindex = unsafe { libc::if_nametoindex(iface_c.as_ptr()) };
+    if ifindex == 0 {
+        return Err(anyhow::anyhow!(
+            "[{}::open_capture_socket] Interface {} not found",
+            module_path!(),
+            iface
+        ));
+    }
+
+    // ETH_P_ALL is needed to also see the outgoing frames
+    let protocol = (libc::ETH_P_ALL as u16).to_be();
+    let fd = unsafe { libc::socket(libc::AF_PACKET, libc::SOCK_RAW, protocol as libc::c_int) };
+    if fd < 0 {
+        return Err(anyhow::anyhow!(
+            "[{}::open_capture_socket] Failed to open raw socket: {}",
+            module_path!(),
+            std::io::Error::last_os_error()
+        ));
+    }
+
+    let mut addr: libc::sockaddr_ll = unsafe { std::mem::zeroed() };
+    addr.sll_family = libc::AF_PACKET as u16;
+    addr.sll_protocol = protocol;
+    addr.sll_ifindex = ifindex as i32;
+
+    let timeout = libc::timeval {
+        tv_sec: 0,
+        tv_usec: 100_000,
+    };
+
+    let bind_res = unsafe {
+        libc::bind(
+            fd,
+            &addr as *const libc::sockaddr_ll as *const libc::sockaddr,
+            std::mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t,
+        )
+    };
+    let timeout_res = unsafe {
+        libc::setsockopt(
+            fd,
+            libc::SOL_SOCKET,
+            libc::SO_RCVTIMEO,
+            &timeout as *const libc::timeval as *const libc::c_void,
+            std::mem::size_of::<libc::timeval>() as libc::socklen_t,
+        )
+    };
+
+    if bind_res < 0 || timeout_res < 0 {
+        let err = std::io::Error::last_os_error();
+        unsafe { libc::close(fd) };
+        return Err(anyhow::anyhow!(
+            "[{}::open_capture_socket] Failed to bind raw socket to {}: {}",
+            module_path!(),
+            iface,
+            err
+        ));
+    }
+
+    Ok(fd)
+}
+
+fn capture_thread(fd: libc::c_int, session: u64) {
+    let state = capture_state();
+    let mut buf = [0u8; SNAPLEN as usize];
+    // frames captured since the dump was requested and when it was requested
+    let mut post_trigger: Option<(usize, Instant)> = None;
+
+    let is_current = || state.session.load(Ordering::SeqCst) == session;
+
+    while is_current() {
+        let n = unsafe { libc::recv(fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len(), 0) };
+
+        // don't push into the ring of a newer session
+        if n > 0 && is_ethercat_frame(&buf[..n as usize]) && is_current() {
+            let timestamp_ns = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos() as u64;
+            state.ring.lock().unwrap().push(CapturedFrame {
+                timestamp_ns,
+                data: buf[..n as usize].to_vec(),
+            });
+            if let Some((frames, _)) = post_trigger.as_mut() {
+                *frames += 1;
+            }
+        }
+
+        if post_trigger.is_none() && state.dump_requested.swap(false, Ordering::Relaxed) {
+            post_trigger = Some((0, Instant::now()));
+        }
+
+        if let Some((frames, since)) = post_trigger {
+            if frames >= POST_TRIGGER_FRAMES || since.elapsed() >= POST_TRIGGER_TIMEOUT {
+                post_trigger = None;
+                if let Err(e) = dump_frame_capture("txrx_failures") {
+                    tracing::error!("{:?}", e);
+                }
+            }
+        }
+    }
+
+    unsafe { libc::close(fd) };
+    tracing::info!("Stopped EtherCAT frame capture");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(timestamp_ns: u64, len: usize) -> CapturedFrame {
+        CapturedFrame {
+            timestamp_ns,
+            data: vec![0xAB; len],
+        }
+    }
+
+    #[test]
+    fn test_frame_ring_drops_oldest() {
+        let mut ring = FrameRing::new(2);
+        ring.push(frame(1, 60));
+        ring.push(frame(2, 60));
+        ring.push(frame(3, 60));
+
+        let timestamps = ring.frames().map(|f| f.timestamp_ns).collect::<Vec<_>>();
+        assert_eq!(timestamps, vec![2, 3]);
+        assert_eq!(ring.dropped(), 1);
+    }
+
+    #[test]
+    fn test_is_ethercat_frame() {
+        let mut data = vec![0u8; 60];
+        data[12] = 0x88;
+        data[13] = 0xA4;
+        assert!(is_ethercat_frame(&data));
+
+        data[13] = 0x00;
+        assert!(!is_ethercat_frame(&data));
+        assert!(!is_ethercat_frame(&[0u8; 10]));
+    }
+
+    #[test]
+    fn test_write_pcapng_layout() {
+        let frames = [frame(0x1_0000_0002, 61)];
+        let mut buf = Vec::new();
+        write_pcapng(&mut buf, "eth0", frames.iter()).unwrap();
+
+        // SHB
+        assert_eq!(&buf[0..4], &0x0A0D0D0Au32.to_le_bytes());
+        assert_eq!(&buf[8..12], &0x1A2B3C4Du32.to_le_bytes());
+
+        // IDB: 20 bytes header + if_name (4 + 4) + if_tsresol (4 + 4) + end (4)
+        let idb = &buf[28..];
+        assert_eq!(u32::from_le_bytes(idb[0..4].try_into().unwrap()), 1);
+        let idb_len = u32::from_le_bytes(idb[4..8].try_into().unwrap()) as usize;
+        assert_eq!(idb_len, 40);
+        assert_eq!(u16::from_le_bytes(idb[8..10].try_into().unwrap()), 1);
+
+        // EPB with padded data
+        let epb = &idb[idb_len..];
+        assert_eq!(u32::from_le_bytes(epb[0..4].try_into().unwrap()), 6);
+        let epb_len = u32::from_le_bytes(epb[4..8].try_into().unwrap()) as usize;
+        assert_eq!(epb_len, 32 + 64);
+        assert_eq!(u32::from_le_bytes(epb[12..16].try_into().unwrap()), 1);
+        assert_eq!(u32::from_le_bytes(epb[16..20].try_into().unwrap()), 2);
+        assert_eq!(u32::from_le_bytes(epb[20..24].try_into().unwrap()), 61);
+        assert_eq!(epb.len(), epb_len);
+        assert_eq!(
+            u32::from_le_bytes(epb[epb_len - 4..].try_into().unwrap()),
+            epb_len as u32
+        );
+    }
+}
@@ -0,0 +1,277 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ethercrab::{MainDevice, SubDeviceIdentity};
+use machines::machine_identification::{
+    DeviceMachineIdentification, machine_device_identification, write_machine_device_identification,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::ethercat::config::PDI_LEN;
+use ethercat_hal::helpers::ethercrab_types::EthercrabSubDeviceOperational;
+
+/// Append-only log of all identification writes, one JSON object per line
+pub const IDENTIFICATION_BACKUP_PATH: &str = "identification_backups.jsonl";
+
+/// ESC identity of the subdevice, so a backup is only restored to the same physical terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupSubDeviceIdentity {
+    pub vendor_id: u32,
+    pub product_id: u32,
+    pub revision: u32,
+    pub serial: u32,
+}
+
+impl From<SubDeviceIdentity> for BackupSubDeviceIdentity {
+    fn from(identity: SubDeviceIdentity) -> Self {
+        Self {
+            vendor_id: identity.vendor_id,
+            product_id: identity.product_id,
+            revision: identity.revision,
+            serial: identity.serial,
+        }
+    }
+}
+
+/// Identification of a subdevice before and after a write
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdentificationBackup {
+    pub timestamp_ms: u128,
+    pub subdevice_index: usize,
+    pub subdevice_name: String,
+    pub subdevice_identity: BackupSubDeviceIdentity,
+    pub previous: DeviceMachineIdentification,
+    pub written: DeviceMachineIdentification,
+    /// Timestamp of the entry this write undid, `None` for regular writes
+    #[serde(default)]
+    pub undo_of: Option<u128>,
+}
+
+/// Appends a backup entry, the file is flushed before the EEPROM is touched
+pub fn append_identification_backup<P: AsRef<Path>>(
+    path: P,
+    backup: &IdentificationBackup,
+) -> Result<(), anyhow::Error> {
+    let line = serde_json::to_string(backup)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)?;
+    file.sync_all()?;
+    Ok(())
+}
+
+/// Finds the most recent write of the subdevice at `subdevice_index` that wasn't undone yet
+///
+/// Only entries with the same ESC identity are considered. Undo entries and the entries they undid
+/// are skipped, so undoing repeatedly walks back through the history. Lines that can't be parsed
+/// are skipped.
+pub fn find_identification_backup_to_undo(
+    content: &str,
+    subdevice_index: usize,
+    subdevice_identity: &BackupSubDeviceIdentity,
+) -> Option<IdentificationBackup> {
+    let backups: Vec<IdentificationBackup> = content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<IdentificationBackup>(line).ok())
+        .filter(|backup| {
+            backup.subdevice_index == subdevice_index
+                && &backup.subdevice_identity == subdevice_identity
+        })
+        .collect();
+    let undone: Vec<u128> = backups.iter().filter_map(|backup| backup.undo_of).collect();
+    backups
+        .into_iter()
+        .rev()
+        .find(|backup| backup.undo_of.is_none() && !undone.contains(&backup.timestamp_ms))
+}
+
+/// Writes the identification after backing up the previous one and verifies it by reading it back
+///
+/// Returns the backup entry, which holds the previous identification.
+pub async fn write_machine_device_identification_with_backup(
+    subdevice: &EthercrabSubDeviceOperational<'_, PDI_LEN>,
+    maindevice: &MainDevice<'_>,
+    subdevice_index: usize,
+    device_identification: &DeviceMachineIdentification,
+) -> Result<IdentificationBackup, anyhow::Error> {
+    write_with_backup(
+        subdevice,
+        maindevice,
+        subdevice_index,
+        device_identification,
+        None,
+    )
+    .await
+}
+
+async fn write_with_backup(
+    subdevice: &EthercrabSubDeviceOperational<'_, PDI_LEN>,
+    maindevice: &MainDevice<'_>,
+    subdevice_index: usize,
+    device_identification: &DeviceMachineIdentification,
+    undo_of: Option<u128>,
+) -> Result<IdentificationBackup, anyhow::Error> {
+    let previous = machine_device_identification(subdevice, maindevice).await?;
+
+    let backup = IdentificationBackup {
+        timestamp_ms: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis(),
+        subdevice_index,
+        subdevice_name: subdevice.name().to_string(),
+        subdevice_identity: subdevice.identity().into(),
+        previous,
+        written: device_identification.clone(),
+        undo_of,
+    };
+    append_identification_backup(IDENTIFICATION_BACKUP_PATH, &backup).map_err(|e| {
+        anyhow::anyhow!(
+            "[{}::write_machine_device_identification_with_backup] Failed to write backup, EEPROM was not touched: {}",
+            module_path!(),
+            e
+        )
+    })?;
+
+    write_machine_device_identification(subdevice, maindevice, device_identification).await?;
+
+    let read_back = machine_device_identification(subdevice, maindevice).await?;
+    if &read_back != device_identification {
+        return Err(anyhow::anyhow!(
+            "[{}::write_machine_device_identification_with_backup] Verification failed for subdevice {}: wrote {:?} but read back {:?}",
+            module_path!(),
+            subdevice_index,
+            device_identification,
+            read_back
+        ));
+    }
+
+    Ok(backup)
+}
+
+/// Writes the identification from before the last write that wasn't undone yet back to the EEPROM
+///
+/// The undo gets its own backup entry marked with `undo_of`, so undoing again restores the write
+/// before that one instead of toggling between two values.
+pub async fn undo_machine_device_identification(
+    subdevice: &EthercrabSubDeviceOperational<'_, PDI_LEN>,
+    maindevice: &MainDevice<'_>,
+    subdevice_index: usize,
+) -> Result<IdentificationBackup, anyhow::Error> {
+    let content = std::fs::read_to_string(IDENTIFICATION_BACKUP_PATH).unwrap_or_default();
+    let last = find_identification_backup_to_undo(
+        &content,
+        subdevice_index,
+        &subdevice.identity().into(),
+    )
+    .ok_or_else(|| {
+        anyhow::anyhow!(
+            "[{}::undo_machine_device_identification] No write left to undo for subdevice {}",
+            module_path!(),
+            subdevice_index
+        )
+    })?;
+
+    write_with_backup(
+        subdevice,
+        maindevice,
+        subdevice_index,
+        &last.previous,
+        Some(last.timestamp_ms),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use machines::machine_identification::{MachineIdentification, MachineIdentificationUnique};
+
+    fn identification(serial: u16) -> DeviceMachineIdentification {
+        DeviceMachineIdentification {
+            machine_identification_unique: MachineIdentificationUnique {
+                machine_identification: MachineIdentification {
+                    vendor: 1,
+                    machine: 2,
+                },
+                serial,
+            },
+            role: 0,
+        }
+    }
+
+    fn backup(subdevice_index: usize, serial: u32, previous: u16) -> IdentificationBackup {
+        IdentificationBackup {
+            timestamp_ms: previous as u128,
+            subdevice_index,
+            subdevice_name: "EL2008".to_string(),
+            subdevice_identity: BackupSubDeviceIdentity {
+                vendor_id: 2,
+                product_id: 0x07d83052,
+                revision: 0x00110000,
+                serial,
+            },
+            previous: identification(previous),
+            written: identification(previous + 1),
+            undo_of: None,
+        }
+    }
+
+    #[test]
+    fn test_find_identification_backup_to_undo() {
+        let content = [
+            serde_json::to_string(&backup(1, 42, 10)).unwrap(),
+            "not json".to_string(),
+            serde_json::to_string(&backup(1, 42, 11)).unwrap(),
+            serde_json::to_string(&backup(2, 42, 20)).unwrap(),
+            serde_json::to_string(&backup(1, 43, 30)).unwrap(),
+        ]
+        .join("\n");
+
+        let identity = backup(1, 42, 0).subdevice_identity;
+        let last = find_identification_backup_to_undo(&content, 1, &identity).unwrap();
+        assert_eq!(last.previous, identification(11));
+
+        // a different terminal at the same position doesn't match
+        let other = backup(3, 44, 0).subdevice_identity;
+        assert!(find_identification_backup_to_undo(&content, 1, &other).is_none());
+    }
+
+    #[test]
+    fn test_undo_walks_back_through_history() {
+        let first = backup(1, 42, 10);
+        let second = backup(1, 42, 11);
+        let identity = first.subdevice_identity;
+        let mut lines = vec![
+            serde_json::to_string(&first).unwrap(),
+            serde_json::to_string(&second).unwrap(),
+        ];
+
+        // the first undo restores the value before the second write
+        let target = find_identification_backup_to_undo(&lines.join("\n"), 1, &identity).unwrap();
+        assert_eq!(target, second);
+        let mut undo = backup(1, 42, 12);
+        undo.undo_of = Some(second.timestamp_ms);
+        lines.push(serde_json::to_string(&undo).unwrap());
+
+        // the second undo skips the undo entry and the undone write
+        let target = find_identification_backup_to_undo(&lines.join("\n"), 1, &identity).unwrap();
+        assert_eq!(target, first);
+        let mut undo = backup(1, 42, 13);
+        undo.undo_of = Some(first.timestamp_ms);
+        lines.push(serde_json::to_string(&undo).unwrap());
+
+        assert!(find_identification_backup_to_undo(&lines.join("\n"), 1, &identity).is_none());
+
+        // entries written before `undo_of` existed still parse
+        let legacy = serde_json::to_string(&first)
+            .unwrap()
+            .replace(",\"undo_of\":null", "");
+        assert!(!legacy.contains("undo_of"));
+        assert_eq!(
+            find_identification_backup_to_undo(&legacy, 1, &identity),
+            Some(first)
+        );
+    }
+}
@@ -0,0 +1,397 @@
+use std::fmt;
+use std::path::Path;
+
+use ethercrab::SubDeviceIdentity;
+use machines::machine_identification::{DeviceMachineIdentification, MachineIdentificationUnique};
+use serde::{Deserialize, Serialize};
+
+/// Optional mapping file for terminals without a written identification, a JSON array of [`IdentificationMappingEntry`]
+pub const IDENTIFICATION_MAPPING_PATH: &str = "identification_mapping.json";
+
+/// How a mapping entry finds its subdevice
+///
+/// If several entries match the same subdevice the most specific one wins: serial, then position, then identity.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum IdentificationMappingKey {
+    /// ESC serial number, only usable on terminals that have one programmed
+    Serial {
+        vendor_id: u32,
+        product_id: u32,
+        serial: u32,
+    },
+    /// Position in the chain, changes when terminals are added or reordered
+    Position { subdevice_index: usize },
+    /// Product identity, must match exactly one subdevice on the bus
+    Identity {
+        vendor_id: u32,
+        product_id: u32,
+        revision: Option<u32>,
+    },
+}
+
+impl IdentificationMappingKey {
+    /// Lower is more specific
+    const fn precedence(&self) -> u8 {
+        match self {
+            Self::Serial { .. } => 0,
+            Self::Position { .. } => 1,
+            Self::Identity { .. } => 2,
+        }
+    }
+
+    fn matches(&self, subdevice_index: usize, identity: &SubDeviceIdentity) -> bool {
+        match self {
+            Self::Serial {
+                vendor_id,
+                product_id,
+                serial,
+            } => {
+                *serial != 0
+                    && identity.vendor_id == *vendor_id
+                    && identity.product_id == *product_id
+                    && identity.serial == *serial
+            }
+            Self::Position {
+                subdevice_index: index,
+            } => *index == subdevice_index,
+            Self::Identity {
+                vendor_id,
+                product_id,
+                revision,
+            } => {
+                identity.vendor_id == *vendor_id
+                    && identity.product_id == *product_id
+                    && revision.is_none_or(|revision| identity.revision == revision)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct IdentificationMappingEntry {
+    pub key: IdentificationMappingKey,
+    pub machine_identification_unique: MachineIdentificationUnique,
+    pub role: u16,
+}
+
+impl IdentificationMappingEntry {
+    fn device_machine_identification(&self) -> DeviceMachineIdentification {
+        DeviceMachineIdentification {
+            machine_identification_unique: self.machine_identification_unique.clone(),
+            role: self.role,
+        }
+    }
+}
+
+/// Problem found while merging the mapping file with the EEPROM identifications
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct IdentificationConflict {
+    /// `None` if the conflict doesn't belong to a single subdevice
+    pub subdevice_index: Option<usize>,
+    pub message: String,
+}
+
+impl IdentificationConflict {
+    fn new(subdevice_index: Option<usize>, message: String) -> Self {
+        Self {
+            subdevice_index,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for IdentificationConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.subdevice_index {
+            Some(subdevice_index) => write!(f, "Subdevice {}: {}", subdevice_index, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Reads the mapping file, a missing file is an empty mapping
+pub fn read_identification_mapping<P: AsRef<Path>>(
+    path: P,
+) -> Result<Vec<IdentificationMappingEntry>, anyhow::Error> {
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => {
+            return Err(anyhow::anyhow!(
+                "[{}::read_identification_mapping] Failed to read {}: {}",
+                module_path!(),
+                path.as_ref().display(),
+                e
+            ));
+        }
+    };
+    serde_json::from_str(&content).map_err(|e| {
+        anyhow::anyhow!(
+            "[{}::read_identification_mapping] Failed to parse {}: {}",
+            module_path!(),
+            path.as_ref().display(),
+            e
+        )
+    })
+}
+
+/// Merges the mapping into the identifications read from the EEPROMs
+///
+/// `identities` and `eeprom_identifications` are indexed by subdevice index.
+/// A matching mapping entry overrides the EEPROM, a differing valid EEPROM identification is reported.
+pub fn merge_identification_mapping(
+    identities: &[SubDeviceIdentity],
+    eeprom_identifications: &[Option<DeviceMachineIdentification>],
+    mapping: &[IdentificationMappingEntry],
+) -> (
+    Vec<Option<DeviceMachineIdentification>>,
+    Vec<IdentificationConflict>,
+) {
+    let mut conflicts = vec![];
+
+    // (precedence, entry index) of the entry used for each subdevice
+    let mut chosen: Vec<Option<(u8, usize)>> = vec![None; identities.len()];
+    for (entry_index, entry) in mapping.iter().enumerate() {
+        if !entry.machine_identification_unique.is_valid() {
+            conflicts.push(IdentificationConflict::new(
+                None,
+                format!(
+                    "Mapping entry {} has an invalid machine identification {}",
+                    entry_index, entry.machine_identification_unique
+                ),
+            ));
+            continue;
+        }
+
+        let matching = identities
+            .iter()
+            .enumerate()
+            .filter(|(subdevice_index, identity)| entry.key.matches(*subdevice_index, identity))
+            .map(|(subdevice_index, _)| subdevice_index)
+            .collect::<Vec<_>>();
+        let subdevice_index = match matching.as_slice() {
+            [subdevice_index] => *subdevice_index,
+            [] => {
+                conflicts.push(IdentificationConflict::new(
+                    None,
+                    format!(
+                        "Mapping entry {} ({:?}) matches no subdevice",
+                        entry_index, entry.key
+                    ),
+                ));
+                continue;
+            }
+            _ => {
+                conflicts.push(IdentificationConflict::new(
+                    None,
+                    format!(
+                        "Mapping entry {} ({:?}) is ambiguous, it matches subdevices {:?}",
+                        entry_index, entry.key, matching
+                    ),
+                ));
+                continue;
+            }
+        };
+
+        let precedence = entry.key.precedence();
+        match chosen[subdevice_index] {
+            None => chosen[subdevice_index] = Some((precedence, entry_index)),
+            Some((chosen_precedence, _)) if precedence < chosen_precedence => {
+                chosen[subdevice_index] = Some((precedence, entry_index))
+            }
+            Some((chosen_precedence, chosen_index))
+                if precedence == chosen_precedence
+                    && mapping[chosen_index].device_machine_identification()
+                        != entry.device_machine_identification() =>
+            {
+                conflicts.push(IdentificationConflict::new(
+                    Some(subdevice_index),
+                    format!(
+                        "Mapping entries {} and {} assign different identifications, using entry {}",
+                        chosen_index, entry_index, chosen_index
+                    ),
+                ));
+            }
+            Some(_) => {}
+        }
+    }
+
+    let merged = chosen
+        .iter()
+        .enumerate()
+        .map(|(subdevice_index, chosen)| {
+            let eeprom = eeprom_identifications
+                .get(subdevice_index)
+                .cloned()
+                .flatten();
+            let Some((_, entry_index)) = chosen else {
+                return eeprom;
+            };
+            let mapped = mapping[*entry_index].device_machine_identification();
+            if let Some(eeprom) = eeprom.filter(|eeprom| eeprom.is_valid() && eeprom != &mapped) {
+                conflicts.push(IdentificationConflict::new(
+                    Some(subdevice_index),
+                    format!(
+                        "EEPROM identifies machine {} role {}, mapping assigns machine {} role {}, using the mapping",
+                        eeprom.machine_identification_unique,
+                        eeprom.role,
+                        mapped.machine_identification_unique,
+                        mapped.role
+                    ),
+                ));
+            }
+            Some(mapped)
+        })
+        .collect::<Vec<_>>();
+
+    // two subdevices with the same role would make the machine pick one of them arbitrarily
+    for (subdevice_index, identification) in merged.iter().enumerate() {
+        let Some(identification) = identification.as_ref().filter(|i| i.is_valid()) else {
+            continue;
+        };
+        if let Some(first_index) = merged[..subdevice_index]
+            .iter()
+            .position(|other| other.as_ref() == Some(identification))
+        {
+            conflicts.push(IdentificationConflict::new(
+                Some(subdevice_index),
+                format!(
+                    "Machine {} role {} is already assigned to subdevice {}",
+                    identification.machine_identification_unique, identification.role, first_index
+                ),
+            ));
+        }
+    }
+
+    (merged, conflicts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use machines::machine_identification::MachineIdentification;
+
+    fn identity(product_id: u32, serial: u32) -> SubDeviceIdentity {
+        SubDeviceIdentity {
+            vendor_id: 2,
+            product_id,
+            revision: 0x00110000,
+            serial,
+        }
+    }
+
+    fn identification(serial: u16, role: u16) -> DeviceMachineIdentification {
+        DeviceMachineIdentification {
+            machine_identification_unique: MachineIdentificationUnique {
+                machine_identification: MachineIdentification {
+                    vendor: 1,
+                    machine: 2,
+                },
+                serial,
+            },
+            role,
+        }
+    }
+
+    fn entry(key: IdentificationMappingKey, serial: u16, role: u16) -> IdentificationMappingEntry {
+        let identification = identification(serial, role);
+        IdentificationMappingEntry {
+            key,
+            machine_identification_unique: identification.machine_identification_unique,
+            role: identification.role,
+        }
+    }
+
+    #[test]
+    fn test_merge_identification_mapping() {
+        let identities = [
+            identity(0x044c2c52, 0),
+            identity(0x07d83052, 0),
+            identity(0x07d83052, 77),
+        ];
+        let eeprom = [None, Some(identification(5, 1)), None];
+        let mapping = [
+            // ambiguous, both EL2008 match
+            entry(
+                IdentificationMappingKey::Identity {
+                    vendor_id: 2,
+                    product_id: 0x07d83052,
+                    revision: None,
+                },
+                9,
+                9,
+            ),
+            entry(
+                IdentificationMappingKey::Position { subdevice_index: 0 },
+                5,
+                0,
+            ),
+            // overridden by the serial entry below
+            entry(
+                IdentificationMappingKey::Position { subdevice_index: 2 },
+                5,
+                3,
+            ),
+            entry(
+                IdentificationMappingKey::Serial {
+                    vendor_id: 2,
+                    product_id: 0x07d83052,
+                    serial: 77,
+                },
+                5,
+                2,
+            ),
+            // differs from the EEPROM
+            entry(
+                IdentificationMappingKey::Position { subdevice_index: 1 },
+                6,
+                1,
+            ),
+        ];
+
+        let (merged, conflicts) = merge_identification_mapping(&identities, &eeprom, &mapping);
+        assert_eq!(
+            merged,
+            vec![
+                Some(identification(5, 0)),
+                Some(identification(6, 1)),
+                Some(identification(5, 2)),
+            ]
+        );
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(conflicts[0].subdevice_index, None);
+        assert_eq!(conflicts[1].subdevice_index, Some(1));
+    }
+
+    #[test]
+    fn test_merge_identification_mapping_duplicates() {
+        let identities = [identity(0x07d83052, 0), identity(0x07d83052, 0)];
+        let eeprom = [Some(identification(5, 1)), None];
+        let mapping = [
+            entry(
+                IdentificationMappingKey::Position { subdevice_index: 1 },
+                5,
+                1,
+            ),
+            entry(
+                IdentificationMappingKey::Position { subdevice_index: 1 },
+                5,
+                2,
+            ),
+        ];
+
+        let (merged, conflicts) = merge_identification_mapping(&identities, &eeprom, &mapping);
+        assert_eq!(
+            merged,
+            vec![Some(identification(5, 1)), Some(identification(5, 1))]
+        );
+        // same position twice and the role already taken by subdevice 0
+        assert_eq!(
+            conflicts
+                .iter()
+                .map(|conflict| conflict.subdevice_index)
+                .collect::<Vec<_>>(),
+            vec![Some(1), Some(1)]
+        );
+    }
+}
@@ -1,4 +1,11 @@
+pub mod assignment;
+pub mod capture;
 pub mod config;
 pub mod ethercat_discovery_info;
+pub mod identification_backup;
+pub mod identification_mapping;
 pub mod init;
+pub mod motor_configuration;
+pub mod rescan;
 pub mod setup;
+pub mod topology;
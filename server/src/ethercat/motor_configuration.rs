@@ -0,0 +1,228 @@
+use ethercat_hal::devices::EthercatDevice;
+use ethercat_hal::shared_config::el70x1::{
+    EL70x1MotorConfigurableDevice, EL70x1MotorConfiguration, as_el70x1_motor_configurable_mut,
+};
+use machines::machine_identification::{DeviceIdentification, MachineIdentificationUnique};
+use machines::motor_configuration::save_motor_configuration;
+use serde::{Deserialize, Serialize};
+use smol::lock::RwLock;
+
+use crate::app_state::{EthercatSetupHandle, SharedState};
+
+/// Stepper terminal with `role` in a machine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MotorConfigurationRequest {
+    pub machine_identification_unique: MachineIdentificationUnique,
+    pub role: u16,
+}
+
+/// New motor configuration for the stepper terminal with `role` in a machine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WriteMotorConfigurationRequest {
+    pub machine_identification_unique: MachineIdentificationUnique,
+    pub role: u16,
+    pub configuration: EL70x1MotorConfiguration,
+}
+
+/// Index of the subdevice with `role` in the machine
+pub fn find_role_subdevice_index<'a>(
+    device_identifications: impl IntoIterator<Item = &'a DeviceIdentification>,
+    machine_identification_unique: &MachineIdentificationUnique,
+    role: u16,
+) -> Option<usize> {
+    device_identifications
+        .into_iter()
+        .position(|device_identification| {
+            device_identification
+                .device_machine_identification
+                .as_ref()
+                .is_some_and(|device_machine_identification| {
+                    &device_machine_identification.machine_identification_unique
+                        == machine_identification_unique
+                        && device_machine_identification.role == role
+                })
+        })
+}
+
+fn role_subdevice_index(
+    handle: &EthercatSetupHandle,
+    machine_identification_unique: &MachineIdentificationUnique,
+    role: u16,
+) -> Result<usize, anyhow::Error> {
+    find_role_subdevice_index(
+        handle
+            .devices
+            .iter()
+            .map(|(identification, _)| identification),
+        machine_identification_unique,
+        role,
+    )
+    .ok_or_else(|| {
+        anyhow::anyhow!(
+            "[{}::role_subdevice_index] Machine {} has no device with role {}",
+            module_path!(),
+            machine_identification_unique,
+            role
+        )
+    })
+}
+
+async fn ethercat_setup_handle(
+    app_state: &SharedState,
+) -> Result<EthercatSetupHandle, anyhow::Error> {
+    app_state
+        .ethercat_setup_handle
+        .read()
+        .await
+        .clone()
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "[{}::ethercat_setup_handle] EtherCAT setup has not finished yet",
+                module_path!()
+            )
+        })
+}
+
+/// Calls `f` with the stepper terminal, the device is only locked for the call
+///
+/// The loop locks the devices every cycle, so no CoE access may happen inside `f`.
+async fn with_motor_configurable_device<T>(
+    device: &RwLock<dyn EthercatDevice>,
+    role: u16,
+    f: impl FnOnce(&mut dyn EL70x1MotorConfigurableDevice) -> T,
+) -> Result<T, anyhow::Error> {
+    let mut device = device.write().await;
+    let device = as_el70x1_motor_configurable_mut(&mut *device).ok_or_else(|| {
+        anyhow::anyhow!(
+            "[{}::with_motor_configurable_device] Device with role {} is not an EL70x1 stepper terminal",
+            module_path!(),
+            role
+        )
+    })?;
+    Ok(f(device))
+}
+
+/// Reads the motor configuration of a stepper terminal via CoE
+///
+/// Runs next to the cyclic exchange, the loop doesn't wait for the mailbox.
+pub async fn read_motor_configuration(
+    app_state: &SharedState,
+    request: &MotorConfigurationRequest,
+) -> Result<EL70x1MotorConfiguration, anyhow::Error> {
+    let handle = ethercat_setup_handle(app_state).await?;
+    let subdevice_index = role_subdevice_index(
+        &handle,
+        &request.machine_identification_unique,
+        request.role,
+    )?;
+
+    let mut configuration = with_motor_configurable_device(
+        &*handle.devices[subdevice_index].1,
+        request.role,
+        |device| device.get_motor_configuration(),
+    )
+    .await?;
+
+    let subdevice = handle
+        .group
+        .subdevice(&handle.maindevice, subdevice_index)?;
+    configuration.read_config(&subdevice).await?;
+    Ok(configuration)
+}
+
+/// Writes the motor configuration of a stepper terminal via CoE and stores it for the next start
+///
+/// Refused while the motor is enabled. Runs next to the cyclic exchange, the loop doesn't wait for
+/// the mailbox.
+pub async fn write_motor_configuration(
+    app_state: &SharedState,
+    request: &WriteMotorConfigurationRequest,
+) -> Result<(), anyhow::Error> {
+    let handle = ethercat_setup_handle(app_state).await?;
+    let subdevice_index = role_subdevice_index(
+        &handle,
+        &request.machine_identification_unique,
+        request.role,
+    )?;
+    let device = &*handle.devices[subdevice_index].1;
+
+    let enabled =
+        with_motor_configurable_device(device, request.role, |device| device.is_motor_enabled())
+            .await?;
+    if enabled {
+        return Err(anyhow::anyhow!(
+            "[{}::write_motor_configuration] Motor with role {} is enabled, disable the axis first",
+            module_path!(),
+            request.role
+        ));
+    }
+
+    let subdevice = handle
+        .group
+        .subdevice(&handle.maindevice, subdevice_index)?;
+    request.configuration.write_config(&subdevice).await?;
+    with_motor_configurable_device(device, request.role, |device| {
+        device.set_motor_configuration(request.configuration.clone())
+    })
+    .await?;
+
+    save_motor_configuration(
+        &request.machine_identification_unique,
+        request.role,
+        request.configuration.clone(),
+    )
+    .map_err(|e| {
+        anyhow::anyhow!(
+            "[{}::write_motor_configuration] Motor configuration was written but not saved, it is lost on restart: {}",
+            module_path!(),
+            e
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use machines::machine_identification::{
+        DeviceHardwareIdentification, DeviceHardwareIdentificationEthercat,
+        DeviceMachineIdentification, MachineIdentification,
+    };
+
+    fn device(subdevice_index: usize, serial: u16, role: u16) -> DeviceIdentification {
+        DeviceIdentification {
+            device_machine_identification: Some(DeviceMachineIdentification {
+                machine_identification_unique: machine(serial),
+                role,
+            }),
+            device_hardware_identification: DeviceHardwareIdentification::Ethercat(
+                DeviceHardwareIdentificationEthercat { subdevice_index },
+            ),
+        }
+    }
+
+    fn machine(serial: u16) -> MachineIdentificationUnique {
+        MachineIdentificationUnique {
+            machine_identification: MachineIdentification {
+                vendor: 1,
+                machine: 2,
+            },
+            serial,
+        }
+    }
+
+    #[test]
+    fn test_find_role_subdevice_index() {
+        let mut unidentified = device(0, 1, 0);
+        unidentified.device_machine_identification = None;
+        let devices = [
+            unidentified,
+            device(1, 1, 2),
+            device(2, 2, 3),
+            device(3, 1, 3),
+        ];
+
+        assert_eq!(find_role_subdevice_index(&devices, &machine(1), 3), Some(3));
+        assert_eq!(find_role_subdevice_index(&devices, &machine(1), 2), Some(1));
+        assert_eq!(find_role_subdevice_index(&devices, &machine(2), 2), None);
+    }
+}
@@ -0,0 +1,366 @@
+use ethercrab::{Command, MainDevice};
+use machines::machine_identification::{DeviceHardwareIdentification, MachineIdentificationUnique};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::{
+    app_state::{HotThreadMessage, SharedState},
+    ethercat::{
+        setup::{ethercat_machines, group_devices_by_identification, rescan_ethercat_setup},
+        topology::{EthercatTopology, read_live_ethercat_topology},
+    },
+};
+
+/// ESC register read by the broadcast that counts the subdevices
+const TYPE_REGISTER: u16 = 0x0000;
+/// ESC register holding the station address the setup configured
+const STATION_ADDRESS_REGISTER: u16 = 0x0010;
+
+/// A subdevice in the chain that wasn't part of the setup, it has no station address of the setup
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct EthercatRescanAddition {
+    /// Position in the live chain
+    pub position: usize,
+    /// Station address the subdevice reported, 0 after power up
+    pub configured_address: u16,
+}
+
+/// Differences between the subdevices on the bus and the setup, by position
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EthercatChainChanges {
+    pub additions: Vec<EthercatRescanAddition>,
+    /// Subdevices of the setup are in a different order, e.g. two terminals were swapped
+    pub reordered: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EthercatRescanResult {
+    /// Subdevices that were found during setup but don't respond anymore
+    pub removed_subdevices: Vec<usize>,
+    /// Subdevices on the bus that weren't part of the setup
+    pub additions: Vec<EthercatRescanAddition>,
+    /// Subdevices of the setup that are in a different position now
+    pub reordered: bool,
+    /// Machines removed from the loop because one of their subdevices is gone
+    pub removed_machines: Vec<MachineIdentificationUnique>,
+    /// Machines created for new or changed device groups
+    pub added_machines: Vec<MachineIdentificationUnique>,
+    /// Machines that had their outputs switched off and were paused while the group was set up again
+    pub interrupted_machines: Vec<MachineIdentificationUnique>,
+    /// Setting the group up again failed, the server has to be restarted
+    pub restart_required: bool,
+    /// Why the group couldn't be set up again
+    pub error: Option<String>,
+    pub topology: EthercatTopology,
+}
+
+/// Compares the station addresses read by position with the setup
+///
+/// The setup configured a station address for every subdevice, so a subdevice with an address that isn't
+/// in the setup was plugged in or replaced since. Subdevices of the setup that stopped responding are
+/// skipped when the order is compared, they are reported as removed.
+pub fn detect_chain_changes(
+    setup: &EthercatTopology,
+    live_addresses: &[u16],
+) -> EthercatChainChanges {
+    let setup_addresses = setup
+        .nodes
+        .iter()
+        .map(|node| node.configured_address)
+        .collect::<Vec<_>>();
+
+    let additions = live_addresses
+        .iter()
+        .enumerate()
+        .filter(|(_, address)| !setup_addresses.contains(address))
+        .map(|(position, address)| EthercatRescanAddition {
+            position,
+            configured_address: *address,
+        })
+        .collect();
+
+    let live_known = live_addresses
+        .iter()
+        .filter(|address| setup_addresses.contains(address));
+    let setup_present = setup_addresses
+        .iter()
+        .filter(|address| live_addresses.contains(address));
+    let reordered = !live_known.eq(setup_present);
+
+    EthercatChainChanges {
+        additions,
+        reordered,
+    }
+}
+
+/// Station addresses of all subdevices on the bus in chain order
+///
+/// Every subdevice increments the working counter of the broadcast read, auto increment reads then
+/// address them by position.
+async fn read_chain_addresses(maindevice: &MainDevice<'_>) -> Result<Vec<u16>, anyhow::Error> {
+    let (_, count) = Command::brd(TYPE_REGISTER)
+        .receive_wkc::<u8>(maindevice)
+        .await
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "[{}::read_chain_addresses] Failed to count subdevices: {:?}",
+                module_path!(),
+                e
+            )
+        })?;
+
+    let mut addresses = Vec::with_capacity(count as usize);
+    for position in 0..count {
+        // auto increment addresses count down from 0 along the chain
+        let address = Command::aprd(0u16.wrapping_sub(position), STATION_ADDRESS_REGISTER)
+            .receive::<u16>(maindevice)
+            .await
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "[{}::read_chain_addresses] Failed to read the station address at position {}: {:?}",
+                    module_path!(),
+                    position,
+                    e
+                )
+            })?;
+        addresses.push(address);
+    }
+    Ok(addresses)
+}
+
+/// Compares the live bus with the setup, removes machines whose subdevices disappeared and adds new ones
+///
+/// Machines whose subdevice is gone are removed first. If subdevices were added, removed or moved the
+/// group is set up again. That interrupts every EtherCAT machine, see [`rescan_ethercat_setup`], only
+/// device groups that changed get a new machine.
+pub async fn rescan_ethercat(
+    app_state: Arc<SharedState>,
+) -> Result<EthercatRescanResult, anyhow::Error> {
+    let live_topology = read_live_ethercat_topology(&app_state).await?;
+    let maindevice = app_state
+        .ethercat_setup_handle
+        .read()
+        .await
+        .as_ref()
+        .map(|handle| handle.maindevice.clone())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "[{}::rescan_ethercat] EtherCAT setup has not finished yet",
+                module_path!()
+            )
+        })?;
+    let live_addresses = read_chain_addresses(&maindevice).await?;
+    let setup_topology = app_state
+        .ethercat_topology
+        .read()
+        .await
+        .clone()
+        .unwrap_or_default();
+
+    let removed_subdevices = live_topology
+        .nodes
+        .iter()
+        .filter(|node| !node.responding)
+        .map(|node| node.subdevice_index)
+        .collect::<Vec<_>>();
+    let EthercatChainChanges {
+        additions,
+        reordered,
+    } = detect_chain_changes(&setup_topology, &live_addresses);
+
+    // the meta data holds the subdevices first, followed by the coupler slot modules
+    let device_identifications = app_state
+        .ethercat_meta_data
+        .read()
+        .await
+        .iter()
+        .take(setup_topology.nodes.len())
+        .map(|meta| meta.device_identification.clone())
+        .collect::<Vec<_>>();
+    let device_groups = group_devices_by_identification(&device_identifications).device_groups;
+
+    let mut removed_machines = vec![];
+    for device_group in device_groups.iter() {
+        let Some(missing_index) =
+            device_group
+                .iter()
+                .find_map(|device| match &device.device_hardware_identification {
+                    DeviceHardwareIdentification::Ethercat(ethercat)
+                        if removed_subdevices.contains(&ethercat.subdevice_index) =>
+                    {
+                        Some(ethercat.subdevice_index)
+                    }
+                    _ => None,
+                })
+        else {
+            continue;
+        };
+
+        let machine_identification_unique = device_group[0]
+            .device_machine_identification
+            .machine_identification_unique
+            .clone();
+
+        // already removed by an earlier rescan
+        if app_state
+            .api_machines
+            .lock()
+            .await
+            .remove(&machine_identification_unique)
+            .is_none()
+        {
+            continue;
+        }
+
+        app_state
+            .rt_machine_creation_channel
+            .send(HotThreadMessage::DeleteMachine(
+                machine_identification_unique.clone(),
+            ))
+            .await
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "[{}::rescan_ethercat] Failed to send HotThreadMessage::DeleteMachine {}",
+                    module_path!(),
+                    e
+                )
+            })?;
+
+        let error = format!(
+            "Subdevice {} ({}) is not responding, rescan after reconnecting it",
+            missing_index, live_topology.nodes[missing_index].name
+        );
+        for machine_obj in app_state.current_machines_meta.lock().await.iter_mut() {
+            if machine_obj.machine_identification_unique == machine_identification_unique {
+                machine_obj.error = Some(error.clone());
+            }
+        }
+
+        tracing::warn!(
+            "Removed machine {:?}: {}",
+            machine_identification_unique,
+            error
+        );
+        removed_machines.push(machine_identification_unique);
+    }
+
+    if !removed_machines.is_empty() {
+        app_state.send_machines_event().await;
+    }
+
+    if removed_subdevices.is_empty() && additions.is_empty() && !reordered {
+        return Ok(EthercatRescanResult {
+            removed_subdevices,
+            additions,
+            reordered,
+            removed_machines,
+            added_machines: vec![],
+            interrupted_machines: vec![],
+            restart_required: false,
+            error: None,
+            topology: live_topology,
+        });
+    }
+
+    let (interrupted_machines, _) = ethercat_machines(&app_state).await;
+    let machines_before = app_state
+        .api_machines
+        .lock()
+        .await
+        .keys()
+        .cloned()
+        .collect::<HashSet<_>>();
+
+    let error = match rescan_ethercat_setup(app_state.clone()).await {
+        Ok(()) => None,
+        Err(e) => {
+            tracing::error!("Failed to set up the EtherCAT group again: {:?}", e);
+            Some(e.to_string())
+        }
+    };
+
+    let added_machines = app_state
+        .api_machines
+        .lock()
+        .await
+        .keys()
+        .filter(|machine| !machines_before.contains(*machine))
+        .cloned()
+        .collect::<Vec<_>>();
+    let topology = match error {
+        None => app_state
+            .ethercat_topology
+            .read()
+            .await
+            .clone()
+            .unwrap_or(live_topology),
+        Some(_) => live_topology,
+    };
+
+    Ok(EthercatRescanResult {
+        removed_subdevices,
+        additions,
+        reordered,
+        removed_machines,
+        added_machines,
+        interrupted_machines,
+        restart_required: error.is_some(),
+        error,
+        topology,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ethercat::topology::EthercatPortState;
+
+    fn setup_topology(addresses: &[u16]) -> EthercatTopology {
+        EthercatTopology::new(
+            addresses
+                .iter()
+                .map(|address| {
+                    (
+                        *address,
+                        "EL2008".to_string(),
+                        [EthercatPortState::default(); 4],
+                        vec![],
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_detect_chain_changes() {
+        let setup = setup_topology(&[0x1000, 0x1001, 0x1002]);
+
+        assert_eq!(
+            detect_chain_changes(&setup, &[0x1000, 0x1001, 0x1002]),
+            EthercatChainChanges::default()
+        );
+
+        // a terminal was plugged in the middle of the chain, the ports of its neighbours don't change
+        let changes = detect_chain_changes(&setup, &[0x1000, 0, 0x1001, 0x1002]);
+        assert_eq!(
+            changes.additions,
+            vec![EthercatRescanAddition {
+                position: 1,
+                configured_address: 0
+            }]
+        );
+        assert!(!changes.reordered);
+
+        // two terminals were swapped
+        let changes = detect_chain_changes(&setup, &[0x1000, 0x1002, 0x1001]);
+        assert!(changes.additions.is_empty());
+        assert!(changes.reordered);
+
+        // a removed terminal shifts the ones behind it, that is no reorder
+        assert_eq!(
+            detect_chain_changes(&setup, &[0x1000, 0x1002]),
+            EthercatChainChanges::default()
+        );
+    }
+}
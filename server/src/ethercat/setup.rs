@@ -1,4 +1,13 @@
-use crate::app_state::{EtherCatDeviceMetaData, EthercatSetup};
+use crate::app_state::{
+    EtherCatDeviceMetaData, EtherCatPdoLengthMismatch, EthercatSetup, HotThreadMessage,
+};
+use crate::ethercat::identification_mapping::{
+    IDENTIFICATION_MAPPING_PATH, IdentificationConflict, merge_identification_mapping,
+    read_identification_mapping,
+};
+use crate::ethercat::topology::{
+    DL_STATUS_REGISTER, EthercatTopology, EthercatTopologyModule, ports_from_dl_status,
+};
 use crate::socketio::main_namespace::MainNamespaceEvents;
 use crate::socketio::main_namespace::ethercat_devices_event::EthercatDevicesEventBuilder;
 use crate::socketio::main_namespace::machines_event::MachineObj;
@@ -11,16 +20,26 @@ use control_core::socketio::namespace::NamespaceCacheingLogic;
 #[cfg(all(target_os = "linux", not(feature = "development-build")))]
 use control_core::{irq_handling::set_irq_affinity, realtime::set_realtime_priority};
 use ethercat_hal::debugging::diagnosis_history::get_most_recent_diagnosis_message;
-use ethercat_hal::devices::devices_from_subdevices;
+use ethercat_hal::devices::generic::GenericEthercatDevice;
 use ethercat_hal::devices::wago_750_354::{
     WAGO_750_354_PRODUCT_ID, WAGO_750_354_VENDOR_ID, Wago750_354,
 };
 use ethercat_hal::devices::wago_modules::ip20_ec_di8_do8::{
     IP20_EC_DI8_DO8_PRODUCT_ID, IP20_EC_DI8_DO8_VENDOR_ID, IP20EcDi8Do8,
 };
+use ethercat_hal::devices::{
+    EthercatDevice, EthercatDeviceUsed, devices_from_subdevices, subdevice_identity_to_tuple,
+    validate_pdo_lengths,
+};
+use ethercat_hal::helpers::ethercrab_types::EthercrabSubDevicePreoperational;
 
 use ethercrab::std::ethercat_now;
-use ethercrab::{MainDevice, MainDeviceConfig, PduStorage, RetryBehaviour, Timeouts};
+use ethercrab::subdevice_group::PreOpPdi;
+use ethercrab::{
+    MainDevice, MainDeviceConfig, PduStorage, RetryBehaviour, SubDeviceGroup, SubDeviceIdentity,
+    Timeouts,
+};
+use machines::hardware_descriptor::{HardwareCheckDevice, HardwareCheckItem};
 use machines::machine_identification::{
     DeviceHardwareIdentification, DeviceHardwareIdentificationEthercat, DeviceIdentification,
     DeviceIdentificationIdentified, MachineIdentificationUnique, read_device_identifications,
@@ -28,12 +47,21 @@ use machines::machine_identification::{
 use machines::registry::{MACHINE_REGISTRY, MachineRegistry};
 use machines::{Machine, MachineNewHardware, MachineNewHardwareEthercat, MachineNewParams};
 use smol::channel::Sender;
+use smol::future::FutureExt;
+use smol::lock::RwLock;
 use socketioxide::extract::SocketRef;
-use std::{sync::Arc, time::Duration};
+use std::{
+    path::Path,
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
 
 const SM_OUTPUT: u16 = 0x1C32;
 const SM_INPUT: u16 = 0x1C33;
 
+/// Directory with ESI files for terminals without a driver
+const GENERIC_DEVICE_ESI_DIR: &str = "esi";
+
 /// Structure to hold the result of grouping devices by identification
 #[derive(Debug)]
 pub struct DeviceGroupingResult {
@@ -109,18 +137,41 @@ pub fn group_devices_by_identification(
     }
 }
 
-pub async fn set_ethercat_devices<const MAX_SUBDEVICES: usize, const MAX_PDI: usize>(
+/// A machine that was constructed but not yet handed to the loop thread
+pub struct NewEthercatMachine {
+    pub machine_identification_unique: MachineIdentificationUnique,
+    /// Subdevice indices of all devices in the device group of the machine
+    pub subdevice_indices: Vec<usize>,
+    pub hardware_checklist: Vec<HardwareCheckItem>,
+    pub machine: Box<dyn Machine>,
+}
+
+/// Structure to hold the result of constructing machines from device groups
+pub struct NewEthercatMachinesResult {
+    /// Machines that were constructed successfully
+    pub machines: Vec<NewEthercatMachine>,
+    /// Machines that could not be constructed, including the error
+    pub failed_machine_objs: Vec<MachineObj>,
+}
+
+/// Constructs a machine for every group of identified devices
+///
+/// Has to be called in PRE-OP since the machines write their configuration to the subdevices.
+/// The machines are not registered yet, see [`register_ethercat_machines`].
+/// Each group is checked against the hardware descriptor of its machine first, `subdevice_identities` is indexed by subdevice index.
+pub fn new_ethercat_machines(
     device_identifications: &Vec<DeviceIdentification>,
+    subdevice_identities: &[SubDeviceIdentity],
     machine_registry: &MachineRegistry,
     hardware: &MachineNewHardwareEthercat<'_, '_, '_>,
-    shared_state: Arc<SharedState>,
+    shared_state: &SharedState,
     socket_queue_tx: Sender<(SocketRef, Arc<control_core::socketio::event::GenericEvent>)>,
-) -> Result<(), anyhow::Error> {
+) -> NewEthercatMachinesResult {
     let device_grouping_result = group_devices_by_identification(device_identifications);
     let machine_new_hardware = MachineNewHardware::Ethercat(hardware);
 
-    let mut machines: Vec<Box<dyn Machine>> = vec![];
-    let mut machine_objs: Vec<MachineObj> = vec![];
+    let mut machines: Vec<NewEthercatMachine> = vec![];
+    let mut failed_machine_objs: Vec<MachineObj> = vec![];
 
     for device_group in device_grouping_result.device_groups.iter() {
         let machine_identification_unique: MachineIdentificationUnique = match device_group.first()
@@ -132,6 +183,36 @@ pub async fn set_ethercat_devices<const MAX_SUBDEVICES: usize, const MAX_PDI: us
             None => continue, // Skip this group if empty
         };
 
+        let subdevice_indices = device_group
+            .iter()
+            .filter_map(|device| match &device.device_hardware_identification {
+                DeviceHardwareIdentification::Ethercat(ethercat) => Some(ethercat.subdevice_index),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let hardware_checklist = machine_registry
+            .hardware_descriptor(&machine_identification_unique.machine_identification)
+            .map(|descriptor| {
+                descriptor.check(&hardware_check_devices(device_group, subdevice_identities))
+            })
+            .unwrap_or_default();
+
+        // report every missing or wrong device at once instead of the first one the machine trips over
+        let problems = hardware_checklist
+            .iter()
+            .filter(|item| item.is_problem())
+            .map(|item| item.to_string())
+            .collect::<Vec<_>>();
+        if !problems.is_empty() {
+            failed_machine_objs.push(MachineObj {
+                machine_identification_unique,
+                error: Some(problems.join("\n")),
+                hardware_checklist,
+            });
+            continue;
+        }
+
         let new_machine = machine_registry.new_machine(&MachineNewParams {
             device_group,
             hardware: &machine_new_hardware,
@@ -141,23 +222,203 @@ pub async fn set_ethercat_devices<const MAX_SUBDEVICES: usize, const MAX_PDI: us
         });
 
         match new_machine {
-            Ok(machine) => {
-                shared_state.clone().api_machines.lock().await.insert(
-                    machine_identification_unique.clone(),
-                    machine.api_get_sender(),
-                );
-                machine_objs.push(MachineObj {
-                    machine_identification_unique,
-                    error: None,
-                });
-                machines.push(machine);
-            }
-            Err(e) => machine_objs.push(MachineObj {
+            Ok(machine) => machines.push(NewEthercatMachine {
+                machine_identification_unique,
+                subdevice_indices,
+                hardware_checklist,
+                machine,
+            }),
+            Err(e) => failed_machine_objs.push(MachineObj {
                 machine_identification_unique,
                 error: Some(e.to_string()),
+                hardware_checklist,
+            }),
+        }
+    }
+
+    NewEthercatMachinesResult {
+        machines,
+        failed_machine_objs,
+    }
+}
+
+/// Role and identity of every EtherCAT device in the group
+fn hardware_check_devices(
+    device_group: &[DeviceIdentificationIdentified],
+    subdevice_identities: &[SubDeviceIdentity],
+) -> Vec<HardwareCheckDevice> {
+    device_group
+        .iter()
+        .filter_map(|device| match &device.device_hardware_identification {
+            DeviceHardwareIdentification::Ethercat(ethercat) => Some(HardwareCheckDevice {
+                role: device.device_machine_identification.role,
+                subdevice_index: ethercat.subdevice_index,
+                identity: subdevice_identity_to_tuple(
+                    subdevice_identities.get(ethercat.subdevice_index)?,
+                ),
             }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Loads the PDO layout of every [`GenericEthercatDevice`], has to run before the process image is mapped
+pub async fn load_generic_device_layouts(
+    devices: &[Arc<RwLock<dyn EthercatDevice>>],
+    subdevices: &[EthercrabSubDevicePreoperational<'_>],
+    maindevice: &MainDevice<'_>,
+) {
+    for (subdevice_index, (device, subdevice)) in devices.iter().zip(subdevices).enumerate() {
+        let mut device = device.write().await;
+        let Some(device) = device.as_any_mut().downcast_mut::<GenericEthercatDevice>() else {
+            continue;
+        };
+
+        match device
+            .load_layout(subdevice, maindevice, Path::new(GENERIC_DEVICE_ESI_DIR))
+            .await
+        {
+            Ok(source) => tracing::info!(
+                "No driver for subdevice {} ({}), using a generic device with {} inputs and {} outputs from the {:?} layout",
+                subdevice_index,
+                subdevice.name(),
+                device.layout.inputs.len(),
+                device.layout.outputs.len(),
+                source
+            ),
+            Err(e) => tracing::warn!(
+                "[{}::load_generic_device_layouts] No driver for subdevice {} ({}) and its layout could not be loaded: {}",
+                module_path!(),
+                subdevice_index,
+                subdevice.name(),
+                e
+            ),
         }
     }
+}
+
+/// Compares the PDO lengths of all used devices with the process image ethercrab mapped per subdevice
+///
+/// Unused devices are skipped since their PDO assignment was never written.
+pub async fn validate_ethercat_pdo_lengths<const MAX_SUBDEVICES: usize, const MAX_PDI: usize>(
+    group: &SubDeviceGroup<MAX_SUBDEVICES, MAX_PDI, PreOpPdi>,
+    maindevice: &MainDevice<'_>,
+    devices: &[(DeviceIdentification, Arc<RwLock<dyn EthercatDevice>>)],
+) -> Vec<EtherCatPdoLengthMismatch> {
+    let mut mismatches = vec![];
+
+    for (subdevice_index, subdevice) in group.iter(maindevice).enumerate() {
+        let device = match devices.get(subdevice_index) {
+            Some((_, device)) => device.read().await,
+            None => continue,
+        };
+
+        if !device.is_used() {
+            continue;
+        }
+
+        let actual_input_bits = subdevice.inputs_raw().len() * 8;
+        let actual_output_bits = subdevice.outputs_raw().len() * 8;
+
+        if let Err(mismatch) = validate_pdo_lengths(&*device, actual_input_bits, actual_output_bits)
+        {
+            tracing::error!(
+                "[{}::validate_ethercat_pdo_lengths] PDO length mismatch on subdevice {} ({}): {}",
+                module_path!(),
+                subdevice_index,
+                subdevice.name(),
+                mismatch
+            );
+            mismatches.push(EtherCatPdoLengthMismatch {
+                subdevice_index,
+                configured_address: subdevice.configured_address(),
+                name: subdevice.name().to_string(),
+                expected_input_bits: mismatch.expected_input_bits,
+                actual_input_bits: mismatch.actual_input_bits,
+                expected_output_bits: mismatch.expected_output_bits,
+                actual_output_bits: mismatch.actual_output_bits,
+            });
+        }
+    }
+
+    mismatches
+}
+
+/// Refuses all machines that use a device with a PDO length mismatch
+///
+/// The devices of refused machines are marked as unused so the loop doesn't copy shifted process data into or out of them.
+pub async fn refuse_machines_with_pdo_mismatches(
+    new_machines_result: NewEthercatMachinesResult,
+    mismatches: &[EtherCatPdoLengthMismatch],
+    devices: &[(DeviceIdentification, Arc<RwLock<dyn EthercatDevice>>)],
+) -> NewEthercatMachinesResult {
+    let NewEthercatMachinesResult {
+        machines,
+        mut failed_machine_objs,
+    } = new_machines_result;
+    let mut accepted_machines = vec![];
+
+    for new_machine in machines {
+        let machine_mismatches = mismatches
+            .iter()
+            .filter(|mismatch| {
+                new_machine
+                    .subdevice_indices
+                    .contains(&mismatch.subdevice_index)
+            })
+            .collect::<Vec<_>>();
+
+        if machine_mismatches.is_empty() {
+            accepted_machines.push(new_machine);
+            continue;
+        }
+
+        for subdevice_index in new_machine.subdevice_indices.iter() {
+            if let Some((_, device)) = devices.get(*subdevice_index) {
+                device.write().await.set_used(false);
+            }
+        }
+
+        let error = machine_mismatches
+            .iter()
+            .map(|mismatch| mismatch.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        failed_machine_objs.push(MachineObj {
+            machine_identification_unique: new_machine.machine_identification_unique,
+            error: Some(error),
+            hardware_checklist: new_machine.hardware_checklist,
+        });
+    }
+
+    NewEthercatMachinesResult {
+        machines: accepted_machines,
+        failed_machine_objs,
+    }
+}
+
+/// Hands the machines to the loop thread and publishes them to the REST API and the frontend
+pub async fn register_ethercat_machines(
+    new_machines_result: NewEthercatMachinesResult,
+    shared_state: Arc<SharedState>,
+) {
+    let mut machines: Vec<Box<dyn Machine>> = vec![];
+    let mut machine_objs: Vec<MachineObj> = vec![];
+
+    for new_machine in new_machines_result.machines {
+        shared_state.api_machines.lock().await.insert(
+            new_machine.machine_identification_unique.clone(),
+            new_machine.machine.api_get_sender(),
+        );
+        machine_objs.push(MachineObj {
+            machine_identification_unique: new_machine.machine_identification_unique,
+            error: None,
+            hardware_checklist: new_machine.hardware_checklist,
+        });
+        machines.push(new_machine.machine);
+    }
+    machine_objs.extend(new_machines_result.failed_machine_objs);
+
     let _ = shared_state
         .rt_machine_creation_channel
         .send(crate::app_state::HotThreadMessage::AddMachines(machines))
@@ -165,16 +426,263 @@ pub async fn set_ethercat_devices<const MAX_SUBDEVICES: usize, const MAX_PDI: us
 
     shared_state.add_machines_if_not_exists(machine_objs).await;
     shared_state.clone().send_machines_event().await;
-
-    Ok(())
 }
 
+/// Main device of the EtherCAT interface, kept so the group can be set up again without a restart
+///
+/// The PDU storage can only be split once, so there is only ever one main device per process.
+static ETHERCAT_MAINDEVICE: OnceLock<Arc<MainDevice<'static>>> = OnceLock::new();
+
 pub async fn setup_loop(
     interface: &str,
     app_state: Arc<SharedState>,
 ) -> Result<EthercatSetup, anyhow::Error> {
     tracing::info!("Starting Ethercat PDU loop");
 
+    let maindevice = ETHERCAT_MAINDEVICE
+        .get_or_init(|| start_ethercat_maindevice(interface))
+        .clone();
+
+    setup_ethercat_group(maindevice, app_state, None).await
+}
+
+/// Devices and machines of the setup that was running before the group is set up again
+pub struct PreviousEthercatSetup {
+    pub devices: Vec<(DeviceIdentification, Arc<RwLock<dyn EthercatDevice>>)>,
+    /// Machines paused in the loop, they continue if their device group is unchanged
+    pub machines: Vec<MachineIdentificationUnique>,
+}
+
+/// EtherCAT machines of the current setup, split into the ones in the loop and the ones that failed or were removed
+pub async fn ethercat_machines(
+    app_state: &SharedState,
+) -> (
+    Vec<MachineIdentificationUnique>,
+    Vec<MachineIdentificationUnique>,
+) {
+    let api_machines = app_state.api_machines.lock().await;
+    let mut running_machines: Vec<MachineIdentificationUnique> = vec![];
+    let mut stopped_machines: Vec<MachineIdentificationUnique> = vec![];
+    for meta in app_state.ethercat_meta_data.read().await.iter() {
+        if let Some(device_machine_identification) =
+            &meta.device_identification.device_machine_identification
+        {
+            let unique = &device_machine_identification.machine_identification_unique;
+            let list = if api_machines.contains_key(unique) {
+                &mut running_machines
+            } else {
+                &mut stopped_machines
+            };
+            if !list.contains(unique) {
+                list.push(unique.clone());
+            }
+        }
+    }
+    (running_machines, stopped_machines)
+}
+
+/// Makes the loop switch off the outputs and drop the EtherCAT setup together with `machines`
+///
+/// `paused_machines` stay in the loop without running until the next setup is added.
+/// Returns the removed setup once the loop doesn't use the group anymore.
+async fn remove_ethercat_setup(
+    app_state: &SharedState,
+    machines: Vec<MachineIdentificationUnique>,
+    paused_machines: Vec<MachineIdentificationUnique>,
+) -> Result<Option<EthercatSetup>, anyhow::Error> {
+    // no register or mailbox access while the subdevices are reset
+    app_state.ethercat_setup_handle.write().await.take();
+
+    let (reply_tx, reply_rx) = smol::channel::bounded(1);
+    app_state
+        .rt_machine_creation_channel
+        .send(HotThreadMessage::RemoveEthercatSetup(
+            machines,
+            paused_machines,
+            reply_tx,
+        ))
+        .await
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "[{}::remove_ethercat_setup] Failed to send HotThreadMessage::RemoveEthercatSetup {}",
+                module_path!(),
+                e
+            )
+        })?;
+    async { reply_rx.recv().await.ok() }
+        .or(async {
+            smol::Timer::after(Duration::from_secs(5)).await;
+            None
+        })
+        .await
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "[{}::remove_ethercat_setup] Loop didn't release the EtherCAT setup",
+                module_path!()
+            )
+        })
+}
+
+async fn add_ethercat_setup(
+    app_state: &SharedState,
+    setup: EthercatSetup,
+) -> Result<(), anyhow::Error> {
+    app_state
+        .rt_machine_creation_channel
+        .send(HotThreadMessage::AddEtherCatSetup(setup))
+        .await
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "[{}::add_ethercat_setup] Failed to send HotThreadMessage::AddEtherCatSetup {}",
+                module_path!(),
+                e
+            )
+        })
+}
+
+/// Sets the group up again after subdevices were added, removed, moved or assigned to a machine
+///
+/// The subdevices go back to INIT, so all EtherCAT machines are interrupted: their outputs are switched off
+/// and they are paused until the group is in OP again. Machines whose device group is unchanged continue
+/// with their devices afterwards, only new or changed machines are created.
+pub async fn rescan_ethercat_setup(app_state: Arc<SharedState>) -> Result<(), anyhow::Error> {
+    let maindevice = ETHERCAT_MAINDEVICE.get().cloned().ok_or_else(|| {
+        anyhow::anyhow!(
+            "[{}::rescan_ethercat_setup] EtherCAT has not been set up yet",
+            module_path!()
+        )
+    })?;
+
+    let (machines, stopped_machines) = ethercat_machines(&app_state).await;
+
+    // the loop has to stop using the group before the subdevices are reset
+    let devices = remove_ethercat_setup(&app_state, vec![], machines.clone())
+        .await?
+        .map(|setup| setup.devices)
+        .unwrap_or_default();
+
+    // machines that failed or were removed are reported again by the new setup
+    app_state
+        .current_machines_meta
+        .lock()
+        .await
+        .retain(|machine_obj| {
+            !stopped_machines.contains(&machine_obj.machine_identification_unique)
+        });
+
+    let setup = setup_ethercat_group(
+        maindevice,
+        app_state.clone(),
+        Some(PreviousEthercatSetup { devices, machines }),
+    )
+    .await?;
+    add_ethercat_setup(&app_state, setup).await?;
+    app_state.send_machines_event().await;
+
+    Ok(())
+}
+
+/// Takes over the devices of previous machines whose device group is unchanged
+///
+/// A group is unchanged if it has the same roles on devices of the same type.
+/// Returns the devices with the previous ones in place and the machines that keep running.
+async fn take_over_previous_devices(
+    previous: &PreviousEthercatSetup,
+    device_identifications: &[DeviceIdentification],
+    mut devices: Vec<Arc<RwLock<dyn EthercatDevice>>>,
+) -> (
+    Vec<Arc<RwLock<dyn EthercatDevice>>>,
+    Vec<MachineIdentificationUnique>,
+) {
+    let mut kept_machines = vec![];
+
+    for machine in previous.machines.iter() {
+        let previous_devices = previous
+            .devices
+            .iter()
+            .filter_map(|(device_identification, device)| {
+                let identification = device_identification
+                    .device_machine_identification
+                    .as_ref()?;
+                (&identification.machine_identification_unique == machine)
+                    .then(|| (identification.role, device.clone()))
+            })
+            .collect::<Vec<_>>();
+
+        let mut replacements = vec![];
+        for (subdevice_index, device_identification) in device_identifications.iter().enumerate() {
+            let Some(identification) = &device_identification.device_machine_identification else {
+                continue;
+            };
+            if &identification.machine_identification_unique != machine {
+                continue;
+            }
+            let Some((_, previous_device)) = previous_devices
+                .iter()
+                .find(|(role, _)| *role == identification.role)
+            else {
+                break;
+            };
+            // another terminal with the same identification needs a new machine
+            if previous_device.read().await.as_any().type_id()
+                != devices[subdevice_index].read().await.as_any().type_id()
+            {
+                break;
+            }
+            replacements.push((subdevice_index, previous_device.clone()));
+        }
+
+        let group_len = device_identifications
+            .iter()
+            .filter(|device_identification| {
+                device_identification
+                    .device_machine_identification
+                    .as_ref()
+                    .is_some_and(|identification| {
+                        &identification.machine_identification_unique == machine
+                    })
+            })
+            .count();
+        if replacements.len() != previous_devices.len() || replacements.len() != group_len {
+            continue;
+        }
+
+        for (subdevice_index, previous_device) in replacements {
+            devices[subdevice_index] = previous_device;
+        }
+        kept_machines.push(machine.clone());
+    }
+
+    (devices, kept_machines)
+}
+
+/// Removes machines from the loop, the REST API and the frontend
+async fn delete_ethercat_machines(
+    app_state: &SharedState,
+    machines: &[MachineIdentificationUnique],
+    error: Option<&str>,
+) {
+    for machine in machines {
+        app_state.api_machines.lock().await.remove(machine);
+        let _ = app_state
+            .rt_machine_creation_channel
+            .send(HotThreadMessage::DeleteMachine(machine.clone()))
+            .await;
+        match error {
+            Some(error) => {
+                for machine_obj in app_state.current_machines_meta.lock().await.iter_mut() {
+                    if &machine_obj.machine_identification_unique == machine {
+                        machine_obj.error = Some(error.to_string());
+                    }
+                }
+            }
+            None => app_state.remove_machine(machine).await,
+        }
+    }
+}
+
+/// Starts the tx/rx thread on the interface and creates the main device
+fn start_ethercat_maindevice(interface: &str) -> Arc<MainDevice<'static>> {
     // Setup ethercrab tx/rx task
     let pdu_storage = Box::leak(Box::new(PduStorage::<MAX_FRAMES, MAX_PDU_DATA>::new()));
     let (tx, rx, pdu) = pdu_storage.try_split().expect("can only split once");
@@ -219,7 +727,7 @@ pub async fn setup_loop(
         .expect("Building thread");
 
     // Create maindevice
-    let maindevice = MainDevice::new(
+    Arc::new(MainDevice::new(
         pdu,
         Timeouts {
             // Default 5000ms
@@ -241,7 +749,17 @@ pub async fn setup_loop(
             // Default 10_000
             dc_static_sync_iterations: 10_000,
         },
-    );
+    ))
+}
+
+/// Initializes the subdevices and creates the machines, the group ends up in OP
+///
+/// With a `previous` setup only machines whose device group changed are created again, see [`rescan_ethercat_setup`].
+pub async fn setup_ethercat_group(
+    maindevice: Arc<MainDevice<'static>>,
+    app_state: Arc<SharedState>,
+    previous: Option<PreviousEthercatSetup>,
+) -> Result<EthercatSetup, anyhow::Error> {
     {
         let app_state_clone = app_state.clone();
         let main_namespace = &mut app_state_clone
@@ -276,13 +794,43 @@ pub async fn setup_loop(
     let devices =
         devices_from_subdevices::<MAX_SUBDEVICES, PDI_LEN>(&mut group_preop, &maindevice)?;
     let subdevices = group_preop.iter(&maindevice).collect::<Vec<_>>();
+    load_generic_device_layouts(&devices, &subdevices, &maindevice).await;
 
     // extract device identifications
-    let device_identifications = read_device_identifications(&subdevices, &maindevice)
+    let eeprom_identifications = read_device_identifications(&subdevices, &maindevice)
         .await
+        .into_iter()
+        .map(|result| result.ok())
+        .collect::<Vec<_>>();
+
+    // merge with the mapping file for terminals that can't or shouldn't be identified via EEPROM
+    let subdevice_identities = subdevices
+        .iter()
+        .map(|subdevice| subdevice.identity())
+        .collect::<Vec<_>>();
+    let (merged_identifications, identification_conflicts) =
+        match read_identification_mapping(IDENTIFICATION_MAPPING_PATH) {
+            Ok(mapping) => merge_identification_mapping(
+                &subdevice_identities,
+                &eeprom_identifications,
+                &mapping,
+            ),
+            Err(e) => (
+                eeprom_identifications,
+                vec![IdentificationConflict {
+                    subdevice_index: None,
+                    message: e.to_string(),
+                }],
+            ),
+        };
+    for conflict in identification_conflicts.iter() {
+        tracing::warn!("Identification conflict: {}", conflict);
+    }
+    *app_state.ethercat_identification_conflicts.write().await = identification_conflicts;
+
+    let device_identifications = merged_identifications
         .into_iter()
         .enumerate()
-        .map(|(i, result)| (i, result.ok()))
         .map(
             |(subdevice_index, device_machine_identification)| DeviceIdentification {
                 device_machine_identification,
@@ -292,6 +840,24 @@ pub async fn setup_loop(
             },
         )
         .collect::<Vec<_>>();
+
+    // machines that keep running write no configuration, their subdevices keep the PDO assignment
+    let (devices, kept_machines) = match &previous {
+        Some(previous) => {
+            let (devices, kept_machines) =
+                take_over_previous_devices(previous, &device_identifications, devices).await;
+            let changed_machines = previous
+                .machines
+                .iter()
+                .filter(|machine| !kept_machines.contains(machine))
+                .cloned()
+                .collect::<Vec<_>>();
+            delete_ethercat_machines(&app_state, &changed_machines, None).await;
+            (devices, kept_machines)
+        }
+        None => (devices, vec![]),
+    };
+
     let devices = device_identifications
         .into_iter()
         .zip(devices)
@@ -352,11 +918,18 @@ pub async fn setup_loop(
         meaning we need to convert the PDO Mappings to seperate SubDevices to enable more ease of use
         OR alternatively you could show it like TwinCAT with "slots" on the Coupler
     */
+    let mut coupler_modules: Vec<EthercatTopologyModule> = vec![];
     match (coupler.identity().vendor_id, coupler.identity().product_id) {
         (WAGO_750_354_VENDOR_ID, WAGO_750_354_PRODUCT_ID) => {
             let r = Wago750_354::initialize_modules(coupler).await?;
             for module in r {
                 if coupler.configured_address() == module.belongs_to_addr {
+                    coupler_modules.push(EthercatTopologyModule {
+                        slot: module.slot,
+                        name: "wago module".to_owned(),
+                        vendor_id: module.vendor_id,
+                        product_id: module.product_id,
+                    });
                     match ethercat_meta_devices.get(0) {
                         Some(meta) => {
                             let meta_data = EtherCatDeviceMetaData {
@@ -380,6 +953,12 @@ pub async fn setup_loop(
             let r = IP20EcDi8Do8::initialize_modules(coupler).await?;
             for module in r {
                 if coupler.configured_address() == module.belongs_to_addr {
+                    coupler_modules.push(EthercatTopologyModule {
+                        slot: module.slot,
+                        name: "IP20 EC DI8 DO8 module".to_owned(),
+                        vendor_id: module.vendor_id,
+                        product_id: module.product_id,
+                    });
                     match ethercat_meta_devices.get(0) {
                         Some(meta) => {
                             let meta_data = EtherCatDeviceMetaData {
@@ -411,26 +990,120 @@ pub async fn setup_loop(
         }
     }
 
+    // Read the port states for the topology, the slot modules belong to the coupler at index 0
+    let mut topology_subdevices = vec![];
+    for (subdevice_index, subdevice) in subdevices.iter().enumerate() {
+        let ports = match subdevice.register_read::<u16>(DL_STATUS_REGISTER).await {
+            Ok(dl_status) => ports_from_dl_status(dl_status),
+            Err(e) => {
+                tracing::warn!(
+                    "[{}::setup_loop] Failed to read DL status of subdevice {}: {:?}",
+                    module_path!(),
+                    subdevice_index,
+                    e
+                );
+                Default::default()
+            }
+        };
+        let modules = match subdevice_index {
+            0 => std::mem::take(&mut coupler_modules),
+            _ => vec![],
+        };
+        topology_subdevices.push((
+            subdevice.configured_address(),
+            subdevice.name().to_string(),
+            ports,
+            modules,
+        ));
+    }
+    *app_state.ethercat_topology.write().await = Some(EthercatTopology::new(topology_subdevices));
+
     // remove subdevice from devices tuple
     let devices = devices
         .iter()
         .map(|(device_identification, device, _)| (device_identification.clone(), device.clone()))
         .collect::<Vec<_>>();
-    // Notify client via socketio
 
-    set_ethercat_devices::<MAX_SUBDEVICES, PDI_LEN>(
-        &identified_device_identifications,
+    // Machines write their PDO assignments here, so they have to be created before the process image is mapped
+    let new_device_identifications = identified_device_identifications
+        .iter()
+        .filter(|device_identification| {
+            device_identification
+                .device_machine_identification
+                .as_ref()
+                .is_none_or(|identification| {
+                    !kept_machines.contains(&identification.machine_identification_unique)
+                })
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    let new_machines_result = new_ethercat_machines(
+        &new_device_identifications,
+        &subdevice_identities,
         &MACHINE_REGISTRY,
         &MachineNewHardwareEthercat {
             ethercat_devices: &identified_devices,
             subdevices: &identified_subdevices,
         },
-        app_state.clone(),
+        &app_state,
         app_state.clone().socketio_setup.socket_queue_tx.clone(),
-    )
-    .await?;
+    );
 
-    let group_safe = match group_preop.into_safe_op(&maindevice).await {
+    let group_pdi = match group_preop.into_pre_op_pdi(&maindevice).await {
+        Ok(group_pdi) => {
+            tracing::info!("Group in Pre-OP state with mapped process image");
+            group_pdi
+        }
+        Err(err) => Err(anyhow::anyhow!(
+            "[{}::setup_loop] Failed to map process image: {:?}",
+            module_path!(),
+            err
+        ))?,
+    };
+
+    // Validate that no PDO assignment shifts the process data of the following subdevices
+    let pdo_length_mismatches =
+        validate_ethercat_pdo_lengths(&group_pdi, &maindevice, &devices).await;
+    let new_machines_result =
+        refuse_machines_with_pdo_mismatches(new_machines_result, &pdo_length_mismatches, &devices)
+            .await;
+    for mismatch in pdo_length_mismatches.iter() {
+        let Some(identification) =
+            devices
+                .get(mismatch.subdevice_index)
+                .and_then(|(device_identification, _)| {
+                    device_identification.device_machine_identification.as_ref()
+                })
+        else {
+            continue;
+        };
+        let machine = &identification.machine_identification_unique;
+        if kept_machines.contains(machine) {
+            for (device_identification, device) in devices.iter() {
+                if device_identification
+                    .device_machine_identification
+                    .as_ref()
+                    .is_some_and(|identification| {
+                        &identification.machine_identification_unique == machine
+                    })
+                {
+                    device.write().await.set_used(false);
+                }
+            }
+            delete_ethercat_machines(
+                &app_state,
+                std::slice::from_ref(machine),
+                Some(&mismatch.to_string()),
+            )
+            .await;
+        }
+    }
+    *app_state.ethercat_pdo_length_mismatches.write().await = pdo_length_mismatches;
+
+    // Notify client via socketio
+    register_ethercat_machines(new_machines_result, app_state.clone()).await;
+
+    let group_safe = match group_pdi.into_safe_op(&maindevice).await {
         Ok(group_op) => {
             tracing::info!("Group in Safe-OP state");
             group_op
@@ -477,15 +1150,11 @@ pub async fn setup_loop(
             .write()
             .await
             .main_namespace;
-        let event = EthercatDevicesEventBuilder()
-            .build(&app_state_clone)
-            .await;
+        let event = EthercatDevicesEventBuilder().build(&app_state_clone).await;
         main_namespace.emit(MainNamespaceEvents::EthercatDevicesEvent(event));
     }
 
-    return Ok(EthercatSetup {
-        devices,
-        group: group_op,
-        maindevice,
-    });
+    let setup = EthercatSetup::new(devices, group_op, maindevice);
+    *app_state.ethercat_setup_handle.write().await = Some(setup.handle());
+    return Ok(setup);
 }
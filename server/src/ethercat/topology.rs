@@ -0,0 +1,413 @@
+use serde::{Deserialize, Serialize};
+
+use crate::app_state::{EthercatSetupHandle, SharedState};
+
+/// ESC register holding the link and loop state of all four ports
+pub const DL_STATUS_REGISTER: u16 = 0x0110;
+
+/// State of a single ESC port, decoded from the DL status register
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EthercatPortState {
+    /// Physical link detected
+    pub link: bool,
+    /// Port is closed (looped back), so frames don't leave through it
+    pub loop_closed: bool,
+    /// Stable communication established on this port
+    pub communication: bool,
+}
+
+impl EthercatPortState {
+    /// A port is open if frames are forwarded to a neighbour through it
+    pub fn is_open(&self) -> bool {
+        self.communication && !self.loop_closed
+    }
+}
+
+/// Decodes the DL status register (0x0110) into the states of port 0 to 3
+pub fn ports_from_dl_status(dl_status: u16) -> [EthercatPortState; 4] {
+    std::array::from_fn(|port| EthercatPortState {
+        link: dl_status & (1 << (4 + port)) != 0,
+        loop_closed: dl_status & (1 << (8 + 2 * port)) != 0,
+        communication: dl_status & (1 << (9 + 2 * port)) != 0,
+    })
+}
+
+/// Slot module of a coupler that maps its modules into its own process image
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EthercatTopologyModule {
+    pub slot: u16,
+    pub name: String,
+    pub vendor_id: u32,
+    pub product_id: u32,
+}
+
+/// A subdevice in the EtherCAT chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EthercatTopologyNode {
+    /// Position in the chain, same as the subdevice index
+    pub subdevice_index: usize,
+    pub configured_address: u16,
+    pub name: String,
+    /// Port 0 to 3
+    pub ports: [EthercatPortState; 4],
+    /// Upstream subdevice, `None` if connected to the main device
+    pub parent_index: Option<usize>,
+    /// Downstream subdevices in chain order
+    pub children: Vec<usize>,
+    /// Slot modules, only set for WAGO 750-354 and IP20 couplers
+    pub modules: Vec<EthercatTopologyModule>,
+    /// `false` if the subdevice didn't answer the last DL status read
+    pub responding: bool,
+}
+
+/// Where the chain is most likely broken
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EthercatChainBreak {
+    /// Last subdevice upstream of the break that still responds, `None` if the break is right after the main device
+    pub last_responding_index: Option<usize>,
+    /// Ports of the last responding subdevice that had communication at setup but lost their link
+    pub lost_ports: Vec<u8>,
+    /// All subdevices that don't respond anymore
+    pub missing_indices: Vec<usize>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct EthercatTopology {
+    /// Subdevices in chain order
+    pub nodes: Vec<EthercatTopologyNode>,
+    pub chain_break: Option<EthercatChainBreak>,
+}
+
+/// Reconstructs the parent of every subdevice from the number of open ports
+///
+/// Subdevices are numbered in the order a frame passes them, so the chain is a depth first walk of the tree.
+/// Every open port except the upstream one leads to a child, which lets us rebuild the tree with a stack.
+pub fn infer_parents(open_ports: &[usize]) -> Vec<Option<usize>> {
+    // (subdevice index, remaining downstream ports)
+    let mut stack: Vec<(usize, usize)> = vec![];
+    let mut parents = Vec::with_capacity(open_ports.len());
+
+    for (index, open) in open_ports.iter().enumerate() {
+        while matches!(stack.last(), Some((_, 0))) {
+            stack.pop();
+        }
+
+        let parent = match stack.last_mut() {
+            Some((parent, remaining)) => {
+                *remaining -= 1;
+                Some(*parent)
+            }
+            None => None,
+        };
+        parents.push(parent);
+        stack.push((index, open.saturating_sub(1)));
+    }
+
+    parents
+}
+
+impl EthercatTopology {
+    /// Builds the topology from the DL status of every subdevice, read during setup
+    pub fn new(
+        subdevices: Vec<(
+            u16,
+            String,
+            [EthercatPortState; 4],
+            Vec<EthercatTopologyModule>,
+        )>,
+    ) -> Self {
+        let open_ports = subdevices
+            .iter()
+            .map(|(_, _, ports, _)| ports.iter().filter(|port| port.is_open()).count())
+            .collect::<Vec<_>>();
+        let parents = infer_parents(&open_ports);
+
+        let mut nodes = subdevices
+            .into_iter()
+            .zip(parents.iter())
+            .enumerate()
+            .map(
+                |(subdevice_index, ((configured_address, name, ports, modules), parent_index))| {
+                    EthercatTopologyNode {
+                        subdevice_index,
+                        configured_address,
+                        name,
+                        ports,
+                        parent_index: *parent_index,
+                        children: vec![],
+                        modules,
+                        responding: true,
+                    }
+                },
+            )
+            .collect::<Vec<_>>();
+
+        for (index, parent) in parents.iter().enumerate() {
+            if let Some(parent) = parent {
+                nodes[*parent].children.push(index);
+            }
+        }
+
+        Self {
+            nodes,
+            chain_break: None,
+        }
+    }
+
+    /// Returns true if `index` is `ancestor` or downstream of it
+    fn is_descendant(&self, index: usize, ancestor: usize) -> bool {
+        let mut current = Some(index);
+        while let Some(i) = current {
+            if i == ancestor {
+                return true;
+            }
+            current = self.nodes.get(i).and_then(|node| node.parent_index);
+        }
+        false
+    }
+
+    /// Subdevices that don't need to be read since an upstream subdevice already didn't respond
+    pub fn is_behind_missing(&self, index: usize, dl_status: &[Option<u16>]) -> bool {
+        dl_status
+            .iter()
+            .enumerate()
+            .take(index)
+            .any(|(i, status)| status.is_none() && self.is_descendant(index, i))
+    }
+
+    /// Applies a live read of the DL status registers, `None` for subdevices that didn't respond
+    ///
+    /// The setup topology is kept, only the port states, the responding flags and the break localization change.
+    pub fn with_live_dl_status(&self, dl_status: &[Option<u16>]) -> Self {
+        let mut topology = self.clone();
+
+        for node in topology.nodes.iter_mut() {
+            match dl_status.get(node.subdevice_index).copied().flatten() {
+                Some(status) => {
+                    node.ports = ports_from_dl_status(status);
+                    node.responding = true;
+                }
+                None => node.responding = false,
+            }
+        }
+
+        topology.chain_break = locate_chain_break(self, &topology);
+        topology
+    }
+}
+
+/// Finds the break from the first subdevice that stopped responding
+///
+/// The last responding subdevice is the upstream neighbour of the first missing one.
+/// Its ports that lost the link point to the cable that should be checked.
+pub fn locate_chain_break(
+    setup: &EthercatTopology,
+    live: &EthercatTopology,
+) -> Option<EthercatChainBreak> {
+    let missing_indices = live
+        .nodes
+        .iter()
+        .filter(|node| !node.responding)
+        .map(|node| node.subdevice_index)
+        .collect::<Vec<_>>();
+    let first_missing = *missing_indices.first()?;
+
+    let last_responding_index = live.nodes[first_missing].parent_index;
+    let lost_ports = match last_responding_index {
+        Some(index) => setup.nodes[index]
+            .ports
+            .iter()
+            .zip(live.nodes[index].ports.iter())
+            .enumerate()
+            .filter(|(_, (before, now))| before.is_open() && !now.link)
+            .map(|(port, _)| port as u8)
+            .collect::<Vec<_>>(),
+        None => vec![],
+    };
+
+    let first_missing_node = &live.nodes[first_missing];
+    let message = match last_responding_index {
+        Some(index) => {
+            let last = &live.nodes[index];
+            let ports = if lost_ports.is_empty() {
+                String::new()
+            } else {
+                format!(
+                    " port {}",
+                    lost_ports
+                        .iter()
+                        .map(|p| p.to_string())
+                        .collect::<Vec<_>>()
+                        .join("/")
+                )
+            };
+            format!(
+                "Chain broken between subdevice {} ({}){} and subdevice {} ({}), {} subdevice(s) not responding",
+                last.subdevice_index,
+                last.name,
+                ports,
+                first_missing_node.subdevice_index,
+                first_missing_node.name,
+                missing_indices.len()
+            )
+        }
+        None => format!(
+            "Chain broken between main device and subdevice {} ({}), {} subdevice(s) not responding",
+            first_missing_node.subdevice_index,
+            first_missing_node.name,
+            missing_indices.len()
+        ),
+    };
+
+    Some(EthercatChainBreak {
+        last_responding_index,
+        lost_ports,
+        missing_indices,
+        message,
+    })
+}
+
+/// Reads the DL status of every subdevice and applies it to the setup topology
+///
+/// Subdevices behind one that didn't respond are skipped, each of them would only wait for the PDU timeout.
+pub async fn read_ethercat_topology(
+    handle: &EthercatSetupHandle,
+    setup_topology: &EthercatTopology,
+) -> EthercatTopology {
+    let mut dl_status: Vec<Option<u16>> = vec![];
+
+    for (i, subdevice) in handle.group.iter(&handle.maindevice).enumerate() {
+        if setup_topology.is_behind_missing(i, &dl_status) {
+            dl_status.push(None);
+            continue;
+        }
+        dl_status.push(
+            subdevice
+                .register_read::<u16>(DL_STATUS_REGISTER)
+                .await
+                .ok(),
+        );
+    }
+
+    setup_topology.with_live_dl_status(&dl_status)
+}
+
+/// Re-reads the port states of the setup topology next to the cyclic exchange of the loop
+///
+/// Fails if setup hasn't finished or the group is being set up again.
+pub async fn read_live_ethercat_topology(
+    app_state: &SharedState,
+) -> Result<EthercatTopology, anyhow::Error> {
+    let not_set_up = || {
+        anyhow::anyhow!(
+            "[{}::read_live_ethercat_topology] EtherCAT setup has not finished yet",
+            module_path!()
+        )
+    };
+    let setup_topology = app_state
+        .ethercat_topology
+        .read()
+        .await
+        .clone()
+        .ok_or_else(not_set_up)?;
+    let handle = app_state
+        .ethercat_setup_handle
+        .read()
+        .await
+        .clone()
+        .ok_or_else(not_set_up)?;
+
+    Ok(read_ethercat_topology(&handle, &setup_topology).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// DL status with communication (and link) on the given ports, all other ports closed
+    fn dl_status(open: &[usize]) -> u16 {
+        let mut status = 0u16;
+        for port in 0..4 {
+            if open.contains(&port) {
+                status |= 1 << (4 + port);
+                status |= 1 << (9 + 2 * port);
+            } else {
+                status |= 1 << (8 + 2 * port);
+            }
+        }
+        status
+    }
+
+    fn topology(open: &[&[usize]]) -> EthercatTopology {
+        EthercatTopology::new(
+            open.iter()
+                .enumerate()
+                .map(|(i, ports)| {
+                    (
+                        0x1000 + i as u16,
+                        format!("SD{i}"),
+                        ports_from_dl_status(dl_status(ports)),
+                        vec![],
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_ports_from_dl_status() {
+        let ports = ports_from_dl_status(dl_status(&[0, 1]));
+        assert!(ports[0].is_open());
+        assert!(ports[1].is_open());
+        assert!(!ports[2].is_open());
+        assert!(ports[3].loop_closed);
+    }
+
+    #[test]
+    fn test_infer_parents_line() {
+        assert_eq!(infer_parents(&[2, 2, 1]), vec![None, Some(0), Some(1)]);
+    }
+
+    #[test]
+    fn test_infer_parents_fork() {
+        // 0 is a junction with two branches: 1-2 and 3
+        assert_eq!(
+            infer_parents(&[3, 2, 1, 1]),
+            vec![None, Some(0), Some(1), Some(0)]
+        );
+    }
+
+    #[test]
+    fn test_topology_children() {
+        let topology = topology(&[&[0, 1, 3], &[0, 1], &[0], &[0]]);
+        assert_eq!(topology.nodes[0].children, vec![1, 3]);
+        assert_eq!(topology.nodes[1].children, vec![2]);
+    }
+
+    #[test]
+    fn test_locate_chain_break() {
+        let setup = topology(&[&[0, 1], &[0, 1], &[0, 1], &[0]]);
+        // cable between 1 and 2 unplugged, 1 closes port 1 automatically
+        let live = setup.with_live_dl_status(&[
+            Some(dl_status(&[0, 1])),
+            Some(dl_status(&[0])),
+            None,
+            None,
+        ]);
+
+        let chain_break = live.chain_break.unwrap();
+        assert_eq!(chain_break.last_responding_index, Some(1));
+        assert_eq!(chain_break.lost_ports, vec![1]);
+        assert_eq!(chain_break.missing_indices, vec![2, 3]);
+        assert!(setup.is_behind_missing(3, &[Some(0), Some(0), None]));
+        assert!(!setup.is_behind_missing(1, &[Some(0)]));
+    }
+
+    #[test]
+    fn test_no_chain_break() {
+        let setup = topology(&[&[0, 1], &[0]]);
+        let live = setup.with_live_dl_status(&[Some(dl_status(&[0, 1])), Some(dl_status(&[0]))]);
+        assert!(live.chain_break.is_none());
+    }
+}
@@ -1,14 +1,27 @@
 use crate::app_state::{EthercatSetup, HotThreadMessage};
+use crate::ethercat::assignment::write_machine_assignment;
+use crate::ethercat::identification_backup::{
+    undo_machine_device_identification, write_machine_device_identification_with_backup,
+};
+use crate::ethercat::capture::{
+    dump_frame_capture, is_frame_capture_running, request_frame_capture_dump,
+};
 use crate::performance_metrics::EthercatPerformanceMetrics;
 use bitvec::prelude::*;
 use machines::Machine;
-use machines::machine_identification::write_machine_device_identification;
 use smol::channel::Receiver;
 use spin_sleep::SpinSleeper;
 use std::time::Duration;
 use std::time::Instant;
 
 use crate::metrics::jitter::record_machines_loop_jitter;
+
+/// Consecutive tx_rx failures after which a running frame capture is dumped to disk
+const CAPTURE_TRIGGER_TXRX_FAILURES: u32 = 3;
+
+/// Consecutive tx_rx failures after which the server exits to get restarted
+const EXIT_TXRX_FAILURES: u32 = 20;
+
 pub struct RtLoopInputs<'a> {
     pub machines: &'a mut Vec<Box<dyn Machine>>,
     pub ethercat_setup: Option<Box<EthercatSetup>>,
@@ -37,6 +50,8 @@ pub fn start_loop_thread(
                     .with_spin_strategy(spin_sleep::SpinStrategy::YieldThread);
             let mut ethercat_perf = EthercatPerformanceMetrics::new();
             let mut machines: Vec<Box<dyn Machine>> = vec![];
+            // machines without process data while the EtherCAT group is set up again
+            let mut paused_machines: Vec<Box<dyn Machine>> = vec![];
             let mut last_iter_start: Option<Instant> = None;
             let mut rt_loop_inputs = RtLoopInputs {
                 machines: &mut machines,
@@ -60,35 +75,108 @@ pub fn start_loop_thread(
                     HotThreadMessage::AddEtherCatSetup(ethercat_setup) => {
                         println!("EthercatSetup: {:?}", ethercat_setup.devices);
                         rt_loop_inputs.ethercat_setup = Some(Box::new(ethercat_setup));
+                        rt_loop_inputs.machines.append(&mut paused_machines);
                     }
-                    HotThreadMessage::WriteMachineDeviceInfo(info_request) => {
+                    HotThreadMessage::WriteMachineDeviceInfo(info_request, reply) => {
+                        let subdevice_index = info_request
+                            .hardware_identification_ethercat
+                            .subdevice_index;
+                        let res = match &rt_loop_inputs.ethercat_setup {
+                            Some(ethercat_setup) => ethercat_setup
+                                .group
+                                .subdevice(&ethercat_setup.maindevice, subdevice_index)
+                                .map_err(anyhow::Error::from)
+                                .and_then(|subdevice| {
+                                    smol::block_on(write_machine_device_identification_with_backup(
+                                        &subdevice,
+                                        &ethercat_setup.maindevice,
+                                        subdevice_index,
+                                        &info_request.device_machine_identification,
+                                    ))
+                                }),
+                            None => Err(anyhow::anyhow!("EtherCAT setup has not finished yet")),
+                        };
+                        match &res {
+                            Ok(_) => tracing::info!(
+                                "Successfully wrote machine device identification to EEPROM"
+                            ),
+                            Err(e) => tracing::error!(
+                                "Failed to write machine device identification to EEPROM: {:?}",
+                                e
+                            ),
+                        }
+                        let _ = reply.try_send(res);
+                    }
+                    HotThreadMessage::UndoMachineDeviceInfo(hardware_identification, reply) => {
+                        let subdevice_index = hardware_identification.subdevice_index;
+                        let res = match &rt_loop_inputs.ethercat_setup {
+                            Some(ethercat_setup) => ethercat_setup
+                                .group
+                                .subdevice(&ethercat_setup.maindevice, subdevice_index)
+                                .map_err(anyhow::Error::from)
+                                .and_then(|subdevice| {
+                                    smol::block_on(undo_machine_device_identification(
+                                        &subdevice,
+                                        &ethercat_setup.maindevice,
+                                        subdevice_index,
+                                    ))
+                                }),
+                            None => Err(anyhow::anyhow!("EtherCAT setup has not finished yet")),
+                        };
+                        if let Err(e) = &res {
+                            tracing::error!(
+                                "Failed to undo machine device identification: {:?}",
+                                e
+                            );
+                        }
+                        let _ = reply.try_send(res);
+                    }
+                    HotThreadMessage::AssignMachineDeviceInfo(info_requests, reply) => {
+                        let res = match &rt_loop_inputs.ethercat_setup {
+                            Some(ethercat_setup) => smol::block_on(write_machine_assignment(
+                                ethercat_setup,
+                                &info_requests,
+                            )),
+                            None => Err(anyhow::anyhow!("EtherCAT setup has not finished yet")),
+                        };
+                        if let Err(e) = &res {
+                            tracing::error!(
+                                "Failed to assign machine device identifications: {:?}",
+                                e
+                            );
+                        }
+                        let _ = reply.try_send(res);
+                    }
+                    HotThreadMessage::RemoveEthercatSetup(unique_ids, paused_ids, reply) => {
+                        rt_loop_inputs
+                            .machines
+                            .retain(|m| {
+                                !unique_ids.contains(&m.get_machine_identification_unique())
+                            });
+                        let (paused, running) = rt_loop_inputs
+                            .machines
+                            .drain(..)
+                            .partition::<Vec<_>, _>(|m| {
+                                paused_ids.contains(&m.get_machine_identification_unique())
+                            });
+                        *rt_loop_inputs.machines = running;
+                        paused_machines.extend(paused);
                         if let Some(ethercat_setup) = &rt_loop_inputs.ethercat_setup {
-                            if let Ok(subdevice) = ethercat_setup.group.subdevice(
-                                &ethercat_setup.maindevice,
-                                info_request
-                                    .hardware_identification_ethercat
-                                    .subdevice_index,
-                            ) {
-                                let _res = smol::block_on(write_machine_device_identification(
-                                    &subdevice,
-                                    &ethercat_setup.maindevice,
-                                    &info_request.device_machine_identification,
-                                ));
-                                if let Err(e) = _res {
-                                    tracing::error!("Failed to write machine device identification to EEPROM: {}", e);
-                                } else {
-                                    tracing::info!("Successfully wrote machine device identification to EEPROM");
-                                }
+                            let res = smol::block_on(switch_off_ethercat_outputs(ethercat_setup));
+                            if let Err(e) = res {
+                                tracing::error!("Failed to switch off EtherCAT outputs: {:?}", e);
                             }
-
-                            // Recreate machines after assignment is handled by REST flow.
-                            // No action in the RT loop.
                         }
+                        let ethercat_setup = rt_loop_inputs.ethercat_setup.take();
+                        rt_loop_inputs.consecutive_txrx_failures = 0;
+                        let _ = reply.try_send(ethercat_setup.map(|setup| *setup));
                     }
                     HotThreadMessage::DeleteMachine(unique_id) => {
                         rt_loop_inputs
                             .machines
                             .retain(|m| m.get_machine_identification_unique() != unique_id);
+                        paused_machines
+                            .retain(|m| m.get_machine_identification_unique() != unique_id);
                     }
                     HotThreadMessage::AddMachines(machine_vec) => {
                         tracing::info!("received machines{:?}", machine_vec);
@@ -97,6 +185,7 @@ pub fn start_loop_thread(
                             if !rt_loop_inputs
                                 .machines
                                 .iter()
+                                .chain(paused_machines.iter())
                                 .any(|m| m.get_machine_identification_unique() == id)
                             {
                                 rt_loop_inputs.machines.push(new_machine);
@@ -136,6 +225,27 @@ pub fn start_loop_thread(
     return res;
 }
 
+/// Sends one cycle with a zeroed output image, so no output stays on while the group leaves OP
+pub async fn switch_off_ethercat_outputs(
+    ethercat_setup: &EthercatSetup,
+) -> Result<(), anyhow::Error> {
+    for subdevice in ethercat_setup.group.iter(&ethercat_setup.maindevice) {
+        subdevice.outputs_raw_mut().fill(0);
+    }
+    ethercat_setup
+        .group
+        .tx_rx(&ethercat_setup.maindevice)
+        .await
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "[{}::switch_off_ethercat_outputs] tx_rx failed: {:?}",
+                module_path!(),
+                e
+            )
+        })?;
+    Ok(())
+}
+
 pub async fn copy_ethercat_inputs(
     ethercat_setup: Option<&EthercatSetup>,
 ) -> Result<bool, anyhow::Error> {
@@ -270,6 +380,10 @@ pub fn loop_once<'maindevice>(inputs: &mut RtLoopInputs<'_>) -> Result<(), anyho
                     inputs.consecutive_txrx_failures = 0;
                 } else {
                     inputs.consecutive_txrx_failures = inputs.consecutive_txrx_failures.saturating_add(1);
+                    if inputs.consecutive_txrx_failures == CAPTURE_TRIGGER_TXRX_FAILURES {
+                        // the capture thread writes the file, the loop only sets a flag
+                        request_frame_capture_dump();
+                    }
                 }
             }
             Err(e) => {
@@ -278,11 +392,17 @@ pub fn loop_once<'maindevice>(inputs: &mut RtLoopInputs<'_>) -> Result<(), anyho
         };
 
         // Auto-restart on persistent EtherCAT failures
-        if inputs.consecutive_txrx_failures >= 20 {
+        if inputs.consecutive_txrx_failures >= EXIT_TXRX_FAILURES {
             tracing::error!(
                 "EtherCAT connection lost after {} consecutive failures. Restarting server...",
                 inputs.consecutive_txrx_failures
             );
+            // Keep the traffic leading up to the connection loss, we are exiting anyway
+            if is_frame_capture_running() {
+                if let Err(e) = dump_frame_capture("connection_lost") {
+                    tracing::error!("{:?}", e);
+                }
+            }
             // Exit with code 2 to signal EtherCAT connection loss
             // systemd or the startup script will automatically restart the server
             std::process::exit(2);
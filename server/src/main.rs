@@ -15,6 +15,7 @@ use machines::{
 use std::sync::atomic::{AtomicBool, Ordering};
 
 use app_state::{HotThreadMessage, SharedState};
+use ethercat::capture::start_frame_capture_from_env;
 use ethercat::ethercat_discovery_info::send_ethercat_discovering;
 use r#loop::start_loop_thread;
 use metrics::io::set_ethercat_iface;
@@ -99,6 +100,7 @@ pub async fn add_serial_device(
         .add_machines_if_not_exists(vec![MachineObj {
             machine_identification_unique: machine_identification.clone(),
             error: None,
+            hardware_checklist: vec![],
         }])
         .await;
 
@@ -133,6 +135,7 @@ pub async fn start_interface_discovery(
     let interface = find_ethercat_interface().await;
     tracing::info!("Inferface found {}, setting up EtherCAT loop", interface);
     set_ethercat_iface(interface.clone());
+    start_frame_capture_from_env(&interface);
 
     let res = setup_loop(&interface, app_state.clone()).await;
 
@@ -3,7 +3,9 @@ use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use smol::Timer;
 
 use crate::metrics::csv_writer::{RuntimeSample, append_runtime_sample_csv};
-use crate::metrics::io::{NetDevCounters, get_ethercat_iface, read_netdev_counters};
+use crate::metrics::io::{
+    NetDevCounters, get_ethercat_iface, read_netdev_counters, read_netdev_health,
+};
 use crate::metrics::jitter::snapshot_machines_jitter;
 use crate::metrics::preemption::{get_rt_loop_tid, read_thread_sched_stats};
 use crate::metrics::process::ProcessMetrics;
@@ -76,6 +78,16 @@ pub fn spawn_runtime_metrics_sampler(cfg: RuntimeMetricsConfig) {
                     }
                     last_net = Some((now_inst, curr));
                 }
+
+                let health = read_netdev_health(iface);
+                sample.nic_rx_errors = health.rx_errors;
+                sample.nic_tx_errors = health.tx_errors;
+                sample.nic_rx_dropped = health.rx_dropped;
+                sample.nic_tx_dropped = health.tx_dropped;
+                sample.nic_carrier_changes = health.carrier_changes;
+                sample.nic_link_up = health.link_up;
+                sample.nic_speed_mbps = health.speed_mbps;
+                sample.nic_duplex = health.duplex;
             }
 
             // 4) Preemption stats (cumulative)
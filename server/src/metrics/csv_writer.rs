@@ -1,7 +1,8 @@
-use std::fs::OpenOptions;
-use std::io::{BufWriter, Write};
-use std::path::Path;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
 
+use crate::metrics::io::NetDevDuplex;
 use crate::metrics::process::ProcessMetrics;
 
 /// One row of runtime metrics for CSV export.
@@ -27,6 +28,16 @@ pub struct RuntimeSample {
     pub rx_rate_bytes_per_sec: f64,
     pub tx_rate_bytes_per_sec: f64,
 
+    // NIC errors (cumulative) and link state
+    pub nic_rx_errors: Option<u64>,
+    pub nic_tx_errors: Option<u64>,
+    pub nic_rx_dropped: Option<u64>,
+    pub nic_tx_dropped: Option<u64>,
+    pub nic_carrier_changes: Option<u64>,
+    pub nic_link_up: Option<bool>,
+    pub nic_speed_mbps: Option<u32>,
+    pub nic_duplex: Option<NetDevDuplex>,
+
     // preemption stats
     pub rt_nr_switches: Option<u64>,
     pub rt_nr_voluntary_switches: Option<u64>,
@@ -51,6 +62,15 @@ impl RuntimeSample {
             rx_rate_bytes_per_sec: 0.0,
             tx_rate_bytes_per_sec: 0.0,
 
+            nic_rx_errors: None,
+            nic_tx_errors: None,
+            nic_rx_dropped: None,
+            nic_tx_dropped: None,
+            nic_carrier_changes: None,
+            nic_link_up: None,
+            nic_speed_mbps: None,
+            nic_duplex: None,
+
             rt_nr_switches: None,
             rt_nr_voluntary_switches: None,
             rt_nr_involuntary_switches: None,
@@ -58,10 +78,7 @@ impl RuntimeSample {
     }
 }
 
-fn write_header<W: Write>(mut w: W) -> std::io::Result<()> {
-    writeln!(
-        w,
-        "timestamp_ms,\
+const HEADER: &str = "timestamp_ms,\
          rss_bytes,\
          process_cpu_time_s,\
          minor_faults,\
@@ -72,16 +89,48 @@ fn write_header<W: Write>(mut w: W) -> std::io::Result<()> {
          rt_loop_cpu_time_s,\
          rx_bytes_per_s,\
          tx_bytes_per_s,\
+         nic_rx_errors,\
+         nic_tx_errors,\
+         nic_rx_dropped,\
+         nic_tx_dropped,\
+         nic_carrier_changes,\
+         nic_link_up,\
+         nic_speed_mbps,\
+         nic_duplex,\
          rt_nr_switches,\
          rt_nr_voluntary_switches,\
-         rt_nr_involuntary_switches"
-    )
+         rt_nr_involuntary_switches";
+
+fn write_header<W: Write>(mut w: W) -> std::io::Result<()> {
+    writeln!(w, "{}", HEADER)
+}
+
+/// Whether the first line of an existing file is not the current header
+fn has_other_header(path: &Path) -> std::io::Result<bool> {
+    let mut first_line = String::new();
+    BufReader::new(File::open(path)?).read_line(&mut first_line)?;
+    Ok(first_line.trim_end() != HEADER)
+}
+
+/// `metrics.csv` becomes `metrics.<timestamp_ms>.csv`
+fn rotated_path(path: &Path, timestamp_ms: u128) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(extension) => format!("{}.{}.{}", stem, timestamp_ms, extension.to_string_lossy()),
+        None => format!("{}.{}", stem, timestamp_ms),
+    };
+    path.with_file_name(name)
 }
 
 fn opt_u64(v: Option<u64>) -> String {
     v.map(|x| x.to_string()).unwrap_or_default()
 }
 
+fn opt_bool(v: Option<bool>) -> String {
+    v.map(|x| if x { "1" } else { "0" }.to_string())
+        .unwrap_or_default()
+}
+
 fn opt_f64(v: Option<f64>) -> String {
     v.map(|x| format!("{:.6}", x)).unwrap_or_default()
 }
@@ -91,7 +140,13 @@ pub fn append_runtime_sample_csv<P: AsRef<Path>>(
     sample: &RuntimeSample,
 ) -> std::io::Result<()> {
     let path = path.as_ref();
-    let file_existed = path.exists();
+    let mut file_existed = path.exists();
+
+    // columns changed since the file was created, keep it aside instead of appending shifted rows
+    if file_existed && has_other_header(path)? {
+        std::fs::rename(path, rotated_path(path, sample.timestamp_ms))?;
+        file_existed = false;
+    }
 
     let file = OpenOptions::new().create(true).append(true).open(path)?;
 
@@ -107,6 +162,7 @@ pub fn append_runtime_sample_csv<P: AsRef<Path>>(
          {},{},{} ,\
          {},\
          {:.3},{:.3},\
+         {},{},{},{},{},{},{},{},\
          {},{},{}",
         sample.timestamp_ms,
         sample.rss_bytes,
@@ -119,6 +175,14 @@ pub fn append_runtime_sample_csv<P: AsRef<Path>>(
         opt_f64(sample.rt_loop_cpu_time_seconds),
         sample.rx_rate_bytes_per_sec,
         sample.tx_rate_bytes_per_sec,
+        opt_u64(sample.nic_rx_errors),
+        opt_u64(sample.nic_tx_errors),
+        opt_u64(sample.nic_rx_dropped),
+        opt_u64(sample.nic_tx_dropped),
+        opt_u64(sample.nic_carrier_changes),
+        opt_bool(sample.nic_link_up),
+        opt_u64(sample.nic_speed_mbps.map(u64::from)),
+        sample.nic_duplex.map(|d| d.as_str()).unwrap_or_default(),
         opt_u64(sample.rt_nr_switches),
         opt_u64(sample.rt_nr_voluntary_switches),
         opt_u64(sample.rt_nr_involuntary_switches),
@@ -127,3 +191,36 @@ pub fn append_runtime_sample_csv<P: AsRef<Path>>(
     writer.flush()?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotates_file_with_other_header() {
+        let dir = std::env::temp_dir().join(format!("csv_writer_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("metrics.csv");
+        std::fs::write(&path, "timestamp_ms,rss_bytes\n1,2\n").unwrap();
+
+        let metrics = ProcessMetrics {
+            rss_bytes: 1,
+            cpu_time_seconds: 0.5,
+            minor_faults: 2,
+            major_faults: 0,
+        };
+        let sample = RuntimeSample::from_process_metrics(metrics, 42);
+        append_runtime_sample_csv(&path, &sample).unwrap();
+        append_runtime_sample_csv(&path, &sample).unwrap();
+
+        let rotated = std::fs::read_to_string(dir.join("metrics.42.csv")).unwrap();
+        assert_eq!(rotated, "timestamp_ms,rss_bytes\n1,2\n");
+        let current = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<_> = current.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], HEADER);
+        assert_eq!(lines[1].split(',').count(), HEADER.split(',').count());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -9,6 +9,40 @@ pub struct NetDevCounters {
     pub tx_bytes: u64,
 }
 
+/// Duplex mode of a network interface link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NetDevDuplex {
+    Full,
+    Half,
+}
+
+impl NetDevDuplex {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NetDevDuplex::Full => "full",
+            NetDevDuplex::Half => "half",
+        }
+    }
+}
+
+/// Error counters and link state for a network interface.
+///
+/// Counters are cumulative since the interface came up. Every field is
+/// optional since drivers don't expose all of them, and speed/duplex can't be
+/// read while the link is down.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NetDevHealth {
+    pub rx_errors: Option<u64>,
+    pub tx_errors: Option<u64>,
+    pub rx_dropped: Option<u64>,
+    pub tx_dropped: Option<u64>,
+    pub carrier_changes: Option<u64>,
+    pub link_up: Option<bool>,
+    pub speed_mbps: Option<u32>,
+    pub duplex: Option<NetDevDuplex>,
+}
+
 // Global storage for the discovered EtherCAT interface name.
 static ETHERCAT_IFACE: OnceLock<String> = OnceLock::new();
 
@@ -40,3 +74,67 @@ pub fn read_netdev_counters(iface: &str) -> Option<NetDevCounters> {
         tx_bytes: read_u64_from(&tx_path)?,
     })
 }
+
+/// Parse the content of `/sys/class/net/<iface>/speed`.
+///
+/// Drivers report `-1` (or an unsigned wrap of it) when the speed is unknown.
+fn parse_speed_mbps(s: &str) -> Option<u32> {
+    let speed = s.trim().parse::<i64>().ok()?;
+    match speed {
+        1..=1_000_000 => Some(speed as u32),
+        _ => None,
+    }
+}
+
+/// Parse the content of `/sys/class/net/<iface>/duplex`.
+fn parse_duplex(s: &str) -> Option<NetDevDuplex> {
+    match s.trim() {
+        "full" => Some(NetDevDuplex::Full),
+        "half" => Some(NetDevDuplex::Half),
+        _ => None,
+    }
+}
+
+/// Read error counters and link state for a network interface from /sys/class/net.
+///
+/// Files that are missing or unreadable are reported as None.
+pub fn read_netdev_health(iface: &str) -> NetDevHealth {
+    let base = Path::new("/sys/class/net").join(iface);
+    let stats = base.join("statistics");
+
+    NetDevHealth {
+        rx_errors: read_u64_from(&stats.join("rx_errors")),
+        tx_errors: read_u64_from(&stats.join("tx_errors")),
+        rx_dropped: read_u64_from(&stats.join("rx_dropped")),
+        tx_dropped: read_u64_from(&stats.join("tx_dropped")),
+        carrier_changes: read_u64_from(&base.join("carrier_changes")),
+        link_up: read_u64_from(&base.join("carrier")).map(|c| c == 1),
+        speed_mbps: fs::read_to_string(base.join("speed"))
+            .ok()
+            .and_then(|s| parse_speed_mbps(&s)),
+        duplex: fs::read_to_string(base.join("duplex"))
+            .ok()
+            .and_then(|s| parse_duplex(&s)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_speed_mbps() {
+        assert_eq!(parse_speed_mbps("100\n"), Some(100));
+        assert_eq!(parse_speed_mbps("1000"), Some(1000));
+        assert_eq!(parse_speed_mbps("-1\n"), None);
+        assert_eq!(parse_speed_mbps("4294967295"), None);
+        assert_eq!(parse_speed_mbps(""), None);
+    }
+
+    #[test]
+    fn test_parse_duplex() {
+        assert_eq!(parse_duplex("full\n"), Some(NetDevDuplex::Full));
+        assert_eq!(parse_duplex("half"), Some(NetDevDuplex::Half));
+        assert_eq!(parse_duplex("unknown\n"), None);
+    }
+}
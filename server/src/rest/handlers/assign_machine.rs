@@ -0,0 +1,22 @@
+use axum::{Json, extract::State, http::Response};
+use std::sync::Arc;
+
+use crate::{
+    app_state::SharedState,
+    ethercat::assignment::{MachineAssignmentRequest, assign_machine},
+    rest::util::ResponseUtil,
+};
+
+/// Writes the identifications for all subdevices of a machine and creates it
+///
+/// Replies with the backup entries of the written subdevices.
+#[axum::debug_handler]
+pub async fn post_assign_machine(
+    State(app_state): State<Arc<SharedState>>,
+    Json(body): Json<MachineAssignmentRequest>,
+) -> Response<axum::body::Body> {
+    match assign_machine(app_state, body).await {
+        Ok(backups) => ResponseUtil::ok(backups),
+        Err(e) => ResponseUtil::error(&e.to_string()),
+    }
+}
@@ -0,0 +1,74 @@
+use axum::{Json, http::Response};
+
+use crate::{
+    ethercat::capture::{
+        DEFAULT_CAPTURE_CAPACITY, dump_frame_capture, frame_capture_pcapng, frame_capture_status,
+        start_frame_capture, stop_frame_capture,
+    },
+    metrics::io::get_ethercat_iface,
+    rest::util::ResponseUtil,
+};
+
+#[derive(serde::Deserialize, Debug, Clone, Default)]
+pub struct StartCaptureRequest {
+    /// Ring capacity in frames, defaults to [`DEFAULT_CAPTURE_CAPACITY`]
+    pub capacity: Option<usize>,
+}
+
+#[derive(serde::Serialize)]
+pub struct DumpCaptureResponse {
+    pub path: String,
+}
+
+#[axum::debug_handler]
+pub async fn post_ethercat_capture_start(
+    Json(body): Json<StartCaptureRequest>,
+) -> Response<axum::body::Body> {
+    let Some(iface) = get_ethercat_iface() else {
+        return ResponseUtil::error("No EtherCAT interface discovered yet");
+    };
+
+    let capacity = body.capacity.unwrap_or(DEFAULT_CAPTURE_CAPACITY);
+    // joins the thread of a previous session, keep it off the executor
+    match smol::unblock(move || start_frame_capture(iface, capacity)).await {
+        Ok(_) => ResponseUtil::ok(frame_capture_status()),
+        Err(e) => ResponseUtil::error(&e.to_string()),
+    }
+}
+
+#[axum::debug_handler]
+pub async fn post_ethercat_capture_stop() -> Response<axum::body::Body> {
+    // waits up to the receive timeout of the capture socket
+    smol::unblock(stop_frame_capture).await;
+    ResponseUtil::ok(frame_capture_status())
+}
+
+#[axum::debug_handler]
+pub async fn get_ethercat_capture_status() -> Response<axum::body::Body> {
+    ResponseUtil::ok(frame_capture_status())
+}
+
+/// Writes the current ring to disk on the server, useful when the frontend can't download files
+#[axum::debug_handler]
+pub async fn post_ethercat_capture_dump() -> Response<axum::body::Body> {
+    match dump_frame_capture("manual") {
+        Ok(path) => ResponseUtil::ok(DumpCaptureResponse {
+            path: path.display().to_string(),
+        }),
+        Err(e) => ResponseUtil::error(&e.to_string()),
+    }
+}
+
+/// Returns the current ring as pcapng file for Wireshark
+#[axum::debug_handler]
+pub async fn get_ethercat_capture_pcapng() -> Response<axum::body::Body> {
+    Response::builder()
+        .status(axum::http::StatusCode::OK)
+        .header("Content-Type", "application/vnd.tcpdump.pcap")
+        .header(
+            "Content-Disposition",
+            "attachment; filename=\"ethercat_capture.pcapng\"",
+        )
+        .body(axum::body::Body::from(frame_capture_pcapng()))
+        .unwrap()
+}
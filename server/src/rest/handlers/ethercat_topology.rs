@@ -0,0 +1,41 @@
+use axum::{extract::State, http::Response};
+use std::sync::Arc;
+
+use crate::{
+    app_state::SharedState,
+    ethercat::{rescan::rescan_ethercat, topology::read_live_ethercat_topology},
+    rest::util::ResponseUtil,
+};
+
+/// Returns the live topology of the EtherCAT chain including the cable break localization
+///
+/// Falls back to the topology read during setup while the group is set up again.
+#[axum::debug_handler]
+pub async fn get_ethercat_topology(
+    State(app_state): State<Arc<SharedState>>,
+) -> Response<axum::body::Body> {
+    let Some(setup_topology) = app_state.ethercat_topology.read().await.clone() else {
+        return ResponseUtil::not_found("EtherCAT setup has not finished yet");
+    };
+
+    match read_live_ethercat_topology(&app_state).await {
+        Ok(topology) => ResponseUtil::ok(topology),
+        Err(e) => {
+            tracing::warn!("{:?}, returning setup topology", e);
+            ResponseUtil::ok(setup_topology)
+        }
+    }
+}
+
+/// Compares the bus with the setup, removes machines whose subdevices disappeared and adds new ones
+///
+/// Setting the group up again interrupts all EtherCAT machines, they are listed in `interrupted_machines`.
+#[axum::debug_handler]
+pub async fn post_ethercat_rescan(
+    State(app_state): State<Arc<SharedState>>,
+) -> Response<axum::body::Body> {
+    match rescan_ethercat(app_state).await {
+        Ok(result) => ResponseUtil::ok(result),
+        Err(e) => ResponseUtil::error(&e.to_string()),
+    }
+}
@@ -4,6 +4,7 @@ use axum::{Json, Router, routing::get};
 use serde::Serialize;
 
 use crate::SharedState;
+use crate::metrics::io::NetDevDuplex;
 use crate::metrics::process::ProcessMetrics;
 use crate::metrics::state::get_latest_runtime_sample;
 
@@ -40,6 +41,16 @@ pub struct RuntimeMetricsResponse {
     pub rx_rate_bytes_per_sec: f64,
     pub tx_rate_bytes_per_sec: f64,
 
+    // NIC errors (cumulative) and link state
+    pub nic_rx_errors: Option<u64>,
+    pub nic_tx_errors: Option<u64>,
+    pub nic_rx_dropped: Option<u64>,
+    pub nic_tx_dropped: Option<u64>,
+    pub nic_carrier_changes: Option<u64>,
+    pub nic_link_up: Option<bool>,
+    pub nic_speed_mbps: Option<u32>,
+    pub nic_duplex: Option<NetDevDuplex>,
+
     // preemption stats (cumulative)
     pub rt_nr_switches: Option<u64>,
     pub rt_nr_voluntary_switches: Option<u64>,
@@ -72,6 +83,15 @@ async fn get_runtime_metrics_latest() -> Json<Option<RuntimeMetricsResponse>> {
         rx_rate_bytes_per_sec: s.rx_rate_bytes_per_sec,
         tx_rate_bytes_per_sec: s.tx_rate_bytes_per_sec,
 
+        nic_rx_errors: s.nic_rx_errors,
+        nic_tx_errors: s.nic_tx_errors,
+        nic_rx_dropped: s.nic_rx_dropped,
+        nic_tx_dropped: s.nic_tx_dropped,
+        nic_carrier_changes: s.nic_carrier_changes,
+        nic_link_up: s.nic_link_up,
+        nic_speed_mbps: s.nic_speed_mbps,
+        nic_duplex: s.nic_duplex,
+
         rt_nr_switches: s.rt_nr_switches,
         rt_nr_voluntary_switches: s.rt_nr_voluntary_switches,
         rt_nr_involuntary_switches: s.rt_nr_involuntary_switches,
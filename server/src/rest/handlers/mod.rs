@@ -1,5 +1,9 @@
 pub mod machine_mutation;
 pub mod metrics;
+pub mod motor_configuration;
 pub mod mutation;
 pub mod write_machine_device_identification;
+pub mod assign_machine;
+pub mod ethercat_capture;
 pub mod ethercat_recover;
+pub mod ethercat_topology;
@@ -0,0 +1,49 @@
+use crate::{
+    app_state::SharedState,
+    ethercat::motor_configuration::{
+        MotorConfigurationRequest, WriteMotorConfigurationRequest, read_motor_configuration,
+        write_motor_configuration,
+    },
+    rest::util::ResponseUtil,
+};
+use axum::{Json, extract::State, http::Response};
+use std::sync::Arc;
+
+use super::mutation::MutationResponse;
+
+/// Replies with the motor configuration as read from the stepper terminal
+#[axum::debug_handler]
+pub async fn post_read_motor_configuration(
+    State(app_state): State<Arc<SharedState>>,
+    Json(body): Json<MotorConfigurationRequest>,
+) -> Response<axum::body::Body> {
+    match read_motor_configuration(&app_state, &body).await {
+        Ok(configuration) => ResponseUtil::ok(configuration),
+        Err(e) => {
+            tracing::error!("Failed to read motor configuration: {:?}", e);
+            ResponseUtil::error(&e.to_string())
+        }
+    }
+}
+
+/// Writes the motor configuration to the stepper terminal and keeps it for the next start
+#[axum::debug_handler]
+pub async fn post_write_motor_configuration(
+    State(app_state): State<Arc<SharedState>>,
+    Json(body): Json<WriteMotorConfigurationRequest>,
+) -> Response<axum::body::Body> {
+    match write_motor_configuration(&app_state, &body).await {
+        Ok(()) => {
+            tracing::info!(
+                "Wrote motor configuration of role {} on {}",
+                body.role,
+                body.machine_identification_unique
+            );
+            ResponseUtil::ok(MutationResponse::success())
+        }
+        Err(e) => {
+            tracing::error!("Failed to write motor configuration: {:?}", e);
+            ResponseUtil::error(&e.to_string())
+        }
+    }
+}
@@ -1,4 +1,8 @@
-use crate::{app_state::SharedState, rest::util::ResponseUtil};
+use crate::ethercat::identification_backup::IdentificationBackup;
+use crate::{
+    app_state::{HotThreadMessage, SharedState},
+    rest::util::ResponseUtil,
+};
 use axum::{Json, extract::State, http::Response};
 use crate::socketio::main_namespace::machines_event::MachineObj;
 use crate::ethercat::setup::group_devices_by_identification;
@@ -6,7 +10,9 @@ use machines::machine_identification::{
     DeviceHardwareIdentificationEthercat, DeviceMachineIdentification,
 };
 
-use std::sync::Arc;
+use smol::channel::Sender;
+use smol::future::FutureExt;
+use std::{sync::Arc, time::Duration};
 
 use super::mutation::MutationResponse;
 
@@ -16,32 +22,58 @@ pub struct MachineDeviceInfoRequest {
     pub hardware_identification_ethercat: DeviceHardwareIdentificationEthercat,
 }
 
-#[axum::debug_handler]
-pub async fn post_write_machine_device_identification(
-    State(app_state): State<Arc<SharedState>>,
-    Json(body): Json<MachineDeviceInfoRequest>,
-) -> Response<axum::body::Body> {
-    let res = app_state
+/// Request to restore the identification from the last backup of a subdevice
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct UndoMachineDeviceInfoRequest {
+    pub hardware_identification_ethercat: DeviceHardwareIdentificationEthercat,
+}
+
+/// Sends the message to the loop and waits for the outcome of the EEPROM write
+async fn send_identification_message(
+    app_state: &SharedState,
+    message: impl FnOnce(
+        Sender<Result<IdentificationBackup, anyhow::Error>>,
+    ) -> HotThreadMessage,
+) -> Result<IdentificationBackup, anyhow::Error> {
+    let (reply_tx, reply_rx) = smol::channel::bounded(1);
+    app_state
         .rt_machine_creation_channel
-        .send(crate::app_state::HotThreadMessage::WriteMachineDeviceInfo(
-            body.clone(),
-        ))
-        .await;
-
-    match res {
-        Ok(_) => (),
-        Err(e) => tracing::error!(
-            "Failed to send HotThreadMessage::WriteMachineDeviceInfo {}",
-            e
-        ),
-    }
+        .send(message(reply_tx))
+        .await
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "[{}::send_identification_message] Failed to send HotThreadMessage {}",
+                module_path!(),
+                e
+            )
+        })?;
+
+    async { reply_rx.recv().await.ok() }
+        .or(async {
+            smol::Timer::after(Duration::from_secs(30)).await;
+            None
+        })
+        .await
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "[{}::send_identification_message] Loop didn't answer the EEPROM write",
+                module_path!()
+            )
+        })?
+}
 
+/// Updates the in-memory identification of a subdevice and re-groups the machines
+async fn apply_device_identification(
+    app_state: Arc<SharedState>,
+    subdevice_index: usize,
+    device_machine_identification: DeviceMachineIdentification,
+) {
     // Update the in-memory device identification
     let mut ethercat_meta_data = app_state.ethercat_meta_data.write().await;
     for meta in ethercat_meta_data.iter_mut() {
         if let machines::machine_identification::DeviceHardwareIdentification::Ethercat(ethercat) = &meta.device_identification.device_hardware_identification {
-            if ethercat.subdevice_index == body.hardware_identification_ethercat.subdevice_index {
-                meta.device_identification.device_machine_identification = Some(body.device_machine_identification.clone());
+            if ethercat.subdevice_index == subdevice_index {
+                meta.device_identification.device_machine_identification = Some(device_machine_identification.clone());
                 break;
             }
         }
@@ -55,6 +87,7 @@ pub async fn post_write_machine_device_identification(
         MachineObj {
             machine_identification_unique: first_device.device_machine_identification.machine_identification_unique.clone(),
             error: None,
+            hardware_checklist: vec![],
         }
     }).collect();
 
@@ -72,19 +105,54 @@ pub async fn post_write_machine_device_identification(
 
     // Re-send the ethercat devices event to update the frontend
     app_state.clone().send_ethercat_devices_event().await;
+}
 
-    // Re-calculate machines
-    let ethercat_meta_data = app_state.ethercat_meta_data.read().await;
-    let device_identifications: Vec<_> = ethercat_meta_data.iter().map(|meta| meta.device_identification.clone()).collect();
-    let grouping = group_devices_by_identification(&device_identifications);
-    let machine_objs: Vec<_> = grouping.device_groups.into_iter().map(|group: Vec<machines::machine_identification::DeviceIdentificationIdentified>| {
-        let first = group.first().unwrap();
-        MachineObj {
-            machine_identification_unique: first.device_machine_identification.machine_identification_unique.clone(),
-            error: None,
-        }
-    }).collect();
-    app_state.add_machines_if_not_exists(machine_objs).await;
+#[axum::debug_handler]
+pub async fn post_write_machine_device_identification(
+    State(app_state): State<Arc<SharedState>>,
+    Json(body): Json<MachineDeviceInfoRequest>,
+) -> Response<axum::body::Body> {
+    let res = send_identification_message(&app_state, |reply| {
+        HotThreadMessage::WriteMachineDeviceInfo(body.clone(), reply)
+    })
+    .await;
+
+    if let Err(e) = res {
+        return ResponseUtil::error(&e.to_string());
+    }
+
+    apply_device_identification(
+        app_state,
+        body.hardware_identification_ethercat.subdevice_index,
+        body.device_machine_identification,
+    )
+    .await;
+
+    ResponseUtil::ok(MutationResponse::success())
+}
+
+/// Restores the identification from before the last write that wasn't undone yet
+#[axum::debug_handler]
+pub async fn post_undo_machine_device_identification(
+    State(app_state): State<Arc<SharedState>>,
+    Json(body): Json<UndoMachineDeviceInfoRequest>,
+) -> Response<axum::body::Body> {
+    let res = send_identification_message(&app_state, |reply| {
+        HotThreadMessage::UndoMachineDeviceInfo(body.hardware_identification_ethercat.clone(), reply)
+    })
+    .await;
+
+    let backup = match res {
+        Ok(backup) => backup,
+        Err(e) => return ResponseUtil::error(&e.to_string()),
+    };
+
+    apply_device_identification(
+        app_state,
+        body.hardware_identification_ethercat.subdevice_index,
+        backup.written,
+    )
+    .await;
 
     ResponseUtil::ok(MutationResponse::success())
 }
@@ -1,14 +1,25 @@
 use anyhow::Result;
-use axum::routing::post;
+use axum::routing::{get, post};
 use std::sync::Arc;
 use std::thread;
 use tower_http::cors::CorsLayer;
 use tower_http::trace::{DefaultMakeSpan, DefaultOnRequest, DefaultOnResponse, TraceLayer};
 use tracing::Level;
 
+use super::handlers::assign_machine::post_assign_machine;
 use super::handlers::machine_mutation::post_machine_mutate;
+use super::handlers::ethercat_capture::{
+    get_ethercat_capture_pcapng, get_ethercat_capture_status, post_ethercat_capture_dump,
+    post_ethercat_capture_start, post_ethercat_capture_stop,
+};
 use super::handlers::ethercat_recover::post_ethercat_recover;
-use super::handlers::write_machine_device_identification::post_write_machine_device_identification;
+use super::handlers::ethercat_topology::{get_ethercat_topology, post_ethercat_rescan};
+use super::handlers::motor_configuration::{
+    post_read_motor_configuration, post_write_motor_configuration,
+};
+use super::handlers::write_machine_device_identification::{
+    post_undo_machine_device_identification, post_write_machine_device_identification,
+};
 use crate::app_state::SharedState;
 use crate::socketio::init::init_socketio;
 
@@ -28,8 +39,43 @@ async fn init_api(app_state: Arc<SharedState>) -> Result<()> {
             "/api/v1/write_machine_device_identification",
             post(post_write_machine_device_identification),
         )
+        .route(
+            "/api/v1/undo_machine_device_identification",
+            post(post_undo_machine_device_identification),
+        )
         .route("/api/v1/machine/mutate", post(post_machine_mutate))
+        .route("/api/v1/machine/assign", post(post_assign_machine))
+        .route(
+            "/api/v1/machine/motor_configuration/read",
+            post(post_read_motor_configuration),
+        )
+        .route(
+            "/api/v1/machine/motor_configuration/write",
+            post(post_write_motor_configuration),
+        )
         .route("/api/v1/ethercat/recover", post(post_ethercat_recover))
+        .route("/api/v1/ethercat/topology", get(get_ethercat_topology))
+        .route("/api/v1/ethercat/rescan", post(post_ethercat_rescan))
+        .route(
+            "/api/v1/ethercat/capture/start",
+            post(post_ethercat_capture_start),
+        )
+        .route(
+            "/api/v1/ethercat/capture/stop",
+            post(post_ethercat_capture_stop),
+        )
+        .route(
+            "/api/v1/ethercat/capture/dump",
+            post(post_ethercat_capture_dump),
+        )
+        .route(
+            "/api/v1/ethercat/capture/status",
+            get(get_ethercat_capture_status),
+        )
+        .route(
+            "/api/v1/ethercat/capture/pcapng",
+            get(get_ethercat_capture_pcapng),
+        )
         .nest("/api/v1/metrics", metrics_router())
         .layer(socketio_layer)
         .layer(cors)
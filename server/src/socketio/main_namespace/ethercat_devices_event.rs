@@ -1,10 +1,18 @@
-use crate::app_state::{EtherCatDeviceMetaData, SharedState};
+use crate::app_state::{EtherCatDeviceMetaData, EtherCatPdoLengthMismatch, SharedState};
+use crate::ethercat::identification_mapping::IdentificationConflict;
+use crate::ethercat::topology::EthercatTopology;
 use control_core::socketio::event::Event;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct EthercatSetupDone {
     pub devices: Vec<EtherCatDeviceMetaData>,
+    /// Subdevices whose machines were refused because of a PDO length mismatch
+    pub pdo_length_mismatches: Vec<EtherCatPdoLengthMismatch>,
+    /// Chain order, port states and coupler slots as read during setup
+    pub topology: Option<EthercatTopology>,
+    /// Conflicts between the identification mapping file and the EEPROM identifications
+    pub identification_conflicts: Vec<IdentificationConflict>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -24,6 +32,17 @@ impl EthercatDevicesEventBuilder {
             Self::NAME,
             EthercatDevicesEvent::Done(EthercatSetupDone {
                 devices: app_state.ethercat_meta_data.read().await.to_vec(),
+                pdo_length_mismatches: app_state
+                    .ethercat_pdo_length_mismatches
+                    .read()
+                    .await
+                    .to_vec(),
+                topology: app_state.ethercat_topology.read().await.clone(),
+                identification_conflicts: app_state
+                    .ethercat_identification_conflicts
+                    .read()
+                    .await
+                    .to_vec(),
             }),
         )
     }
@@ -1,4 +1,5 @@
 use control_core::socketio::event::Event;
+use machines::hardware_descriptor::HardwareCheckItem;
 use machines::machine_identification::MachineIdentificationUnique;
 use serde::{Deserialize, Serialize};
 
@@ -11,6 +12,9 @@ pub struct MachinesEvent {
 pub struct MachineObj {
     pub machine_identification_unique: MachineIdentificationUnique,
     pub error: Option<String>,
+    /// Commissioning checklist from the machine's hardware descriptor, empty for non EtherCAT machines
+    #[serde(default)]
+    pub hardware_checklist: Vec<HardwareCheckItem>,
 }
 pub struct MachinesEventBuilder();
 
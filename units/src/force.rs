@@ -0,0 +1,16 @@
+quantity! {
+    /// Force (base unit newton, kg · m · s⁻²).
+    quantity: Force; "force";
+    /// Dimension of force, LMT⁻² (base unit newton, kg · m · s⁻²).
+    dimension: ISQ<
+        P1,  // length
+        P1,  // mass
+        N2,  // time
+        Z0,  // electric current
+        Z0,  // thermodynamic temperature
+        Z0,  // amount of substance
+        Z0>; // luminous intensity
+    units {
+        @newton: 1.0; "N", "newton", "newtons";
+    }
+}
@@ -37,16 +37,20 @@ system! {
         angular_velocity::AngularVelocity,
         electric_current::ElectricCurrent,
         electric_potential::ElectricPotential,
+        energy::Energy,
+        force::Force,
         frequency::Frequency,
         jerk::Jerk,
         length::Length,
         luminous_intensity::LuminousIntensity,
         mass::Mass,
+        power::Power,
         pressure::Pressure,
         ratio::Ratio,
         thermodynamic_temperature::ThermodynamicTemperature,
         time::Time,
         velocity::Velocity,
+        volume::Volume,
         volume_rate::VolumeRate,
     }
 }
@@ -12,5 +12,6 @@ quantity! {
         Z0>; // luminous intensity
     units {
         @kilogram: 1.0; "kg", "kilogram", "kilograms";
+        @gram: 1.0e-3; "g", "gram", "grams";
     }
 }
@@ -0,0 +1,18 @@
+quantity! {
+    /// Volume (base unit cubic meter, m³).
+    quantity: Volume; "volume";
+    /// Dimension of volume, L³ (base unit cubic meter, m³).
+    dimension: ISQ<
+        P3,     // length
+        Z0,     // mass
+        Z0,     // time
+        Z0,     // electric current
+        Z0,     // thermodynamic temperature
+        Z0,     // amount of substance
+        Z0>;    // luminous intensity
+    units {
+        @cubic_meter: 1.0; "m³", "cubic meter", "cubic meters";
+        @liter: 1.0e-3; "L", "liter", "liters";
+        @milliliter: 1.0e-6; "mL", "milliliter", "milliliters";
+    }
+}